@@ -0,0 +1,173 @@
+/*
+ * Example OBS source: shows the picture `magic-eraser --obs` shares (see
+ * include/magic_eraser_shm.h and src/shm.rs). Build against OBS's libobs:
+ *
+ *     cmake -B build -S examples/obs && cmake --build build
+ *
+ * and copy the plugin into OBS's plugin folder (e.g. ~/.config/obs-studio/plugins/
+ * magic-eraser-source/bin/64bit/ on Linux). Then add a "Magic Eraser" source.
+ *
+ * It maps the file read-only and checks it once per OBS frame; a new sequence
+ * number means a new picture. If the app isn't running the source is empty, and
+ * it picks the app up again when it starts. POSIX only (Linux, macOS); Windows
+ * would map the file with CreateFileMapping instead.
+ */
+#include <obs-module.h>
+#include <util/bmem.h>
+#include <util/platform.h>
+
+#include <fcntl.h>
+#include <sys/mman.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "magic_eraser_shm.h"
+
+OBS_DECLARE_MODULE()
+
+#define DEFAULT_PATH "/dev/shm/magic-eraser.frame"
+
+struct me_source {
+    obs_source_t *source;
+    char *path;
+    uint8_t *map;          /* the file, read-only; NULL while it doesn't exist */
+    size_t map_len;
+    ino_t inode;           /* a restarted app makes a new file */
+    uint8_t *pixels;       /* the last complete frame */
+    size_t pixels_len;
+    uint64_t sequence;
+};
+
+static void unmap(struct me_source *s)
+{
+    if (s->map)
+        munmap(s->map, s->map_len);
+    s->map = NULL;
+    s->map_len = 0;
+    s->sequence = 0;
+}
+
+/* (Re)map the file if it appeared, went away or was replaced. */
+static void remap(struct me_source *s)
+{
+    struct stat st;
+    if (stat(s->path, &st) != 0 || (size_t)st.st_size < ME_SHM_HEADER_LEN) {
+        if (s->map) {
+            unmap(s);
+            obs_source_output_video(s->source, NULL); /* app gone: empty source */
+        }
+        return;
+    }
+    if (s->map && st.st_ino == s->inode && (size_t)st.st_size == s->map_len)
+        return;
+    unmap(s);
+    int fd = open(s->path, O_RDONLY);
+    if (fd < 0)
+        return;
+    void *map = mmap(NULL, (size_t)st.st_size, PROT_READ, MAP_SHARED, fd, 0);
+    close(fd);
+    if (map == MAP_FAILED)
+        return;
+    s->map = map;
+    s->map_len = (size_t)st.st_size;
+    s->inode = st.st_ino;
+}
+
+static const char *me_get_name(void *unused)
+{
+    UNUSED_PARAMETER(unused);
+    return "Magic Eraser";
+}
+
+static void me_update(void *data, obs_data_t *settings)
+{
+    struct me_source *s = data;
+    bfree(s->path);
+    s->path = bstrdup(obs_data_get_string(settings, "path"));
+    unmap(s);
+}
+
+static void *me_create(obs_data_t *settings, obs_source_t *source)
+{
+    struct me_source *s = bzalloc(sizeof *s);
+    s->source = source;
+    me_update(s, settings);
+    return s;
+}
+
+static void me_destroy(void *data)
+{
+    struct me_source *s = data;
+    unmap(s);
+    bfree(s->pixels);
+    bfree(s->path);
+    bfree(s);
+}
+
+static void me_get_defaults(obs_data_t *settings)
+{
+    obs_data_set_default_string(settings, "path", DEFAULT_PATH);
+}
+
+static obs_properties_t *me_get_properties(void *unused)
+{
+    UNUSED_PARAMETER(unused);
+    obs_properties_t *props = obs_properties_create();
+    obs_properties_add_text(props, "path", "Shared frame file (magic-eraser --obs FILE)", OBS_TEXT_DEFAULT);
+    return props;
+}
+
+/* Once per OBS frame: hand over the app's picture if there is a new one. */
+static void me_video_tick(void *data, float seconds)
+{
+    UNUSED_PARAMETER(seconds);
+    struct me_source *s = data;
+    remap(s);
+    if (!s->map)
+        return;
+
+    me_shm_header header;
+    memcpy(&header, s->map, sizeof header);
+    size_t len = (size_t)header.height * header.stride;
+    if (header.format != ME_SHM_FORMAT_BGRA8 || ME_SHM_HEADER_LEN + len > s->map_len)
+        return;
+    if (s->pixels_len != len) {
+        bfree(s->pixels);
+        s->pixels = bmalloc(len);
+        s->pixels_len = len;
+    }
+    uint64_t sequence = me_shm_read(s->map, &header, s->pixels);
+    if (sequence == 0 || sequence == s->sequence)
+        return; /* being written, or nothing new */
+    s->sequence = sequence;
+
+    struct obs_source_frame frame = {
+        .data = {s->pixels},
+        .linesize = {header.stride},
+        .width = header.width,
+        .height = header.height,
+        .timestamp = os_gettime_ns(),
+        .format = VIDEO_FORMAT_BGRA,
+        .full_range = true,
+    };
+    obs_source_output_video(s->source, &frame);
+}
+
+static struct obs_source_info me_source_info = {
+    .id = "magic_eraser_source",
+    .type = OBS_SOURCE_TYPE_INPUT,
+    .output_flags = OBS_SOURCE_ASYNC_VIDEO,
+    .get_name = me_get_name,
+    .create = me_create,
+    .destroy = me_destroy,
+    .update = me_update,
+    .get_defaults = me_get_defaults,
+    .get_properties = me_get_properties,
+    .video_tick = me_video_tick,
+};
+
+bool obs_module_load(void)
+{
+    obs_register_source(&me_source_info);
+    return true;
+}
@@ -0,0 +1,55 @@
+/*
+ * Magic Eraser C API (see src/ffi.rs). Build the library with
+ *
+ *     cargo build --release --no-default-features --features ffi
+ *
+ * and link target/release/libmagic_eraser.a (or the .so / .dylib / .dll).
+ * Static linking also needs the platform's threading and math libraries
+ * (e.g. -lpthread -ldl -lm on Linux).
+ */
+#ifndef MAGIC_ERASER_H
+#define MAGIC_ERASER_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ME_OK            0
+#define ME_ERR_ARGUMENT (-1)  /* NULL pointer, zero size or unknown format */
+#define ME_ERR_PROCESS  (-2)  /* see me_last_error */
+#define ME_ERR_PANIC    (-3)  /* engine bug: free the engine */
+
+#define ME_FORMAT_RGBA8 0u
+#define ME_FORMAT_BGRA8 1u
+#define ME_FORMAT_RGB24 2u
+#define ME_FORMAT_I420  3u
+#define ME_FORMAT_NV12  4u
+
+typedef struct me_engine me_engine;
+
+typedef struct me_params {
+    uint32_t blur_radius; /* pixels, 1..96 (clamped) */
+    uint32_t format;      /* ME_FORMAT_* */
+} me_params;
+
+/* A new engine; one per thread or stream. */
+me_engine *me_engine_new(void);
+void me_engine_free(me_engine *engine);
+
+/* Blur `pixels` (width x height, tightly packed in params->format) in place under
+ * `mask` (width * height bytes, 0 = untouched .. 255 = full blur; NULL = no effect). */
+int me_process_frame(me_engine *engine, uint8_t *pixels, uint32_t width, uint32_t height,
+                     const uint8_t *mask, const me_params *params);
+
+/* Message for the last call's failure ("" if it succeeded); valid until the next call. */
+const char *me_last_error(const me_engine *engine);
+
+const char *me_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MAGIC_ERASER_H */
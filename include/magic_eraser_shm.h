@@ -0,0 +1,68 @@
+/*
+ * Magic Eraser shared-memory frames (see src/shm.rs). Run the app with
+ *
+ *     magic-eraser --obs [FILE]
+ *
+ * and it keeps FILE (default /dev/shm/magic-eraser.frame on Linux, the temp
+ * folder elsewhere) updated with the clean picture: this header, then the
+ * pixels. All numbers are little-endian. The file is removed when the app exits.
+ *
+ * The sequence is odd while a frame is being written and even once it is
+ * complete: copy the pixels, and keep the copy only if the sequence was the
+ * same even number before and after (me_shm_read does that; its fences are
+ * the GCC / Clang builtins, use MemoryBarrier() with MSVC).
+ */
+#ifndef MAGIC_ERASER_SHM_H
+#define MAGIC_ERASER_SHM_H
+
+#include <stdint.h>
+#include <string.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ME_SHM_MAGIC        "MEFRAME1"
+#define ME_SHM_VERSION      1u
+#define ME_SHM_HEADER_LEN   64u
+#define ME_SHM_FORMAT_BGRA8 1u  /* B, G, R, A bytes per pixel */
+
+typedef struct me_shm_header {
+    char magic[8];          /* "MEFRAME1" */
+    uint32_t version;       /* ME_SHM_VERSION */
+    uint32_t width;
+    uint32_t height;
+    uint32_t stride;        /* bytes per row */
+    uint32_t format;        /* ME_SHM_FORMAT_* */
+    uint32_t fps;
+    uint64_t sequence;      /* odd: being written; 0: no frame yet */
+    uint64_t time_ns;       /* when it was written, since the Unix epoch */
+    uint8_t reserved[16];
+} me_shm_header;
+
+/*
+ * Copy the latest complete frame out of the mapped file `map` (at least
+ * ME_SHM_HEADER_LEN + height * stride bytes) into `pixels` (height * stride
+ * bytes). Returns its sequence number, or 0 if there is no complete frame
+ * (not written yet, being written, or not a version-1 file).
+ */
+static inline uint64_t me_shm_read(const volatile uint8_t *map, me_shm_header *header, uint8_t *pixels)
+{
+    memcpy(header, (const uint8_t *)map, sizeof *header);
+    if (memcmp(header->magic, ME_SHM_MAGIC, 8) != 0 || header->version != ME_SHM_VERSION)
+        return 0;
+    uint64_t before = header->sequence;
+    if (before == 0 || (before & 1))
+        return 0;
+    __atomic_thread_fence(__ATOMIC_ACQUIRE);
+    memcpy(pixels, (const uint8_t *)map + ME_SHM_HEADER_LEN, (size_t)header->height * header->stride);
+    __atomic_thread_fence(__ATOMIC_ACQUIRE);
+    uint64_t after = *(const volatile uint64_t *)(map + 32);
+    return after == before ? before : 0;
+}
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MAGIC_ERASER_SHM_H */
@@ -0,0 +1,560 @@
+// The interactive app's state, and the one place events change it.
+// Visual expectation: nothing new on screen. Every key, click, panel button, gamepad
+// step, OSC message or script call arrives as an `Event` (see events.rs), and
+// `App::apply` carries it out — the brush, the mask and its undo history, the effect,
+// the filters and every on/off the HUD, panel and help sheet show. The frame loop
+// (interactive.rs) reads the result to build and draw the picture.
+//
+// Loading a --session file or a crash checkpoint, files dropped onto the window and
+// the session written by F6 / checkpoints / the exit autosave go through here too, so
+// they change the same state the keys do (and land in the same undo history).
+
+use crate::cli::Options;
+use crate::color::{Adjust, ColorAdjust, WhiteBalance};
+use crate::compare::Compare;
+use crate::config::{BrushConfig, CameraConfig, BRUSH_SIZE_MAX, BRUSH_SIZE_MIN};
+use crate::cursor::{CursorMode, CursorStyles};
+use crate::effects::EffectRegistry;
+use crate::error::{Context, Error};
+use crate::events::{Action, EffectRef, Event};
+use crate::filter::FilterChain;
+use crate::filters::{self, Denoise, WhiteBalancePick};
+use crate::fx::Fx;
+use crate::guides::Guides;
+use crate::history::{Doc, History};
+use crate::imageio;
+use crate::loupe::Loupe;
+use crate::lut::CubeLut;
+use crate::mode::{Mode, Review};
+use crate::pyramid::BlurQuality;
+use crate::session::{Session, SessionFilters};
+use crate::statusbar::StatusCell;
+use crate::types::{FrameBuffer, Mask, MaskTiles, Rect, Stamp};
+use crate::ui::Ui;
+use crate::view::View;
+use crate::vision::{self, brush_stamp, BackgroundModel, BlurCache, BlurKind};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Dropped files that play as the picture source instead of the camera (through ffmpeg).
+const VIDEO_EXTENSIONS: [&str; 6] = [".mp4", ".mov", ".mkv", ".webm", ".avi", ".m4v"];
+
+/// What the blur depends on: radius, algorithm, quality, linear light and the region.
+pub type BlurKey = (usize, BlurKind, BlurQuality, bool, Rect);
+
+/// Brush radius limits at processing resolution: `BRUSH_SIZE_MIN..=BRUSH_SIZE_MAX`
+/// window pixels, halved with `--half`.
+fn brush_limits(scale: usize) -> (i32, i32) {
+    (BRUSH_SIZE_MIN as i32 / scale as i32, BRUSH_SIZE_MAX as i32 / scale as i32)
+}
+
+/// Everything an `Event` can change, at processing resolution (`--half` halves it).
+pub struct App {
+    pub scale: usize,                      // --half: 2 window pixels per frame pixel
+    session_path: PathBuf,                 // where F6 / SAVE SESSION writes
+
+    /* --- Mask & brush stamp ---
+       Visual: α mask controls where the effect appears (1 = effect, 0 = raw live). */
+    pub mask: Mask,
+    pub mask_tiles: MaskTiles,             // untouched tiles skip blending
+    pub eraser_radius: i32,                // visual: brush size in (window) pixels
+    pub brush_hardness: f32,               // visual: 0 = very soft edge, 1 = crisp (status bar HARD n%)
+    pub stamp: Stamp,
+    pub brush_at: Option<(i32, i32)>,      // last dab: how fast the brush moves (FX intensity)
+    pub privacy: Option<(Mask, usize)>,    // privacy hotkey: mask and effect before
+
+    /* --- Clone / heal source ---
+       Visual: right-click marks the source; your next stroke fixes the offset
+       (source − stroke start), and painted areas then show that shifted texture. */
+    pub clone_src: Option<(i32, i32)>,     // visual: picked source point (blue +)
+    pub clone_offset: Option<(i32, i32)>,  // visual: how far the copied texture is shifted
+
+    /* --- Brush effects ---
+       Visual: Tab / number keys switch what appears under painted pixels (HUD shows which).
+       An image path as the first argument adds the BACKGROUND effect (virtual background). */
+    pub effects: EffectRegistry,
+
+    /* --- Undo / redo ---
+       Visual: Ctrl+Z / Ctrl+Y step through strokes, clears and effect switches (status bar: EDIT). */
+    pub history: History,
+    pub history_note: Option<(String, Instant)>, // "UNDO STROKE", shown for a moment
+
+    /* --- Blur ---
+       Visual: `,` / `.` soften or sharpen it (past 16 px the pyramid keeps it fast).
+       K: box or stack. Q: full, half or quarter resolution. E: blur every frame, every
+       2nd or every 4th (static scenes barely change). L: average light, not sRGB. */
+    pub blur_radius: usize,                // visual: softness of the blur brush (bigger = softer)
+    pub blur_linear_on: bool,              // L: average light, not sRGB code values
+    pub blur_kind: BlurKind,               // K: box ↔ stack
+    pub blur_quality: BlurQuality,         // Q: full / half / quarter resolution
+    pub blur_cache: BlurCache<BlurKey>,    // E: reuse the blur for N frames; skip when unused
+
+    /* --- Filter chains (see filters.rs) ---
+       Visual: live-frame corrections before the brush (M denoise, W / V white balance,
+       A auto-levels, V grade, U skin smoothing). */
+    pub live_filters: FilterChain,
+    pub levels_on: bool,                   // A: contrast stretch
+    pub grade_sel: Adjust,                 // V: what -/= change (second HUD line)
+    pub beauty_on: bool,                   // U: all skin smoothed
+    pub vignette_on: bool,                 // N, G: both off by default
+    pub grain_on: bool,
+    pub wb_pick_armed: bool,               // visual: HUD says PICK WHITE
+    pub picker_armed: bool,                // X: visual: HUD says PICK
+
+    /* --- Background capture + portrait mode ---
+       Visual: R records ~1 s of the empty scene; O then keeps you sharp and blurs the room. */
+    pub bg_model: BackgroundModel,         // one frame of memory
+    pub have_background: bool,
+    pub portrait_on: bool,
+
+    /* --- Mode ---
+       Visual: the MODE cell says LIVE / PAINTING / CAPTURING BG / a review view (B, D, mask). */
+    pub mode: Mode,
+
+    /* --- FX (sparkles/lightning) ---
+       Visual: glows around your brush while painting; fades on its own. */
+    pub fx: Fx,
+    pub fx_on: bool,                       // F, --no-fx, OSC /eraser/fx
+
+    /* --- Overlays ---
+       Visual: what is drawn over the picture, and which of it shows. */
+    pub view: View,                        // Ctrl + scroll zoom, middle-drag pan, Home resets
+    pub compare: Compare,                  // F3: raw camera left of a draggable divider
+    pub guides: Guides,                    // F5: thirds grid / centre cross / safe margins
+    pub loupe: Loupe,                      // Z: magnifier inset
+    pub ui: Ui,                            // S: the settings panel's widgets
+    pub cursor_styles: CursorStyles,       // F2: per mode (painting / picking)
+    pub help_on: bool,                     // F1 / ?: every key and the current settings
+    pub thumb_on: bool,                    // F4 hides the mask thumbnail
+    pub hud_on: bool,                      // H hides all HUD text
+    pub graph_on: bool,                    // T: frame-time graph
+    pub histogram_on: bool,                // I: RGB/luma histogram
+    pub profile_on: bool,                  // P: per-stage milliseconds + graph
+    pub panel_on: bool,                    // S: settings panel
+    pub session_due: Option<PathBuf>,      // F6 / SAVE SESSION: written after this frame
+    pub video_due: Option<PathBuf>,        // dropped video: the frame loop switches to it
+}
+
+impl App {
+    /// The state a run starts from: flags and config.toml for the brush and filters, the
+    /// first-argument picture as BACKGROUND, everything else off. `pw`×`ph` is the
+    /// processing size, `window_w` the window's width (for the zoom view).
+    pub fn new(opts: &Options, pw: usize, ph: usize, window_w: usize) -> Result<Self, Error> {
+        let scale = if opts.half_res { 2 } else { 1 };
+        let (lo, hi) = brush_limits(scale);
+        let eraser_radius = (opts.brush_size / scale as i32).clamp(lo, hi);
+        let background_image: Option<FrameBuffer> = match &opts.image_path {
+            Some(path) => Some(imageio::load_image_cover(path, pw, ph)?),
+            None => None,
+        };
+        let mut fx = Fx::new(600);
+        fx.set_params(opts.config.fx.params());        // [fx] in config.toml
+        fx.set_colors(opts.theme.sparkle, opts.theme.bolt, opts.theme.smoke);
+        Ok(Self {
+            scale,
+            session_path: opts.session_path.clone(),
+            mask: Mask::new(pw, ph),
+            mask_tiles: MaskTiles::new(pw, ph),
+            eraser_radius,
+            brush_hardness: opts.brush_hardness,
+            stamp: brush_stamp(eraser_radius, opts.brush_hardness),
+            brush_at: None,
+            privacy: None,
+            clone_src: None,
+            clone_offset: None,
+            effects: EffectRegistry::new(background_image).with_order(&opts.config.pipeline.effects)?,
+            history: History::default(),
+            history_note: None,
+            blur_radius: opts.blur_radius,
+            blur_linear_on: false,
+            blur_kind: BlurKind::Box,
+            blur_quality: BlurQuality::Full,
+            blur_cache: BlurCache::new(),
+            live_filters: filters::build(&opts.filters, "--filters")?,
+            levels_on: false,
+            grade_sel: Adjust::Brightness,
+            beauty_on: false,
+            vignette_on: false,
+            grain_on: false,
+            wb_pick_armed: false,
+            picker_armed: false,
+            bg_model: BackgroundModel::new(pw, ph),
+            have_background: false,
+            portrait_on: false,
+            mode: Mode::Live,
+            fx,
+            fx_on: opts.fx_on,
+            view: View::new(pw, ph, window_w),
+            compare: Compare::new(pw, ph),
+            guides: Guides::default(),
+            loupe: Loupe::new(),
+            ui: Ui::new(opts.theme),
+            cursor_styles: opts.cursor,
+            help_on: false,
+            thumb_on: true,
+            hud_on: true,
+            graph_on: true,
+            histogram_on: false,
+            profile_on: false,
+            panel_on: false,
+            session_due: None,
+            video_due: None,
+        })
+    }
+
+    /// New brush size and / or hardness (the dab is rebuilt to match). The radius is in
+    /// processing pixels and kept within `brush_limits`, the hardness within 0..1.
+    pub fn set_brush(&mut self, radius: i32, hardness: f32) {
+        let (lo, hi) = self.brush_limits();
+        let (radius, hardness) = (radius.clamp(lo, hi), hardness.clamp(0.0, 1.0));
+        self.eraser_radius = radius;
+        self.brush_hardness = hardness;
+        self.stamp = brush_stamp(radius, hardness);
+    }
+
+    /// Smallest and largest brush radius in processing pixels (the settings slider's range).
+    pub fn brush_limits(&self) -> (i32, i32) {
+        brush_limits(self.scale)
+    }
+
+    /// Which cursor style applies: picking while W / X is armed, painting otherwise.
+    pub fn cursor_mode(&self) -> CursorMode {
+        if self.wb_pick_armed || self.picker_armed { CursorMode::Pick } else { CursorMode::Paint }
+    }
+
+    /// Carry out one event. True when it painted (the stroke goes on this frame).
+    pub fn apply(&mut self, event: Event, now: Instant) -> bool {
+        match event {
+            Event::Press(Action::Help) => self.help_on = !self.help_on, // visual: cheat sheet opens/closes
+            Event::Press(Action::ZoomReset) => self.view.reset(),       // visual: whole frame again
+            Event::Press(Action::MaskThumb) => {                        // visual: thumbnail hides/returns
+                self.thumb_on = !self.thumb_on;
+                self.mode = self.mode.close_review(Review::Mask);
+            }
+            Event::Press(Action::Compare) => self.compare.on = !self.compare.on, // visual: divider appears/disappears
+            Event::Press(Action::Guides) => self.guides.cycle(),        // visual: grid / cross / margins
+            Event::Press(Action::SaveSession) => self.session_due = Some(self.session_path.clone()), // visual: FILE SAVING → SAVED
+            Event::Press(Action::ShowBlur) => self.mode = self.mode.toggle_review(Review::Blur), // visual: BLUR preview (debug)
+            Event::Press(Action::Diff) => self.mode = self.mode.cycle_diff(self.have_background), // visual: heat map on/off
+            Event::Press(Action::Median) => {
+                if let Some(d) = self.live_filters.get_mut::<Denoise>() {
+                    d.radius = (d.radius + 1) % 3;                      // visual: denoise off/3x3/5x5
+                }
+            }
+            Event::Press(Action::Levels) => self.levels_on = !self.levels_on, // visual: contrast stretch on/off
+            Event::Press(Action::ColourControl) => self.grade_sel = self.grade_sel.next(), // visual: HUD marker moves
+            Event::Press(Action::ColourReset) => {                      // visual: back to neutral
+                if let Some(grade) = self.live_filters.get_mut::<ColorAdjust>() { *grade = ColorAdjust::new(); }
+                if let Some(white) = self.live_filters.get_mut::<WhiteBalancePick>() { white.wb = WhiteBalance::new(); }
+            }
+            Event::Press(Action::WhiteBalance) => self.wb_pick_armed = !self.wb_pick_armed, // visual: eyedropper armed
+            Event::Press(Action::Picker) => self.picker_armed = !self.picker_armed, // visual: colour picker armed
+            Event::Press(Action::CursorStyle) => {                      // visual: cursor style changes
+                let style = self.cursor_styles.get_mut(self.cursor_mode());
+                *style = style.next();
+            }
+            Event::Press(Action::CaptureBackground) => {                // visual: HUD starts counting frames
+                self.bg_model.reset();
+                self.mode = self.mode.start_capture();
+            }
+            Event::SetPortrait(on) if on == self.portrait_on => {}
+            Event::Press(Action::Portrait) | Event::SetPortrait(_) => { // visual: room blurs, you stay sharp
+                self.portrait_on = !self.portrait_on;
+                if self.portrait_on && !self.have_background && !self.mode.capturing() {
+                    self.bg_model.reset();
+                    self.mode = self.mode.start_capture();              // no background yet: grab one first
+                }
+            }
+            Event::Press(Action::Skin) => self.beauty_on = !self.beauty_on, // visual: all skin smoothed
+            Event::Press(Action::Profiler) => self.profile_on = !self.profile_on, // visual: timing table + graph
+            Event::Press(Action::Hud) => self.hud_on = !self.hud_on,    // visual: HUD text disappears/returns
+            Event::Press(Action::Graph) => self.graph_on = !self.graph_on, // visual: frame-time graph disappears/returns
+            Event::Press(Action::Histogram) => self.histogram_on = !self.histogram_on, // visual: histogram appears/disappears
+            Event::Press(Action::Loupe) => self.loupe.zoom = self.loupe.zoom.next(), // visual: loupe 4× → 8× → closed
+            Event::Press(Action::Settings) => {                         // visual: settings panel opens/closes
+                self.panel_on = !self.panel_on;
+                if !self.panel_on { self.ui.hide(); }
+            }
+            Event::Press(Action::BlurReuse) => self.blur_cache.next_every(), // visual: HUD shows /2 or /4
+            Event::Press(Action::BlurQuality) => self.blur_quality = self.blur_quality.next(), // visual: HUD shows HALF/QUARTER
+            Event::Press(Action::BlurKind) => self.blur_kind = self.blur_kind.next(), // visual: blur shape changes
+            Event::Press(Action::LinearBlur) => self.blur_linear_on = !self.blur_linear_on, // visual: blur brightens at edges
+            Event::Press(Action::Grain) => self.grain_on = !self.grain_on, // visual: film grain on/off
+            Event::Press(Action::Vignette) => self.vignette_on = !self.vignette_on, // visual: dark corners on/off
+            Event::Press(Action::Fx) => self.fx_on = !self.fx_on,       // visual: sparkles and bolts on/off
+            // PRIVACY blurs everything until it is off: nothing may take paint away meanwhile.
+            Event::Press(Action::Clear) | Event::ClearAt(_) | Event::Paint { subtract: true, .. } if self.privacy.is_some() => {}
+            Event::Press(Action::Clear) => {                            // visual: eraser cleared (blur disappears)
+                if let Some(b) = self.mask.bounds {
+                    self.history.mask_changing("CLEAR", &self.mask, (b.x0 as i32, b.y0 as i32, b.x1 as i32, b.y1 as i32));
+                }
+                if self.fx_on && let (Some(b), Some((cx, cy))) = (self.mask.bounds, vision::mask_centroid(&self.mask)) {
+                    let reach = (b.x1 - b.x0).max(b.y1 - b.y0) as f32 * 0.5;
+                    self.fx.burst(cx, cy, reach);                       // visual: confetti pops where the paint was
+                }
+                vision::clear_mask(&mut self.mask);
+                self.mask_tiles.clear();
+                self.history.mask_done(&self.mask);
+            }
+            Event::Press(Action::Privacy) => {
+                self.history.mask_done(&self.mask);                     // the fill itself is not an edit
+                match self.privacy.take() {
+                    None => {                                           // visual: the whole picture blurs at once
+                        self.privacy = Some((self.mask.clone(), self.effects.active_index()));
+                        vision::fill_mask(&mut self.mask);
+                        self.effects.select_by_name("blur");            // BLUR, whatever the brush was
+                    }
+                    Some((saved, effect)) => {                          // visual: the painting you had returns
+                        self.mask = saved;
+                        self.effects.select(effect);
+                    }
+                }
+                let (w, h) = (self.mask.width as i32, self.mask.height as i32);
+                self.mask_tiles.update_rect(&self.mask, 0, 0, w, h);
+            }
+            Event::Press(Action::NextEffect) | Event::SelectEffect(_) if self.privacy.is_some() => {} // BLUR until it is off
+            Event::Press(Action::NextEffect) | Event::SelectEffect(_) => { // visual: HUD shows the new effect
+                let from = self.effects.active_index();
+                match event {
+                    Event::SelectEffect(EffectRef::Index(i)) => self.effects.select(i),
+                    Event::SelectEffect(EffectRef::Name(name)) => { self.effects.select_by_name(&name); }
+                    _ => self.effects.next(),
+                }
+                if self.effects.active_index() != from {
+                    self.history.effect_changed(from, self.effects.active_index(), &self.effects);
+                }
+            }
+            Event::Press(Action::Undo | Action::Redo) if self.privacy.is_some() => {}
+            Event::Press(action @ (Action::Undo | Action::Redo)) => {   // visual: the last change comes off / back
+                let mut doc = Doc { mask: &mut self.mask, tiles: &mut self.mask_tiles, effects: &mut self.effects };
+                let note = if action == Action::Undo {
+                    self.history.undo(&mut doc).map_or_else(|| String::from("NOTHING TO UNDO"), |l| format!("UNDO {l}"))
+                } else {
+                    self.history.redo(&mut doc).map_or_else(|| String::from("NOTHING TO REDO"), |l| format!("REDO {l}"))
+                };
+                self.history_note = Some((note, now));
+            }
+            Event::AdjustBrush { delta } => {                           // visual: brush ring grows/shrinks
+                self.set_brush(self.eraser_radius + 2 * delta, self.brush_hardness);
+            }
+            Event::AdjustBlur { delta } => {                            // visual: blur softer/sharper
+                self.blur_radius = (self.blur_radius as i32 + delta).clamp(1, 96) as usize;
+            }
+            Event::AdjustColour { delta } => {
+                if let Some(grade) = self.live_filters.get_mut::<ColorAdjust>() {
+                    grade.nudge(self.grade_sel, delta);                 // visual: image brightens/darkens…
+                }
+                if let Some(white) = self.live_filters.get_mut::<WhiteBalancePick>() {
+                    white.wb.nudge(self.grade_sel, delta);              // visual: …or warms/cools
+                }
+            }
+            Event::SetBrushRadius(px) => {
+                self.set_brush((px / self.scale as f32).round() as i32, self.brush_hardness);
+            }
+            Event::SetBrushHardness(h) => self.set_brush(self.eraser_radius, h),
+            Event::SetBlurRadius(px) => self.blur_radius = (px.round() as usize).clamp(1, 96),
+            Event::SetFx(on) => self.fx_on = on,
+            Event::CloneSource(p) => {                                  // visual: clone source moves to the cursor
+                self.clone_src = Some(p);
+                self.clone_offset = None;                               // next stroke re-anchors the offset
+            }
+            // Paint: α grows under the cursor (soft edges).
+            Event::Paint { from, to, subtract, start } => {
+                self.paint(from, to, subtract, start);
+                return true;                                            // visual: MODE PAINTING
+            }
+            Event::ClearAt((x, y)) => {
+                let r = self.stamp.radius;
+                self.history.mask_changing("HOLE", &self.mask, (x - r, y - r, x + r + 1, y + r + 1));
+                vision::clear_circle(&mut self.mask, x, y, r);          // visual: paint gone under the cursor
+                self.mask_tiles.update_rect(&self.mask, x - r, y - r, x + r + 1, y + r + 1);
+            }
+        }
+        false
+    }
+
+    /// Dab along from → to (a straight Shift stroke; a single dab while dragging).
+    fn paint(&mut self, (x0, y0): (i32, i32), (x1, y1): (i32, i32), subtract: bool, start: bool) {
+        if start && !subtract && self.clone_offset.is_none() && let Some((sx, sy)) = self.clone_src {
+            self.clone_offset = Some((sx - x1, sy - y1));                   // visual: texture locks on
+        }
+        let r = self.stamp.radius;
+        let spacing = (r / 4).max(1) as f32;
+        let steps = (((x1 - x0) as f32).hypot((y1 - y0) as f32) / spacing).ceil() as i32;
+        let dab: fn(&mut Mask, i32, i32, &Stamp) = if subtract { vision::lift_mask } else { vision::dab_mask };
+        let touched = (x0.min(x1) - r, y0.min(y1) - r, x0.max(x1) + r + 1, y0.max(y1) + r + 1);
+        if self.privacy.is_none() {
+            self.history.mask_changing(if subtract { "ERASE" } else { "STROKE" }, &self.mask, touched);
+        }
+        for i in 0..=steps {
+            let t = if steps == 0 { 1.0 } else { i as f32 / steps as f32 };
+            let x = x0 + ((x1 - x0) as f32 * t).round() as i32;
+            let y = y0 + ((y1 - y0) as f32 * t).round() as i32;
+            dab(&mut self.mask, x, y, &self.stamp);                        // visual: mask accumulates / fades
+        }
+        self.mask_tiles.update_rect(&self.mask, touched.0, touched.1, touched.2, touched.3);
+        // How far the brush went since its last dab (a drag sends from = to).
+        let (mx, my) = match self.brush_at {
+            Some((px, py)) if !start => (x1 - px, y1 - py),
+            _ => (x1 - x0, y1 - y0),
+        };
+        self.brush_at = Some((x1, y1));
+        if !subtract && self.fx_on {
+            let fx = &mut self.fx;
+            fx.brush_moved(mx as f32, my as f32);                          // visual: faster strokes, more FX
+            fx.spawn_sparkles(x1 as f32, y1 as f32, fx.sparkles_per_dab()); // visual: glows appear
+            fx.maybe_spawn_bolt(x1 as f32, y1 as f32);
+            fx.spawn_smoke(x1 as f32, y1 as f32, mx as f32, my as f32);    // visual: a trail of haze
+        }
+    }
+
+    /// Take over a session (--session FILE, or the crash checkpoint). Nothing changes if
+    /// its mask or background can't be read. `from_flag`: the brush stays as the flags
+    /// and config set it, and the restored mask is not an undo step.
+    pub fn restore(&mut self, s: &Session, from_flag: bool) -> Result<(), Error> {
+        let (w, h) = (self.mask.width, self.mask.height);
+        let saved = s.load_mask(w, h).context("Session mask")?;
+        let room = s.load_background(w, h).context("Session background")?;
+        if !s.effect.is_empty() && !self.effects.select_by_name(&s.effect) {
+            log::warn!("session: unknown effect '{}' — keeping {}", s.effect, self.effects.active_name());
+        }
+        self.portrait_on = s.portrait;
+        let on = |name: &str| s.filters.on.iter().any(|n| n == name);
+        (self.levels_on, self.beauty_on, self.vignette_on, self.grain_on) = (on("levels"), on("smooth"), on("vignette"), on("grain"));
+        if let Some(d) = self.live_filters.get_mut::<Denoise>() {
+            d.radius = s.filters.denoise.min(2);
+        }
+        if !from_flag {
+            // The brush too (for --session, flags and Options already decided it).
+            self.blur_radius = (s.brush.blur_radius as usize).clamp(1, 96);
+            self.set_brush(s.brush.size as i32 / self.scale as i32, s.brush.hardness);
+            self.fx_on = s.brush.fx;
+        }
+        if let Some(saved) = saved {
+            if let Some((kept, _)) = &mut self.privacy {
+                *kept = saved;                                  // PRIVACY stays; the painting returns with it off
+            } else {
+                if !from_flag {
+                    self.history.mask_changing("RESTORE", &self.mask, (0, 0, w as i32, h as i32));
+                }
+                self.mask = saved;
+                self.mask_tiles.rebuild(&self.mask);
+                self.history.mask_done(&self.mask);             // Ctrl+Z: back to the blank mask
+            }
+        }
+        if let Some(room) = room {
+            self.bg_model.restore(&room)?;
+            self.have_background = true;
+        }
+        Ok(())
+    }
+
+    /// A file dropped onto the window (winit backend): a picture, a saved mask, a
+    /// grading LUT or a video (opened by the frame loop, see `video_due`).
+    /// Returns what the FILE cell of the status bar should say (`warning`: its colour
+    /// when the file didn't load).
+    pub fn load_dropped(&mut self, path: &Path, warning: u32) -> StatusCell {
+        let file = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        let lower = file.to_ascii_lowercase();
+        let warn = |value: String| StatusCell { value_color: Some(warning), ..StatusCell::new("FILE", value) };
+        let shown = path.to_string_lossy();
+        let (w, h) = (self.mask.width, self.mask.height);
+        if lower.ends_with("-mask.png") {
+            if self.privacy.is_some() {
+                return warn(String::from("PRIVACY ON: MASK NOT LOADED"));
+            }
+            match imageio::load_mask(&shown, w, h) {
+                Ok(loaded) => {                                 // visual: the saved painting appears
+                    self.history.mask_changing("LOAD", &self.mask, (0, 0, w as i32, h as i32));
+                    self.mask = loaded;
+                    self.mask_tiles.rebuild(&self.mask);
+                    self.history.mask_done(&self.mask);
+                    StatusCell::new("FILE", format!("LOADED {file}"))
+                }
+                Err(e) => {
+                    log::warn!("{e}");
+                    warn(String::from("LOAD FAILED"))
+                }
+            }
+        } else if [".png", ".jpg", ".jpeg"].iter().any(|ext| lower.ends_with(ext)) {
+            match imageio::load_image_cover(&shown, w, h) {
+                Ok(image) => {                                  // visual: painted areas show the picture
+                    let added = self.effects.set_background(image);
+                    if let Some((_, effect)) = &mut self.privacy {
+                        if let Some(at) = added && *effect >= at {
+                            *effect += 1;                       // the brush PRIVACY gives back
+                        }
+                    } else {
+                        self.effects.select_by_name("background");
+                    }
+                    StatusCell::new("FILE", format!("LOADED {file}"))
+                }
+                Err(e) => {
+                    log::warn!("{e}");
+                    warn(String::from("LOAD FAILED"))
+                }
+            }
+        } else if lower.ends_with(".cube") {
+            match CubeLut::load(path) {
+                Ok(lut) => {                                    // visual: the whole picture takes the look
+                    match self.live_filters.get_mut::<CubeLut>() {
+                        Some(current) => *current = lut,
+                        None => self.live_filters.push(Box::new(lut)),
+                    }
+                    StatusCell::new("FILE", format!("LUT {file}"))
+                }
+                Err(e) => {
+                    log::warn!("{e}");
+                    warn(String::from("LOAD FAILED"))
+                }
+            }
+        } else if VIDEO_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) {
+            self.video_due = Some(path.to_path_buf());          // visual: the video replaces the camera
+            StatusCell::new("FILE", format!("PLAYING {file}"))
+        } else {
+            log::warn!("dropped {}: pictures, *-mask.png masks, .cube LUTs and videos load", path.display());
+            warn(format!("UNSUPPORTED FILE {file}"))
+        }
+    }
+
+    /// The painting and effect a session keeps: with PRIVACY on, the real ones from before.
+    pub fn painting(&self) -> (&Mask, &str) {
+        match &self.privacy {
+            Some((saved, index)) => (saved, self.effects.name_at(*index)),
+            None => (&self.mask, self.effects.active_name()),
+        }
+    }
+
+    /// The whole-frame filters a session switches on by name.
+    pub fn filters_on(&self) -> [(&'static str, bool); 4] {
+        [("levels", self.levels_on), ("smooth", self.beauty_on), ("vignette", self.vignette_on), ("grain", self.grain_on)]
+    }
+
+    /// The session as it stands (F6 / SAVE SESSION, checkpoints, the autosave on exit).
+    /// The mask and background are handed to `Session::save` separately.
+    pub fn session(&self, opts: &Options) -> Session {
+        Session {
+            effect: self.painting().1.to_string(),
+            portrait: self.portrait_on,
+            mask: None,                                        // named by `save`
+            background: None,
+            camera: CameraConfig {
+                index: opts.camera,
+                resolution: format!("{}x{}", opts.resolution.0, opts.resolution.1),
+                fps: opts.fps,
+            },
+            brush: BrushConfig {
+                blur_radius: self.blur_radius as u32,
+                size: (self.eraser_radius * self.scale as i32) as u32,
+                hardness: self.brush_hardness,
+                fx: self.fx_on,
+            },
+            filters: SessionFilters {
+                live: opts.filters.clone(),
+                post: opts.post_filters.clone(),
+                on: self.filters_on().iter().filter(|&&(_, on)| on).map(|&(name, _)| name.to_string()).collect(),
+                denoise: self.live_filters.get::<Denoise>().map_or(0, |d| d.radius),
+            },
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// Skin smoothing ("beauty" filter).
+// Visual expectation: skin looks softer and more even (blemishes, pores and
+// compression noise fade), while eyes, lips, hair, edges and the background stay sharp.
+//
+// How: mix each pixel towards a blurred copy, but only as much as
+// (a) the pixel looks like skin (YCbCr range test) and
+// (b) it is NOT an edge (blur and original differ a lot → keep the original detail).
+
+use crate::error::Error;
+use crate::types::FrameBuffer;
+
+/// Overall strength (0..1) of the smoothing on perfect skin with no edges.
+const STRENGTH: f32 = 0.85;
+/// Difference (0..255) below which we treat the blur as "just noise/texture" → smooth fully.
+const EDGE_LO: i32 = 10;
+/// Difference above which we treat it as a real edge → keep the original completely.
+const EDGE_HI: i32 = 40;
+
+/// Soft 0..1 membership for `v` inside [lo, hi], ramping over `soft` units outside.
+#[inline]
+fn soft_range(v: f32, lo: f32, hi: f32, soft: f32) -> f32 {
+    if v < lo { (1.0 - (lo - v) / soft).max(0.0) }
+    else if v > hi { (1.0 - (v - hi) / soft).max(0.0) }
+    else { 1.0 }
+}
+
+/// How skin-like a colour is (0..1), using the classic Cb/Cr box with soft edges.
+/// Works across skin tones because it ignores brightness (Y).
+#[inline]
+fn skin_likelihood(r: f32, g: f32, b: f32) -> f32 {
+    let cb = 128.0 - 0.168_736 * r - 0.331_264 * g + 0.5 * b;
+    let cr = 128.0 + 0.5 * r - 0.418_688 * g - 0.081_312 * b;
+    soft_range(cb, 77.0, 127.0, 8.0) * soft_range(cr, 133.0, 173.0, 8.0)
+}
+
+/// Write the skin-smoothed version of `live` into `dst`, using `blurred` (a blur of `live`).
+/// What you SEE (as sink or applied to the whole frame): softer skin, untouched details.
+pub fn skin_smooth(live: &FrameBuffer, blurred: &FrameBuffer, dst: &mut FrameBuffer) -> Result<(), Error> {
+    if live.width != blurred.width || live.height != blurred.height {
+        return Err(Error::CameraFrame("skin_smooth: size mismatch live↔blur".into()));
+    }
+    if live.width != dst.width || live.height != dst.height {
+        return Err(Error::CameraFrame("skin_smooth: size mismatch dst".into()));
+    }
+
+    for ((d, &p), &q) in dst.pixels.iter_mut().zip(&live.pixels).zip(&blurred.pixels) {
+        let (r, g, b) = (((p >> 16) & 0xFF) as i32, ((p >> 8) & 0xFF) as i32, (p & 0xFF) as i32);
+        let (br, bg, bb) = (((q >> 16) & 0xFF) as i32, ((q >> 8) & 0xFF) as i32, (q & 0xFF) as i32);
+
+        // Edge test: the largest channel difference between original and blur.
+        let diff = (r - br).abs().max((g - bg).abs()).max((b - bb).abs());
+        let keep_detail = ((diff - EDGE_LO) as f32 / (EDGE_HI - EDGE_LO) as f32).clamp(0.0, 1.0);
+
+        // Skin test on the blurred colour (stable against the very noise we remove).
+        let k = STRENGTH * skin_likelihood(br as f32, bg as f32, bb as f32) * (1.0 - keep_detail);
+        if k <= 0.0 {
+            *d = p; // visual: not skin, or a real edge → unchanged
+            continue;
+        }
+
+        let mix = |a: i32, b: i32| (a as f32 + (b - a) as f32 * k).round().clamp(0.0, 255.0) as u32;
+        *d = (mix(r, br) << 16) | (mix(g, bg) << 8) | mix(b, bb);
+    }
+    Ok(())
+}
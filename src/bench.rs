@@ -0,0 +1,99 @@
+// `--bench`: the processing stage on synthetic frames, no camera and no window.
+// Visual expectation: nothing opens; after a few seconds the terminal prints a table
+// of milliseconds per frame and frames per second for every stage, plus the overall
+// rate. Same machine + same commit = same numbers, so regressions are easy to spot.
+//
+// The frames are a moving colour pattern (so blur and blend have real work), and
+// a scripted "mouse" paints a circle with the brush while the benchmark runs.
+// `--source synthetic` shows the same pattern in the window (paced to --fps), for
+// trying the app without a camera.
+
+use crate::window::Input;
+use crate::error::Error;
+use crate::pipeline::FrameSource;
+use crate::profile::{Profiler, Stage};
+use crate::types::{FrameBuffer, FramePool};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+/// Frames processed when `--bench` is given without a count.
+pub const DEFAULT_BENCH_FRAMES: usize = 300;
+
+/// Deterministic stand-in for the camera: every call returns the next frame of the pattern.
+pub struct SyntheticSource {
+    pool: FramePool,
+    width: u32,
+    height: u32,
+    t: AtomicU32,
+    pace: Option<(Duration, Mutex<Instant>)>, // frame interval and when the next frame is due
+}
+
+impl SyntheticSource {
+    /// As fast as the processing stage takes them (for --bench).
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { pool: FramePool::new(width as usize, height as usize), width, height, t: AtomicU32::new(0), pace: None }
+    }
+
+    /// At most `fps` frames per second, like a camera would deliver them.
+    pub fn paced(width: u32, height: u32, fps: u32) -> Self {
+        let interval = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+        Self { pace: Some((interval, Mutex::new(Instant::now()))), ..Self::new(width, height) }
+    }
+}
+
+impl FrameSource for SyntheticSource {
+    /// Gradients drifting at different speeds plus a fine XOR texture (edges for the blur).
+    fn next_frame(&self) -> Result<FrameBuffer, Error> {
+        if let Some((interval, due)) = &self.pace && let Ok(mut due) = due.lock() {
+            std::thread::sleep(due.saturating_duration_since(Instant::now()));
+            *due = (*due + *interval).max(Instant::now());
+        }
+        let t = self.t.fetch_add(1, Ordering::Relaxed);
+        let mut frame = self.pool.get();
+        let w = frame.width;
+        for (i, p) in frame.pixels.iter_mut().enumerate() {
+            let (x, y) = ((i % w) as u32, (i / w) as u32);
+            let r = (x + t) & 0xFF;
+            let g = (y * 2 + t) & 0xFF;
+            let b = ((x ^ y) + t * 3) & 0xFF;
+            *p = (r << 16) | (g << 8) | b;
+        }
+        Ok(frame)
+    }
+
+    fn recycle(&self, frame: FrameBuffer) {
+        self.pool.put(frame);
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+/// Scripted input for frame `i`: left button held, cursor going round a circle.
+pub fn brush_input(i: usize, width: u32, height: u32) -> Input {
+    let a = i as f32 * 0.1;
+    let (cx, cy) = (width as f32 * 0.5, height as f32 * 0.5);
+    let r = width.min(height) as f32 * 0.3;
+    Input {
+        mouse_pos: Some(((cx + r * a.cos()) as usize, (cy + r * a.sin()) as usize)),
+        left_mouse_down: true,
+        ..Input::default()
+    }
+}
+
+/// Print the per-stage table: average ms per frame and the rate each stage alone could sustain.
+pub fn report(profiler: &Profiler, elapsed: Duration, width: u32, height: u32) {
+    let frames = profiler.frames().max(1) as f64;
+    println!("bench: {} frames at {}x{}", profiler.frames(), width, height);
+    println!("{:<8} {:>10} {:>12}", "stage", "ms/frame", "frames/s");
+    for stage in Stage::ALL {
+        let ms = profiler.total_ms(stage) / frames;
+        let rate = if ms > 0.0 { 1000.0 / ms } else { f64::INFINITY };
+        println!("{:<8} {:>10.3} {:>12.1}", stage.label(), ms, rate);
+    }
+    let fps = frames / elapsed.as_secs_f64().max(1e-9);
+    let mpix = fps * width as f64 * height as f64 / 1e6;
+    println!("overall: {fps:.1} frames/s ({mpix:.1} Mpixel/s)");
+}
@@ -1,7 +1,8 @@
 // Opens the default camera and converts frames into a buffer suitable for the window.
-// Visual expectation: when main.rs calls `next_frame()`, you get a
-// Vec<u32> where each pixel is 0x00RRGGBB, ready to push to the screen.
+// Visual expectation: when the capture thread calls `next_frame_into()`, you get a
+// frame where each pixel is 0x00RRGGBB, ready to push to the screen.
 
+use crate::convert;
 use crate::error::Error;
 use crate::types::FrameBuffer;
 
@@ -22,20 +23,21 @@ pub struct CameraCapture {
     cam: Camera,
     width: u32,
     height: u32,
+    rgb: Vec<u8>, // decode scratch (RGB bytes), reused every frame
 }
 
 impl CameraCapture {
-    /// Try to open camera index 0 at a target resolution (falls back if not exact).
-    /// On success, nothing is shown on screen yet — we just hold an open stream.
-
-    pub fn new(index: u32, width: u32, height: u32) -> Result<Self, Error> {
+    /// Try to open camera `index` at a target resolution and frame rate (falls back to the
+    /// closest format it has). On success, nothing is shown on screen yet — we just hold
+    /// an open stream.
+    pub fn new(index: u32, width: u32, height: u32, fps: u32) -> Result<Self, Error> {
         // 1) Choose the device (0 = default webcam)
         let idx = CameraIndex::Index(index);
 
         let fmt = CameraFormat::new(
             Resolution::new(width, height),
             FrameFormat::YUYV, // uncompressed; cheap to convert to RGB
-            30,                // target FPS
+            fps,               // target FPS
         );
 
          // 2) Ask for RGB frames, prioritizing the highest frame rate near our request.
@@ -65,41 +67,43 @@ impl CameraCapture {
             cam,
             width: actual.width(),
             height: actual.height(),
+            rgb: Vec::new(),
         })
     }
 
-    /// Grab one frame from the camera and convert it to 0x00RRGGBB pixels.
-    /// What you’ll see: after main.rs pushes this buffer to the window,
-    /// the live camera image updates by one frame.
-    pub fn next_frame(&mut self) -> Result<FrameBuffer, Error> {
+    /// Grab one frame from the camera and convert it to 0x00RRGGBB pixels in `out`
+    /// (typically from a `FramePool`). YUYV frames are converted straight from the raw
+    /// bytes; anything else (e.g. MJPEG) is decoded through a reused RGB scratch buffer.
+    /// Either way no memory is allocated once sizes have settled.
+    /// What you’ll see: once this frame reaches the window, the live image updates by one frame.
+    pub fn next_frame_into(&mut self, out: &mut FrameBuffer) -> Result<(), Error> {
         // 1) Pull a frame from the camera (this blocks until a new frame is ready).
         let frame = self
             .cam
             .frame()
             .map_err(|e| Error::CameraFrame(format!("Fetch frame: {e}")))?;
 
-        // 2) Decode to an ImageBuffer<Rgb<u8>, Vec<u8>> (handles various raw formats safely).
-        let rgb_img = frame
-            .decode_image::<RgbFormat>() // tells nokhwa to produce ImageBuffer<Rgb<u8>, Vec<u8>>
-            .map_err(|e| Error::CameraFrame(format!("Decode RGB: {e}")))?;
+        // 2) Make sure `out` matches the frame (resizes only if the camera changed size).
+        let res = frame.resolution();
+        let (w, h) = (res.width() as usize, res.height() as usize);
+        if out.width != w || out.height != h {
+            *out = FrameBuffer { width: w, height: h, pixels: vec![0u32; w * h] };
+        }
 
-        // 3) Prepare the pixel buffer for the window (u32 per pixel, 0x00RRGGBB).
-        //    You won't see anything yet; this just builds the data in RAM.
-        let (w, h) = rgb_img.dimensions();
-        let mut out = Vec::with_capacity((w as usize) * (h as usize));
-        for (_x, _y, pixel) in rgb_img.enumerate_pixels() {
-            // Each `pixel` is RGB<u8>. We pack it as 0x00RRGGBB.
-            let r = pixel[0] as u32;
-            let g = pixel[1] as u32;
-            let b = pixel[2] as u32;
-            out.push((r << 16) | (g << 8) | b);
+        // 3a) YUYV (our requested format): convert the raw bytes directly.
+        let raw = frame.buffer();
+        if matches!(frame.source_frame_format(), FrameFormat::YUYV) && raw.len() >= w * h * 2 {
+            convert::yuyv_to_xrgb(raw, &mut out.pixels);
+            return Ok(());
         }
 
-        Ok(FrameBuffer {
-            width: w as usize,
-            height: h as usize,
-            pixels: out,
-        })
+        // 3b) Anything else: decode into our scratch bytes, then pack (SIMD where available).
+        self.rgb.resize(w * h * 3, 0);
+        frame
+            .decode_image_to_buffer::<RgbFormat>(&mut self.rgb)
+            .map_err(|e| Error::CameraFrame(format!("Decode RGB: {e}")))?;
+        convert::pack_rgb24(&self.rgb, &mut out.pixels);
+        Ok(())
     }
 
     /// Report the actual resolution the camera is delivering.
@@ -0,0 +1,485 @@
+// Command-line flags (clap). `magic-eraser --help` lists them all.
+// Visual expectation: the same window as always, but set up from the command line —
+// e.g. `magic-eraser --camera 1 --resolution 1280x720 --fps 60 --blur-radius 16
+// --brush-size 40 --no-fx` starts the second webcam at 720p with a softer, bigger brush
+// and no sparkles, every time, without editing constants in the source. Flags left out
+// come from a --session file (session.rs), then the settings file (config.rs), then
+// the built-in defaults.
+// `magic-eraser process IN OUT ...` runs the same effects over a video file instead,
+// with no window, and `magic-eraser stream OUT ...` over the live camera (see offline.rs).
+
+#[cfg(feature = "window")]
+use crate::bench::DEFAULT_BENCH_FRAMES;
+use crate::config::Config;
+#[cfg(feature = "window")]
+use crate::config::OutputConfig;
+#[cfg(feature = "window")]
+use crate::cursor::CursorStyles;
+#[cfg(feature = "window")]
+use crate::debugwin::DebugView;
+use crate::error::Error;
+#[cfg(feature = "window")]
+use crate::hud::HudLayout;
+#[cfg(feature = "window")]
+use crate::keys::KeyMap;
+#[cfg(feature = "window")]
+use crate::session;
+use crate::session::Session;
+#[cfg(feature = "window")]
+use crate::theme::Theme;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Where frames come from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// The webcam picked with --camera.
+    Camera,
+    /// A moving test pattern (no camera needed; the one --bench uses).
+    Synthetic,
+}
+
+/// Which library draws the window (see window.rs).
+#[cfg(feature = "winit")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Backend {
+    /// minifb: a fixed-size window.
+    Minifb,
+    /// winit + softbuffer: resizable, high-DPI aware, touch (see winit_window.rs).
+    Winit,
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "magic-eraser", version, about = "Paint blur (and other effects) onto a live webcam feed.")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Picture for the BACKGROUND brush effect (virtual background).
+    #[arg(value_name = "IMAGE")]
+    pub image: Option<String>,
+
+    /// Where frames come from [default: camera, or `[pipeline] source`].
+    #[arg(long, value_enum)]
+    pub source: Option<Source>,
+
+    /// Webcam index (0 = the default camera) [default: 0].
+    #[arg(long, global = true, value_name = "INDEX")]
+    pub camera: Option<u32>,
+
+    /// Requested camera resolution; the camera may pick the closest it supports [default: 640x480].
+    #[arg(long, global = true, value_name = "WxH", value_parser = parse_resolution)]
+    pub resolution: Option<(u32, u32)>,
+
+    /// Target frame rate (also the frame-time budget for adaptive quality) [default: 30].
+    #[arg(long, global = true, value_name = "FPS", value_parser = clap::value_parser!(u32).range(1..=240))]
+    pub fps: Option<u32>,
+
+    /// Starting blur radius in pixels (, and . change it while running) [default: 8].
+    #[arg(long, global = true, value_name = "PX", value_parser = clap::value_parser!(u32).range(1..=96))]
+    pub blur_radius: Option<u32>,
+
+    /// Starting brush radius in window pixels [default: 22].
+    #[arg(long, value_name = "PX", value_parser = clap::value_parser!(u32).range(4..=160))]
+    pub brush_size: Option<u32>,
+
+    /// No sparkles or lightning while painting.
+    #[arg(long)]
+    pub no_fx: bool,
+
+    /// Process at half resolution and scale the finished picture up (slow machines).
+    #[arg(long)]
+    pub half: bool,
+
+    /// Touch screen: pinch resizes the brush, two-finger drag pans (see touch.rs).
+    #[arg(long)]
+    pub touch: bool,
+
+    /// Accept OSC remote control on this UDP port (see osc.rs).
+    #[arg(long, value_name = "PORT")]
+    pub osc: Option<u16>,
+
+    /// Share the clean picture with OBS through shared memory (see shm.rs, examples/obs/)
+    /// [default FILE: /dev/shm/magic-eraser.frame, or the temp folder].
+    #[arg(long, value_name = "FILE", num_args = 0..=1)]
+    pub obs: Option<Option<PathBuf>>,
+
+    /// Window library.
+    #[cfg(feature = "winit")]
+    #[arg(long, value_enum, default_value_t = Backend::Minifb)]
+    pub backend: Backend,
+
+    /// Run this rhai script every frame: recapture on a timer, animate the blur, ... (see script.rs).
+    #[cfg(feature = "script")]
+    #[arg(long, value_name = "FILE")]
+    pub script: Option<PathBuf>,
+
+    /// Extra windows showing pipeline stages, e.g. `mask,sink` (see debugwin.rs).
+    #[cfg(feature = "window")]
+    #[arg(long, value_name = "LIST")]
+    pub debug_windows: Option<String>,
+
+    /// Run N synthetic frames without a window and print per-stage timings.
+    #[arg(long, value_name = "FRAMES", num_args = 0..=1)]
+    pub bench: Option<Option<usize>>,
+
+    /// HUD layout, e.g. `status:bl,controls:br:ffcc33,-profiler` (see hud.rs).
+    #[arg(long, value_name = "SPEC")]
+    pub hud: Option<String>,
+
+    /// Cursor styles, e.g. `paint=ring,pick=crosshair` (see cursor.rs).
+    #[arg(long, value_name = "SPEC")]
+    pub cursor: Option<String>,
+
+    /// Colour theme: dark, light, high-vis, or a theme file (see theme.rs).
+    #[arg(long, value_name = "NAME|FILE")]
+    pub theme: Option<String>,
+
+    /// Live-frame filters in the order they run, e.g. `levels,grade,median` (see filters.rs)
+    /// [default: median,white-balance,levels,grade,smooth].
+    #[arg(long, value_name = "LIST")]
+    pub filters: Option<String>,
+
+    /// Filters for the finished picture, in order [default: vignette,grain].
+    #[arg(long, value_name = "LIST")]
+    pub post: Option<String>,
+
+    /// PNG shown in the top-right corner (logo / watermark).
+    #[arg(long, value_name = "PNG")]
+    pub logo: Option<String>,
+
+    /// File the SAVE SNAPSHOT button writes (the extension picks the format) [default: snapshot.png].
+    #[arg(long, value_name = "FILE")]
+    pub snapshot: Option<String>,
+
+    /// Settings file to read and update instead of the platform default (see config.rs).
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Start from a saved session (F6 writes one), or `autosave` for the last run's (see session.rs).
+    #[arg(long, value_name = "FILE|autosave")]
+    pub session: Option<String>,
+
+    /// Log level, optionally per module, e.g. `warn,pipeline=debug` (see logging.rs)
+    /// [default: $MAGIC_ERASER_LOG, else info].
+    #[arg(long, global = true, value_name = "SPEC")]
+    pub log: Option<String>,
+
+    /// Also append the log to this file (for bug reports).
+    #[arg(long, global = true, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// Write per-frame stage timings and FPS to this file: CSV, or JSON Lines for
+    /// .json / .jsonl (see metrics.rs).
+    #[arg(long, value_name = "FILE")]
+    pub metrics: Option<PathBuf>,
+}
+
+/// Modes other than the interactive window.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Apply a saved mask (or --auto) and an effect to every frame of a video, without a window.
+    Process(ProcessArgs),
+    /// Apply a saved mask and an effect to many still images (e.g. a folder of screenshots).
+    Batch(BatchArgs),
+    /// Apply a saved mask (or --auto) and an effect to the live camera, without a window.
+    #[cfg(feature = "camera")]
+    Stream(StreamArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ProcessArgs {
+    /// Video to read (anything ffmpeg can decode).
+    #[arg(value_name = "IN")]
+    pub input: String,
+
+    /// Video to write; the extension picks the format.
+    #[arg(value_name = "OUT")]
+    pub output: String,
+
+    /// Grey-scale mask PNG, white = full effect (SAVE SNAPSHOT writes one as `NAME-mask.png`).
+    #[arg(long, value_name = "PNG", required_unless_present = "auto", conflicts_with = "auto")]
+    pub mask: Option<String>,
+
+    /// Portrait mode: learn the room from the first frames and apply the effect to it.
+    #[arg(long)]
+    pub auto: bool,
+
+    #[command(flatten)]
+    pub effect: EffectArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    /// Images, folders (every image in them) or patterns like `shots/*.png` (`*` and `?`).
+    #[arg(value_name = "IMAGES", required = true)]
+    pub inputs: Vec<String>,
+
+    /// Folder for the results (created if missing); file names are kept.
+    #[arg(long, value_name = "DIR")]
+    pub out: PathBuf,
+
+    /// Grey-scale mask PNG, white = full effect (stretched to each image's size).
+    #[arg(long, value_name = "PNG")]
+    pub mask: String,
+
+    #[command(flatten)]
+    pub effect: EffectArgs,
+}
+
+#[cfg(feature = "camera")]
+#[derive(Args, Debug)]
+pub struct StreamArgs {
+    /// Where to write: a video file (the extension picks the format) or any ffmpeg
+    /// output, e.g. `udp://127.0.0.1:1234` with --format mpegts.
+    #[arg(value_name = "OUT")]
+    pub output: String,
+
+    /// ffmpeg output format, for OUTs without a telling extension (e.g. mpegts, flv).
+    #[arg(long, value_name = "FMT")]
+    pub format: Option<String>,
+
+    /// Grey-scale mask PNG, white = full effect (SAVE SNAPSHOT writes one as `NAME-mask.png`).
+    #[arg(long, value_name = "PNG", required_unless_present = "auto", conflicts_with = "auto")]
+    pub mask: Option<String>,
+
+    /// Portrait mode: learn the empty room from the first frames (step out), then apply the effect to it.
+    #[arg(long)]
+    pub auto: bool,
+
+    /// Stop after this many frames [default: until Ctrl+C].
+    #[arg(long, value_name = "N")]
+    pub frames: Option<usize>,
+
+    #[command(flatten)]
+    pub effect: EffectArgs,
+}
+
+/// What `process`, `batch` and `stream` do under the mask.
+#[derive(Args, Debug)]
+pub struct EffectArgs {
+    /// Apply the effect outside the mask instead (with --auto: to the moving subject).
+    #[arg(long)]
+    pub invert: bool,
+
+    /// Brush effect to apply: blur, fill, smooth, pixelate, gray, sharpen, emboss, edges.
+    #[arg(long, value_name = "NAME", default_value = "blur")]
+    pub effect: String,
+
+    /// Filters before the effect, all on, e.g. `levels,grade` (see filters.rs) [default: none].
+    #[arg(long, value_name = "LIST", default_value = "")]
+    pub filters: String,
+
+    /// Filters after the effect, e.g. `vignette` [default: none].
+    #[arg(long, value_name = "LIST", default_value = "")]
+    pub post: String,
+}
+
+/// "1280x720" → (1280, 720).
+fn parse_resolution(s: &str) -> Result<(u32, u32), String> {
+    let (w, h) = s.split_once(['x', 'X']).ok_or_else(|| format!("'{s}' is not WxH, e.g. 640x480"))?;
+    let dim = |v: &str| v.trim().parse::<u32>().ok().filter(|&n| (16..=8192).contains(&n));
+    match (dim(w), dim(h)) {
+        (Some(w), Some(h)) => Ok((w, h)),
+        _ => Err(format!("'{s}': width and height must be 16..8192")),
+    }
+}
+
+/// Everything the app needs from the command line, with the specs already parsed.
+pub struct Options {
+    pub command: Option<Command>,
+    #[cfg(feature = "window")]
+    pub image_path: Option<String>,
+    #[cfg(feature = "window")]
+    pub source: Source,
+    #[cfg(any(feature = "window", feature = "camera"))]
+    pub camera: u32,
+    #[cfg(any(feature = "window", feature = "camera"))]
+    pub resolution: (u32, u32),
+    #[cfg(any(feature = "window", feature = "camera"))]
+    pub fps: u32,
+    pub blur_radius: usize,
+    #[cfg(feature = "window")]
+    pub brush_size: i32,
+    #[cfg(feature = "window")]
+    pub brush_hardness: f32,
+    #[cfg(feature = "window")]
+    pub fx_on: bool,
+    #[cfg(feature = "window")]
+    pub half_res: bool,
+    #[cfg(feature = "window")]
+    pub touch: bool,
+    #[cfg(feature = "window")]
+    pub bench_frames: Option<usize>,
+    #[cfg(feature = "window")]
+    pub debug_windows: Vec<DebugView>,
+    #[cfg(feature = "window")]
+    pub osc_port: Option<u16>,
+    /// `[[pipeline.output]]` from config.toml, plus the one `--obs` adds.
+    #[cfg(feature = "window")]
+    pub outputs: Vec<OutputConfig>,
+    #[cfg(feature = "winit")]
+    pub backend: Backend,
+    #[cfg(feature = "script")]
+    pub script: Option<PathBuf>,
+    #[cfg(feature = "window")]
+    pub hud: HudLayout,
+    #[cfg(feature = "window")]
+    pub cursor: CursorStyles,
+    #[cfg(feature = "window")]
+    pub theme: Theme,
+    #[cfg(feature = "window")]
+    pub filters: String,      // checked filter lists (built per run, see filters.rs)
+    #[cfg(feature = "window")]
+    pub post_filters: String,
+    #[cfg(feature = "window")]
+    pub logo_path: Option<String>,
+    #[cfg(feature = "window")]
+    pub snapshot_path: String,
+    #[cfg(feature = "window")]
+    pub keymap: KeyMap,
+    pub log: Option<String>,
+    pub log_file: Option<PathBuf>,
+    #[cfg(feature = "window")]
+    pub metrics: Option<PathBuf>,
+    /// The --session file as read (mask, background, effect and toggles are applied
+    /// by the processing stage), and where F6 writes.
+    #[cfg(feature = "window")]
+    pub session: Option<Session>,
+    #[cfg(feature = "window")]
+    pub session_path: PathBuf,
+    /// The settings file as read (written back on exit with what changed), and where.
+    #[cfg(feature = "window")]
+    pub config: Config,
+    #[cfg(feature = "window")]
+    pub config_path: Option<PathBuf>,
+}
+
+/// The configured outputs; `--obs` adds a clean feed to shared memory for this run only.
+#[cfg(feature = "window")]
+fn outputs(config: &Config, obs: Option<Option<PathBuf>>) -> Vec<OutputConfig> {
+    let mut outputs = config.pipeline.output.clone();
+    if let Some(file) = obs {
+        outputs.push(OutputConfig {
+            to: file.unwrap_or_else(crate::shm::default_path).display().to_string(),
+            format: Some(crate::shm::OUTPUT_FORMAT.into()),
+            tap: crate::config::Tap::Clean,
+        });
+    }
+    outputs
+}
+
+impl Options {
+    /// Parse the process arguments (clap prints --help / usage errors and exits itself).
+    pub fn from_args() -> Result<Self, Error> {
+        Self::from_cli(Cli::parse())
+    }
+
+    fn from_cli(cli: Cli) -> Result<Self, Error> {
+        // --bench ignores the settings file, so timings compare across machines.
+        let config_path = if cli.bench.is_some() { None } else { cli.config.or_else(Config::default_path) };
+        let config = match &config_path {
+            Some(path) => Config::load(path)?.unwrap_or_default(),
+            None => Config::default(),
+        };
+        let session_path = cli.session.as_deref().map(Session::resolve).transpose()?;
+        let session = session_path.as_deref().map(Session::load).transpose()?;
+        // What a flag leaves out comes from the session if there is one, else config.toml.
+        #[cfg(any(feature = "window", feature = "camera"))]
+        let camera = session.as_ref().map_or(&config.camera, |s| &s.camera);
+        let brush = session.as_ref().map_or(&config.brush, |s| &s.brush);
+        #[cfg(any(feature = "window", feature = "camera"))]
+        let resolution = match cli.resolution {
+            Some(res) => res,
+            None => parse_resolution(&camera.resolution).map_err(|e| Error::Config(format!("[camera] resolution {e}")))?,
+        };
+        // The window's filter lists, effect order and colours, checked before anything opens.
+        #[cfg(feature = "window")]
+        let (filters, post_filters, theme) = {
+            let filters = cli.filters.unwrap_or_else(|| session.as_ref().map_or(&config.filters.live, |s| &s.filters.live).clone());
+            let post_filters = cli.post.unwrap_or_else(|| session.as_ref().map_or(&config.filters.post, |s| &s.filters.post).clone());
+            crate::filters::build(&filters, "--filters")?;
+            crate::filters::build(&post_filters, "--post")?;
+            crate::effects::EffectRegistry::new(None).with_order(&config.pipeline.effects)?;
+            let mut theme = match cli.theme.as_deref().or(config.display.theme.as_deref()) {
+                Some(name) => Theme::from_arg(name)?,
+                None => Theme::default(),
+            };
+            config.fx.apply_colors(&mut theme)?;          // [fx] colours win over any theme
+            (filters, post_filters, theme)
+        };
+        Ok(Self {
+            command: cli.command,
+            #[cfg(feature = "window")]
+            image_path: cli.image,
+            #[cfg(feature = "window")]
+            source: cli.source.or(config.pipeline.source).unwrap_or(Source::Camera),
+            #[cfg(any(feature = "window", feature = "camera"))]
+            camera: cli.camera.unwrap_or(camera.index),
+            #[cfg(any(feature = "window", feature = "camera"))]
+            resolution,
+            #[cfg(any(feature = "window", feature = "camera"))]
+            fps: cli.fps.unwrap_or(camera.fps).clamp(1, 240),
+            blur_radius: cli.blur_radius.unwrap_or(brush.blur_radius).clamp(1, 96) as usize,
+            #[cfg(feature = "window")]
+            brush_size: cli.brush_size.unwrap_or(brush.size).clamp(crate::config::BRUSH_SIZE_MIN, crate::config::BRUSH_SIZE_MAX) as i32,
+            #[cfg(feature = "window")]
+            brush_hardness: brush.hardness.clamp(0.0, 1.0),
+            #[cfg(feature = "window")]
+            fx_on: !cli.no_fx && brush.fx,
+            #[cfg(feature = "window")]
+            half_res: cli.half || config.display.half,
+            #[cfg(feature = "window")]
+            touch: cli.touch || config.display.touch,
+            #[cfg(feature = "window")]
+            bench_frames: cli.bench.map(|n| n.unwrap_or(DEFAULT_BENCH_FRAMES)),
+            #[cfg(feature = "window")]
+            debug_windows: DebugView::parse_list(cli.debug_windows.as_deref().unwrap_or("")).map_err(Error::Config)?,
+            #[cfg(feature = "window")]
+            osc_port: cli.osc,
+            #[cfg(feature = "window")]
+            outputs: outputs(&config, cli.obs),
+            #[cfg(feature = "winit")]
+            backend: cli.backend,
+            #[cfg(feature = "script")]
+            script: cli.script,
+            // --hud colours default to the theme's text colour, so it is parsed after --theme.
+            #[cfg(feature = "window")]
+            hud: HudLayout::parse(cli.hud.as_deref().or(config.display.hud.as_deref()).unwrap_or(""), theme.text)?,
+            #[cfg(feature = "window")]
+            cursor: match cli.cursor.as_deref().or(config.display.cursor.as_deref()) {
+                Some(spec) => CursorStyles::parse(spec)?,
+                None => CursorStyles::default(),
+            },
+            #[cfg(feature = "window")]
+            theme,
+            #[cfg(feature = "window")]
+            filters,
+            #[cfg(feature = "window")]
+            post_filters,
+            #[cfg(feature = "window")]
+            logo_path: cli.logo.or_else(|| config.display.logo.clone()),
+            #[cfg(feature = "window")]
+            snapshot_path: cli.snapshot.unwrap_or_else(|| config.display.snapshot.clone()),
+            #[cfg(feature = "window")]
+            keymap: KeyMap::from_overrides(&config.keys).map_err(Error::Config)?,
+            log: cli.log,
+            log_file: cli.log_file,
+            #[cfg(feature = "window")]
+            metrics: cli.metrics,
+            #[cfg(feature = "window")]
+            session_path: match (&session_path, cli.session.as_deref()) {
+                (Some(path), Some(arg)) if arg != session::AUTOSAVE => path.clone(),
+                _ => PathBuf::from(session::DEFAULT_PATH),     // F6 never overwrites the autosave slot
+            },
+            #[cfg(feature = "window")]
+            session,
+            #[cfg(feature = "window")]
+            config,
+            #[cfg(feature = "window")]
+            config_path,
+        })
+    }
+}
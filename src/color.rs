@@ -0,0 +1,295 @@
+// Colour correction applied to the live frame before anything else.
+// Visual expectation: washed-out webcam images get proper blacks and whites, and
+// white balance, brightness/contrast/saturation can be dialled in live; both the live feed and the
+// painted effect see the same corrected frame, so they always match.
+
+use crate::gamma::GammaLut;
+use crate::types::FrameBuffer;
+
+/// Fraction of pixels allowed to clip at each end when stretching (ignores specks/hot pixels).
+const CLIP_FRACTION: f32 = 0.005;
+/// How fast the stretch follows the scene (0..1 per frame); low = no flicker.
+const SMOOTHING: f32 = 0.1;
+
+#[inline]
+fn luma(p: u32) -> usize {
+    // Integer Rec.601 weights (77+150+29 = 256); good enough for a histogram.
+    let r = (p >> 16) & 0xFF;
+    let g = (p >> 8) & 0xFF;
+    let b = p & 0xFF;
+    ((77 * r + 150 * g + 29 * b) >> 8) as usize
+}
+
+/// Auto-levels: stretch the luminance range of each frame to full 0..255.
+/// Keeps smoothed black/white points between frames so exposure changes don't pump.
+pub struct AutoLevels {
+    lo: f32, // current black point (0..255)
+    hi: f32, // current white point (0..255)
+}
+
+impl AutoLevels {
+    /// Start with an identity stretch. Visual: no change until the first frame is analysed.
+    pub fn new() -> Self {
+        Self { lo: 0.0, hi: 255.0 }
+    }
+
+    /// Measure the frame's luminance histogram and stretch it in place.
+    /// What you SEE: darker blacks, brighter whites, colours keep their hue.
+    pub fn apply(&mut self, fb: &mut FrameBuffer) {
+        if fb.pixels.is_empty() { return; }
+
+        // 1) Luminance histogram of this frame.
+        let mut hist = [0u32; 256];
+        for &p in &fb.pixels { hist[luma(p)] += 1; }
+
+        // 2) Find black/white points that clip CLIP_FRACTION of pixels at each end.
+        let clip = (fb.pixels.len() as f32 * CLIP_FRACTION) as u32;
+        let mut acc = 0u32;
+        let mut lo = 0usize;
+        for (v, &n) in hist.iter().enumerate() {
+            acc += n;
+            if acc > clip { lo = v; break; }
+        }
+        acc = 0;
+        let mut hi = 255usize;
+        for (v, &n) in hist.iter().enumerate().rev() {
+            acc += n;
+            if acc > clip { hi = v; break; }
+        }
+
+        // 3) Ease towards the new points (prevents flicker when someone walks by).
+        self.lo += (lo as f32 - self.lo) * SMOOTHING;
+        self.hi += (hi as f32 - self.hi) * SMOOTHING;
+        let range = (self.hi - self.lo).max(16.0); // never blow up nearly-flat frames
+
+        // 4) Build a 256-entry table once, then remap every channel through it.
+        let mut table = [0u8; 256];
+        for (v, t) in table.iter_mut().enumerate() {
+            *t = ((v as f32 - self.lo) * 255.0 / range).round().clamp(0.0, 255.0) as u8;
+        }
+        for p in &mut fb.pixels {
+            let r = table[((*p >> 16) & 0xFF) as usize] as u32;
+            let g = table[((*p >> 8) & 0xFF) as usize] as u32;
+            let b = table[(*p & 0xFF) as usize] as u32;
+            *p = (r << 16) | (g << 8) | b;
+        }
+    }
+}
+
+/// Which colour control the -/= keys currently change.
+#[cfg(feature = "window")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Adjust {
+    Brightness,
+    Contrast,
+    Saturation,
+    Temperature,
+    Tint,
+}
+
+#[cfg(feature = "window")]
+impl Adjust {
+    /// Cycle to the next control (V key). Visual: HUD highlights the new one.
+    pub fn next(self) -> Self {
+        match self {
+            Adjust::Brightness => Adjust::Contrast,
+            Adjust::Contrast => Adjust::Saturation,
+            Adjust::Saturation => Adjust::Temperature,
+            Adjust::Temperature => Adjust::Tint,
+            Adjust::Tint => Adjust::Brightness,
+        }
+    }
+}
+
+/// Brightness / contrast / saturation, applied in linear light.
+/// Visual: brighter/darker, punchier/flatter, more/less colourful — without the
+/// hue shifts and crushed shadows that doing this on sRGB values produces.
+#[derive(Clone, Copy, Debug)]
+pub struct ColorAdjust {
+    pub brightness: f32, // exposure in stops: 0 = unchanged, +1 = twice the light
+    pub contrast: f32,   // 1 = unchanged; >1 pushes away from mid-grey
+    pub saturation: f32, // 1 = unchanged, 0 = greyscale
+}
+
+/// Linear-light mid-grey (18% reflectance); contrast pivots around it.
+const MID_GREY: f32 = 0.18;
+
+impl ColorAdjust {
+    /// Neutral settings. Visual: image unchanged.
+    pub fn new() -> Self {
+        Self { brightness: 0.0, contrast: 1.0, saturation: 1.0 }
+    }
+
+    /// True when applying would change nothing (lets the main loop skip the pass).
+    pub fn is_neutral(&self) -> bool {
+        self.brightness == 0.0 && self.contrast == 1.0 && self.saturation == 1.0
+    }
+
+    /// Nudge one control by `steps` key presses (negative = down), clamped to sane ranges.
+    #[cfg(feature = "window")]
+    pub fn nudge(&mut self, which: Adjust, steps: i32) {
+        let s = steps as f32;
+        match which {
+            Adjust::Brightness => self.brightness = (self.brightness + 0.1 * s).clamp(-3.0, 3.0),
+            Adjust::Contrast => self.contrast = (self.contrast + 0.05 * s).clamp(0.2, 3.0),
+            Adjust::Saturation => self.saturation = (self.saturation + 0.1 * s).clamp(0.0, 3.0),
+            Adjust::Temperature | Adjust::Tint => {} // handled by WhiteBalance
+        }
+    }
+
+    /// Apply to every pixel of `fb` in place (sRGB → linear via LUT, adjust, back).
+    /// What you SEE: the whole frame re-graded; painted effects built afterwards match it.
+    pub fn apply(&self, fb: &mut FrameBuffer, lut: &GammaLut) {
+        if self.is_neutral() { return; }
+        let gain = self.brightness.exp2();
+
+        // Exposure + contrast only depend on the 8-bit input, so precompute them once:
+        // a power curve pivoting at MID_GREY keeps blacks black and never makes negative light.
+        let mut curve = [0.0f32; 256];
+        for (v, c) in curve.iter_mut().enumerate() {
+            let l = lut.srgb_u8_to_linear(v as u8) * gain;
+            *c = MID_GREY * (l / MID_GREY).powf(self.contrast);
+        }
+
+        for p in &mut fb.pixels {
+            let r = curve[((*p >> 16) & 0xFF) as usize];
+            let g = curve[((*p >> 8) & 0xFF) as usize];
+            let b = curve[(*p & 0xFF) as usize];
+
+            // Saturation: mix with linear luminance (Rec.709 weights).
+            let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            let s = self.saturation;
+            let (r, g, b) = (y + (r - y) * s, y + (g - y) * s, y + (b - y) * s);
+
+            let r = lut.linear_to_srgb_u8(r) as u32;
+            let g = lut.linear_to_srgb_u8(g) as u32;
+            let b = lut.linear_to_srgb_u8(b) as u32;
+            *p = (r << 16) | (g << 8) | b;
+        }
+    }
+}
+
+/* -------------------- white balance (per-channel gains) -------------------- */
+
+/// sRGB-encoded 0..1 → linear light (exact formula; used off the per-pixel path).
+#[inline]
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// Approximate colour of a black-body light at `kelvin`, as linear RGB.
+/// (Tanner Helland's curve fit; plenty accurate for a webcam white-balance slider.)
+fn kelvin_to_linear_rgb(kelvin: f32) -> [f32; 3] {
+    let t = kelvin / 100.0;
+    let r = if t <= 66.0 { 255.0 } else { 329.698_73 * (t - 60.0).powf(-0.133_204_76) };
+    let g = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_17 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+    [r, g, b].map(|c| srgb_to_linear((c / 255.0).clamp(0.0, 1.0)).max(1e-4))
+}
+
+/// Scale gains so overall brightness stays put (only the colour cast changes).
+fn normalize_gains(g: [f32; 3]) -> [f32; 3] {
+    let y = 0.2126 * g[0] + 0.7152 * g[1] + 0.0722 * g[2];
+    if y > 0.0 { g.map(|c| c / y) } else { [1.0; 3] }
+}
+
+/// Reference white: at this temperature the correction is neutral.
+const NEUTRAL_KELVIN: f32 = 6500.0;
+
+/// Colour temperature + tint correction, or gains picked with the eyedropper.
+/// Visual: orange tungsten or green fluorescent casts disappear; whites look white.
+#[derive(Clone, Copy, Debug)]
+pub struct WhiteBalance {
+    pub kelvin: f32,              // light the scene was lit by (lower = warmer light → cooler fix)
+    pub tint: f32,                // -1..1: positive removes green (adds magenta)
+    picked: Option<[f32; 3]>,     // eyedropper result overrides kelvin/tint until nudged
+}
+
+impl WhiteBalance {
+    /// Neutral (6500 K, no tint). Visual: image unchanged.
+    pub fn new() -> Self {
+        Self { kelvin: NEUTRAL_KELVIN, tint: 0.0, picked: None }
+    }
+
+    pub fn is_neutral(&self) -> bool {
+        self.picked.is_none() && self.kelvin == NEUTRAL_KELVIN && self.tint == 0.0
+    }
+
+    /// True when the gains came from the eyedropper (HUD shows "PICKED").
+    #[cfg(feature = "window")]
+    pub fn is_picked(&self) -> bool {
+        self.picked.is_some()
+    }
+
+    /// Nudge temperature (100 K per step) or tint (0.05 per step); drops any eyedropper pick.
+    #[cfg(feature = "window")]
+    pub fn nudge(&mut self, which: Adjust, steps: i32) {
+        if steps == 0 { return; }
+        let s = steps as f32;
+        match which {
+            Adjust::Temperature => self.kelvin = (self.kelvin + 100.0 * s).clamp(2000.0, 12000.0),
+            Adjust::Tint => self.tint = (self.tint + 0.05 * s).clamp(-1.0, 1.0),
+            _ => return,
+        }
+        self.picked = None;
+    }
+
+    /// Eyedropper: make the 5x5 area around (x,y) neutral grey.
+    /// What you SEE: the clicked spot (e.g. a white wall) turns colourless; the cast is gone.
+    pub fn pick_neutral(&mut self, fb: &FrameBuffer, x: i32, y: i32, lut: &GammaLut) {
+        let mut sum = [0.0f32; 3];
+        let mut n = 0.0f32;
+        for sy in (y - 2)..=(y + 2) {
+            for sx in (x - 2)..=(x + 2) {
+                if sx < 0 || sy < 0 || sx >= fb.width as i32 || sy >= fb.height as i32 { continue; }
+                let p = fb.pixels[sy as usize * fb.width + sx as usize];
+                sum[0] += lut.srgb_u8_to_linear(((p >> 16) & 0xFF) as u8);
+                sum[1] += lut.srgb_u8_to_linear(((p >> 8) & 0xFF) as u8);
+                sum[2] += lut.srgb_u8_to_linear((p & 0xFF) as u8);
+                n += 1.0;
+            }
+        }
+        if n == 0.0 || sum.iter().any(|&c| c <= 0.0) { return; } // black/clipped sample: nothing to learn
+        self.picked = Some(normalize_gains(sum.map(|c| n / c)));
+    }
+
+    /// Current per-channel linear gains.
+    fn gains(&self) -> [f32; 3] {
+        if let Some(g) = self.picked { return g; }
+        let light = kelvin_to_linear_rgb(self.kelvin);
+        let white = kelvin_to_linear_rgb(NEUTRAL_KELVIN);
+        let mut g = [white[0] / light[0], white[1] / light[1], white[2] / light[2]];
+        g[1] *= (-0.5 * self.tint).exp2();
+        normalize_gains(g)
+    }
+
+    /// Apply the gains in linear light. Each channel only depends on its own 8-bit value,
+    /// so three 256-entry tables make this a pure lookup per pixel.
+    /// What you SEE: the colour cast shifts; painted effects built afterwards match.
+    pub fn apply(&self, fb: &mut FrameBuffer, lut: &GammaLut) {
+        if self.is_neutral() { return; }
+        let gains = self.gains();
+        let mut tables = [[0u8; 256]; 3];
+        for (table, gain) in tables.iter_mut().zip(gains) {
+            for (v, t) in table.iter_mut().enumerate() {
+                *t = lut.linear_to_srgb_u8(lut.srgb_u8_to_linear(v as u8) * gain);
+            }
+        }
+        for p in &mut fb.pixels {
+            let r = tables[0][((*p >> 16) & 0xFF) as usize] as u32;
+            let g = tables[1][((*p >> 8) & 0xFF) as usize] as u32;
+            let b = tables[2][(*p & 0xFF) as usize] as u32;
+            *p = (r << 16) | (g << 8) | b;
+        }
+    }
+}
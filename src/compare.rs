@@ -0,0 +1,85 @@
+// Before/after split view.
+// Visual expectation: press F3 and a vertical white divider appears. Left of it you see
+// the camera exactly as it arrived (no colour correction, blur or effects); right of it
+// the finished picture. Drag the divider with the left mouse button to sweep the
+// comparison across the frame — much easier to judge strength than flicking B.
+//
+// The split lives in frame coordinates, so it stays on the same spot of the picture
+// while zoomed (see view.rs).
+
+use crate::draw::{draw_text_5x7, fill_rect, fill_rect_blend};
+use crate::types::FrameBuffer;
+use crate::view::View;
+
+/// How close (window pixels) a press has to be to grab the divider.
+const GRAB_DISTANCE: i32 = 8;
+
+pub struct Compare {
+    pub on: bool,
+    split: f32,   // frame x of the divider
+    dragging: bool,
+    was_down: bool,   // left button last frame (grab only on a fresh press)
+    raw: FrameBuffer, // this frame as the camera delivered it
+}
+
+impl Compare {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            on: false,
+            split: width as f32 * 0.5,
+            dragging: false,
+            was_down: false,
+            raw: FrameBuffer { width, height, pixels: vec![0u32; width * height] },
+        }
+    }
+
+    /// Remember the untouched camera frame (call before any correction).
+    pub fn capture_raw(&mut self, live: &FrameBuffer) {
+        if self.raw.pixels.len() == live.pixels.len() {
+            self.raw.pixels.copy_from_slice(&live.pixels);
+        }
+    }
+
+    /// Let the divider take the left button: grab it when pressed near it, follow while held.
+    /// Returns true while the mouse belongs to the divider (the caller should not paint).
+    pub fn handle_mouse(&mut self, mouse: Option<(usize, usize)>, left_down: bool, view: &View) -> bool {
+        let pressed = left_down && !self.was_down;
+        self.was_down = left_down;
+        let Some(m) = mouse.filter(|_| left_down) else {
+            self.dragging = false;
+            return false;
+        };
+        if pressed {
+            let (dx, _) = view.to_window((self.split as i32, 0));
+            self.dragging = (m.0 as i32 - dx).abs() <= GRAB_DISTANCE;
+        }
+        if self.dragging {
+            self.split = view.to_frame(m).0.clamp(0.0, self.raw.width as f32);
+        }
+        self.dragging
+    }
+
+    /// Put the raw frame back left of the divider (`screen` is the finished picture).
+    pub fn apply(&self, screen: &mut FrameBuffer) {
+        let w = screen.width;
+        if self.raw.width != w || self.raw.height != screen.height {
+            return;
+        }
+        let cut = (self.split as usize).min(w);
+        for (dst, src) in screen.pixels.chunks_exact_mut(w).zip(self.raw.pixels.chunks_exact(w)) {
+            dst[..cut].copy_from_slice(&src[..cut]);
+        }
+    }
+
+    /// Divider line, grab handle and BEFORE / AFTER labels in `color`, at window size.
+    pub fn draw(&self, fb: &mut FrameBuffer, view: &View, scale: i32, color: u32) {
+        let (x, _) = view.to_window((self.split as i32, 0));
+        let h = fb.height as i32;
+        fill_rect(fb, x - scale / 2 - 1, 0, scale + 1, h, color);       // visual: divider (white by default)
+        let handle_h = 24 * scale;
+        fill_rect_blend(fb, x - 4 * scale, (h - handle_h) / 2, 8 * scale, handle_h, color, 200);
+        let label_y = h - 16 * scale;
+        draw_text_5x7(fb, x - 42 * scale, label_y, "BEFORE", color, scale);
+        draw_text_5x7(fb, x + 6 * scale, label_y, "AFTER", color, scale);
+    }
+}
@@ -0,0 +1,358 @@
+// Settings file: the preferences that should survive a restart.
+// Visual expectation: change the blur radius or brush size, quit, start again — the
+// brush is how you left it. The file lives in the usual place for the platform
+// (~/.config/magic-eraser/config.toml on Linux, ~/Library/Application Support/... on
+// macOS, %APPDATA%\magic-eraser\... on Windows; `--config FILE` picks another) and is
+// plain TOML, so camera and display defaults can be edited by hand:
+//
+//     [camera]
+//     index = 1
+//     resolution = "1280x720"
+//     fps = 60
+//
+//     [brush]
+//     blur_radius = 12
+//     size = 30
+//     hardness = 0.5
+//     fx = true
+//
+//     [display]
+//     theme = "light"
+//
+//     [fx]                 # sparkles, lightning and smoke (see fx.rs); the panel sets the first two
+//     sparkles_per_dab = 6 # at a brisk stroke; fewer when slow, more when fast
+//     bolt_chance = 0.01   # per dab, 0..1
+//     sparkle_life = [0.2, 0.5]
+//     sparkle_speed = [20.0, 60.0]
+//     smoke_per_dab = 0    # no smoke trail
+//     sparkle_color = "80c0ff"  # instead of the theme's
+//
+//     [filters]            # order of the whole-frame steps (see filters.rs)
+//     live = "levels,grade,median"
+//
+//     [keys]               # move keys by binding name (see keys.rs)
+//     show_blur = "J"
+//
+//     [pipeline]           # source → [filters] live → effects → [filters] post → outputs
+//     source = "camera"
+//     effects = ["blur", "pixelate", "fill"]   # Tab order; leave one out and it's gone
+//
+//     [[pipeline.output]]  # extra copies of the picture (see outputs.rs)
+//     to = "/dev/video10"
+//     format = "v4l2"
+//     tap = "clean"        # live, clean (no HUD or FX) or screen
+//
+// Command-line flags win over the file for that run but are not saved; what changes
+// while the app runs (brush, blur, snapshot name) is written back on exit. The first
+// run writes the file with every default, as a template.
+//
+// Saving the file (or the theme file it names) while the app runs applies it within
+// a second: brush and blur, FX and [fx], theme, HUD layout, cursor styles and keys. Camera
+// settings, [filters], [pipeline] and --half need a restart.
+
+use crate::cli::Source;
+#[cfg(feature = "window")]
+use crate::cursor::CursorStyles;
+use crate::error::Error;
+use crate::filters;
+use crate::fx::FxParams;
+#[cfg(feature = "window")]
+use crate::hud::HudLayout;
+#[cfg(feature = "window")]
+use crate::keys::KeyMap;
+#[cfg(feature = "window")]
+use crate::theme::{parse_hex_color, Theme};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "window")]
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often `ConfigWatch` looks at the files' modification times.
+#[cfg(feature = "window")]
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct Config {
+    pub camera: CameraConfig,
+    pub brush: BrushConfig,
+    pub display: DisplayConfig,
+    pub fx: FxConfig,
+    pub filters: FiltersConfig,
+    pub keys: BTreeMap<String, String>, // binding name → key name
+    pub pipeline: PipelineConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct CameraConfig {
+    pub index: u32,
+    pub resolution: String, // "WxH"
+    pub fps: u32,
+}
+
+/// Brush radius limits in window pixels, for flags, config.toml and every live control.
+#[cfg(feature = "window")]
+pub const BRUSH_SIZE_MIN: u32 = 4;
+#[cfg(feature = "window")]
+pub const BRUSH_SIZE_MAX: u32 = 160;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct BrushConfig {
+    pub blur_radius: u32,
+    pub size: u32,     // radius in window pixels
+    pub hardness: f32, // 0 = very soft edge, 1 = crisp
+    pub fx: bool,      // sparkles and lightning while painting
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub half: bool,
+    pub touch: bool, // touch-screen gestures (see touch.rs)
+    pub theme: Option<String>,
+    pub hud: Option<String>,
+    pub cursor: Option<String>,
+    pub logo: Option<String>,
+    pub snapshot: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct FxConfig {
+    pub sparkles_per_dab: u32,
+    pub max_sparkles: u32,             // alive at once; lower on slow machines
+    pub sparkle_life: [f32; 2],        // seconds, shortest..longest
+    pub sparkle_speed: [f32; 2],       // px/sec, slowest..fastest
+    pub bolt_chance: f32,              // per dab, 0..1
+    pub bolt_life: f32,                // seconds
+    pub smoke_per_dab: f32,            // average puffs per dab; 0 = no smoke trail
+    pub sparkle_color: Option<String>, // RRGGBB; default: the theme's
+    pub bolt_color: Option<String>,
+    pub smoke_color: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct FiltersConfig {
+    pub live: String, // comma-separated, in order
+    pub post: String,
+}
+
+/// How the stages are put together; empty = the built-in pipeline (window only).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct PipelineConfig {
+    pub source: Option<Source>,   // --source wins
+    pub effects: Vec<String>,     // brush effects in Tab order; empty = all of them
+    pub output: Vec<OutputConfig>,
+}
+
+/// One `[[pipeline.output]]`: where a copy of the picture goes.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct OutputConfig {
+    pub to: String,             // file, URL or device ffmpeg can write
+    #[serde(default)]
+    pub format: Option<String>, // ffmpeg's -f (None: from the name)
+    #[serde(default)]
+    pub tap: Tap,
+}
+
+/// Where in the pipeline an output takes its picture from.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Tap {
+    /// The camera frame after the live filters, before any brush effect.
+    Live,
+    /// The finished picture (effects and post filters) without FX, guides or HUD.
+    #[default]
+    Clean,
+    /// Exactly what the window shows.
+    Screen,
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Self { index: 0, resolution: "640x480".into(), fps: 30 }
+    }
+}
+
+impl Default for BrushConfig {
+    fn default() -> Self {
+        Self { blur_radius: 8, size: 22, hardness: 0.5, fx: true }
+    }
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self { half: false, touch: false, theme: None, hud: None, cursor: None, logo: None, snapshot: "snapshot.png".into() }
+    }
+}
+
+impl Default for FxConfig {
+    fn default() -> Self {
+        FxConfig::from_params(&FxParams::default())
+    }
+}
+
+impl FxConfig {
+    pub fn from_params(p: &FxParams) -> Self {
+        Self {
+            sparkles_per_dab: p.per_dab as u32,
+            max_sparkles: p.max_particles as u32,
+            sparkle_life: [p.life.0, p.life.1],
+            sparkle_speed: [p.speed.0, p.speed.1],
+            bolt_chance: p.bolt_chance,
+            bolt_life: p.bolt_life,
+            smoke_per_dab: p.smoke_per_dab,
+            sparkle_color: None,
+            bolt_color: None,
+            smoke_color: None,
+        }
+    }
+
+    /// The numbers for `Fx::set_params` (which clamps them).
+    #[cfg(feature = "window")]
+    pub fn params(&self) -> FxParams {
+        FxParams {
+            per_dab: self.sparkles_per_dab as usize,
+            max_particles: self.max_sparkles as usize,
+            life: (self.sparkle_life[0], self.sparkle_life[1]),
+            speed: (self.sparkle_speed[0], self.sparkle_speed[1]),
+            bolt_chance: self.bolt_chance,
+            bolt_life: self.bolt_life,
+            smoke_per_dab: self.smoke_per_dab,
+        }
+    }
+
+    /// Put `sparkle_color` / `bolt_color` / `smoke_color` (if set) into `theme`.
+    #[cfg(feature = "window")]
+    pub fn apply_colors(&self, theme: &mut Theme) -> Result<(), Error> {
+        let colors = [
+            (&self.sparkle_color, &mut theme.sparkle, "sparkle_color"),
+            (&self.bolt_color, &mut theme.bolt, "bolt_color"),
+            (&self.smoke_color, &mut theme.smoke, "smoke_color"),
+        ];
+        for (value, slot, key) in colors {
+            if let Some(value) = value {
+                *slot = parse_hex_color(value).ok_or_else(|| Error::Config(format!("[fx] {key}: '{value}' is not RRGGBB")))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for FiltersConfig {
+    fn default() -> Self {
+        Self { live: filters::DEFAULT_LIVE.into(), post: filters::DEFAULT_POST.into() }
+    }
+}
+
+impl Config {
+    /// `<platform config dir>/magic-eraser/config.toml`, if the platform has one.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("magic-eraser").join("config.toml"))
+    }
+
+    /// Read `path`; a missing file is not an error (`None`: defaults, write on exit).
+    pub fn load(path: &Path) -> Result<Option<Config>, Error> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::Io(format!("Read {}", path.display()), e)),
+        };
+        toml::from_str(&text).map(Some).map_err(|e| Error::Config(format!("{}: {e}", path.display())))
+    }
+
+    /// Write to `path`, creating its folder if needed.
+    #[cfg(feature = "window")]
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let text = toml::to_string_pretty(self).map_err(|e| Error::Encode(format!("Settings: {e}")))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| Error::Io(format!("Create {}", dir.display()), e))?;
+        }
+        std::fs::write(path, text).map_err(|e| Error::Io(format!("Write {}", path.display()), e))
+    }
+
+    /// The settings that can change while running, parsed (for a reload).
+    #[cfg(feature = "window")]
+    pub fn live_settings(&self) -> Result<LiveSettings, Error> {
+        let mut theme = match self.display.theme.as_deref() {
+            Some(name) => Theme::from_arg(name)?,
+            None => Theme::default(),
+        };
+        self.fx.apply_colors(&mut theme)?;
+        Ok(LiveSettings {
+            hud: HudLayout::parse(self.display.hud.as_deref().unwrap_or(""), theme.text)?,
+            cursor: match self.display.cursor.as_deref() {
+                Some(spec) => CursorStyles::parse(spec)?,
+                None => CursorStyles::default(),
+            },
+            keymap: KeyMap::from_overrides(&self.keys).map_err(Error::Config)?,
+            theme,
+        })
+    }
+
+    /// The theme file `[display] theme` names, if it is a file rather than a built-in.
+    #[cfg(feature = "window")]
+    pub fn theme_file(&self) -> Option<PathBuf> {
+        self.display.theme.as_deref().filter(|t| Theme::builtin(t).is_none()).map(PathBuf::from)
+    }
+}
+
+/// `[display]` and `[keys]` parsed, ready to swap in.
+#[cfg(feature = "window")]
+pub struct LiveSettings {
+    pub theme: Theme,
+    pub hud: HudLayout,
+    pub cursor: CursorStyles,
+    pub keymap: KeyMap,
+}
+
+/// Notices edits to the settings file and the theme file it names.
+#[cfg(feature = "window")]
+pub struct ConfigWatch {
+    path: PathBuf,
+    theme: Option<PathBuf>,
+    stamps: (Option<SystemTime>, Option<SystemTime>), // last seen: config, theme
+    next_check: Instant,
+}
+
+#[cfg(feature = "window")]
+impl ConfigWatch {
+    pub fn new(path: PathBuf, config: &Config) -> Self {
+        let theme = config.theme_file();
+        let stamps = (modified(&path), theme.as_deref().and_then(modified));
+        Self { path, theme, stamps, next_check: Instant::now() + WATCH_INTERVAL }
+    }
+
+    /// At most once per WATCH_INTERVAL: the re-read settings if either file changed.
+    pub fn poll(&mut self) -> Option<Result<Config, Error>> {
+        let now = Instant::now();
+        if now < self.next_check {
+            return None;
+        }
+        self.next_check = now + WATCH_INTERVAL;
+        let stamps = (modified(&self.path), self.theme.as_deref().and_then(modified));
+        if stamps == self.stamps {
+            return None;
+        }
+        self.stamps = stamps;
+        let config = match Config::load(&self.path) {
+            Ok(config) => config.unwrap_or_default(),
+            Err(e) => return Some(Err(e)),
+        };
+        if config.theme_file() != self.theme {
+            self.theme = config.theme_file();
+            self.stamps.1 = self.theme.as_deref().and_then(modified);
+        }
+        Some(Ok(config))
+    }
+}
+
+#[cfg(feature = "window")]
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,108 @@
+// Camera byte formats → our 0x00RRGGBB pixels, as fast as the CPU allows.
+// Visual expectation: identical frames to before; they just arrive sooner (frame
+// ingest was ~2 ms per 640×480 frame and double-digit ms at 1080p).
+//
+// Both converters split the frame into row bands for rayon. Inside a band:
+// • RGB24: 4 pixels per SSSE3 shuffle on x86_64 (checked at runtime), 16 per
+//   NEON load/store on aarch64, plain loop elsewhere.
+// • YUYV: straight from the camera's raw bytes (skips nokhwa's RGB decode and our
+//   scratch copy); integer BT.601 maths on 2 pixels at a time.
+
+use rayon::prelude::*;
+
+/// Pixels per parallel work item (about 32 rows at 640 px).
+const BAND_PIXELS: usize = 640 * 32;
+
+/// Pack RGB24 bytes (R,G,B,R,G,B,…) into 0x00RRGGBB pixels; `dst.len()` pixels are written.
+pub fn pack_rgb24(src: &[u8], dst: &mut [u32]) {
+    let n = dst.len().min(src.len() / 3);
+    dst[..n]
+        .par_chunks_mut(BAND_PIXELS)
+        .zip(src[..n * 3].par_chunks(BAND_PIXELS * 3))
+        .for_each(|(d, s)| pack_rgb24_band(s, d));
+}
+
+/// One band: the widest path this CPU has, then a scalar tail.
+fn pack_rgb24_band(src: &[u8], dst: &mut [u32]) {
+    #[allow(unused_mut)]
+    let mut done = 0;
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("ssse3") {
+        // SAFETY: the CPU supports SSSE3 (checked just above).
+        done = unsafe { pack_rgb24_ssse3(src, dst) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        done = pack_rgb24_neon(src, dst);
+    }
+
+    for (p, c) in dst[done..].iter_mut().zip(src[done * 3..].chunks_exact(3)) {
+        *p = ((c[0] as u32) << 16) | ((c[1] as u32) << 8) | c[2] as u32;
+    }
+}
+
+/// 4 pixels per step: load 16 bytes (12 used), shuffle to B,G,R,0 per pixel.
+/// Returns how many pixels were written (the caller finishes the tail).
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn pack_rgb24_ssse3(src: &[u8], dst: &mut [u32]) -> usize {
+    use std::arch::x86_64::*;
+    // Little-endian u32 0x00RRGGBB is the bytes B,G,R,0; -1 (0x80) writes a zero.
+    let shuffle = _mm_setr_epi8(2, 1, 0, -1, 5, 4, 3, -1, 8, 7, 6, -1, 11, 10, 9, -1);
+    let mut i = 0;
+    // Each load reads 16 bytes, so stop while 16 are still inside `src`.
+    while i + 4 <= dst.len() && i * 3 + 16 <= src.len() {
+        // SAFETY: bounds checked by the loop condition; unaligned loads/stores.
+        unsafe {
+            let v = _mm_loadu_si128(src.as_ptr().add(i * 3) as *const __m128i);
+            _mm_storeu_si128(dst.as_mut_ptr().add(i) as *mut __m128i, _mm_shuffle_epi8(v, shuffle));
+        }
+        i += 4;
+    }
+    i
+}
+
+/// 16 pixels per step: de-interleaving load of R,G,B planes, interleaving store of B,G,R,0.
+#[cfg(target_arch = "aarch64")]
+fn pack_rgb24_neon(src: &[u8], dst: &mut [u32]) -> usize {
+    use std::arch::aarch64::*;
+    let mut i = 0;
+    while i + 16 <= dst.len() && (i + 16) * 3 <= src.len() {
+        // SAFETY: NEON is part of the aarch64 baseline; bounds checked by the loop condition.
+        unsafe {
+            let rgb = vld3q_u8(src.as_ptr().add(i * 3));
+            let out = uint8x16x4_t(rgb.2, rgb.1, rgb.0, vdupq_n_u8(0));
+            vst4q_u8(dst.as_mut_ptr().add(i) as *mut u8, out);
+        }
+        i += 16;
+    }
+    i
+}
+
+/// Convert packed YUYV 4:2:2 (Y0,U,Y1,V per pixel pair) into 0x00RRGGBB pixels.
+/// Same integer BT.601 formula nokhwa uses, so colours match its RGB decode.
+pub fn yuyv_to_xrgb(src: &[u8], dst: &mut [u32]) {
+    let n = (dst.len().min(src.len() / 2)) & !1; // whole pixel pairs only
+    dst[..n]
+        .par_chunks_mut(BAND_PIXELS)
+        .zip(src[..n * 2].par_chunks(BAND_PIXELS * 2))
+        .for_each(|(d, s)| {
+            for (pair, q) in d.chunks_exact_mut(2).zip(s.chunks_exact(4)) {
+                let (u, v) = (q[1] as i32 - 128, q[3] as i32 - 128);
+                // Chroma terms are shared by both pixels of the pair.
+                let (rv, guv, bu) = (409 * v + 128, -100 * u - 208 * v + 128, 516 * u + 128);
+                pair[0] = yuv_pixel(q[0], rv, guv, bu);
+                pair[1] = yuv_pixel(q[2], rv, guv, bu);
+            }
+        });
+}
+
+#[inline]
+fn yuv_pixel(y: u8, rv: i32, guv: i32, bu: i32) -> u32 {
+    let c = 298 * (y as i32 - 16);
+    let r = ((c + rv) >> 8).clamp(0, 255) as u32;
+    let g = ((c + guv) >> 8).clamp(0, 255) as u32;
+    let b = ((c + bu) >> 8).clamp(0, 255) as u32;
+    (r << 16) | (g << 8) | b
+}
@@ -0,0 +1,114 @@
+// Cursor styles: what we draw at the mouse, separately for painting and for picking.
+// Visual expectation: F2 cycles the cursor of the current mode — crosshair + brush
+// ring, crosshair only, ring only, or nothing (the OS arrow is hidden inside the
+// window too, for clean recordings). Painting and picking (W / X armed) remember
+// their own style, e.g. `--cursor paint=hidden,pick=crosshair` records without a cursor
+// but still shows a precise + while sampling a colour.
+
+use crate::draw::{draw_circle, draw_crosshair};
+use crate::error::Error;
+use crate::types::FrameBuffer;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CursorStyle {
+    Both,      // crosshair + brush-size ring
+    Crosshair, // + only
+    Ring,      // brush-size ring only
+    Hidden,    // nothing, OS cursor hidden as well
+}
+
+impl CursorStyle {
+    pub fn next(self) -> Self {
+        match self {
+            CursorStyle::Both => CursorStyle::Crosshair,
+            CursorStyle::Crosshair => CursorStyle::Ring,
+            CursorStyle::Ring => CursorStyle::Hidden,
+            CursorStyle::Hidden => CursorStyle::Both,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CursorStyle::Both => "BOTH",
+            CursorStyle::Crosshair => "CROSSHAIR",
+            CursorStyle::Ring => "RING",
+            CursorStyle::Hidden => "HIDDEN",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "both" => Some(CursorStyle::Both),
+            "crosshair" => Some(CursorStyle::Crosshair),
+            "ring" => Some(CursorStyle::Ring),
+            "hidden" | "none" => Some(CursorStyle::Hidden),
+            _ => None,
+        }
+    }
+
+    /// Draw this style at (mx, my) in `color` (the theme accent); `ring` is the brush
+    /// radius in window pixels.
+    pub fn draw(self, fb: &mut FrameBuffer, mx: i32, my: i32, ring: f32, color: u32) {
+        if matches!(self, CursorStyle::Both | CursorStyle::Crosshair) {
+            draw_crosshair(fb, mx, my, 12, color);                   // visual: + at cursor
+        }
+        if matches!(self, CursorStyle::Both | CursorStyle::Ring) {
+            draw_circle(fb, mx as f32, my as f32, ring, 1.5, color); // visual: brush-size ring
+        }
+    }
+}
+
+/// Which mode the cursor is in this frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CursorMode {
+    Paint, // the brush is live
+    Pick,  // an eyedropper (white balance or colour picker) waits for a click
+}
+
+/// One style per mode.
+#[derive(Clone, Copy, Debug)]
+pub struct CursorStyles {
+    pub paint: CursorStyle,
+    pub pick: CursorStyle,
+}
+
+impl Default for CursorStyles {
+    fn default() -> Self {
+        Self { paint: CursorStyle::Both, pick: CursorStyle::Crosshair }
+    }
+}
+
+impl CursorStyles {
+    /// Parse a `--cursor` spec: comma-separated `mode=style`, modes paint / pick,
+    /// styles both / crosshair / ring / hidden.
+    pub fn parse(spec: &str) -> Result<Self, Error> {
+        let mut styles = Self::default();
+        for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (mode, style) = item
+                .split_once('=')
+                .ok_or_else(|| Error::Config(format!("--cursor: expected mode=style, got '{item}'")))?;
+            let style = CursorStyle::parse(style)
+                .ok_or_else(|| Error::Config(format!("--cursor: unknown style '{style}'")))?;
+            match mode {
+                "paint" => styles.paint = style,
+                "pick" => styles.pick = style,
+                _ => return Err(Error::Config(format!("--cursor: unknown mode '{mode}'"))),
+            }
+        }
+        Ok(styles)
+    }
+
+    pub fn get(&self, mode: CursorMode) -> CursorStyle {
+        match mode {
+            CursorMode::Paint => self.paint,
+            CursorMode::Pick => self.pick,
+        }
+    }
+
+    pub fn get_mut(&mut self, mode: CursorMode) -> &mut CursorStyle {
+        match mode {
+            CursorMode::Paint => &mut self.paint,
+            CursorMode::Pick => &mut self.pick,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+// Debug windows (`--debug-windows mask,sink`): intermediate pictures of the pipeline,
+// each in a window of its own next to the main one.
+// Visual expectation: besides the usual window, one small window per name, updated
+// every frame while you paint:
+//
+//   mask         the mask the effect goes through (white = full effect; portrait included)
+//   background   the background model R captured (black until then)
+//   live         the camera after the live filters, before the brush
+//   blur         the blurred frame BLUR and HEAL read
+//   sink         what the active effect puts under the brush
+//   diff         heat map of what changed since the previous frame
+//
+// The same pictures the B / D / mask-thumbnail toggles put in the main window, but side
+// by side and without taking the main view away. Closing a debug window only closes
+// that one. They are always plain minifb windows (whatever `--backend` says), ignore
+// input, and a window that can't keep up skips frames instead of slowing the app.
+
+use crate::error::Error;
+use crate::types::FrameBuffer;
+use crate::window::{Drawer, WindowBackend};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+
+/// An intermediate picture a debug window can show.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DebugView {
+    Mask,
+    Background,
+    Live,
+    Blur,
+    Sink,
+    Diff,
+}
+
+impl DebugView {
+    pub const ALL: [DebugView; 6] =
+        [DebugView::Mask, DebugView::Background, DebugView::Live, DebugView::Blur, DebugView::Sink, DebugView::Diff];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DebugView::Mask => "mask",
+            DebugView::Background => "background",
+            DebugView::Live => "live",
+            DebugView::Blur => "blur",
+            DebugView::Sink => "sink",
+            DebugView::Diff => "diff",
+        }
+    }
+
+    /// A comma-separated list of names, e.g. `mask,sink` (each at most once).
+    pub fn parse_list(spec: &str) -> Result<Vec<DebugView>, String> {
+        let mut views = Vec::new();
+        for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let Some(view) = Self::ALL.into_iter().find(|v| v.name().eq_ignore_ascii_case(name)) else {
+                let names: Vec<&str> = Self::ALL.iter().map(|v| v.name()).collect();
+                return Err(format!("--debug-windows: unknown view '{name}' (one of: {})", names.join(", ")));
+            };
+            if views.contains(&view) {
+                return Err(format!("--debug-windows: '{name}' is listed twice"));
+            }
+            views.push(view);
+        }
+        Ok(views)
+    }
+}
+
+/// Processing side: fill and send the pictures the debug windows show.
+pub struct DebugLink {
+    views: Vec<DebugView>,
+    frames: SyncSender<(DebugView, FrameBuffer)>,
+    recycled: Receiver<FrameBuffer>,
+    spare: Option<FrameBuffer>, // filled but not taken last time
+}
+
+/// Window side: one window per view, opened with its first picture.
+pub struct DebugWindows {
+    windows: Vec<(DebugView, Option<Drawer>)>,
+    frames: Receiver<(DebugView, FrameBuffer)>,
+    recycle: Sender<FrameBuffer>,
+}
+
+/// The two ends for `views` (empty: nothing is ever sent).
+pub fn debug_channel(views: &[DebugView]) -> (DebugLink, DebugWindows) {
+    let (frame_tx, frame_rx) = mpsc::sync_channel(views.len().max(1));
+    let (recycle_tx, recycle_rx) = mpsc::channel();
+    (
+        DebugLink { views: views.to_vec(), frames: frame_tx, recycled: recycle_rx, spare: None },
+        DebugWindows { windows: views.iter().map(|&v| (v, None)).collect(), frames: frame_rx, recycle: recycle_tx },
+    )
+}
+
+impl DebugLink {
+    /// True if a debug window shows `view` (so the stage behind it has to run).
+    pub fn wants(&self, view: DebugView) -> bool {
+        self.views.contains(&view)
+    }
+
+    /// If `view` is shown: let `fill` draw it into a `width`×`height` buffer and send it.
+    /// Dropped if the windows are still behind.
+    pub fn send(&mut self, view: DebugView, width: usize, height: usize,
+                fill: impl FnOnce(&mut FrameBuffer) -> Result<(), Error>) -> Result<(), Error> {
+        if !self.wants(view) {
+            return Ok(());
+        }
+        let mut buf = self.spare.take().or_else(|| self.recycled.try_recv().ok()).unwrap_or_else(|| FrameBuffer {
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+        });
+        buf.width = width;
+        buf.height = height;
+        buf.pixels.resize(width * height, 0);
+        fill(&mut buf)?;
+        match self.frames.try_send((view, buf)) {
+            Ok(()) => {}
+            Err(TrySendError::Full((_, buf))) => self.spare = Some(buf), // visual: that window skips a frame
+            Err(TrySendError::Disconnected(_)) => self.views.clear(),   // window side gone
+        }
+        Ok(())
+    }
+}
+
+impl DebugWindows {
+    /// Show whatever arrived and keep the other windows alive; call once per main-window frame.
+    pub fn update(&mut self) -> Result<(), Error> {
+        while let Ok((view, frame)) = self.frames.try_recv() {
+            if let Some((_, slot)) = self.windows.iter_mut().find(|(v, _)| *v == view) {
+                match slot {
+                    Some(window) if window.is_open() => window.present(&frame)?,
+                    Some(_) => {}                                              // closed by the user
+                    None => {
+                        let title = format!("Magic Eraser — {}", view.name());
+                        let mut window = Drawer::new(&title, frame.width, frame.height)?;
+                        window.present(&frame)?;                               // visual: the window opens
+                        *slot = Some(window);
+                    }
+                }
+            }
+            let _ = self.recycle.send(frame);
+        }
+        for window in self.windows.iter_mut().filter_map(|(_, w)| w.as_mut()) {
+            if window.is_open() {
+                window.update();
+            }
+        }
+        Ok(())
+    }
+}
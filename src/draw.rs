@@ -1,276 +1,606 @@
-// Window + software drawing utilities.
+// Software drawing utilities, straight into a `FrameBuffer` (no window needed).
 // Visual effects provided here:
-// 1) A window that shows your live camera image.
-// 2) A crosshair that follows your mouse.
-// 3) A tiny 5x7 bitmap font to render HUD text on top of the video.
+// 1) A crosshair that follows your mouse.
+// 2) A tiny 5x7 bitmap font to render HUD text on top of the video (ASCII and Latin-1;
+//    anything else shows as a small box).
+// 3) Shapes for overlays: anti-aliased lines and arcs, plain / see-through / rounded
+//    boxes, outlines and soft drop shadows.
+// The window that shows the result is the binary's (window.rs).
 
-use crate::error::Error;
+use crate::gamma::GammaLut;
 use crate::types::FrameBuffer;
-use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
 
-pub struct Drawer {
-    window: Window, // the on-screen window you see
-}
+/* ---------- Software drawing: pixels, crosshair, tiny bitmap font ---------- */
 
-impl Drawer {
-    /// Create a window sized to the camera feed.
-    /// Visual: a new empty window appears with your chosen title.
-    pub fn new(title: &str, width: usize, height: usize) -> Result<Self, Error> {
-        let window = Window::new(title, width, height, WindowOptions::default())
-            .map_err(|e| Error::WindowInit(e.to_string()))?;
-        Ok(Self { window })
+/// Put a pixel on the framebuffer if (x,y) is inside bounds.
+/// Visual: the exact pixel at (x,y) changes color.
+#[inline]
+fn put_pixel(fb: &mut FrameBuffer, x: i32, y: i32, color: u32) {
+    if x < 0 || y < 0 {
+        return;
+    }
+    let (x, y) = (x as usize, y as usize);
+    if x >= fb.width || y >= fb.height {
+        return;
     }
+    let idx = y * fb.width + x;
+    fb.pixels[idx] = color;
+}
 
-    /// Push the pixels for this frame to the screen.
-    /// Visual: the window immediately displays the new image (live video).
-    pub fn present(&mut self, framebuffer: &FrameBuffer) -> Result<(), Error> {
-        self.window
-            .update_with_buffer(&framebuffer.pixels, framebuffer.width, framebuffer.height)
-            .map_err(|e| Error::WindowUpdate(e.to_string()))?;
-        Ok(())
+/// Mix `color` over the pixel at (x,y) with opacity `alpha` (0 = untouched, 255 = opaque),
+/// in sRGB. Off-frame coordinates are ignored.
+/// Visual: a partly see-through dot; used for the soft edges of anti-aliased shapes.
+#[inline]
+pub fn put_pixel_blend(fb: &mut FrameBuffer, x: i32, y: i32, color: u32, alpha: u8) {
+    if alpha == 0 || x < 0 || y < 0 || x as usize >= fb.width || y as usize >= fb.height {
+        return;
     }
+    let idx = y as usize * fb.width + x as usize;
+    let (a, dst) = (alpha as u32, fb.pixels[idx]);
+    let mix = |shift: u32| {
+        let (c, d) = ((color >> shift) & 0xFF, (dst >> shift) & 0xFF);
+        ((c * a + d * (255 - a) + 127) / 255) << shift
+    };
+    fb.pixels[idx] = mix(16) | mix(8) | mix(0);
+}
 
-    /// Returns false when the user closes the window (so we can stop the loop).
-    pub fn is_open(&self) -> bool {
-        self.window.is_open()
+/// Fractional part that is always in 0..1 (also for negative values).
+#[inline]
+fn fpart(v: f32) -> f32 {
+    v - v.floor()
+}
+
+/// Draw an anti-aliased 1-pixel line from (x0,y0) to (x1,y1) (Xiaolin Wu's algorithm).
+/// Pixel centres sit on whole coordinates; sub-pixel endpoints are fine.
+/// Visual: a smooth line — each step lights the two nearest pixels in proportion to
+/// how close the ideal line passes, so diagonals don't show staircase jaggies.
+pub fn draw_line_aa(fb: &mut FrameBuffer, x0: f32, y0: f32, x1: f32, y1: f32, color: u32) {
+    // Walk along the longer axis; `steep` lines are drawn transposed.
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    let (mut x0, mut y0, mut x1, mut y1) = if steep { (y0, x0, y1, x1) } else { (x0, y0, x1, y1) };
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
     }
+    let dx = x1 - x0;
+    let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+    let mut plot = |x: i32, y: i32, coverage: f32| {
+        let alpha = (coverage.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+        if steep { put_pixel_blend(fb, y, x, color, alpha) } else { put_pixel_blend(fb, x, y, color, alpha) }
+    };
 
-    /// True while ESC is held down (we’ll exit when this is pressed).
-    pub fn esc_pressed(&self) -> bool {
-        self.window.is_key_down(Key::Escape)
+    // End points: weighted by how much of their pixel the line actually covers.
+    let xend = x0.round();
+    let yend = y0 + gradient * (xend - x0);
+    let xgap = 1.0 - fpart(x0 + 0.5);
+    let (xa, ya) = (xend as i32, yend.floor() as i32);
+    plot(xa, ya, (1.0 - fpart(yend)) * xgap);
+    plot(xa, ya + 1, fpart(yend) * xgap);
+    let mut intery = yend + gradient;
+
+    let xend = x1.round();
+    let yend = y1 + gradient * (xend - x1);
+    let xgap = fpart(x1 + 0.5);
+    let (xb, yb) = (xend as i32, yend.floor() as i32);
+    if xb != xa {
+        plot(xb, yb, (1.0 - fpart(yend)) * xgap);
+        plot(xb, yb + 1, fpart(yend) * xgap);
     }
 
-    /// Current mouse position in window pixel coordinates (clamped to the window).
-    /// Visual: when this returns Some(x,y), your crosshair will be drawn at that pixel.
-    pub fn mouse_pos(&self) -> Option<(usize, usize)> {
-        self.window
-            .get_mouse_pos(MouseMode::Clamp)
-            .map(|(x, y)| (x.max(0.0) as usize, y.max(0.0) as usize))
+    // Everything in between: two pixels per step, split by the line's sub-pixel position.
+    for x in xa + 1..xb {
+        let y = intery.floor() as i32;
+        plot(x, y, 1.0 - fpart(intery));
+        plot(x, y + 1, fpart(intery));
+        intery += gradient;
     }
+}
 
-    // when this returns true, we will *start* capturing the BG.
-    pub fn r_pressed_once(&self) -> bool {
-        self.window.is_key_pressed(Key::R, KeyRepeat::No)
+/// Opacity for a pixel whose centre is `d` pixels outside a shape's edge (negative = inside):
+/// fully opaque a half pixel inside, fading to nothing a half pixel outside.
+#[inline]
+fn edge_alpha(d: f32) -> u8 {
+    ((0.5 - d).clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+/// Pixel box around a shape (centre ± extent), clipped to the frame.
+fn clip_box(fb: &FrameBuffer, x0: f32, y0: f32, x1: f32, y1: f32) -> (i32, i32, i32, i32) {
+    (
+        (x0.floor() as i32).max(0),
+        (y0.floor() as i32).max(0),
+        (x1.ceil() as i32).min(fb.width as i32 - 1),
+        (y1.ceil() as i32).min(fb.height as i32 - 1),
+    )
+}
+
+/// Draw a line `width` pixels thick from (x0,y0) to (x1,y1), with round caps and soft edges.
+/// Visual: a smooth stroke like a marker; width 1 looks like `draw_line_aa`.
+pub fn draw_line_thick(fb: &mut FrameBuffer, x0: f32, y0: f32, x1: f32, y1: f32, width: f32, color: u32) {
+    let hw = width.max(1.0) * 0.5;
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let len2 = dx * dx + dy * dy;
+    let (bx0, by0, bx1, by1) = clip_box(fb, x0.min(x1) - hw - 1.0, y0.min(y1) - hw - 1.0,
+                                        x0.max(x1) + hw + 1.0, y0.max(y1) + hw + 1.0);
+    for y in by0..=by1 {
+        for x in bx0..=bx1 {
+            // Distance from the pixel centre to the closest point of the segment.
+            let (px, py) = (x as f32 - x0, y as f32 - y0);
+            let t = if len2 > 0.0 { ((px * dx + py * dy) / len2).clamp(0.0, 1.0) } else { 0.0 };
+            let (ex, ey) = (px - t * dx, py - t * dy);
+            put_pixel_blend(fb, x, y, color, edge_alpha((ex * ex + ey * ey).sqrt() - hw));
+        }
     }
+}
 
-    // we flip a boolean in main to switch displayed buffer.
-    pub fn b_pressed_once(&self) -> bool {
-        self.window.is_key_pressed(Key::B, KeyRepeat::No)
+/// Draw part of an ellipse outline centred at (cx,cy) with radii `rx`/`ry`, `width` pixels
+/// thick, from angle `start` to `end` (radians, clockwise on screen from 3 o'clock).
+/// A span of 2π or more draws the whole outline.
+/// Visual: a smooth curved stroke, e.g. a progress ring or a selection preview.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_arc(fb: &mut FrameBuffer, cx: f32, cy: f32, rx: f32, ry: f32, start: f32, end: f32, width: f32, color: u32) {
+    use std::f32::consts::TAU;
+    let (rx, ry) = (rx.max(0.5), ry.max(0.5));
+    let hw = width.max(1.0) * 0.5;
+    let span = end - start;
+    let (bx0, by0, bx1, by1) = clip_box(fb, cx - rx - hw - 1.0, cy - ry - hw - 1.0,
+                                        cx + rx + hw + 1.0, cy + ry + hw + 1.0);
+    for y in by0..=by1 {
+        for x in bx0..=bx1 {
+            let (px, py) = (x as f32 - cx, y as f32 - cy);
+            if span < TAU && (py.atan2(px) - start).rem_euclid(TAU) > span {
+                continue; // outside the arc's angle range
+            }
+            // Distance to the outline, first-order approximation: f / |∇f| for
+            // f = (x/rx)² + (y/ry)² − 1 (exact for circles, close for ellipses).
+            let f = (px / rx).powi(2) + (py / ry).powi(2) - 1.0;
+            let (gx, gy) = (2.0 * px / (rx * rx), 2.0 * py / (ry * ry));
+            let g = (gx * gx + gy * gy).sqrt().max(1e-6);
+            put_pixel_blend(fb, x, y, color, edge_alpha((f / g).abs() - hw));
+        }
     }
+}
 
-    // Step 4 helpers
-    /// Visual: when true, dabbing occurs at the mouse position (you see erase happening).
-    pub fn left_mouse_down(&self) -> bool {
-        self.window.get_mouse_down(MouseButton::Left)
+/// Draw an ellipse outline (see `draw_arc`).
+/// Visual: a smooth oval ring.
+pub fn draw_ellipse(fb: &mut FrameBuffer, cx: f32, cy: f32, rx: f32, ry: f32, width: f32, color: u32) {
+    draw_arc(fb, cx, cy, rx, ry, 0.0, std::f32::consts::TAU, width, color);
+}
+
+/// Draw a circle outline of radius `r`, `width` pixels thick.
+/// Visual: a smooth ring, e.g. the brush-size ring around the cursor.
+pub fn draw_circle(fb: &mut FrameBuffer, cx: f32, cy: f32, r: f32, width: f32, color: u32) {
+    draw_ellipse(fb, cx, cy, r, r, width, color);
+}
+
+/// Fill an axis-aligned rectangle (clipped to the frame).
+/// Visual: a solid block of `color`, e.g. one bar of the frame-time graph.
+pub fn fill_rect(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, color: u32) {
+    for row in rect_rows(fb, x, y, w, h) {
+        row.fill(color);
     }
+}
 
-    /// Visual: when pressed, the current erase mask is cleared (screen looks un-erased again).
-    pub fn c_pressed_once(&self) -> bool {
-        self.window.is_key_pressed(Key::C, KeyRepeat::No)
+/// Fill a rectangle with `color` at opacity `alpha` (0..255), mixed in sRGB (cheap).
+/// Visual: a see-through panel, e.g. behind the profiler table.
+pub fn fill_rect_blend(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, color: u32, alpha: u8) {
+    let a = alpha as u32;
+    for row in rect_rows(fb, x, y, w, h) {
+        for p in row {
+            let mix = |shift: u32| {
+                let (c, d) = ((color >> shift) & 0xFF, (*p >> shift) & 0xFF);
+                ((c * a + d * (255 - a) + 127) / 255) << shift
+            };
+            *p = mix(16) | mix(8) | mix(0);
+        }
     }
 }
 
-/* ---------- Software drawing: pixels, crosshair, tiny bitmap font ---------- */
+/// Like `fill_rect_blend`, but mixed in linear light through the LUT's blend tables
+/// (same maths as the mask blend): dark panels dim highlights naturally, no muddy greys.
+/// Visual: a smoky, see-through panel, e.g. behind the HUD text.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_rect_blend_linear(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, color: u32, alpha: u8, lut: &GammaLut) {
+    let table = lut.blend_table(alpha);
+    for row in rect_rows(fb, x, y, w, h) {
+        for p in row {
+            let mix = |shift: u32| {
+                table[((((color >> shift) & 0xFF) << 8) | ((*p >> shift) & 0xFF)) as usize] as u32
+            };
+            *p = (mix(16) << 16) | (mix(8) << 8) | mix(0);
+        }
+    }
+}
 
-/// Put a pixel on the framebuffer if (x,y) is inside bounds.
-/// Visual: the exact pixel at (x,y) changes color.
+/// Signed distance from the pixel centre (px,py) to the edge of a rectangle with corner
+/// radius `r`, given its centre and half size (negative = inside).
 #[inline]
-fn put_pixel(fb: &mut FrameBuffer, x: i32, y: i32, color: u32) {
-    if x < 0 || y < 0 {
+fn rounded_rect_distance(px: f32, py: f32, cx: f32, cy: f32, hw: f32, hh: f32, r: f32) -> f32 {
+    let r = r.clamp(0.0, hw.min(hh));
+    let (qx, qy) = ((px - cx).abs() - (hw - r), (py - cy).abs() - (hh - r));
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+    outside + qx.max(qy).min(0.0) - r
+}
+
+/// Cover a rounded rectangle: `coverage(d)` turns the signed edge distance of each pixel
+/// into 0..1, which scales `alpha`. `grow` widens the pixel box (for soft shadows).
+#[allow(clippy::too_many_arguments)]
+fn shade_rounded_rect(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, radius: i32, grow: f32,
+                      color: u32, alpha: u8, coverage: impl Fn(f32) -> f32) {
+    if w <= 0 || h <= 0 || alpha == 0 {
         return;
     }
-    let (x, y) = (x as usize, y as usize);
-    if x >= fb.width || y >= fb.height {
-        return;
+    let (hw, hh) = (w as f32 * 0.5, h as f32 * 0.5);
+    let (cx, cy) = (x as f32 + hw - 0.5, y as f32 + hh - 0.5); // pixel centres sit on integers
+    let (bx0, by0, bx1, by1) = clip_box(fb, x as f32 - grow - 1.0, y as f32 - grow - 1.0,
+                                        (x + w) as f32 + grow, (y + h) as f32 + grow);
+    for py in by0..=by1 {
+        for px in bx0..=bx1 {
+            let d = rounded_rect_distance(px as f32, py as f32, cx, cy, hw, hh, radius as f32);
+            let a = (coverage(d).clamp(0.0, 1.0) * alpha as f32 + 0.5) as u8;
+            put_pixel_blend(fb, px, py, color, a);
+        }
     }
-    let idx = y * fb.width + x;
-    fb.pixels[idx] = color;
 }
 
-/// Draw a thin line between (x0,y0) and (x1,y1) using Bresenham.
-/// Visual: a straight 1-pixel line appears on top of the camera image.
-fn draw_line(fb: &mut FrameBuffer, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
-    let (mut x0, mut y0, x1, y1) = (x0, y0, x1, y1);
-    let dx = (x1 - x0).abs();
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let dy = -(y1 - y0).abs();
-    let sy = if y0 < y1 { 1 } else { -1 };
-    let mut err = dx + dy;
-    loop {
-        put_pixel(fb, x0, y0, color);
-        if x0 == x1 && y0 == y1 {
-            break;
-        }
-        let e2 = 2 * err;
-        if e2 >= dy {
-            err += dy;
-            x0 += sx;
+/// Fill a rectangle with corners rounded to `radius` pixels, at opacity `alpha`.
+/// Visual: a pill / card shape with smooth corners, e.g. a button or a toast.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_rounded_rect(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, radius: i32, color: u32, alpha: u8) {
+    if radius <= 0 && alpha == 255 {
+        return fill_rect(fb, x, y, w, h, color);
+    }
+    shade_rounded_rect(fb, x, y, w, h, radius, 0.0, color, alpha, |d| edge_alpha(d) as f32 / 255.0);
+}
+
+/// Outline a rectangle with a border `thickness` pixels wide, drawn inside (x, y, w, h).
+/// Visual: a crisp frame, e.g. around a detected face.
+pub fn draw_rect(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, thickness: i32, color: u32) {
+    let t = thickness.max(1).min(w / 2).min(h / 2).max(1);
+    fill_rect(fb, x, y, w, t, color);
+    fill_rect(fb, x, y + h - t, w, t, color);
+    fill_rect(fb, x, y + t, t, h - 2 * t, color);
+    fill_rect(fb, x + w - t, y + t, t, h - 2 * t, color);
+}
+
+/// Outline a rounded rectangle with a border `thickness` pixels wide, drawn inside it.
+/// Visual: a smooth frame with round corners, e.g. around a thumbnail.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_rounded_rect(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, radius: i32, thickness: i32, color: u32) {
+    let t = thickness.max(1) as f32;
+    shade_rounded_rect(fb, x, y, w, h, radius, 0.0, color, 255, |d| {
+        // Inside the outer edge but not yet past the inner one (border of width t).
+        (edge_alpha(d) as f32 - edge_alpha(d + t) as f32) / 255.0
+    });
+}
+
+/// Soft shadow under a box: how far it falls, how blurry it is, how dark.
+#[derive(Clone, Copy, Debug)]
+pub struct Shadow {
+    pub dx: i32,
+    pub dy: i32,
+    pub blur: f32, // pixels over which the shadow fades out
+    pub alpha: u8,
+}
+
+impl Shadow {
+    /// A short, soft shadow below and right (scaled with the HUD).
+    pub fn hud(scale: i32) -> Self {
+        Self { dx: 2 * scale, dy: 2 * scale, blur: 3.0 * scale as f32, alpha: 110 }
+    }
+}
+
+/// Draw `shadow` for a box at (x, y, w, h) with corner `radius`; draw the box itself after.
+/// Visual: the box seems to float a little above the picture.
+pub fn draw_drop_shadow(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, radius: i32, shadow: Shadow) {
+    let blur = shadow.blur.max(1.0);
+    shade_rounded_rect(fb, x + shadow.dx, y + shadow.dy, w, h, radius, blur, 0, shadow.alpha, |d| {
+        let t = (0.5 - d / blur).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t) // smoothstep: no visible edge where the fade ends
+    });
+}
+
+/// Draw a progress bar in (x, y, w, h): `progress` 0..1 fills it from the left; `None`
+/// means busy for an unknown time, and a short block sweeps back and forth instead
+/// (`phase` in seconds drives it, so it keeps moving at the same speed at any FPS).
+/// Visual: a rounded see-through track in `track` with a solid `fill` part.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_progress_bar(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32, progress: Option<f32>,
+                         phase: f32, fill: u32, track: u32) {
+    let r = h / 2;
+    fill_rounded_rect(fb, x, y, w, h, r, track, 140);
+    match progress {
+        Some(p) => {
+            let fw = (w as f32 * p.clamp(0.0, 1.0)).round() as i32;
+            if fw > 0 {
+                fill_rounded_rect(fb, x, y, fw.max(h), h, r, fill, 255);
+            }
         }
-        if e2 <= dx {
-            err += dx;
-            y0 += sy;
+        None => {
+            let bw = (w / 4).max(h);
+            let t = (phase * 0.8).fract();
+            let t = if t < 0.5 { t * 2.0 } else { 2.0 - t * 2.0 }; // there and back
+            let bx = x + ((w - bw) as f32 * t).round() as i32;
+            fill_rounded_rect(fb, bx, y, bw, h, r, fill, 255);
         }
     }
 }
 
+/// The rows of a rectangle clipped to the frame, as mutable pixel slices.
+fn rect_rows(fb: &mut FrameBuffer, x: i32, y: i32, w: i32, h: i32) -> impl Iterator<Item = &mut [u32]> {
+    let x0 = x.clamp(0, fb.width as i32) as usize;
+    let x1 = (x + w).clamp(x0 as i32, fb.width as i32) as usize;
+    let y0 = y.clamp(0, fb.height as i32) as usize;
+    let y1 = (y + h).clamp(y0 as i32, fb.height as i32) as usize;
+    let width = fb.width.max(1);
+    fb.pixels[y0 * fb.width..y1 * fb.width]
+        .chunks_mut(width)
+        .map(move |row| &mut row[x0..x1])
+}
+
 /// Draw a small crosshair centered at (cx,cy).
-/// Visual: a “+” shape (with a tiny gap at the center) follows your mouse.
+/// Visual: a “+” shape (with a tiny gap at the center, soft anti-aliased arms) follows your mouse.
 pub fn draw_crosshair(fb: &mut FrameBuffer, cx: i32, cy: i32, size: i32, color: u32) {
+    let (x, y, s) = (cx as f32, cy as f32, size as f32);
     // Horizontal line (left part)
-    draw_line(fb, cx - size, cy, cx - 2, cy, color);
+    draw_line_aa(fb, x - s, y, x - 2.0, y, color);
     // Horizontal line (right part)
-    draw_line(fb, cx + 2, cy, cx + size, cy, color);
+    draw_line_aa(fb, x + 2.0, y, x + s, y, color);
     // Vertical line (top part)
-    draw_line(fb, cx, cy - size, cx, cy - 2, color);
+    draw_line_aa(fb, x, y - s, x, y - 2.0, color);
     // Vertical line (bottom part)
-    draw_line(fb, cx, cy + 2, cx, cy + size, color);
+    draw_line_aa(fb, x, y + 2.0, x, y + s, color);
     // Small center dot to anchor the crosshair visually
     put_pixel(fb, cx, cy, color);
 }
 
-/* ---------- 5x7 bitmap font (ASCII subset we need for "IDLE | FPS: 00.0") ---------- */
+/* ---------- 5x7 bitmap font (printable ASCII, Latin-1, and a box for anything else) ---------- */
 
-/// Return a 5x7 glyph bitmap for a limited character set.
+/// Glyph rows for every printable ASCII character, in code order from ' '.
 /// Each u8 is a row; the low 5 bits are the pixels (bit 4 = leftmost).
-fn glyph5x7(ch: char) -> Option<[u8; 7]> {
-    // Helper macro to define a glyph quickly
-    macro_rules! g {
-        ($a:expr,$b:expr,$c:expr,$d:expr,$e:expr,$f:expr,$g:expr) => {
-            Some([$a, $b, $c, $d, $e, $f, $g])
-        };
-    }
+/// Lowercase letters sit on the same baseline as capitals; g/j/p/q/y use the
+/// bottom row as a short descender.
+const FONT_5X7: [[u8; 7]; 95] = [
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // ' '
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100], // '!'
+    [0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000], // '"'
+    [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010], // '#'
+    [0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100], // '$'
+    [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011], // '%'
+    [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101], // '&'
+    [0b00100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000], // '''
+    [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010], // '('
+    [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000], // ')'
+    [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000], // '*'
+    [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000], // '+'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000], // ','
+    [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000], // '-'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00100, 0b00000], // '.'
+    [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000], // '/'
+    [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110], // '0'
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110], // '1'
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111], // '2'
+    [0b11110, 0b00001, 0b00001, 0b01110, 0b00001, 0b00001, 0b11110], // '3'
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010], // '4'
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110], // '5'
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110], // '6'
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000], // '7'
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110], // '8'
+    [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100], // '9'
+    [0b00000, 0b00100, 0b00000, 0b00000, 0b00100, 0b00000, 0b00000], // ':'
+    [0b00000, 0b00100, 0b00000, 0b00000, 0b00100, 0b00100, 0b01000], // ';'
+    [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010], // '<'
+    [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000], // '='
+    [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000], // '>'
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100], // '?'
+    [0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110], // '@'
+    [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001], // 'A'
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110], // 'B'
+    [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110], // 'C'
+    [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100], // 'D'
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111], // 'E'
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000], // 'F'
+    [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01110], // 'G'
+    [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001], // 'H'
+    [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110], // 'I'
+    [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100], // 'J'
+    [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001], // 'K'
+    [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111], // 'L'
+    [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001], // 'M'
+    [0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001, 0b10001], // 'N'
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110], // 'O'
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000], // 'P'
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101], // 'Q'
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001], // 'R'
+    [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110], // 'S'
+    [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100], // 'T'
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110], // 'U'
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100], // 'V'
+    [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010], // 'W'
+    [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001], // 'X'
+    [0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100], // 'Y'
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111], // 'Z'
+    [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110], // '['
+    [0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000], // '\'
+    [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110], // ']'
+    [0b00100, 0b01010, 0b10001, 0b00000, 0b00000, 0b00000, 0b00000], // '^'
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111], // '_'
+    [0b01000, 0b00100, 0b00010, 0b00000, 0b00000, 0b00000, 0b00000], // '`'
+    [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111], // 'a'
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110], // 'b'
+    [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110], // 'c'
+    [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111], // 'd'
+    [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110], // 'e'
+    [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000], // 'f'
+    [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110], // 'g'
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001], // 'h'
+    [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110], // 'i'
+    [0b00010, 0b00000, 0b00110, 0b00010, 0b00010, 0b10010, 0b01100], // 'j'
+    [0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010], // 'k'
+    [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110], // 'l'
+    [0b00000, 0b00000, 0b11010, 0b10101, 0b10101, 0b10001, 0b10001], // 'm'
+    [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001], // 'n'
+    [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110], // 'o'
+    [0b00000, 0b00000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000], // 'p'
+    [0b00000, 0b00000, 0b01101, 0b10011, 0b01111, 0b00001, 0b00001], // 'q'
+    [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000], // 'r'
+    [0b00000, 0b00000, 0b01110, 0b10000, 0b01110, 0b00001, 0b11110], // 's'
+    [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110], // 't'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101], // 'u'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100], // 'v'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010], // 'w'
+    [0b00000, 0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001], // 'x'
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110], // 'y'
+    [0b00000, 0b00000, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111], // 'z'
+    [0b00010, 0b00100, 0b00100, 0b01000, 0b00100, 0b00100, 0b00010], // '{'
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100], // '|'
+    [0b01000, 0b00100, 0b00100, 0b00010, 0b00100, 0b00100, 0b01000], // '}'
+    [0b00000, 0b00000, 0b01000, 0b10101, 0b00010, 0b00000, 0b00000], // '~'
+];
+
+/// Accent marks for Latin-1 letters, two rows tall (sit above the letter).
+const GRAVE: [u8; 2] = [0b01000, 0b00100];
+const ACUTE: [u8; 2] = [0b00010, 0b00100];
+const CIRCUMFLEX: [u8; 2] = [0b00100, 0b01010];
+const TILDE: [u8; 2] = [0b01101, 0b10110];
+const DIAERESIS: [u8; 2] = [0b01010, 0b00000];
+const RING: [u8; 2] = [0b01110, 0b01010];
 
-    match ch {
-        // Digits 0..9
-        '0' => g!(
-            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110
-        ),
-        '1' => g!(
-            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110
-        ),
-        '2' => g!(
-            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111
-        ),
-        '3' => g!(
-            0b11110, 0b00001, 0b00001, 0b01110, 0b00001, 0b00001, 0b11110
-        ),
-        '4' => g!(
-            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010
-        ),
-        '5' => g!(
-            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110
-        ),
-        '6' => g!(
-            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110
-        ),
-        '7' => g!(
-            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000
-        ),
-        '8' => g!(
-            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110
-        ),
-        '9' => g!(
-            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100
-        ),
-
-        // Uppercase letters we need: I D L E F P S
-        'I' => g!(
-            0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110
-        ),
-        'D' => g!(
-            0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100
-        ),
-        'L' => g!(
-            0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111
-        ),
-        'E' => g!(
-            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111
-        ),
-        'F' => g!(
-            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000
-        ),
-        'P' => g!(
-            0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000
-        ),
-        'S' => g!(
-            0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110
-        ),
-
-        'A' => g!(
-            0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001
-        ),
-        'B' => g!(
-            0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110
-        ),
-        'C' => g!(
-            0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110
-        ),
-        'G' => g!(
-            0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01110
-        ),
-        'N' => g!(
-            0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001, 0b10001
-        ),
-        'R' => g!(
-            0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001
-        ),
-        'T' => g!(
-            0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100
-        ),
-        'U' => g!(
-            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110
-        ),
-        'Y' => g!(
-            0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100
-        ),
-
-        // Punctuation: space, vertical bar, colon, dot
-        ' ' => g!(
-            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000
-        ),
-        '|' => g!(
-            0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100
-        ),
-        ':' => g!(
-            0b00000, 0b00100, 0b00000, 0b00000, 0b00100, 0b00000, 0b00000
-        ),
-        '.' => g!(
-            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00100, 0b00000
-        ),
-
-        _ => None,
+/// Latin-1 letters that are an ASCII letter plus an accent: (letter, base, accent).
+/// Capitals are squeezed to five rows to make room; lowercase letters already have it
+/// (ì–ï use a dotless i).
+const ACCENTED: &[(char, char, [u8; 2])] = &[
+    ('À', 'A', GRAVE), ('Á', 'A', ACUTE), ('Â', 'A', CIRCUMFLEX), ('Ã', 'A', TILDE), ('Ä', 'A', DIAERESIS), ('Å', 'A', RING),
+    ('È', 'E', GRAVE), ('É', 'E', ACUTE), ('Ê', 'E', CIRCUMFLEX), ('Ë', 'E', DIAERESIS),
+    ('Ì', 'I', GRAVE), ('Í', 'I', ACUTE), ('Î', 'I', CIRCUMFLEX), ('Ï', 'I', DIAERESIS),
+    ('Ò', 'O', GRAVE), ('Ó', 'O', ACUTE), ('Ô', 'O', CIRCUMFLEX), ('Õ', 'O', TILDE), ('Ö', 'O', DIAERESIS),
+    ('Ù', 'U', GRAVE), ('Ú', 'U', ACUTE), ('Û', 'U', CIRCUMFLEX), ('Ü', 'U', DIAERESIS),
+    ('Ý', 'Y', ACUTE),
+    ('à', 'a', GRAVE), ('á', 'a', ACUTE), ('â', 'a', CIRCUMFLEX), ('ã', 'a', TILDE), ('ä', 'a', DIAERESIS), ('å', 'a', RING),
+    ('è', 'e', GRAVE), ('é', 'e', ACUTE), ('ê', 'e', CIRCUMFLEX), ('ë', 'e', DIAERESIS),
+    ('ì', 'ı', GRAVE), ('í', 'ı', ACUTE), ('î', 'ı', CIRCUMFLEX), ('ï', 'ı', DIAERESIS),
+    ('ñ', 'n', TILDE),
+    ('ò', 'o', GRAVE), ('ó', 'o', ACUTE), ('ô', 'o', CIRCUMFLEX), ('õ', 'o', TILDE), ('ö', 'o', DIAERESIS),
+    ('ù', 'u', GRAVE), ('ú', 'u', ACUTE), ('û', 'u', CIRCUMFLEX), ('ü', 'u', DIAERESIS),
+    ('ý', 'y', ACUTE), ('ÿ', 'y', DIAERESIS),
+];
+
+/// The rest of Latin-1 (U+00A1..U+00FF), drawn by hand.
+const LATIN1_5X7: &[(char, [u8; 7])] = &[
+    ('¡', [0b00100, 0b00000, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('¢', [0b00100, 0b01110, 0b10100, 0b10100, 0b10101, 0b01110, 0b00100]),
+    ('£', [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01001, 0b10110]),
+    ('¤', [0b00000, 0b10001, 0b01110, 0b01010, 0b01110, 0b10001, 0b00000]),
+    ('¥', [0b10001, 0b01010, 0b00100, 0b11111, 0b00100, 0b11111, 0b00100]),
+    ('¦', [0b00100, 0b00100, 0b00100, 0b00000, 0b00100, 0b00100, 0b00100]),
+    ('§', [0b01110, 0b10000, 0b01110, 0b10001, 0b01110, 0b00001, 0b01110]),
+    ('¨', [0b01010, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('©', [0b01110, 0b10001, 0b10111, 0b11001, 0b10111, 0b10001, 0b01110]),
+    ('ª', [0b01110, 0b00001, 0b01111, 0b10001, 0b01111, 0b00000, 0b11111]),
+    ('«', [0b00000, 0b00101, 0b01010, 0b10100, 0b01010, 0b00101, 0b00000]),
+    ('¬', [0b00000, 0b00000, 0b11111, 0b00001, 0b00001, 0b00000, 0b00000]),
+    ('\u{AD}', [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]), // soft hyphen
+    ('®', [0b01110, 0b11101, 0b11011, 0b11101, 0b11011, 0b10001, 0b01110]),
+    ('¯', [0b11111, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('°', [0b01100, 0b10010, 0b10010, 0b01100, 0b00000, 0b00000, 0b00000]),
+    ('±', [0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000, 0b11111]),
+    ('²', [0b01100, 0b10010, 0b00100, 0b01000, 0b11110, 0b00000, 0b00000]),
+    ('³', [0b11100, 0b00010, 0b01100, 0b00010, 0b11100, 0b00000, 0b00000]),
+    ('´', [0b00010, 0b00100, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('µ', [0b00000, 0b00000, 0b10001, 0b10001, 0b10011, 0b11101, 0b10000]),
+    ('¶', [0b01111, 0b11101, 0b11101, 0b01101, 0b00101, 0b00101, 0b00101]),
+    ('·', [0b00000, 0b00000, 0b00000, 0b00100, 0b00000, 0b00000, 0b00000]),
+    ('¸', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00100, 0b01100]),
+    ('¹', [0b01000, 0b11000, 0b01000, 0b01000, 0b11100, 0b00000, 0b00000]),
+    ('º', [0b01110, 0b10001, 0b10001, 0b01110, 0b00000, 0b11111, 0b00000]),
+    ('»', [0b00000, 0b10100, 0b01010, 0b00101, 0b01010, 0b10100, 0b00000]),
+    ('¼', [0b10000, 0b10001, 0b10010, 0b00101, 0b01011, 0b10111, 0b00001]),
+    ('½', [0b10000, 0b10001, 0b10010, 0b00110, 0b01001, 0b10010, 0b00111]),
+    ('¾', [0b11000, 0b01001, 0b11010, 0b00101, 0b01011, 0b10111, 0b00001]),
+    ('¿', [0b00100, 0b00000, 0b00100, 0b01000, 0b10000, 0b10001, 0b01110]),
+    ('Æ', [0b01111, 0b10100, 0b10100, 0b11111, 0b10100, 0b10100, 0b10111]),
+    ('Ç', [0b01110, 0b10001, 0b10000, 0b10001, 0b01110, 0b00100, 0b01100]),
+    ('Ð', [0b11110, 0b01001, 0b01001, 0b11101, 0b01001, 0b01001, 0b11110]),
+    ('Ñ', [0b01101, 0b10110, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001]),
+    ('×', [0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b00000]),
+    ('Ø', [0b01110, 0b10011, 0b10101, 0b10101, 0b10101, 0b11001, 0b01110]),
+    ('Þ', [0b10000, 0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000]),
+    ('ß', [0b01100, 0b10010, 0b10010, 0b10110, 0b10001, 0b10001, 0b10110]),
+    ('æ', [0b00000, 0b00000, 0b11010, 0b00101, 0b01111, 0b10100, 0b01011]),
+    ('ç', [0b00000, 0b01110, 0b10000, 0b10000, 0b01110, 0b00100, 0b01100]),
+    ('ð', [0b10100, 0b01000, 0b10110, 0b00001, 0b01111, 0b10001, 0b01110]),
+    ('÷', [0b00000, 0b00100, 0b00000, 0b11111, 0b00000, 0b00100, 0b00000]),
+    ('ø', [0b00000, 0b00000, 0b01111, 0b10011, 0b10101, 0b11001, 0b11110]),
+    ('þ', [0b00000, 0b10000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000]),
+    ('ı', [0b00000, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110]), // dotless i (accent base)
+];
+
+/// Drawn for characters the font doesn't have, so missing text shows up as boxes
+/// instead of silently vanishing.
+const MISSING_5X7: [u8; 7] = [0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11111];
+
+/// Return the 5x7 glyph bitmap for `ch`.
+/// Printable ASCII and Latin-1 characters have a glyph; anything else is `MISSING_5X7`.
+fn glyph5x7(ch: char) -> [u8; 7] {
+    let code = ch as u32;
+    if (0x20..=0x7E).contains(&code) {
+        return FONT_5X7[(code - 0x20) as usize];
     }
+    if ch == '\u{A0}' {
+        return FONT_5X7[0]; // no-break space
+    }
+    if let Some(&(_, rows)) = LATIN1_5X7.iter().find(|(c, _)| *c == ch) {
+        return rows;
+    }
+    if let Some(&(_, base, accent)) = ACCENTED.iter().find(|(c, _, _)| *c == ch) {
+        let b = glyph5x7(base);
+        return if base.is_ascii_uppercase() {
+            [accent[0], accent[1], b[0], b[1], b[3], b[5], b[6]] // capital squeezed to 5 rows
+        } else {
+            [accent[0], accent[1], b[2], b[3], b[4], b[5], b[6]]
+        };
+    }
+    MISSING_5X7
 }
 
-/// Draw a single 5x7 character at (x,y).
-/// Visual: a tiny white glyph appears with a 1-pixel black shadow for contrast.
-fn draw_char_5x7(fb: &mut FrameBuffer, x: i32, y: i32, ch: char, color: u32) {
-    if let Some(rows) = glyph5x7(ch) {
-        // Shadow pass: offset by (1,1) in black to improve readability
-        for (ry, rowbits) in rows.iter().enumerate() {
+/// Draw a single 5x7 character at (x,y), each font pixel as a `scale`×`scale` block.
+/// Visual: a tiny white glyph appears with a black shadow (offset by one font pixel) for contrast.
+fn draw_char_5x7(fb: &mut FrameBuffer, x: i32, y: i32, ch: char, color: u32, scale: i32) {
+    let rows = glyph5x7(ch);
+    // Shadow pass, then the glyph itself in the chosen color
+    for (dx, dy, c) in [(scale, scale, 0x00000000), (0, 0, color)] {
+        for (ry, rowbits) in (0..).zip(rows.iter()) {
             for rx in 0..5 {
                 if (rowbits & (1 << (4 - rx))) != 0 {
-                    put_pixel(fb, x + rx as i32 + 1, y + ry as i32 + 1, 0x00000000);
-                }
-            }
-        }
-
-        // Foreground pass: actual glyph in chosen color
-        for (ry, rowbits) in rows.iter().enumerate() {
-            for rx in 0..5 {
-                if (rowbits & (1 << (4 - rx))) != 0 {
-                    put_pixel(fb, x + rx as i32, y + ry as i32, color);
+                    if scale == 1 {
+                        put_pixel(fb, x + rx + dx, y + ry + dy, c);
+                    } else {
+                        fill_rect(fb, x + rx * scale + dx, y + ry * scale + dy, scale, scale, c);
+                    }
                 }
             }
         }
     }
 }
 
-/// Draw a text string using 5x7 glyphs.
-/// Visual: a compact HUD string appears; each glyph is 5x7 with 1-pixel spacing.
-pub fn draw_text_5x7(fb: &mut FrameBuffer, mut x: i32, y: i32, text: &str, color: u32) {
+/// Draw a text string using 5x7 glyphs, enlarged `scale` times (nearest-neighbour; 1 = native).
+/// Visual: a compact HUD string appears; each glyph is 5x7 font pixels with 1 pixel spacing.
+pub fn draw_text_5x7(fb: &mut FrameBuffer, mut x: i32, y: i32, text: &str, color: u32, scale: i32) {
+    let scale = scale.max(1);
     for ch in text.chars() {
-        draw_char_5x7(fb, x, y, ch, color);
-        x += 6; // 5 pixels glyph width + 1 pixel spacing
+        draw_char_5x7(fb, x, y, ch, color, scale);
+        x += 6 * scale; // 5 pixels glyph width + 1 pixel spacing
     }
 }
+
+/// Size in pixels (width, height) of `text` drawn by `draw_text_5x7` at `scale`.
+pub fn text_size_5x7(text: &str, scale: i32) -> (i32, i32) {
+    let scale = scale.max(1);
+    let n = text.chars().count() as i32;
+    ((6 * n - 1).max(0) * scale, 7 * scale)
+}
+
+/// HUD text scale for a window of this height: 1 up to ~720p, 2 at 1080p, 3 at 1440p, 4 at 4K.
+/// Visual: the HUD covers about the same share of the screen on any camera.
+pub fn hud_scale(height: usize) -> i32 {
+    (height as i32 / 400).clamp(1, 4)
+}
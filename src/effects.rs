@@ -0,0 +1,388 @@
+// Brush effects: what shows up under painted pixels.
+// Visual expectation: Tab cycles the brush through BLUR, FILL, CLONE, HEAL,
+// SMOOTH, PIXELATE, GRAY, SHARPEN, EMBOSS, EDGES (and BACKGROUND when an image
+// was given); number keys jump straight to one. The HUD names the active effect.
+//
+// Each effect turns the (colour-corrected) live frame into a "sink" frame; the
+// main loop then blends that sink into the live image through the mask.
+// `[pipeline] effects = [...]` in config.toml picks which ones Tab goes through and in
+// what order (BLUR always stays, last if it isn't listed: the privacy key uses it).
+// Adding an effect = implement `Effect` and register it in `EffectRegistry::new`.
+
+use crate::beauty;
+use crate::error::Error;
+use crate::gamma::GammaLut;
+use crate::heal;
+use crate::inpaint;
+use crate::types::{FrameBuffer, Kernel, Mask};
+use crate::vision::convolve;
+
+/// Everything an effect may read to build this frame's sink.
+pub struct EffectCtx<'a> {
+    pub live: &'a FrameBuffer,                // corrected live frame
+    pub blur: &'a FrameBuffer,                // BLUR(LIVE), already computed this frame
+    pub mask: &'a Mask,                       // where the effect will be shown (painted/portrait)
+    pub lut: &'a GammaLut,
+    pub clone_offset: Option<(i32, i32)>,     // source − destination, once a stroke anchored it
+}
+
+/// One brush effect.
+pub trait Effect {
+    /// Short lowercase name for the HUD.
+    fn name(&self) -> &'static str;
+
+    /// Build the sink for this frame. May return one of the context frames (no copy)
+    /// or a buffer the effect owns.
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error>;
+
+    /// Called when the brush switches to this effect (e.g. to refresh caches right away).
+    fn activate(&mut self) {}
+
+    /// True if the effect copies from the right-click clone source (main draws a marker).
+    fn uses_clone_source(&self) -> bool { false }
+
+    /// True if `render` reads `ctx.blur` (main skips blurring for effects that don't).
+    fn needs_blur(&self) -> bool { false }
+}
+
+/// Make `buf` the same size as `like` (reallocates only when the size changes).
+fn ensure_size(buf: &mut FrameBuffer, like: &FrameBuffer) {
+    if buf.width != like.width || buf.height != like.height {
+        *buf = FrameBuffer { width: like.width, height: like.height, pixels: vec![0u32; like.pixels.len()] };
+    }
+}
+
+fn empty_frame() -> FrameBuffer {
+    FrameBuffer { width: 0, height: 0, pixels: Vec::new() }
+}
+
+/* -------------------- blur (shared sink, no extra work) -------------------- */
+
+/// Visual: painted areas are soft and blurred.
+pub struct BlurEffect;
+
+impl Effect for BlurEffect {
+    fn name(&self) -> &'static str { "blur" }
+    fn needs_blur(&self) -> bool { true }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        Ok(ctx.blur)
+    }
+}
+
+/* -------------------- content-aware fill (low frequency) -------------------- */
+
+/// Visual: painted objects melt into their surroundings (refreshes ~once a second).
+pub struct FillEffect {
+    sink: FrameBuffer,
+    every: u32,       // recompute every N frames (PatchMatch is expensive)
+    since: u32,       // frames since the last fill; 0 = fill now
+    seed: u32,        // fresh random search each refresh
+}
+
+impl FillEffect {
+    pub fn new(every: u32) -> Self {
+        Self { sink: empty_frame(), every: every.max(1), since: 0, seed: 1 }
+    }
+}
+
+impl Effect for FillEffect {
+    fn name(&self) -> &'static str { "fill" }
+    fn activate(&mut self) { self.since = 0; }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        ensure_size(&mut self.sink, ctx.live);
+        if self.since == 0 {
+            inpaint::inpaint_patchmatch(ctx.live, ctx.mask, &mut self.sink, self.seed)?;
+            self.seed = self.seed.wrapping_add(1);
+        }
+        self.since = (self.since + 1) % self.every;
+        Ok(&self.sink)
+    }
+}
+
+/* -------------------- clone / heal -------------------- */
+
+/// Visual: painted areas show texture copied from the clone source.
+pub struct CloneEffect { sink: FrameBuffer }
+
+impl Effect for CloneEffect {
+    fn name(&self) -> &'static str { "clone" }
+    fn uses_clone_source(&self) -> bool { true }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        ensure_size(&mut self.sink, ctx.live);
+        let (dx, dy) = ctx.clone_offset.unwrap_or((0, 0)); // no source yet → looks like live
+        heal::clone_offset(ctx.live, &mut self.sink, dx, dy)?;
+        Ok(&self.sink)
+    }
+}
+
+/// Visual: copied texture, re-lit to match the painted spot (no visible patch).
+pub struct HealEffect { sink: FrameBuffer }
+
+impl Effect for HealEffect {
+    fn name(&self) -> &'static str { "heal" }
+    fn needs_blur(&self) -> bool { true }
+    fn uses_clone_source(&self) -> bool { true }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        ensure_size(&mut self.sink, ctx.live);
+        let (dx, dy) = ctx.clone_offset.unwrap_or((0, 0));
+        heal::heal_offset(ctx.live, ctx.blur, &mut self.sink, dx, dy, ctx.lut)?;
+        Ok(&self.sink)
+    }
+}
+
+/* -------------------- skin smoothing -------------------- */
+
+/// Visual: painted skin softens; edges and non-skin stay sharp.
+pub struct SmoothEffect { sink: FrameBuffer }
+
+impl Effect for SmoothEffect {
+    fn name(&self) -> &'static str { "smooth" }
+    fn needs_blur(&self) -> bool { true }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        ensure_size(&mut self.sink, ctx.live);
+        beauty::skin_smooth(ctx.live, ctx.blur, &mut self.sink)?;
+        Ok(&self.sink)
+    }
+}
+
+/* -------------------- virtual background image -------------------- */
+
+/// Visual: painted areas (or the room, in portrait mode) show a picture.
+pub struct ImageEffect { image: FrameBuffer }
+
+impl ImageEffect {
+    pub fn new(image: FrameBuffer) -> Self { Self { image } }
+}
+
+impl Effect for ImageEffect {
+    fn name(&self) -> &'static str { "background" }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        if self.image.width != ctx.live.width || self.image.height != ctx.live.height {
+            return Err(Error::CameraFrame("background image: size differs from camera".into()));
+        }
+        Ok(&self.image)
+    }
+}
+
+/* -------------------- pixelate -------------------- */
+
+/// Visual: painted areas turn into big flat squares (classic censoring mosaic).
+pub struct PixelateEffect { sink: FrameBuffer, block: usize }
+
+impl PixelateEffect {
+    pub fn new(block: usize) -> Self { Self { sink: empty_frame(), block: block.max(1) } }
+}
+
+impl Effect for PixelateEffect {
+    fn name(&self) -> &'static str { "pixelate" }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        ensure_size(&mut self.sink, ctx.live);
+        let (w, h, b) = (ctx.live.width, ctx.live.height, self.block);
+        for by in (0..h).step_by(b) {
+            for bx in (0..w).step_by(b) {
+                let (x1, y1) = ((bx + b).min(w), (by + b).min(h));
+                // Average the block…
+                let (mut sr, mut sg, mut sb, mut n) = (0u32, 0u32, 0u32, 0u32);
+                for y in by..y1 {
+                    for &p in &ctx.live.pixels[y * w + bx..y * w + x1] {
+                        sr += (p >> 16) & 0xFF; sg += (p >> 8) & 0xFF; sb += p & 0xFF; n += 1;
+                    }
+                }
+                let avg = ((sr / n) << 16) | ((sg / n) << 8) | (sb / n);
+                // …and fill it with that one colour.
+                for y in by..y1 {
+                    self.sink.pixels[y * w + bx..y * w + x1].fill(avg);
+                }
+            }
+        }
+        Ok(&self.sink)
+    }
+}
+
+/* -------------------- per-pixel transforms (grayscale, …) -------------------- */
+
+/// Effect that maps every pixel independently: `f(pixel, lut) -> pixel`.
+pub struct PerPixelEffect {
+    name: &'static str,
+    f: fn(u32, &GammaLut) -> u32,
+    sink: FrameBuffer,
+}
+
+impl PerPixelEffect {
+    pub fn new(name: &'static str, f: fn(u32, &GammaLut) -> u32) -> Self {
+        Self { name, f, sink: empty_frame() }
+    }
+}
+
+impl Effect for PerPixelEffect {
+    fn name(&self) -> &'static str { self.name }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        ensure_size(&mut self.sink, ctx.live);
+        for (d, &p) in self.sink.pixels.iter_mut().zip(&ctx.live.pixels) {
+            *d = (self.f)(p, ctx.lut);
+        }
+        Ok(&self.sink)
+    }
+}
+
+/// Linear-light luminance as grey. Visual: black-and-white with correct brightness.
+pub fn grayscale(p: u32, lut: &GammaLut) -> u32 {
+    let r = lut.srgb_u8_to_linear(((p >> 16) & 0xFF) as u8);
+    let g = lut.srgb_u8_to_linear(((p >> 8) & 0xFF) as u8);
+    let b = lut.srgb_u8_to_linear((p & 0xFF) as u8);
+    let y = lut.linear_to_srgb_u8(0.2126 * r + 0.7152 * g + 0.0722 * b) as u32;
+    (y << 16) | (y << 8) | y
+}
+
+/* -------------------- convolution kernels (sharpen / emboss / edges) -------------------- */
+
+/// Visual: whatever the kernel does, only where painted.
+pub struct KernelEffect { name: &'static str, kernel: Kernel, sink: FrameBuffer }
+
+impl KernelEffect {
+    pub fn new(name: &'static str, kernel: Kernel) -> Self {
+        Self { name, kernel, sink: empty_frame() }
+    }
+}
+
+impl Effect for KernelEffect {
+    fn name(&self) -> &'static str { self.name }
+    fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        ensure_size(&mut self.sink, ctx.live);
+        convolve(ctx.live, &mut self.sink, &self.kernel)?;
+        Ok(&self.sink)
+    }
+}
+
+/* -------------------- registry -------------------- */
+
+/// All brush effects in Tab order, plus which one is active.
+pub struct EffectRegistry {
+    effects: Vec<Box<dyn Effect>>,
+    active: usize,
+}
+
+impl EffectRegistry {
+    /// The built-in effects; BACKGROUND only when an image was loaded.
+    pub fn new(background: Option<FrameBuffer>) -> Self {
+        let mut effects: Vec<Box<dyn Effect>> = vec![
+            Box::new(BlurEffect),
+            Box::new(FillEffect::new(30)),
+            Box::new(CloneEffect { sink: empty_frame() }),
+            Box::new(HealEffect { sink: empty_frame() }),
+            Box::new(SmoothEffect { sink: empty_frame() }),
+        ];
+        if let Some(img) = background {
+            effects.push(Box::new(ImageEffect::new(img)));
+        }
+        effects.push(Box::new(PixelateEffect::new(16)));
+        effects.push(Box::new(PerPixelEffect::new("gray", grayscale)));
+        effects.push(Box::new(KernelEffect::new("sharpen", Kernel::sharpen())));
+        effects.push(Box::new(KernelEffect::new("emboss", Kernel::emboss())));
+        effects.push(Box::new(KernelEffect::new("edges", Kernel::edge())));
+        Self { effects, active: 0 }
+    }
+
+    /// Keep only the effects named in `order` (any case), in that order, for Tab and
+    /// the number keys; empty keeps them all. BACKGROUND without an image is skipped.
+    #[cfg(feature = "window")]
+    pub fn with_order(mut self, order: &[String]) -> Result<Self, Error> {
+        if order.is_empty() {
+            return Ok(self);
+        }
+        let mut known: Vec<&str> = self.effects.iter().map(|e| e.name()).collect();
+        if !known.contains(&"background") {
+            known.push("background");                     // valid in the file, used when an image is given
+        }
+        let mut picked: Vec<Box<dyn Effect>> = Vec::new();
+        for (i, name) in order.iter().enumerate() {
+            if !known.iter().any(|k| k.eq_ignore_ascii_case(name)) {
+                return Err(Error::Config(format!("[pipeline] effects: unknown effect '{name}' (one of: {})", known.join(", "))));
+            }
+            if order[..i].iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                return Err(Error::Config(format!("[pipeline] effects: '{name}' is listed twice")));
+            }
+            if let Some(at) = self.effects.iter().position(|e| e.name().eq_ignore_ascii_case(name)) {
+                picked.push(self.effects.remove(at));
+            }
+        }
+        if let Some(at) = self.effects.iter().position(|e| e.name() == "blur") {
+            picked.push(self.effects.remove(at));
+        }
+        Ok(Self { effects: picked, active: 0 })
+    }
+
+    /// Show `image` as BACKGROUND (a picture dropped onto the window): replaces the
+    /// current one, or adds the effect after SMOOTH (or last) if there was none.
+    /// Returns where it was added (later effects moved up by one), if it was.
+    #[cfg(feature = "window")]
+    pub fn set_background(&mut self, image: FrameBuffer) -> Option<usize> {
+        if let Some(at) = self.effects.iter().position(|e| e.name() == "background") {
+            self.effects[at] = Box::new(ImageEffect::new(image));
+            return None;
+        }
+        let at = self.effects.iter().position(|e| e.name() == "smooth").map_or(self.effects.len(), |i| i + 1);
+        self.effects.insert(at, Box::new(ImageEffect::new(image)));
+        if self.active >= at {
+            self.active += 1;                             // the same effect stays active
+        }
+        Some(at)
+    }
+
+    /// Switch to effect `index` (0-based); out-of-range indices are ignored.
+    pub fn select(&mut self, index: usize) {
+        if index < self.effects.len() {
+            self.active = index;
+            self.effects[index].activate();
+        }
+    }
+
+    /// Switch to the effect called `name` (any case); false if there is none.
+    pub fn select_by_name(&mut self, name: &str) -> bool {
+        match self.effects.iter().position(|e| e.name().eq_ignore_ascii_case(name)) {
+            Some(index) => { self.select(index); true }
+            None => false,
+        }
+    }
+
+    /// Tab: next effect, wrapping around.
+    #[cfg(feature = "window")]
+    pub fn next(&mut self) {
+        self.select((self.active + 1) % self.effects.len());
+    }
+
+    #[cfg(feature = "window")]
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    #[cfg(feature = "window")]
+    pub fn active_name(&self) -> &'static str {
+        self.effects[self.active].name()
+    }
+
+    /// Name of effect `index` (e.g. the one the privacy hotkey will switch back to).
+    #[cfg(feature = "window")]
+    pub fn name_at(&self, index: usize) -> &'static str {
+        self.effects.get(index).map_or(self.active_name(), |e| e.name())
+    }
+
+    /// True while the plain BLUR effect is active.
+    #[cfg(feature = "window")]
+    pub fn active_is_blur(&self) -> bool {
+        self.active_name() == "blur"
+    }
+
+    pub fn active_uses_clone_source(&self) -> bool {
+        self.effects[self.active].uses_clone_source()
+    }
+
+    pub fn active_needs_blur(&self) -> bool {
+        self.effects[self.active].needs_blur()
+    }
+
+    /// Build the active effect's sink for this frame.
+    pub fn render<'a>(&'a mut self, ctx: &EffectCtx<'a>) -> Result<&'a FrameBuffer, Error> {
+        self.effects[self.active].render(ctx)
+    }
+}
@@ -1,5 +1,9 @@
 // A tiny error type so we don't rely on anyhow/thiserror.
-// Every variant states *where* things went wrong.
+// Every variant states *where* things went wrong; `context` adds *what we were doing*
+// on top and keeps the original as `source()`. Some failures only cost one frame or
+// one action (a garbled camera frame, a snapshot that couldn't be written):
+// `is_recoverable` lets the main loop report those and carry on, while everything
+// else still ends the app with the message.
 use std::fmt::{self, Display};
 
 #[derive(Debug)]
@@ -8,6 +12,28 @@ pub enum Error {
     WindowUpdate(String), // Updating the window buffer failed
     CameraInit(String),   // Opening/starting the camera failed
     CameraFrame(String),  // Grabbing/decoding a frame failed
+    CameraLost(String),   // The camera stopped delivering frames altogether
+    ImageLoad(String),    // Reading/decoding an image file failed
+    ImageSave(String),    // Encoding/writing an image file failed
+    Config(String),       // A command-line option or setting could not be understood
+    Clipboard(String),    // Handing text to the system clipboard failed
+    Osc(String),          // Opening the OSC control port failed
+    Video(String),        // Reading or writing a video file (via ffmpeg) failed
+    Io(String, std::io::Error), // A file, folder or pipe operation failed (what, cause)
+    Encode(String),       // Turning data into bytes (settings, pixels) failed
+    // Loading a model file (e.g. segmentation weights) failed (which file, cause).
+    // Nothing in the tree loads weights yet; the variant is here for the loader
+    // that will, so its errors chain like `Io` instead of being flattened to text.
+    Model(String, Option<Box<dyn std::error::Error + Send + Sync>>),
+    Context(String, Box<Error>), // What we were doing when the inner error happened
+    #[cfg(feature = "gpu")]
+    Gpu(String),          // Setting up or running the GPU path failed
+    #[cfg(feature = "gamepad")]
+    Gamepad(String),      // Opening the gamepad backend failed
+    #[cfg(feature = "hotkey")]
+    Hotkey(String),       // Registering the system-wide hotkey failed
+    #[cfg(feature = "script")]
+    Script(String),       // Compiling or starting the --script file failed
 }
 
 impl Display for Error {
@@ -18,10 +44,67 @@ impl Display for Error {
             Error::WindowUpdate(s) => write!(f, "Window update error: {s}"),
             Error::CameraInit(s) => write!(f, "Camera init error: {s}"),
             Error::CameraFrame(s) => write!(f, "Camera frame error: {s}"),
+            Error::CameraLost(s) => write!(f, "Camera lost: {s}"),
+            Error::ImageLoad(s) => write!(f, "Image load error: {s}"),
+            Error::ImageSave(s) => write!(f, "Image save error: {s}"),
+            Error::Config(s) => write!(f, "Config error: {s}"),
+            Error::Clipboard(s) => write!(f, "Clipboard error: {s}"),
+            Error::Osc(s) => write!(f, "OSC error: {s}"),
+            Error::Video(s) => write!(f, "Video error: {s}"),
+            Error::Io(s, e) => write!(f, "I/O error: {s}: {e}"),
+            Error::Encode(s) => write!(f, "Encode error: {s}"),
+            Error::Model(s, Some(e)) => write!(f, "Model load error: {s}: {e}"),
+            Error::Model(s, None) => write!(f, "Model load error: {s}"),
+            Error::Context(what, e) => write!(f, "{what}: {e}"),
+            #[cfg(feature = "gpu")]
+            Error::Gpu(s) => write!(f, "GPU error: {s}"),
+            #[cfg(feature = "gamepad")]
+            Error::Gamepad(s) => write!(f, "Gamepad error: {s}"),
+            #[cfg(feature = "hotkey")]
+            Error::Hotkey(s) => write!(f, "Hotkey error: {s}"),
+            #[cfg(feature = "script")]
+            Error::Script(s) => write!(f, "Script error: {s}"),
         }
     }
 }
 
-// We don't implement std::error::Error for now to keep things minimal.
-// It's easy to add later when we wire in more components.
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, e) => Some(e),
+            Error::Model(_, Some(e)) => Some(e.as_ref()),
+            Error::Context(_, e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// Wrap with what we were doing: "Reload config.toml: Config error: ...".
+    pub fn context(self, what: impl Into<String>) -> Self {
+        Error::Context(what.into(), Box::new(self))
+    }
+
+    /// True for failures that spoil one frame or one action, not the session: the
+    /// caller may report them and keep going. (Size checks inside the pipeline use
+    /// `CameraFrame` too; only errors from fetching a frame are treated this way.)
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Error::CameraFrame(_) | Error::ImageLoad(_) | Error::ImageSave(_) | Error::Clipboard(_) | Error::Io(..) => true,
+            Error::Context(_, e) => e.is_recoverable(),
+            _ => false,
+        }
+    }
+}
+
+/// `.context("what we were doing")` on any `Result<_, Error>`.
+pub trait Context<T> {
+    fn context(self, what: impl Into<String>) -> Result<T, Error>;
+}
+
+impl<T> Context<T> for Result<T, Error> {
+    fn context(self, what: impl Into<String>) -> Result<T, Error> {
+        self.map_err(|e| e.context(what))
+    }
+}
 
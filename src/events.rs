@@ -0,0 +1,197 @@
+// Events: everything that changes the app, as one queue, whoever asked for it.
+// Visual expectation: nothing new on screen — but the keyboard and mouse, the gamepad,
+// OSC, the settings panel and anything else holding an `EventSender` (a macro, a test,
+// a script) all produce the same `Event`s, and the processing stage applies them in
+// order, once per frame. A remote Paint lands exactly like a mouse dab; a remote
+// NextEffect is exactly Tab, undo history and all.
+//
+// Order within a frame: the window's keys (in keys.rs table order) and steps, then the
+// queue (remote control, last frame's panel clicks), then the brush gesture.
+
+use crate::gesture::Gesture;
+use crate::keys::KEY_BINDINGS;
+use crate::window::Input;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Which brush effect to switch to.
+pub enum EffectRef {
+    Index(usize), // 0-based
+    Name(String),
+}
+
+/// A single-press control: what a key in keys.rs, a panel button or a remote asks for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Help,
+    NextEffect,
+    Clear,
+    ShowBlur,
+    Skin,
+    Median,
+    Levels,
+    ColourControl,
+    ColourReset,
+    WhiteBalance,
+    Picker,
+    Grain,
+    Vignette,
+    Fx,
+    BlurKind,
+    BlurQuality,
+    BlurReuse,
+    LinearBlur,
+    CaptureBackground,
+    Portrait,
+    Diff,
+    Settings,
+    Profiler,
+    Graph,
+    Histogram,
+    Loupe,
+    CursorStyle,
+    Compare,
+    MaskThumb,
+    Guides,
+    SaveSession,
+    ZoomReset,
+    Hud,
+    Undo,
+    Redo,
+    Privacy,
+}
+
+impl Action {
+    /// The action behind a keys.rs binding name (`confirm` belongs to text fields: none).
+    pub fn from_binding(name: &str) -> Option<Self> {
+        Some(match name {
+            "help" => Action::Help,
+            "next_effect" => Action::NextEffect,
+            "clear" => Action::Clear,
+            "show_blur" => Action::ShowBlur,
+            "skin" => Action::Skin,
+            "median" => Action::Median,
+            "levels" => Action::Levels,
+            "colour_control" => Action::ColourControl,
+            "colour_reset" => Action::ColourReset,
+            "white_balance" => Action::WhiteBalance,
+            "picker" => Action::Picker,
+            "grain" => Action::Grain,
+            "vignette" => Action::Vignette,
+            "fx" => Action::Fx,
+            "blur_kind" => Action::BlurKind,
+            "blur_quality" => Action::BlurQuality,
+            "blur_reuse" => Action::BlurReuse,
+            "linear_blur" => Action::LinearBlur,
+            "capture_bg" => Action::CaptureBackground,
+            "portrait" => Action::Portrait,
+            "diff" => Action::Diff,
+            "settings" => Action::Settings,
+            "profiler" => Action::Profiler,
+            "graph" => Action::Graph,
+            "histogram" => Action::Histogram,
+            "loupe" => Action::Loupe,
+            "redo" => Action::Redo,
+            "cursor_style" => Action::CursorStyle,
+            "compare" => Action::Compare,
+            "mask_thumb" => Action::MaskThumb,
+            "guides" => Action::Guides,
+            "save_session" => Action::SaveSession,
+            "zoom_reset" => Action::ZoomReset,
+            "hud" => Action::Hud,
+            _ => return None,
+        })
+    }
+}
+
+/// One thing to do. Points are frame pixels; radii are window pixels like --brush-size.
+pub enum Event {
+    Press(Action),
+    SelectEffect(EffectRef),
+    /// Dab along from → to (a single dab when they are equal); `start`: first dab of a stroke.
+    Paint { from: (i32, i32), to: (i32, i32), subtract: bool, start: bool },
+    ClearAt((i32, i32)),
+    CloneSource((i32, i32)),
+    AdjustBrush { delta: i32 },  // brush size steps (gamepad bumpers, pinch)
+    AdjustBlur { delta: i32 },   // , and .
+    AdjustColour { delta: i32 }, // - and = on the V control
+    SetBrushRadius(f32),
+    SetBrushHardness(f32),       // 0..1
+    SetBlurRadius(f32),
+    SetPortrait(bool),
+    SetFx(bool),
+}
+
+impl From<Gesture> for Event {
+    fn from(gesture: Gesture) -> Self {
+        match gesture {
+            Gesture::Paint { from, to, subtract, start } => Event::Paint { from, to, subtract, start },
+            Gesture::ClearAt(p) => Event::ClearAt(p),
+            Gesture::CloneSource(p) => Event::CloneSource(p),
+        }
+    }
+}
+
+/// This frame's keys and steps from the window, as events.
+/// Ctrl turns Z (loupe) into undo and Y into redo; Ctrl+Shift+Z is redo too.
+pub fn from_input(input: &Input) -> Vec<Event> {
+    let mut probe = input.clone();
+    let mut events: Vec<Event> = KEY_BINDINGS
+        .iter()
+        .filter(|b| *(b.flag)(&mut probe))
+        .filter_map(|b| match Action::from_binding(b.name)? {
+            Action::Loupe if input.mods.ctrl => Some(if input.mods.shift { Action::Redo } else { Action::Undo }),
+            Action::Redo if !input.mods.ctrl => None,
+            action => Some(action),
+        })
+        .map(Event::Press)
+        .collect();
+    if input.privacy_pressed {
+        events.push(Event::Press(Action::Privacy));
+    }
+    if let Some(d) = input.digit {
+        events.push(Event::SelectEffect(EffectRef::Index(d as usize - 1)));
+    }
+    if input.brush_steps != 0 {
+        events.push(Event::AdjustBrush { delta: input.brush_steps });
+    }
+    if input.blur_radius_steps != 0 {
+        events.push(Event::AdjustBlur { delta: input.blur_radius_steps });
+    }
+    if input.adjust_steps != 0 {
+        events.push(Event::AdjustColour { delta: input.adjust_steps });
+    }
+    events
+}
+
+/// Hands events to the processing stage from any thread.
+#[derive(Clone)]
+pub struct EventSender(Sender<Event>);
+
+impl EventSender {
+    /// Queue `event` for the next frame; false once processing has stopped.
+    pub fn send(&self, event: Event) -> bool {
+        self.0.send(event).is_ok()
+    }
+}
+
+/// The queue the processing stage drains once per frame.
+pub struct EventQueue {
+    tx: Sender<Event>,
+    rx: Receiver<Event>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self { tx, rx }
+    }
+
+    pub fn sender(&self) -> EventSender {
+        EventSender(self.tx.clone())
+    }
+
+    /// Everything sent since the last call.
+    pub fn drain(&self) -> impl Iterator<Item = Event> + '_ {
+        self.rx.try_iter()
+    }
+}
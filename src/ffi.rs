@@ -0,0 +1,216 @@
+// C API: the blur brush engine inside programs that aren't written in Rust
+// (`cargo build --release --features ffi` → libmagic_eraser.a / .so / .dylib / .dll;
+// declarations in include/magic_eraser.h).
+// Visual expectation: a C or C++ capture tool hands each frame and its own mask to
+// `me_process_frame` and gets the frame back with the blur painted in under the mask —
+// the same picture the magic-eraser window shows for the same mask and radius.
+//
+//     me_engine *engine = me_engine_new();
+//     me_params params = { .blur_radius = 12, .format = ME_FORMAT_BGRA8 };
+//     while (capture(pixels, mask))                     /* your loop */
+//         if (me_process_frame(engine, pixels, w, h, mask, &params) != ME_OK)
+//             fprintf(stderr, "%s\n", me_last_error(engine));
+//     me_engine_free(engine);
+//
+// Frames are tightly packed bytes in any `PixelFormat` (no row padding), processed in
+// place. The mask is one byte per pixel, 0 = untouched .. 255 = full blur; NULL leaves
+// the frame as it is. An engine keeps its buffers between calls (a steady stream
+// allocates nothing) and is not thread-safe: one engine per thread or stream.
+// Nothing panics across the boundary: a panic comes back as `ME_ERR_PANIC`.
+
+use crate::error::Error;
+use crate::gamma::GammaLut;
+use crate::pixel::{PixelBuffer, PixelFormat};
+use crate::types::{FrameBuffer, Mask, MaskTiles, Rect};
+use crate::vision;
+use std::ffi::{c_char, c_int, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+pub const ME_OK: c_int = 0;
+/// A NULL pointer, a zero size or an unknown format.
+pub const ME_ERR_ARGUMENT: c_int = -1;
+/// The frame could not be processed (see `me_last_error`).
+pub const ME_ERR_PROCESS: c_int = -2;
+/// A bug in the engine; the engine should be freed.
+pub const ME_ERR_PANIC: c_int = -3;
+
+pub const ME_FORMAT_RGBA8: u32 = 0;
+pub const ME_FORMAT_BGRA8: u32 = 1;
+pub const ME_FORMAT_RGB24: u32 = 2;
+pub const ME_FORMAT_I420: u32 = 3;
+pub const ME_FORMAT_NV12: u32 = 4;
+
+/// Per-call settings (`me_params` in C).
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct MeParams {
+    /// Blur radius in pixels, 1..96 (clamped).
+    pub blur_radius: u32,
+    /// One of the `ME_FORMAT_*` values.
+    pub format: u32,
+}
+
+fn pixel_format(format: u32) -> Option<PixelFormat> {
+    Some(match format {
+        ME_FORMAT_RGBA8 => PixelFormat::Rgba8,
+        ME_FORMAT_BGRA8 => PixelFormat::Bgra8,
+        ME_FORMAT_RGB24 => PixelFormat::Rgb24,
+        ME_FORMAT_I420 => PixelFormat::I420,
+        ME_FORMAT_NV12 => PixelFormat::Nv12,
+        _ => return None,
+    })
+}
+
+/// Buffers for one frame size and format, remade when either changes.
+struct Frames {
+    bytes: PixelBuffer,
+    live: FrameBuffer,
+    tmp: FrameBuffer,
+    blur: FrameBuffer,
+    mask: Mask,
+    tiles: MaskTiles,
+}
+
+impl Frames {
+    fn new(width: usize, height: usize, format: PixelFormat) -> Self {
+        let blank = || FrameBuffer { width, height, pixels: vec![0u32; width * height] };
+        Self {
+            bytes: PixelBuffer::new(width, height, format),
+            live: blank(),
+            tmp: blank(),
+            blur: blank(),
+            mask: Mask::new(width, height),
+            tiles: MaskTiles::new(width, height),
+        }
+    }
+}
+
+/// An engine handle (`me_engine` in C; opaque there).
+pub struct MeEngine {
+    frames: Option<Frames>,
+    lut: GammaLut,
+    last_error: CString,
+}
+
+impl MeEngine {
+    fn fail(&mut self, code: c_int, message: String) -> c_int {
+        self.last_error = CString::new(message.replace('\0', " ")).unwrap_or_default();
+        code
+    }
+
+    /// Blur `bytes` (already copied into `frames.bytes`) under `alpha`, in place.
+    fn process(&mut self, alpha: &[u8], blur_radius: usize) -> Result<(), Error> {
+        let Some(f) = self.frames.as_mut() else { return Ok(()) };
+        f.mask.alpha.copy_from_slice(alpha);
+        f.mask.bounds = alpha_bounds(&f.mask);
+        let Some(bounds) = f.mask.bounds else { return Ok(()) };  // nothing painted: frame untouched
+        f.tiles.rebuild(&f.mask);
+        f.bytes.read_frame(&mut f.live)?;
+        vision::box_blur_rgb_rect(&f.live, &mut f.tmp, &mut f.blur, blur_radius, bounds)?;
+        vision::blend_linear_in_place(&mut f.live, &f.blur, &f.mask, &f.tiles, &self.lut)?;
+        f.bytes.write_frame(&f.live)
+    }
+}
+
+/// The smallest box holding every α > 0 (None if the mask is empty).
+fn alpha_bounds(mask: &Mask) -> Option<Rect> {
+    let mut bounds: Option<Rect> = None;
+    for (y, row) in mask.alpha.chunks_exact(mask.width.max(1)).enumerate() {
+        let (Some(x0), Some(x1)) = (row.iter().position(|&a| a > 0), row.iter().rposition(|&a| a > 0)) else { continue };
+        let r = Rect { x0, y0: y, x1: x1 + 1, y1: y + 1 };
+        bounds = Some(bounds.map_or(r, |b| b.union(r)));
+    }
+    bounds
+}
+
+/// A new engine. Free it with `me_engine_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn me_engine_new() -> *mut MeEngine {
+    Box::into_raw(Box::new(MeEngine { frames: None, lut: GammaLut::new(), last_error: CString::default() }))
+}
+
+/// Free an engine from `me_engine_new` (NULL is ignored).
+///
+/// # Safety
+/// `engine` is NULL or came from `me_engine_new` and hasn't been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn me_engine_free(engine: *mut MeEngine) {
+    if !engine.is_null() {
+        drop(unsafe { Box::from_raw(engine) });
+    }
+}
+
+/// Blur `pixels` (`width`×`height`, `params->format`, tightly packed) in place under
+/// `mask` (`width`×`height` bytes, or NULL for no effect). Returns `ME_OK` or an `ME_ERR_*`.
+///
+/// # Safety
+/// `engine` came from `me_engine_new`; `pixels` points to the whole frame
+/// (`PixelFormat::buffer_len` bytes), writable; `mask` is NULL or points to
+/// `width * height` bytes; `params` points to a `MeParams`. None of them are used by
+/// another thread during the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn me_process_frame(
+    engine: *mut MeEngine,
+    pixels: *mut u8,
+    width: u32,
+    height: u32,
+    mask: *const u8,
+    params: *const MeParams,
+) -> c_int {
+    let Some(engine) = (unsafe { engine.as_mut() }) else { return ME_ERR_ARGUMENT };
+    engine.last_error = CString::default();                       // a good call leaves ""
+    let Some(params) = (unsafe { params.as_ref() }).copied() else {
+        return engine.fail(ME_ERR_ARGUMENT, "params is NULL".into());
+    };
+    let Some(format) = pixel_format(params.format) else {
+        return engine.fail(ME_ERR_ARGUMENT, format!("unknown format {}", params.format));
+    };
+    let (w, h) = (width as usize, height as usize);
+    if pixels.is_null() || w == 0 || h == 0 {
+        return engine.fail(ME_ERR_ARGUMENT, format!("no frame (pixels {pixels:?}, {w}x{h})"));
+    }
+    if mask.is_null() {
+        return ME_OK;                                             // no mask: frame untouched
+    }
+    let len = format.buffer_len(w, h);
+    let bytes = unsafe { std::slice::from_raw_parts_mut(pixels, len) };
+    let alpha = unsafe { std::slice::from_raw_parts(mask, w * h) };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if engine.frames.as_ref().is_none_or(|f| (f.bytes.width, f.bytes.height, f.bytes.format) != (w, h, format)) {
+            engine.frames = Some(Frames::new(w, h, format));
+        }
+        if let Some(f) = engine.frames.as_mut() {
+            f.bytes.data.copy_from_slice(bytes);
+        }
+        engine.process(alpha, params.blur_radius.clamp(1, 96) as usize)?;
+        if let Some(f) = engine.frames.as_ref() {
+            bytes.copy_from_slice(&f.bytes.data);
+        }
+        Ok::<(), Error>(())
+    }));
+    match result {
+        Ok(Ok(())) => ME_OK,
+        Ok(Err(e)) => engine.fail(ME_ERR_PROCESS, e.to_string()),
+        Err(_) => engine.fail(ME_ERR_PANIC, "panic in me_process_frame".into()),
+    }
+}
+
+/// What went wrong in the last `me_process_frame` on `engine` ("" if it succeeded).
+/// Valid until the next call on the same engine; NULL engine gives "".
+///
+/// # Safety
+/// `engine` is NULL or came from `me_engine_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn me_last_error(engine: *const MeEngine) -> *const c_char {
+    match unsafe { engine.as_ref() } {
+        Some(engine) => engine.last_error.as_ptr(),
+        None => c"".as_ptr(),
+    }
+}
+
+/// The library version, e.g. "0.1.0" (static; don't free).
+#[unsafe(no_mangle)]
+pub extern "C" fn me_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
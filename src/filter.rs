@@ -0,0 +1,113 @@
+// Whole-frame filters (colour correction, denoise, grading, polish) behind one trait,
+// run as an ordered chain that is put together at runtime.
+// Visual expectation: the same picture as when these steps were hard-coded, but the
+// order and the set are a setting — `--filters levels,grade,median` stretches the
+// contrast before grading and denoises last; leaving a name out removes that step.
+//
+// Adding a filter = implement `Filter`; whoever builds the chain decides where it goes.
+// The binary's built-in filters are in filters.rs.
+
+use crate::error::Error;
+use crate::gamma::GammaLut;
+use crate::types::FrameBuffer;
+use std::any::Any;
+
+/// One adjustable number of a filter, with its range (for panels, OSC, settings files).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Param {
+    pub name: &'static str,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// One step of a `FilterChain`.
+pub trait Filter: Any + Send {
+    /// Short lowercase name, as used in chain specs and on the HUD.
+    fn name(&self) -> &'static str;
+
+    /// Process `frame` in place. Neutral settings should cost next to nothing.
+    fn apply(&mut self, frame: &mut FrameBuffer, lut: &GammaLut) -> Result<(), Error>;
+
+    /// The filter's adjustable numbers and their current values.
+    fn params(&self) -> Vec<Param> { Vec::new() }
+
+    /// Set parameter `name` (clamped to its range); an error for names it doesn't have.
+    fn set_param(&mut self, name: &str, _value: f32) -> Result<(), Error> {
+        Err(Error::Config(format!("filter '{}' has no parameter '{name}'", self.name())))
+    }
+
+    /// True if the filter is cosmetic and may be skipped when frames run late.
+    fn optional(&self) -> bool { false }
+}
+
+/// A filter in the chain and whether it currently runs.
+struct Stage {
+    filter: Box<dyn Filter>,
+    enabled: bool,
+}
+
+/// Filters applied one after another, in order.
+#[derive(Default)]
+pub struct FilterChain {
+    stages: Vec<Stage>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `filter` (enabled).
+    pub fn push(&mut self, filter: Box<dyn Filter>) {
+        self.stages.push(Stage { filter, enabled: true });
+    }
+
+    /// Filter names in the order they run.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.stages.iter().map(|s| s.filter.name())
+    }
+
+    /// Turn the filter called `name` on or off (no-op if the chain doesn't have it).
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        self.stages.iter_mut().filter(|s| s.filter.name() == name).for_each(|s| s.enabled = enabled);
+    }
+
+    /// True if the chain has `name` and it is on.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.stages.iter().any(|s| s.enabled && s.filter.name() == name)
+    }
+
+    /// True if any filter in the chain is on (false: `apply` changes nothing).
+    pub fn any_enabled(&self) -> bool {
+        self.stages.iter().any(|s| s.enabled)
+    }
+
+    /// The first filter of type `T`, if the chain has one (to drive its own controls).
+    pub fn get<T: Filter>(&self) -> Option<&T> {
+        self.stages.iter().find_map(|s| (s.filter.as_ref() as &dyn Any).downcast_ref())
+    }
+
+    /// Writable `get`.
+    pub fn get_mut<T: Filter>(&mut self) -> Option<&mut T> {
+        self.stages.iter_mut().find_map(|s| (s.filter.as_mut() as &mut dyn Any).downcast_mut())
+    }
+
+    /// Set parameter `param` of the filter called `filter`.
+    pub fn set_param(&mut self, filter: &str, param: &str, value: f32) -> Result<(), Error> {
+        match self.stages.iter_mut().find(|s| s.filter.name() == filter) {
+            Some(s) => s.filter.set_param(param, value),
+            None => Err(Error::Config(format!("no filter '{filter}' in the chain"))),
+        }
+    }
+
+    /// Run every enabled filter over `frame`, in order; `lean` skips the optional ones.
+    pub fn apply(&mut self, frame: &mut FrameBuffer, lut: &GammaLut, lean: bool) -> Result<(), Error> {
+        for s in &mut self.stages {
+            if s.enabled && !(lean && s.filter.optional()) {
+                s.filter.apply(frame, lut)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+// The built-in whole-frame filters, and the two chains they run in.
+// Visual expectation: nothing new by default — the live frame is denoised, white-
+// balanced, levelled, graded and skin-smoothed in that order before the brush builds
+// its effect, and the finished picture gets vignette and grain, as always. Keys still
+// switch them (M, W / V, A, U, N, G); the order is a setting:
+//
+//   --filters median,white-balance,levels,grade,smooth   live frame, before the brush
+//   --post vignette,grain                                 finished picture, before the HUD
+//
+// (also `[filters] live = "..."` / `post = "..."` in config.toml). Leave a name out
+// and that step is gone, keys and all; list it elsewhere and it moves there. A `.cube`
+// file dropped onto the window grades the live frame last (see lut.rs).
+// Adding a filter = implement `Filter` (filter.rs) and give it a name in `build`.
+
+use crate::beauty;
+use crate::color::{AutoLevels, ColorAdjust, WhiteBalance};
+use crate::error::Error;
+use crate::filter::{Filter, FilterChain, Param};
+use crate::gamma::GammaLut;
+#[cfg(feature = "window")]
+use crate::lut::CubeLut;
+use crate::median::MedianFilter;
+use crate::post::{Grain, Vignette};
+use crate::types::FrameBuffer;
+use crate::vision::box_blur_rgb;
+
+/// Default live-frame chain (the order the steps always ran in).
+pub const DEFAULT_LIVE: &str = "median,white-balance,levels,grade,smooth";
+/// Default chain for the finished picture.
+pub const DEFAULT_POST: &str = "vignette,grain";
+/// Every name `build` knows, for error messages.
+const NAMES: &str = "median, white-balance, levels, grade, smooth, vignette, grain";
+
+/// Build a chain from a comma-separated list of filter names.
+/// `which` names the setting in error messages ("--filters", "--post").
+pub fn build(spec: &str, which: &str) -> Result<FilterChain, Error> {
+    let mut chain = FilterChain::new();
+    for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let filter: Box<dyn Filter> = match name {
+            "median" => Box::new(Denoise { median: MedianFilter::new(), radius: 0 }),
+            "white-balance" => Box::new(WhiteBalancePick { wb: WhiteBalance::new(), pick_at: None }),
+            "levels" => Box::new(AutoLevels::new()),
+            "grade" => Box::new(ColorAdjust::new()),
+            "smooth" => Box::new(SkinSmooth::new(6)),
+            "vignette" => Box::new(VignetteFilter::new(0.55)),
+            "grain" => Box::new(Grain::new(12)),
+            _ => return Err(Error::Config(format!("{which}: unknown filter '{name}' (one of: {NAMES})"))),
+        };
+        if chain.names().any(|n| n == name) {
+            return Err(Error::Config(format!("{which}: '{name}' is listed twice")));
+        }
+        chain.push(filter);
+    }
+    Ok(chain)
+}
+
+/// Clamp `value` into `p`'s range.
+fn clamp_to(p: &Param, value: f32) -> f32 {
+    value.clamp(p.min, p.max)
+}
+
+/// Set the parameter of `filter` called `name` via `set`, clamped; unknown names are errors.
+fn set_listed(filter: &dyn Filter, name: &str, value: f32, set: impl FnOnce(f32)) -> Result<(), Error> {
+    match filter.params().iter().find(|p| p.name == name) {
+        Some(p) => { set(clamp_to(p, value)); Ok(()) }
+        None => Err(Error::Config(format!("filter '{}' has no parameter '{name}'", filter.name()))),
+    }
+}
+
+/* -------------------- median denoise -------------------- */
+
+/// Visual: M cycles off → 3x3 → 5x5; speckle noise disappears, edges stay crisp.
+pub struct Denoise {
+    median: MedianFilter,
+    pub radius: usize, // 0 = off, 1 = 3x3, 2 = 5x5
+}
+
+impl Filter for Denoise {
+    fn name(&self) -> &'static str { "median" }
+    fn apply(&mut self, frame: &mut FrameBuffer, _lut: &GammaLut) -> Result<(), Error> {
+        self.median.apply_rgb(frame, self.radius) // no-op at radius 0
+    }
+    fn params(&self) -> Vec<Param> {
+        vec![Param { name: "radius", value: self.radius as f32, min: 0.0, max: 2.0 }]
+    }
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let mut radius = self.radius;
+        set_listed(self, name, value, |v| radius = v.round() as usize)?;
+        self.radius = radius;
+        Ok(())
+    }
+}
+
+/* -------------------- white balance (+ eyedropper) -------------------- */
+
+/// Visual: temperature / tint via V and -/=; W then click makes that spot grey.
+pub struct WhiteBalancePick {
+    pub wb: WhiteBalance,
+    pub pick_at: Option<(i32, i32)>, // eyedropper click, sampled on the next frame
+}
+
+impl Filter for WhiteBalancePick {
+    fn name(&self) -> &'static str { "white-balance" }
+    fn apply(&mut self, frame: &mut FrameBuffer, lut: &GammaLut) -> Result<(), Error> {
+        if let Some((x, y)) = self.pick_at.take() {
+            self.wb.pick_neutral(frame, x, y, lut); // sampled before this step's correction
+        }
+        self.wb.apply(frame, lut);                   // no-op while neutral
+        Ok(())
+    }
+    fn params(&self) -> Vec<Param> {
+        vec![
+            Param { name: "kelvin", value: self.wb.kelvin, min: 2000.0, max: 12000.0 },
+            Param { name: "tint", value: self.wb.tint, min: -1.0, max: 1.0 },
+        ]
+    }
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let (mut kelvin, mut tint) = (self.wb.kelvin, self.wb.tint);
+        set_listed(self, name, value, |v| if name == "kelvin" { kelvin = v } else { tint = v })?;
+        self.wb = WhiteBalance::new();                // drops an eyedropper pick, like nudging
+        (self.wb.kelvin, self.wb.tint) = (kelvin, tint);
+        Ok(())
+    }
+}
+
+/* -------------------- auto-levels / grade -------------------- */
+
+/// Visual: A on → dull webcam frames get deep blacks and clean whites.
+impl Filter for AutoLevels {
+    fn name(&self) -> &'static str { "levels" }
+    fn apply(&mut self, frame: &mut FrameBuffer, _lut: &GammaLut) -> Result<(), Error> {
+        AutoLevels::apply(self, frame);
+        Ok(())
+    }
+}
+
+/// Visual: brightness / contrast / saturation from V and -/= (no-op while neutral).
+impl Filter for ColorAdjust {
+    fn name(&self) -> &'static str { "grade" }
+    fn apply(&mut self, frame: &mut FrameBuffer, lut: &GammaLut) -> Result<(), Error> {
+        ColorAdjust::apply(self, frame, lut);
+        Ok(())
+    }
+    fn params(&self) -> Vec<Param> {
+        vec![
+            Param { name: "brightness", value: self.brightness, min: -3.0, max: 3.0 },
+            Param { name: "contrast", value: self.contrast, min: 0.2, max: 3.0 },
+            Param { name: "saturation", value: self.saturation, min: 0.0, max: 3.0 },
+        ]
+    }
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let mut grade = *self;
+        set_listed(self, name, value, |v| match name {
+            "brightness" => grade.brightness = v,
+            "contrast" => grade.contrast = v,
+            _ => grade.saturation = v,
+        })?;
+        *self = grade;
+        Ok(())
+    }
+}
+
+/* -------------------- grading LUT -------------------- */
+
+/// Visual: the look of a `.cube` file dropped onto the window. It has no name in `build`
+/// (it needs the file): the first drop appends it to the live chain, later ones replace it.
+#[cfg(feature = "window")]
+impl Filter for CubeLut {
+    fn name(&self) -> &'static str { "lut" }
+    fn apply(&mut self, frame: &mut FrameBuffer, _lut: &GammaLut) -> Result<(), Error> {
+        CubeLut::apply(self, frame);
+        Ok(())
+    }
+}
+
+/* -------------------- whole-frame skin smoothing -------------------- */
+
+/// Visual: U smooths all skin in the frame (the SMOOTH brush does it only where painted).
+pub struct SkinSmooth {
+    radius: usize, // how much texture counts as "blemish"
+    tmp: FrameBuffer,
+    blur: FrameBuffer,
+    sink: FrameBuffer,
+}
+
+impl SkinSmooth {
+    fn new(radius: usize) -> Self {
+        let empty = || FrameBuffer { width: 0, height: 0, pixels: Vec::new() };
+        Self { radius, tmp: empty(), blur: empty(), sink: empty() }
+    }
+}
+
+impl Filter for SkinSmooth {
+    fn name(&self) -> &'static str { "smooth" }
+    fn apply(&mut self, frame: &mut FrameBuffer, _lut: &GammaLut) -> Result<(), Error> {
+        for buf in [&mut self.tmp, &mut self.blur, &mut self.sink] {
+            if buf.width != frame.width || buf.height != frame.height {
+                *buf = FrameBuffer { width: frame.width, height: frame.height, pixels: vec![0u32; frame.pixels.len()] };
+            }
+        }
+        box_blur_rgb(frame, &mut self.tmp, &mut self.blur, self.radius)?;
+        beauty::skin_smooth(frame, &self.blur, &mut self.sink)?;
+        frame.pixels.copy_from_slice(&self.sink.pixels);
+        Ok(())
+    }
+    fn params(&self) -> Vec<Param> {
+        vec![Param { name: "radius", value: self.radius as f32, min: 1.0, max: 24.0 }]
+    }
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let mut radius = self.radius;
+        set_listed(self, name, value, |v| radius = v.round() as usize)?;
+        self.radius = radius;
+        Ok(())
+    }
+}
+
+/* -------------------- polish: vignette / grain -------------------- */
+
+/// Visual: N darkens the corners. The falloff is rebuilt when the size or strength changes.
+pub struct VignetteFilter {
+    strength: f32,
+    vignette: Option<(usize, usize, Vignette)>, // built for this width × height
+}
+
+impl VignetteFilter {
+    fn new(strength: f32) -> Self {
+        Self { strength, vignette: None }
+    }
+}
+
+impl Filter for VignetteFilter {
+    fn name(&self) -> &'static str { "vignette" }
+    fn apply(&mut self, frame: &mut FrameBuffer, _lut: &GammaLut) -> Result<(), Error> {
+        let size = (frame.width, frame.height);
+        let (_, _, vignette) = match &mut self.vignette {
+            Some(v) if (v.0, v.1) == size => v,
+            slot => slot.insert((size.0, size.1, Vignette::new(size.0, size.1, self.strength))),
+        };
+        vignette.apply(frame);
+        Ok(())
+    }
+    fn params(&self) -> Vec<Param> {
+        vec![Param { name: "strength", value: self.strength, min: 0.0, max: 1.0 }]
+    }
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let mut strength = self.strength;
+        set_listed(self, name, value, |v| strength = v)?;
+        self.strength = strength;
+        self.vignette = None;
+        Ok(())
+    }
+}
+
+/// Visual: G adds flickering film grain; the first thing dropped when frames run late.
+impl Filter for Grain {
+    fn name(&self) -> &'static str { "grain" }
+    fn optional(&self) -> bool { true }
+    fn apply(&mut self, frame: &mut FrameBuffer, _lut: &GammaLut) -> Result<(), Error> {
+        Grain::apply(self, frame);
+        Ok(())
+    }
+    fn params(&self) -> Vec<Param> {
+        vec![Param { name: "amount", value: self.amount() as f32, min: 0.0, max: 64.0 }]
+    }
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let mut amount = self.amount();
+        set_listed(self, name, value, |v| amount = v.round() as i32)?;
+        *self = Grain::new(amount);
+        Ok(())
+    }
+}
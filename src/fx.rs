@@ -1,22 +1,36 @@
-// FX: sparkles + lightning with precomputed glow discs (no per-pixel exp()).
+// FX: sparkles + lightning + smoke with precomputed glow discs (no per-pixel exp()).
 // What you SEE on screen:
 // - Warm sparkles pop around your eraser stroke, drift a bit, then fade out.
-// - Occasionally a bluish lightning bolt flickers briefly and disappears.
+// - The faster the brush moves, the more of them: a slow careful stroke gets a few
+//   sparkles and hardly any lightning, a quick flourish lights up.
+// - Pressing C to clear the paint sets off a short burst of confetti from where the
+//   paint was, instead of it just vanishing.
+// - Faint smoke puffs trail behind the brush, drifting back and up while they swell
+//   and thin out, so a fast stroke leaves a wake.
+// - Occasionally a bluish lightning bolt flickers briefly and disappears: a jagged main
+//   channel with thinner, dimmer forks, inside a soft glow.
 // - Visuals match the previous version, but run much faster.
-
+// - F switches all of it off (and on again) for careful redaction work; off, it costs
+//   nothing (`--no-fx` starts that way).
+// How many (at a brisk stroke), how long, how fast and how often is `FxParams` ([fx] in config.toml and
+// the settings panel in the app); the colours come from the theme.
+// Each frame `update` moves everything and lists the glow discs to draw (`FxDab`);
+// `render` stamps them here, and the app's GPU path composites the same list instead.
+
+use crate::theme::{rgb, Theme};
 use crate::types::FrameBuffer;
 
-/* -------------------- tiny RNG (visual jitter only) -------------------- */
+/* -------------------- tiny RNG (visual jitter; also drives inpaint search) -------------------- */
 
 #[derive(Clone)]
-struct Rng32 { state: u32 }
+pub struct Rng32 { state: u32 }
 
 impl Rng32 {
     // Creates a repeatable random sequence (so the "feel" is consistent).
     pub fn from_seed(seed: u32) -> Self { Self { state: seed | 1 } }
 
     // Produces the next random 32-bit number (used for velocity/angles/chance).
-    #[inline] fn next_u32(&mut self) -> u32 {
+    #[inline] pub fn next_u32(&mut self) -> u32 {
         let mut x = self.state;
         x ^= x << 13; x ^= x >> 17; x ^= x << 5;
         self.state = x;
@@ -24,12 +38,12 @@ impl Rng32 {
     }
 
     // Uniform float in [0,1); used to scale velocities/lifetimes.
-    #[inline] fn next_f32(&mut self) -> f32 {
+    #[inline] pub fn next_f32(&mut self) -> f32 {
         (self.next_u32() >> 8) as f32 / ((1u32 << 24) as f32)
     }
 
     // Picks a random value in [min,max); used for speeds/angles/jitter.
-    #[inline] fn range(&mut self, min: f32, max: f32) -> f32 {
+    #[inline] pub fn range(&mut self, min: f32, max: f32) -> f32 {
         min + (max - min) * self.next_f32()
     }
 }
@@ -148,9 +162,71 @@ impl DiscKernel {
             }
         }
     }
+
+    /// Blends this disc at (cx,cy) towards `color` by at most `opacity` [0,1].
+    /// Unlike `stamp_additive` it never brightens past the colour itself.
+    /// What you SEE: a hazy see-through patch, like a wisp of smoke.
+    #[inline]
+    fn stamp_blend(
+        &self,
+        fb: &mut FrameBuffer,
+        cx: i32, cy: i32,
+        (base_r, base_g, base_b): (u8, u8, u8),
+        opacity: f32,
+    ) {
+        let o8: u32 = (opacity.clamp(0.0, 1.0) * 255.0).round() as u32;
+        if o8 == 0 { return; }
+
+        let w = fb.width as i32;
+        let h = fb.height as i32;
+        let r = self.radius;
+        let dim = self.dim;
+
+        // Clip the bounding box once; the inner loop then has no bounds checks.
+        let (kx0, kx1) = ((r - cx).max(0), (w - cx + r).min(dim));
+        let (ky0, ky1) = ((r - cy).max(0), (h - cy + r).min(dim));
+        for ky in ky0..ky1 {
+            let row = ((cy + ky - r) * w) as usize;
+            for kx in kx0..kx1 {
+                let w8 = self.weights[(ky * dim + kx) as usize] as u32;
+                let a = (w8 * o8 + 127) / 255; // 0..255: how much smoke over this pixel
+                if a == 0 { continue; }
+
+                let idx = row + (cx + kx - r) as usize;
+                let old = fb.pixels[idx];
+                let mix = |o: u32, c: u8| (o * (255 - a) + c as u32 * a + 127) / 255;
+                let nr = mix((old >> 16) & 0xFF, base_r);
+                let ng = mix((old >>  8) & 0xFF, base_g);
+                let nb = mix( old        & 0xFF, base_b);
+                fb.pixels[idx] = (nr << 16) | (ng << 8) | nb;
+            }
+        }
+    }
+}
+
+/* -------------------- tunables -------------------- */
+
+/// How lively the FX are. What you SEE: more / fewer, longer / shorter, faster / slower
+/// sparkles, and more or less frequent lightning. The defaults are the classic look.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FxParams {
+    pub per_dab: usize,        // sparkles spawned per painted dab
+    pub max_particles: usize,  // cap on sparkles alive at once (cost per frame)
+    pub life: (f32, f32),      // sparkle lifetime range (seconds)
+    pub speed: (f32, f32),     // sparkle launch speed range (px/sec)
+    pub bolt_chance: f32,      // chance of a lightning bolt per dab, 0..1
+    pub bolt_life: f32,        // how long a bolt flashes (seconds)
+    pub smoke_per_dab: f32,    // average smoke puffs per dab (0: no smoke trail)
+}
+
+impl Default for FxParams {
+    fn default() -> Self {
+        Self { per_dab: 12, max_particles: 600, life: (0.35, 0.75), speed: (30.0, 90.0), bolt_chance: 0.03, bolt_life: 0.10,
+               smoke_per_dab: 0.5 }
+    }
 }
 
-/* -------------------- particles (sparkles) + bolt (lightning) -------------------- */
+/* -------------------- particles (sparkles) + bolt (lightning) + puffs (smoke) -------------------- */
 
 /// One sparkle. What you SEE: tiny glow that moves a bit and fades out.
 pub struct Particle {
@@ -159,28 +235,98 @@ pub struct Particle {
     pub life: f32,               // remaining lifetime (seconds)
     pub max_life: f32,           // initial lifetime (for fade)
     pub energy: f32,             // brightness multiplier 0..1
+    pub rgb: (u8, u8, u8),       // colour (the theme's sparkle colour, or confetti)
+}
+
+/// One smoke puff. What you SEE: a faint haze that drifts off, swells and thins out.
+pub struct Puff {
+    pub x: f32, pub y: f32,      // screen position in pixels
+    pub vx: f32, pub vy: f32,    // drift in px/sec (slow)
+    pub life: f32,               // remaining lifetime (seconds)
+    pub max_life: f32,           // initial lifetime (for fade and growth)
+    pub radius: f32,             // radius at birth (pixels); grows to SMOKE_GROWTH× by the end
 }
 
-/// One lightning bolt. What you SEE: jagged bright line that flickers briefly.
+/// Confetti pieces in one clear burst (fewer if the sparkle cap is nearly reached).
+const BURST_COUNT: usize = 90;
+/// How far a burst flies (pixels), whatever the size of the cleared paint: big enough
+/// to read as a burst, small enough never to cover the whole screen.
+const BURST_REACH: (f32, f32) = (40.0, 160.0);
+
+/// Brush speed (px/sec) at which `per_dab` and `bolt_chance` apply as configured.
+const BRUSH_SPEED_REF: f32 = 600.0;
+/// Intensity at a standstill and at most (× `per_dab` / `bolt_chance`).
+const INTENSITY_RANGE: (f32, f32) = (0.25, 2.5);
+
+/// Cap on puffs alive at once (they are big, so each one costs more than a sparkle).
+const SMOKE_MAX_PUFFS: usize = 160;
+/// How much a puff swells over its life.
+const SMOKE_GROWTH: f32 = 2.5;
+/// Opacity of a fresh puff at its centre; fades to 0 with life.
+const SMOKE_OPACITY: f32 = 0.18;
+/// Radii of the smoke discs (pixels); a puff uses the closest one.
+const SMOKE_RADII: [i32; 5] = [8, 11, 15, 20, 26];
+
+/// One lightning bolt. What you SEE: jagged bright lines that flicker briefly.
 pub struct Bolt {
+    pub strokes: Vec<BoltStroke>, // main channel first, then its forks
+    pub ttl: f32,                 // time to live (seconds)
+    pub life: f32,                // initial ttl (for fade)
+}
+
+/// One polyline of a bolt. What you SEE: the main zap (depth 0) or a fork off it
+/// (1, a fork of a fork 2) — each level thinner and dimmer.
+pub struct BoltStroke {
     pub pts: Vec<(f32,f32)>,     // polyline points
-    pub ttl: f32,                // time to live (seconds)
+    pub depth: u8,
+}
+
+/// Forks below the main channel (2: forks of forks, no deeper).
+const BOLT_MAX_DEPTH: u8 = 2;
+/// Chance that a joint of a stroke sprouts a fork.
+const BOLT_FORK_CHANCE: f32 = 0.25;
+/// Per depth: (core disc index, core strength, glow strength). Disc 6 (radius 8) is the glow.
+const BOLT_LOOK: [(usize, f32, f32); 3] = [(1, 1.2, 0.16), (0, 0.7, 0.07), (0, 0.4, 0.0)];
+
+/// One glow disc of this frame's FX, in drawing order: smoke first (blended over the
+/// picture), then sparkles and lightning (added on top).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FxDab {
+    pub x: i32, pub y: i32,      // centre in pixels
+    pub disc: usize,             // which of `Fx::discs`
+    pub rgb: (u8, u8, u8),
+    pub amount: f32,             // 0..1: strength when added, opacity for smoke
+    pub smoke: bool,             // blended towards `rgb` instead of added
 }
 
 /// FX system. What you SEE: all sparkles and the rare lightning on screen.
 pub struct Fx {
     rng: Rng32,
     particles: Vec<Particle>,
-    max_particles: usize,
+    params: FxParams,
     bolt: Option<Bolt>,
+    smoke: Vec<Puff>,
+
+    // How hard the brush is going: px moved since the last frame (None: no dab), and the
+    // resulting multiplier for sparkles and bolts (1 until a stroke is reported).
+    brush_moved: Option<f32>,
+    intensity: f32,
 
     // Precomputed glow discs so stamping is fast (no exp during rendering).
     // We keep a small set that looks good and covers typical sizes.
     kernels: [DiscKernel; 7],    // radii: 2..8 inclusive
+    smoke_kernels: [DiscKernel; SMOKE_RADII.len()], // the wide soft ones for smoke
+
+    sparkle_rgb: (u8, u8, u8),   // warm gold by default (see theme.rs)
+    bolt_rgb: (u8, u8, u8),      // pale blue by default
+    smoke_rgb: (u8, u8, u8),     // light grey by default
+
+    dabs: Vec<FxDab>,            // this frame's discs (see `update`)
 }
 
 impl Fx {
-    /// Create the effect system. What you SEE: nothing yet; ready to spawn FX.
+    /// Create the effect system (default `FxParams`, at most `max_particles` sparkles).
+    /// What you SEE: nothing yet; ready to spawn FX.
     pub fn new(max_particles: usize) -> Self {
         // Build discs once; the cost is paid at startup, never per pixel per frame.
         let kernels = [
@@ -196,71 +342,273 @@ impl Fx {
         Self {
             rng: Rng32::from_seed(0xBADA55),
             particles: Vec::with_capacity(max_particles),
-            max_particles,
+            params: FxParams { max_particles, ..FxParams::default() },
             bolt: None,
+            smoke: Vec::with_capacity(SMOKE_MAX_PUFFS),
+            brush_moved: None,
+            intensity: 1.0,
             kernels,
+            smoke_kernels: SMOKE_RADII.map(DiscKernel::build),
+            sparkle_rgb: rgb(Theme::DARK.sparkle),
+            bolt_rgb: rgb(Theme::DARK.bolt),
+            smoke_rgb: rgb(Theme::DARK.smoke),
+            dabs: Vec::new(),
         }
     }
 
+    /// Sparkle, lightning and smoke colours (0x00RRGGBB) from the theme.
+    /// What you SEE: the same FX, recoloured.
+    pub fn set_colors(&mut self, sparkle: u32, bolt: u32, smoke: u32) {
+        self.sparkle_rgb = rgb(sparkle);
+        self.bolt_rgb = rgb(bolt);
+        self.smoke_rgb = rgb(smoke);
+    }
+
+    pub fn params(&self) -> &FxParams {
+        &self.params
+    }
+
+    /// New tunables (ranges put in order, values clamped to something sane).
+    /// What you SEE: the next sparkles and bolts follow them; live ones finish as they were.
+    pub fn set_params(&mut self, p: FxParams) {
+        let ordered = |(a, b): (f32, f32), lo: f32, hi: f32| {
+            let (a, b) = (a.clamp(lo, hi), b.clamp(lo, hi));
+            (a.min(b), a.max(b))
+        };
+        self.params = FxParams {
+            per_dab: p.per_dab.min(200),
+            max_particles: p.max_particles.min(10_000),
+            life: ordered(p.life, 0.05, 5.0),
+            speed: ordered(p.speed, 0.0, 1000.0),
+            bolt_chance: p.bolt_chance.clamp(0.0, 1.0),
+            bolt_life: p.bolt_life.clamp(0.02, 1.0),
+            smoke_per_dab: p.smoke_per_dab.clamp(0.0, 10.0),
+        };
+        self.particles.truncate(self.params.max_particles);
+    }
+
+    /// The brush dabbed (dx,dy) px away from its last dab; call once per dab. The speed
+    /// over a frame sets the intensity for the next dabs.
+    /// What you SEE: nothing yet; see `sparkles_per_dab`.
+    pub fn brush_moved(&mut self, dx: f32, dy: f32) {
+        *self.brush_moved.get_or_insert(0.0) += dx.hypot(dy);
+    }
+
+    /// `per_dab` scaled by how fast the brush moves.
+    /// What you SEE: a trickle of sparkles on slow strokes, a shower on fast ones.
+    pub fn sparkles_per_dab(&self) -> usize {
+        (self.params.per_dab as f32 * self.intensity).round() as usize
+    }
+
     /// Spawn a handful of warm sparkles at (x,y).
     /// What you SEE: small glows popping at the cursor when you erase.
     pub fn spawn_sparkles(&mut self, x: f32, y: f32, count: usize) {
         for _ in 0..count {
-            if self.particles.len() >= self.max_particles { break; }
+            if self.particles.len() >= self.params.max_particles { break; }
 
             // Random speed and angle → lively motion.
-            let speed = self.rng.range(30.0, 90.0);
+            let speed = self.rng.range(self.params.speed.0, self.params.speed.1);
             let ang = self.rng.range(0.0, std::f32::consts::TAU);
             let vx = speed * ang.cos();
             let vy = speed * ang.sin() - self.rng.range(0.0, 20.0); // slight upward bias
 
             // Lifetime drives fade: short = snappy sparkles.
-            let max_life = self.rng.range(0.35, 0.75);
+            let max_life = self.rng.range(self.params.life.0, self.params.life.1);
 
             self.particles.push(Particle {
                 x, y, vx, vy,
                 life: max_life,
                 max_life,
                 energy: self.rng.range(0.6, 1.0),
+                rgb: self.sparkle_rgb,
+            });
+        }
+    }
+
+    /// Celebrate cleared paint: a burst of confetti flying out from (x,y) about `reach`
+    /// px (clamped to `BURST_REACH`), in the sparkle and lightning colours plus white.
+    /// What you SEE: a short pop of coloured glows where the paint was, falling away.
+    pub fn burst(&mut self, x: f32, y: f32, reach: f32) {
+        let reach = reach.clamp(BURST_REACH.0, BURST_REACH.1);
+        let palette = [self.sparkle_rgb, self.bolt_rgb, (255, 255, 255)];
+        for _ in 0..BURST_COUNT {
+            if self.particles.len() >= self.params.max_particles { break; }
+
+            // Fast at first (drag slows them), tossed upwards so gravity makes them fall back.
+            let speed = reach * self.rng.range(0.6, 1.4);
+            let ang = self.rng.range(0.0, std::f32::consts::TAU);
+            let vx = speed * ang.cos();
+            let vy = speed * ang.sin() - self.rng.range(0.0, reach * 0.5);
+
+            let max_life = self.rng.range(0.6, 1.2);
+            let rgb = palette[self.rng.next_u32() as usize % palette.len()];
+            self.particles.push(Particle {
+                x, y, vx, vy,
+                life: max_life,
+                max_life,
+                energy: self.rng.range(0.8, 1.0),
+                rgb,
+            });
+        }
+    }
+
+    /// Leave smoke behind a dab at (x,y) made while moving by (dx,dy) since the last one.
+    /// `smoke_per_dab` puffs on average (a fraction is a chance of one more).
+    /// What you SEE: faint haze peeling off the back of the brush and drifting up.
+    pub fn spawn_smoke(&mut self, x: f32, y: f32, dx: f32, dy: f32) {
+        let want = self.params.smoke_per_dab;
+        let mut count = want as usize;
+        if self.rng.next_f32() < want.fract() { count += 1; }
+
+        // Drift back along the stroke: faster strokes shed faster smoke (capped).
+        let len = dx.hypot(dy);
+        let (bx, by) = if len > 0.5 { (-dx / len, -dy / len) } else { (0.0, 0.0) };
+        let back = (len * 2.0).min(40.0);
+
+        for _ in 0..count {
+            if self.smoke.len() >= SMOKE_MAX_PUFFS { break; }
+
+            let max_life = self.rng.range(1.2, 2.0);
+            self.smoke.push(Puff {
+                x: x + self.rng.range(-4.0, 4.0),
+                y: y + self.rng.range(-4.0, 4.0),
+                vx: bx * back + self.rng.range(-8.0, 8.0),
+                vy: by * back + self.rng.range(-8.0, 8.0) - self.rng.range(6.0, 14.0), // smoke rises
+                life: max_life,
+                max_life,
+                radius: self.rng.range(7.0, 11.0),
             });
         }
     }
 
+    /// Drop every sparkle, puff and the bolt.
+    /// What you SEE: FX gone at once (nothing left to reappear when they come back on).
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.smoke.clear();
+        self.bolt = None;
+        self.dabs.clear();
+    }
+
     /// Randomly spawn a lightning bolt near (x,y).
     /// What you SEE: an occasional fast “zap” to add excitement.
     pub fn maybe_spawn_bolt(&mut self, x: f32, y: f32) {
-        // ~3% chance per call while erasing (tweak to taste).
-        if self.rng.next_f32() > 0.03 { return; }
+        // ~3% chance per call while erasing by default (`bolt_chance`), more on fast strokes.
+        if self.rng.next_f32() >= (self.params.bolt_chance * self.intensity).min(1.0) { return; }
 
-        let segs = 10;                        // how many segments in the bolt
-        let len  = self.rng.range(40.0, 90.0);// total length (pixels)
+        let len  = self.rng.range(40.0, 90.0);// total length of the main channel (pixels)
         let theta = self.rng.range(0.0, std::f32::consts::TAU);
 
+        let mut strokes = Vec::new();
+        self.grow_bolt(&mut strokes, (x, y), theta, len, 0);
+
+        let life = self.params.bolt_life;
+        self.bolt = Some(Bolt { strokes, ttl: life, life }); // quick flash (~100 ms by default)
+    }
+
+    /// Add a jagged stroke from `from` heading `theta` for about `len` px, then (recursively)
+    /// the shorter forks that leave its joints at an angle.
+    /// What you SEE: lightning that splits like the real thing instead of one scribble.
+    fn grow_bolt(&mut self, strokes: &mut Vec<BoltStroke>, from: (f32, f32), theta: f32, len: f32, depth: u8) {
+        let segs = (10usize >> depth).max(3); // 10 segments on the main channel, fewer on forks
+        let step = len / segs as f32;
         let mut pts = Vec::with_capacity(segs + 1);
-        let (mut px, mut py) = (x, y);
+        let mut forks = Vec::new();
+        let (mut px, mut py) = from;
         pts.push((px, py));
 
         // Build a jagged path with some sideways wobble.
-        for _ in 0..segs {
-            let step = len / segs as f32;
-            let jitter = self.rng.range(-0.6, 0.6);
-            let dir = theta + jitter;
+        for i in 0..segs {
+            let dir = theta + self.rng.range(-0.6, 0.6);
+            px += step * dir.cos() + self.rng.range(-2.0, 2.0);
+            py += step * dir.sin() + self.rng.range(-2.0, 2.0);
+            pts.push((px, py));
 
-            px += step * dir.cos();
-            py += step * dir.sin();
+            // Sometimes a fork leaves this joint, shorter than what is left of the stroke.
+            if depth < BOLT_MAX_DEPTH && i + 1 < segs && self.rng.next_f32() < BOLT_FORK_CHANCE {
+                let side = if self.rng.next_f32() < 0.5 { -1.0 } else { 1.0 };
+                let angle = theta + side * self.rng.range(0.35, 0.9);
+                let rest = step * (segs - i - 1) as f32;
+                forks.push(((px, py), angle, rest * self.rng.range(0.4, 0.7)));
+            }
+        }
+        strokes.push(BoltStroke { pts, depth });
+        for (at, angle, fork_len) in forks {
+            self.grow_bolt(strokes, at, angle, fork_len, depth + 1);
+        }
+    }
 
-            px += self.rng.range(-2.0, 2.0);
-            py += self.rng.range(-2.0, 2.0);
+    /// Update physics and render FX into the framebuffer (smoke blended, the rest additive).
+    /// What you SEE: smoke swells & thins; sparkles drift & fade; bolt flashes then vanishes.
+    pub fn update_and_render(&mut self, fb: &mut FrameBuffer, dt: f32) {
+        self.update(dt);
+        self.render(fb);
+    }
 
-            pts.push((px, py));
-        }
+    /// The glow discs `FxDab::disc` refers to: (radius, weights 0..255 row by row).
+    pub fn discs(&self) -> impl Iterator<Item = (i32, &[u8])> {
+        self.kernels.iter().chain(&self.smoke_kernels).map(|k| (k.radius, k.weights.as_slice()))
+    }
 
-        self.bolt = Some(Bolt { pts, ttl: 0.10 }); // quick flash (~100 ms)
+    /// This frame's discs, as the last `update` listed them.
+    pub fn dabs(&self) -> &[FxDab] {
+        &self.dabs
     }
 
-    /// Update physics and render FX into the framebuffer (additive).
-    /// What you SEE: sparkles drift & fade; bolt flashes then vanishes.
-    pub fn update_and_render(&mut self, fb: &mut FrameBuffer, dt: f32) {
+    /// Stamp the last `update`'s discs into `fb`.
+    /// What you SEE: the FX of this frame over the picture.
+    pub fn render(&self, fb: &mut FrameBuffer) {
+        for d in &self.dabs {
+            match self.kernels.get(d.disc) {
+                Some(kernel) => {
+                    let (r, g, b) = d.rgb;
+                    kernel.stamp_additive(fb, d.x, d.y, r, g, b, d.amount);
+                }
+                None => self.smoke_kernels[d.disc - self.kernels.len()].stamp_blend(fb, d.x, d.y, d.rgb, d.amount),
+            }
+        }
+    }
+
+    /// Update physics and list what to draw this frame (see `dabs`); nothing is drawn.
+    /// What you SEE: nothing until `render` (or the GPU) draws the list.
+    pub fn update(&mut self, dt: f32) {
+        self.dabs.clear();
+        /* ---- Brush speed → intensity (halfway each frame, so uneven frames don't flicker) ---- */
+        if let Some(moved) = self.brush_moved.take() && dt > 0.0 {
+            let speed01 = moved / dt / BRUSH_SPEED_REF;
+            let target = (INTENSITY_RANGE.0 + (1.0 - INTENSITY_RANGE.0) * speed01).min(INTENSITY_RANGE.1);
+            self.intensity += (target - self.intensity) * 0.5;
+        }
+
+        /* ---- Smoke (first, so sparkles and lightning shine through it) ---- */
+        let mut i = 0;
+        while i < self.smoke.len() {
+            let p = &mut self.smoke[i];
+
+            // Slow down quickly (air drag), but keep rising a little.
+            p.x += p.vx * dt;
+            p.y += p.vy * dt;
+            p.vx *= 0.95;
+            p.vy = p.vy * 0.95 - 4.0 * dt;
+            p.life -= dt;
+
+            if p.life > 0.0 {
+                // age01: 0 at birth → 1 at death; the puff swells and thins out.
+                let age01 = 1.0 - (p.life / p.max_life).clamp(0.0, 1.0);
+                let radius = p.radius * (1.0 + (SMOKE_GROWTH - 1.0) * age01);
+                let idx = SMOKE_RADII.iter().position(|&r| r as f32 >= radius).unwrap_or(SMOKE_RADII.len() - 1);
+
+                // Fade in over the first tenth so a puff doesn't pop, then out.
+                let opacity = SMOKE_OPACITY * (age01 * 10.0).min(1.0) * (1.0 - age01);
+
+                let disc = self.kernels.len() + idx;
+                self.dabs.push(FxDab { x: p.x as i32, y: p.y as i32, disc, rgb: self.smoke_rgb, amount: opacity, smoke: true });
+                i += 1;
+            } else {
+                self.smoke.swap_remove(i);
+            }
+        }
+
         /* ---- Particles ---- */
         let mut i = 0;
         while i < self.particles.len() {
@@ -285,16 +633,15 @@ impl Fx {
                 // Bigger near birth, smaller near death (feels like a spark).
                 let desired = (6.0 * life01 + 2.0).round() as i32; // ~2..8
                 let idx = (desired - 2).clamp(0, 6) as usize;
-                let kernel = &self.kernels[idx];
 
                 // Brightness fades with life; energy adds variation.
                 let strength = (0.9 * p.energy * life01).clamp(0.0, 1.0);
 
-                // Warm gold color looks “magical”.
-                let (r, g, b) = (255u8, 200u8, 80u8);
+                // Warm gold color looks “magical” (the theme may pick another; confetti mixes).
+                let rgb = p.rgb;
 
-                // Stamp the disc at the particle position (integer math inside).
-                kernel.stamp_additive(fb, p.x as i32, p.y as i32, r, g, b, strength);
+                // A disc at the particle position (stamped with integer math inside).
+                self.dabs.push(FxDab { x: p.x as i32, y: p.y as i32, disc: idx, rgb, amount: strength, smoke: false });
 
                 i += 1; // keep this particle for the next frame
             } else {
@@ -307,28 +654,34 @@ impl Fx {
         if let Some(b) = &mut self.bolt {
             // Bolt fades quickly (ttl → 0).
             b.ttl -= dt;
-            let s = (b.ttl / 0.10).clamp(0.0, 1.0);
-
-            // Use a small, bright bluish disc to draw along the polyline.
-            let kernel = &self.kernels[1]; // radius 3 → crisp thin bolt
-            let (r, g, bcol) = (210u8, 230u8, 255u8);
-
-            // For each segment, stamp discs every ~2 px to make a continuous line.
-            for seg in 0..b.pts.len().saturating_sub(1) {
-                let (x0, y0) = b.pts[seg];
-                let (x1, y1) = b.pts[seg + 1];
-                let dx = x1 - x0;
-                let dy = y1 - y0;
-                let dist = (dx * dx + dy * dy).sqrt().max(1.0);
-                let steps = (dist / 2.0).ceil() as i32;
-
-                for tstep in 0..=steps {
-                    let t = tstep as f32 / steps as f32;
-                    let x = x0 + dx * t;
-                    let y = y0 + dy * t;
-
-                    // Strength scales with bolt fade (s): starts bright → vanishes.
-                    kernel.stamp_additive(fb, x as i32, y as i32, r, g, bcol, 1.2 * s);
+            let s = (b.ttl / b.life).clamp(0.0, 1.0);
+
+            let rgb = self.bolt_rgb;
+
+            for stroke in &b.strokes {
+                let (core, core_strength, glow_strength) = BOLT_LOOK[(stroke.depth as usize).min(BOLT_LOOK.len() - 1)];
+                // Glow pass: a wide soft disc every ~6 px, faint (the discs' own falloff
+                // makes the halo); then the core: a small bright disc every ~2 px.
+                let passes = [(6, 6.0, glow_strength), (core, 2.0, core_strength)];
+                for (disc, spacing, strength) in passes {
+                    if strength <= 0.0 { continue; }
+                    for seg in 0..stroke.pts.len().saturating_sub(1) {
+                        let (x0, y0) = stroke.pts[seg];
+                        let (x1, y1) = stroke.pts[seg + 1];
+                        let dx = x1 - x0;
+                        let dy = y1 - y0;
+                        let dist = (dx * dx + dy * dy).sqrt().max(1.0);
+                        let steps = (dist / spacing).ceil() as i32;
+
+                        for tstep in 0..=steps {
+                            let t = tstep as f32 / steps as f32;
+                            let x = x0 + dx * t;
+                            let y = y0 + dy * t;
+
+                            // Strength scales with bolt fade (s): starts bright → vanishes.
+                            self.dabs.push(FxDab { x: x as i32, y: y as i32, disc, rgb, amount: strength * s, smoke: false });
+                        }
+                    }
                 }
             }
 
@@ -0,0 +1,94 @@
+// Optional gamepad control (gilrs), built with `cargo run --release --features gamepad`.
+// Visual expectation: plug in a controller and the left stick moves the brush cursor
+// around the window (slowly near the centre, fast at full tilt), holding the right
+// trigger paints, and the bumpers make the brush smaller (LB) or bigger (RB). Touch
+// the mouse and it takes over again; the cursor stays where the mouse put it.
+// For couch / kiosk setups without a mouse.
+
+use crate::window::Input;
+use crate::error::Error;
+use gilrs::{Axis, Button, EventType, Gilrs};
+use std::time::Instant;
+
+/// Stick travel (0..1) that counts as "not touched" (worn sticks never rest at 0).
+const DEADZONE: f32 = 0.15;
+/// Cursor speed at full stick, in window widths per second.
+const CURSOR_SPEED: f32 = 0.6;
+/// Trigger travel (0..1) at which painting starts.
+const TRIGGER_PAINTS: f32 = 0.3;
+
+/// Pad state plus the virtual cursor it drives, in window pixels.
+pub struct Gamepad {
+    gilrs: Gilrs,
+    stick: (f32, f32),
+    trigger: f32,
+    cursor: (f32, f32),
+    driving: bool,                         // the pad moved last (not the mouse)
+    mouse_seen: Option<(usize, usize)>,    // real mouse position last time, to notice it moving
+    last: Instant,
+}
+
+impl Gamepad {
+    pub fn new() -> Result<Self, Error> {
+        let gilrs = Gilrs::new().map_err(|e| Error::Gamepad(format!("Open gamepads: {e}")))?;
+        Ok(Self {
+            gilrs,
+            stick: (0.0, 0.0),
+            trigger: 0.0,
+            cursor: (0.0, 0.0),
+            driving: false,
+            mouse_seen: None,
+            last: Instant::now(),
+        })
+    }
+
+    /// Fold the pad into this window-thread input snapshot (`width` × `height` window):
+    /// while the pad is in use its cursor replaces the mouse position and the trigger
+    /// holds the left button; bumper presses become `brush_steps`.
+    pub fn apply(&mut self, input: &mut Input, width: usize, height: usize) {
+        let dt = self.last.elapsed().as_secs_f32().min(0.1); // no jump after a stall
+        self.last = Instant::now();
+        while let Some(event) = self.gilrs.next_event() {
+            match event.event {
+                EventType::AxisChanged(Axis::LeftStickX, v, _) => self.stick.0 = v,
+                EventType::AxisChanged(Axis::LeftStickY, v, _) => self.stick.1 = v,
+                EventType::ButtonChanged(Button::RightTrigger2, v, _) => self.trigger = v,
+                EventType::ButtonPressed(Button::LeftTrigger, _) => input.brush_steps -= 1,  // visual: brush shrinks
+                EventType::ButtonPressed(Button::RightTrigger, _) => input.brush_steps += 1, // visual: brush grows
+                EventType::Disconnected => {
+                    self.stick = (0.0, 0.0);
+                    self.trigger = 0.0;
+                }
+                _ => {}
+            }
+        }
+
+        // The mouse moved: it is in charge again, and the pad continues from there.
+        if input.mouse_pos != self.mouse_seen {
+            self.mouse_seen = input.mouse_pos;
+            if let Some((x, y)) = input.mouse_pos {
+                self.cursor = (x as f32, y as f32);
+            }
+            self.driving = false;
+        }
+
+        // Rescale past the dead zone, then square: fine control near the centre.
+        let shape = |v: f32| {
+            let v = ((v.abs() - DEADZONE) / (1.0 - DEADZONE)).max(0.0);
+            v * v
+        };
+        let (sx, sy) = (shape(self.stick.0) * self.stick.0.signum(), shape(self.stick.1) * self.stick.1.signum());
+        let painting = self.trigger >= TRIGGER_PAINTS;
+        if sx != 0.0 || sy != 0.0 || painting {
+            self.driving = true;
+        }
+        if !self.driving || width == 0 || height == 0 {
+            return;
+        }
+        let step = CURSOR_SPEED * width as f32 * dt;
+        self.cursor.0 = (self.cursor.0 + sx * step).clamp(0.0, (width - 1) as f32);
+        self.cursor.1 = (self.cursor.1 - sy * step).clamp(0.0, (height - 1) as f32); // stick up = +y
+        input.mouse_pos = Some((self.cursor.0 as usize, self.cursor.1 as usize)); // visual: cursor follows
+        input.left_mouse_down |= painting;                                         // visual: paints
+    }
+}
@@ -1,12 +1,29 @@
 // Speeds up gamma-correct blending by replacing powf with table lookups.
 // Visual: identical to before (gamma-correct edges), but much faster.
 
+/// Alpha levels in the precomputed blend table (64 → steps of ~1.6%, invisible on feathers).
+pub const BLEND_BUCKETS: usize = 64;
+
 pub struct GammaLut {
     // sRGB(0..255) -> linear (0..1) as f32
     srgb_to_linear: [f32; 256],
     // linear(0..1) -> sRGB(0..255) via 4096-step quantization
     // (index = (linear * 4095).round())
     linear_to_srgb: [u8; 4096],
+    // sRGB(0..255) -> linear as 12-bit integer (0..4095), same scale as the table above,
+    // so integer pipelines (linear blur) can go there and back with two lookups
+    srgb_to_linear12: [u16; 256],
+    // Whole linear-light blend per alpha level: [bucket][sink << 8 | live] → sRGB result.
+    // BLEND_BUCKETS × 64 KiB = 4 MiB, built once; the blend loop is then lookups only.
+    blend: Vec<u8>,
+    // 8-bit alpha → nearest bucket in `blend`
+    alpha_bucket: [u8; 256],
+}
+
+impl Default for GammaLut {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GammaLut {
@@ -28,7 +45,37 @@ impl GammaLut {
             l2s[i] = v;
         }
 
-        Self { srgb_to_linear: s2l, linear_to_srgb: l2s }
+        // sRGB -> 12-bit linear (integer version of the first table)
+        let mut s2l12 = [0u16; 256];
+        for v in 0..=255 {
+            s2l12[v] = (s2l[v] * 4095.0).round() as u16;
+        }
+
+        // Blend table: for each alpha bucket, every (sink, live) pair mixed in 12-bit linear.
+        let last = (BLEND_BUCKETS - 1) as u32;
+        let mut blend = vec![0u8; BLEND_BUCKETS * 256 * 256];
+        for (b, table) in blend.chunks_exact_mut(256 * 256).enumerate() {
+            let a = (b as u32 * 255 + last / 2) / last; // bucket's alpha on the 0..255 scale
+            for s in 0..256 {
+                for f in 0..256 {
+                    let l = (s2l12[s] as u32 * a + s2l12[f] as u32 * (255 - a) + 127) / 255;
+                    table[(s << 8) | f] = l2s[l as usize];
+                }
+            }
+        }
+        let mut alpha_bucket = [0u8; 256];
+        for (a, bucket) in alpha_bucket.iter_mut().enumerate() {
+            *bucket = ((a as u32 * last + 127) / 255) as u8;
+        }
+
+        Self { srgb_to_linear: s2l, linear_to_srgb: l2s, srgb_to_linear12: s2l12, blend, alpha_bucket }
+    }
+
+    /// The 64 KiB blend table for mask alpha `a`: index with `(sink << 8) | live` per channel.
+    #[inline]
+    pub fn blend_table(&self, a: u8) -> &[u8] {
+        let b = self.alpha_bucket[a as usize] as usize;
+        &self.blend[b * 65536..(b + 1) * 65536]
     }
 
     #[inline]
@@ -42,4 +89,14 @@ impl GammaLut {
         let idx = (l.clamp(0.0, 1.0) * 4095.0).round() as usize;
         self.linear_to_srgb[idx]
     }
+
+    #[inline]
+    pub fn srgb_u8_to_linear12(&self, v: u8) -> u16 {
+        self.srgb_to_linear12[v as usize]
+    }
+
+    #[inline]
+    pub fn linear12_to_srgb_u8(&self, l: u16) -> u8 {
+        self.linear_to_srgb[(l as usize).min(4095)]
+    }
 }
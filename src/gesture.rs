@@ -0,0 +1,123 @@
+// Mouse gestures: what a click or drag means, given the buttons and modifier keys.
+// Visual expectation:
+//   Left drag             paints the brush effect
+//   Ctrl + left drag      removes paint again (subtract brush)
+//   Shift + left drag     a straight line from where the drag began — a thin line
+//                         previews it, releasing the button paints it
+//   Shift + left click    a straight line from where the last stroke ended
+//   Alt + left click      sets the clone / heal source (as the right button does)
+//   Double-click          clears the paint in a circle under the cursor
+// Everything is decided here from one `Input` snapshot per frame; the processing loop
+// only carries out the resulting `Gesture`.
+
+use crate::window::Input;
+use std::time::{Duration, Instant};
+
+/// Second click within this time (and DOUBLE_CLICK_SLOP pixels) = double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(350);
+const DOUBLE_CLICK_SLOP: i32 = 6;
+
+pub type Point = (i32, i32);
+
+/// One frame's worth of mouse meaning, in frame pixels.
+pub enum Gesture {
+    /// Dab along from → to (a single dab when they are equal). `start`: first dab of a stroke.
+    Paint { from: Point, to: Point, subtract: bool, start: bool },
+    /// Clear the mask in a circle around this point.
+    ClearAt(Point),
+    /// Put the clone / heal source here.
+    CloneSource(Point),
+}
+
+/// What the current left-button drag is doing.
+#[derive(Clone, Copy, PartialEq)]
+enum Drag {
+    Idle,
+    Paint,
+    Line(Point), // Shift: anchored at the press
+    Ignore,      // used up (Alt click, double-click, eyedropper click)
+}
+
+/// Button edges, the current drag and where the last stroke ended, across frames.
+pub struct Gestures {
+    left_was_down: bool,
+    drag: Drag,
+    cursor: Option<Point>,
+    last_dab: Option<Point>,
+    last_press: Option<(Instant, Point)>,
+}
+
+impl Default for Gestures {
+    fn default() -> Self {
+        Self { left_was_down: false, drag: Drag::Idle, cursor: None, last_dab: None, last_press: None }
+    }
+}
+
+impl Gestures {
+    /// Interpret this frame's mouse state (`input.mouse_pos` already in frame pixels).
+    pub fn interpret(&mut self, input: &Input, now: Instant) -> Option<Gesture> {
+        let down = input.left_mouse_down;
+        let (pressed, released) = (down && !self.left_was_down, !down && self.left_was_down);
+        self.left_was_down = down;
+        self.cursor = input.mouse_pos.map(|(x, y)| (x as i32, y as i32));
+        let pos = self.cursor?;
+
+        if input.right_mouse_down {
+            return Some(Gesture::CloneSource(pos));                 // visual: source follows the mouse
+        }
+        if pressed {
+            let double = self.last_press.take().is_some_and(|(t, p)| {
+                now.duration_since(t) <= DOUBLE_CLICK
+                    && (p.0 - pos.0).abs() <= DOUBLE_CLICK_SLOP
+                    && (p.1 - pos.1).abs() <= DOUBLE_CLICK_SLOP
+            });
+            if double {
+                self.drag = Drag::Ignore;
+                return Some(Gesture::ClearAt(pos));                 // visual: a hole in the paint
+            }
+            self.last_press = Some((now, pos));
+            if input.mods.alt {
+                self.drag = Drag::Ignore;
+                return Some(Gesture::CloneSource(pos));
+            }
+            self.drag = if input.mods.shift { Drag::Line(pos) } else { Drag::Paint };
+        }
+
+        let subtract = input.mods.ctrl;
+        match self.drag {
+            Drag::Paint if down => {
+                self.last_dab = Some(pos);
+                Some(Gesture::Paint { from: pos, to: pos, subtract, start: pressed })
+            }
+            Drag::Line(anchor) if released => {
+                self.drag = Drag::Idle;
+                // Barely moved: a Shift+click, which continues from the last stroke.
+                let clicked = (anchor.0 - pos.0).abs() <= DOUBLE_CLICK_SLOP && (anchor.1 - pos.1).abs() <= DOUBLE_CLICK_SLOP;
+                let from = if clicked { self.last_dab.unwrap_or(pos) } else { anchor };
+                self.last_dab = Some(pos);
+                Some(Gesture::Paint { from, to: pos, subtract, start: true })
+            }
+            _ => {
+                if !down {
+                    self.drag = Drag::Idle;
+                }
+                None
+            }
+        }
+    }
+
+    /// The press under way was used up elsewhere (an eyedropper click): the rest of
+    /// the drag paints nothing, and the press doesn't count towards a double-click.
+    pub fn consume_press(&mut self) {
+        self.drag = Drag::Ignore;
+        self.last_press = None;
+    }
+
+    /// The straight line a Shift+drag would paint right now (anchor, cursor), for a preview.
+    pub fn line_preview(&self) -> Option<(Point, Point)> {
+        match (self.drag, self.cursor) {
+            (Drag::Line(anchor), Some(cursor)) => Some((anchor, cursor)),
+            _ => None,
+        }
+    }
+}
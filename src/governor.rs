@@ -0,0 +1,82 @@
+// Adaptive quality: keep the frame rate up when the machine can't keep up.
+// Visual expectation: on a slow laptop (or with a huge blur radius) the picture
+// first gets a slightly softer blur, then loses sparkles/grain, instead of the
+// whole feed stuttering. Once there is time to spare again, quality comes back
+// one step at a time. The HUD shows AUTO n while a step is active.
+//
+// How: smooth the per-frame processing time, compare it with the frame budget,
+// and move one level at a time with hysteresis (slow to restore, so it doesn't flicker).
+
+use crate::pyramid::BlurQuality;
+use std::time::Duration;
+
+/// Processing time we aim to stay under: one camera frame at `fps` (`--fps`, 30 by default).
+pub fn frame_budget(fps: u32) -> Duration {
+    Duration::from_secs_f64(1.0 / fps.max(1) as f64)
+}
+
+/// Highest degradation level (see `Governor::blur_quality` / `skip_fx`).
+const MAX_LEVEL: u8 = 3;
+
+/// Frames to wait after a change before degrading further / restoring.
+const DEGRADE_HOLD: u32 = 15;
+const RESTORE_HOLD: u32 = 90;
+
+/// Smoothing factor for the frame-time average (higher = reacts faster).
+const EMA_ALPHA: f32 = 0.1;
+
+pub struct Governor {
+    budget: f32,   // seconds
+    avg: f32,      // smoothed processing time, seconds
+    level: u8,     // 0 = full quality
+    since_change: u32,
+}
+
+impl Governor {
+    pub fn new(budget: Duration) -> Self {
+        let budget = budget.as_secs_f32();
+        Self { budget, avg: budget * 0.5, level: 0, since_change: 0 }
+    }
+
+    /// Feed one frame's processing time; may move the level up or down by one.
+    pub fn record(&mut self, frame_time: Duration) {
+        self.avg += (frame_time.as_secs_f32() - self.avg) * EMA_ALPHA;
+        self.since_change = self.since_change.saturating_add(1);
+
+        if self.avg > self.budget * 1.1 && self.level < MAX_LEVEL && self.since_change >= DEGRADE_HOLD {
+            self.level += 1;                 // visual: a notch softer/plainer
+            self.since_change = 0;
+            log::info!("quality down to level {} ({:.1} ms/frame, budget {:.1})", self.level, self.avg * 1e3, self.budget * 1e3);
+        } else if self.avg < self.budget * 0.6 && self.level > 0 && self.since_change >= RESTORE_HOLD {
+            self.level -= 1;                 // visual: detail comes back
+            self.since_change = 0;
+            log::info!("quality up to level {} ({:.1} ms/frame)", self.level, self.avg * 1e3);
+        }
+    }
+
+    /// Current degradation level (0 = full quality).
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// The user's blur quality, lowered to what the governor allows
+    /// (level 1: blur at half resolution, level 2+: quarter).
+    pub fn blur_quality(&self, wanted: BlurQuality) -> BlurQuality {
+        let floor = match self.level {
+            0 => BlurQuality::Full,
+            1 => BlurQuality::Half,
+            _ => BlurQuality::Quarter,
+        };
+        if floor.depth() > wanted.depth() { floor } else { wanted }
+    }
+
+    /// Level 3: drop the decorative extras (sparkles/bolts, film grain).
+    pub fn skip_fx(&self) -> bool {
+        self.level >= 3
+    }
+
+    /// Short HUD tag ("" at full quality).
+    pub fn label(&self) -> String {
+        if self.level == 0 { String::new() } else { format!(" AUTO {}", self.level) }
+    }
+}
@@ -0,0 +1,403 @@
+// Optional GPU path (wgpu compute shaders), built with `cargo run --release --features gpu`.
+// Visual expectation: identical to the CPU BLUR brush (box blur + linear-light blend)
+// and FX, but the heavy per-pixel work runs on the graphics card, so 1080p stays smooth.
+//
+// Per frame: upload live pixels + mask (+ this frame's FX discs) → blur rows → blur
+// columns → blend → FX → read back. The FX pass uses fx.rs's integer maths, so the
+// sparkles, smoke and lightning look the same as when the CPU stamps them; interactive.rs
+// hands them over only when nothing runs between blend and FX (no --post filter on,
+// no `clean` output). HUD and the other brush effects still run on the CPU.
+// Only the BOX blur at full Q quality without LIN, up to the pyramid's radius, is done
+// here; with STACK, LIN, HALF or QUARTER (or the governor lowering quality), or a radius
+// above 16 that the CPU blurs through the pyramid, interactive.rs blurs on the CPU
+// instead, so the HUD always names what is on screen.
+
+use crate::error::Error;
+use crate::fx::FxDab;
+use crate::types::{FrameBuffer, Mask};
+use std::borrow::Cow;
+use std::sync::mpsc;
+
+/// All four passes in one module; they share a single bind group layout.
+const SHADER: &str = r#"
+struct Params { width: u32, height: u32, radius: u32, dabs: u32, smoke: u32, _pad0: u32, _pad1: u32, _pad2: u32 };
+
+// One FX disc (`fx::FxDab`): centre, disc radius, where its weights start in `discs`,
+// colour and strength/opacity 0..255.
+struct Dab { x: i32, y: i32, radius: i32, offset: u32, rgb: u32, amount: u32 };
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> live: array<u32>;
+@group(0) @binding(2) var<storage, read_write> tmp: array<u32>;
+@group(0) @binding(3) var<storage, read_write> blurred: array<u32>;
+@group(0) @binding(4) var<storage, read> mask: array<f32>;
+@group(0) @binding(5) var<storage, read_write> out_px: array<u32>;
+@group(0) @binding(6) var<storage, read> dabs: array<Dab>;
+@group(0) @binding(7) var<storage, read> discs: array<u32>;
+
+var<workgroup> tile_dabs: array<u32, 256>;
+var<workgroup> tile_count: atomic<u32>;
+
+fn unpack(p: u32) -> vec3<f32> {
+    return vec3<f32>(f32((p >> 16u) & 255u), f32((p >> 8u) & 255u), f32(p & 255u));
+}
+
+fn pack(c: vec3<f32>) -> u32 {
+    let v = vec3<u32>(clamp(round(c), vec3<f32>(0.0), vec3<f32>(255.0)));
+    return (v.x << 16u) | (v.y << 8u) | v.z;
+}
+
+fn to_linear(c: vec3<f32>) -> vec3<f32> {
+    let s = c / 255.0;
+    return select(pow((s + 0.055) / 1.055, vec3<f32>(2.4)), s / 12.92, s <= vec3<f32>(0.04045));
+}
+
+fn to_srgb(l: vec3<f32>) -> vec3<f32> {
+    let c = clamp(l, vec3<f32>(0.0), vec3<f32>(1.0));
+    return 255.0 * select(1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055, 12.92 * c, c <= vec3<f32>(0.0031308));
+}
+
+// Horizontal box blur, edges extended (same integer averaging as the CPU blur).
+@compute @workgroup_size(16, 16)
+fn blur_h(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) { return; }
+    let r = i32(params.radius);
+    let row = id.y * params.width;
+    let last = i32(params.width) - 1;
+    var sum = vec3<f32>(0.0);
+    for (var dx = -r; dx <= r; dx++) {
+        sum += unpack(live[row + u32(clamp(i32(id.x) + dx, 0, last))]);
+    }
+    tmp[row + id.x] = pack(floor(sum / f32(2 * r + 1)));
+}
+
+// Vertical box blur of the horizontal result.
+@compute @workgroup_size(16, 16)
+fn blur_v(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) { return; }
+    let r = i32(params.radius);
+    let last = i32(params.height) - 1;
+    var sum = vec3<f32>(0.0);
+    for (var dy = -r; dy <= r; dy++) {
+        let y = u32(clamp(i32(id.y) + dy, 0, last));
+        sum += unpack(tmp[y * params.width + id.x]);
+    }
+    blurred[id.y * params.width + id.x] = pack(floor(sum / f32(2 * r + 1)));
+}
+
+// Mix live towards blurred by the mask, in linear light (no dark halos).
+@compute @workgroup_size(16, 16)
+fn blend(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) { return; }
+    let i = id.y * params.width + id.x;
+    let a = clamp(mask[i], 0.0, 1.0);
+    if (a <= 0.0) { out_px[i] = live[i]; return; }
+    if (a >= 1.0) { out_px[i] = blurred[i]; return; }
+    let fg = to_linear(unpack(live[i]));
+    let bg = to_linear(unpack(blurred[i]));
+    out_px[i] = pack(to_srgb(mix(fg, bg, a)));
+}
+
+fn channels(p: u32) -> vec3<u32> {
+    return vec3<u32>((p >> 16u) & 255u, (p >> 8u) & 255u, p & 255u);
+}
+
+// Weight 0..255 of `d`'s disc at (x, y); 0 outside it.
+fn disc_weight(d: Dab, x: i32, y: i32) -> u32 {
+    let dim = 2 * d.radius + 1;
+    let kx = x - d.x + d.radius;
+    let ky = y - d.y + d.radius;
+    if (kx < 0 || ky < 0 || kx >= dim || ky >= dim) { return 0u; }
+    return discs[d.offset + u32(ky * dim + kx)];
+}
+
+// FX over the blended picture, with fx.rs's integer maths: the smoke blended in order,
+// then sparkles and lightning summed and added (clamped, like the CPU's saturating adds).
+// Each workgroup first keeps only the discs that touch its 16x16 tile, 256 at a time.
+@compute @workgroup_size(16, 16)
+fn fx(@builtin(global_invocation_id) id: vec3<u32>, @builtin(workgroup_id) group: vec3<u32>,
+      @builtin(local_invocation_index) li: u32) {
+    let inside = id.x < params.width && id.y < params.height;
+    let i = min(id.y * params.width + id.x, params.width * params.height - 1u);
+    let x = i32(id.x);
+    let y = i32(id.y);
+    var c = channels(out_px[i]);
+    for (var k = 0u; k < params.smoke; k++) {
+        let d = dabs[k];
+        let a = (disc_weight(d, x, y) * d.amount + 127u) / 255u;
+        if (a > 0u) {
+            c = (c * (255u - a) + channels(d.rgb) * a + 127u) / 255u;
+        }
+    }
+    let tile = vec2<i32>(group.xy * 16u);
+    var glow = vec3<u32>(0u);
+    for (var first = params.smoke; first < params.dabs; first += 256u) {
+        if (li == 0u) { atomicStore(&tile_count, 0u); }
+        workgroupBarrier();
+        let k = first + li;
+        if (k < params.dabs) {
+            let d = dabs[k];
+            if (d.x + d.radius >= tile.x && d.x - d.radius < tile.x + 16
+                && d.y + d.radius >= tile.y && d.y - d.radius < tile.y + 16) {
+                tile_dabs[atomicAdd(&tile_count, 1u)] = k;
+            }
+        }
+        workgroupBarrier();
+        let n = atomicLoad(&tile_count);
+        for (var j = 0u; j < n; j++) {
+            let d = dabs[tile_dabs[j]];
+            let w = (disc_weight(d, x, y) * d.amount + 127u) / 255u;
+            glow += (channels(d.rgb) * w + 127u) / 255u;
+        }
+        workgroupBarrier();
+    }
+    if (inside) {
+        let v = min(c + glow, vec3<u32>(255u));
+        out_px[i] = (v.x << 16u) | (v.y << 8u) | v.z;
+    }
+}
+"#;
+
+/// Side length of a compute workgroup (must match `@workgroup_size` above).
+const GROUP: u32 = 16;
+
+/// Bytes per FX disc on the GPU (six 4-byte fields, see `Dab` above).
+const DAB_BYTES: u64 = 24;
+
+/// FX discs the first dab buffer holds; it grows when a frame has more.
+const INITIAL_DABS: u64 = 1024;
+
+/// GPU blur + blend + FX for one fixed frame size.
+pub struct GpuBlend {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    blur_h: wgpu::ComputePipeline,
+    blur_v: wgpu::ComputePipeline,
+    blend: wgpu::ComputePipeline,
+    fx: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    params: wgpu::Buffer,
+    live: wgpu::Buffer,
+    tmp: wgpu::Buffer,
+    blurred: wgpu::Buffer,
+    mask: wgpu::Buffer,
+    out: wgpu::Buffer,
+    dabs: wgpu::Buffer,         // this frame's FX discs (`dab_capacity` of them fit)
+    dab_capacity: u64,
+    discs: wgpu::Buffer,        // every FX disc's weights, one u32 each
+    disc_at: Vec<(u32, i32)>,   // per `Fx::discs` entry: where it starts in `discs`, radius
+    readback: wgpu::Buffer,     // CPU-mappable copy of `out`
+    width: usize,
+    height: usize,
+    upload: Vec<u8>,            // reused byte staging for uploads
+}
+
+impl GpuBlend {
+    /// Pick a GPU and build the pipelines for `width`×`height` frames, with the FX
+    /// glow discs (`Fx::discs`) uploaded once.
+    /// Fails (so the caller can fall back to the CPU) when no adapter is available.
+    pub fn new<'a>(width: usize, height: usize, fx_discs: impl Iterator<Item = (i32, &'a [u8])>) -> Result<Self, Error> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))
+        .ok_or_else(|| Error::Gpu("no suitable GPU adapter".into()))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("magic-eraser"),
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default(),
+                memory_hints: wgpu::MemoryHints::Performance,
+            },
+            None,
+        ))
+        .map_err(|e| Error::Gpu(e.to_string()))?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("blur+blend+fx"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+
+        // One layout for all passes: 0 = params, 1..=7 = the storage buffers above.
+        let storage = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("blur+blend layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, false),
+                storage(3, false),
+                storage(4, true),
+                storage(5, false),
+                storage(6, true),
+                storage(7, true),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("blur+blend pipeline layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry: &str| device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(entry),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: entry,
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let (blur_h, blur_v, blend, fx) = (pipeline("blur_h"), pipeline("blur_v"), pipeline("blend"), pipeline("fx"));
+
+        let frame_bytes = (width * height * 4) as u64; // u32 pixels and f32 alpha (on the GPU) are both 4 bytes
+        let buffer = |label: &str, size: u64, usage: wgpu::BufferUsages| device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage,
+            mapped_at_creation: false,
+        });
+        let rw = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST;
+        let params = buffer("params", 32, wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST);
+        let live = buffer("live", frame_bytes, rw);
+        let tmp = buffer("tmp", frame_bytes, wgpu::BufferUsages::STORAGE);
+        let blurred = buffer("blurred", frame_bytes, wgpu::BufferUsages::STORAGE);
+        let mask = buffer("mask", frame_bytes, rw);
+        let out = buffer("out", frame_bytes, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC);
+        let readback = buffer("readback", frame_bytes, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+        let dabs = buffer("fx dabs", INITIAL_DABS * DAB_BYTES, rw);
+
+        // The disc weights never change: all of them, back to back, uploaded now.
+        let (mut weights, mut disc_at) = (Vec::new(), Vec::new());
+        for (radius, w) in fx_discs {
+            disc_at.push(((weights.len() / 4) as u32, radius));
+            weights.extend(w.iter().flat_map(|&v| (v as u32).to_le_bytes()));
+        }
+        weights.resize(weights.len().max(4), 0);          // a binding can't be empty
+        let discs = buffer("fx discs", weights.len() as u64, rw);
+        queue.write_buffer(&discs, 0, &weights);
+
+        let bind_group = Self::bind(&device, &layout, [&params, &live, &tmp, &blurred, &mask, &out, &dabs, &discs]);
+        Ok(Self {
+            device, queue, blur_h, blur_v, blend, fx, layout, bind_group,
+            params, live, tmp, blurred, mask, out, dabs, dab_capacity: INITIAL_DABS, discs, disc_at, readback,
+            width, height, upload: Vec::with_capacity(width * height * 4),
+        })
+    }
+
+    /// The bind group for `buffers`, in binding order (0 = params … 7 = disc weights).
+    fn bind(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, buffers: [&wgpu::Buffer; 8]) -> wgpu::BindGroup {
+        let entries: Vec<wgpu::BindGroupEntry> = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, b)| wgpu::BindGroupEntry { binding: i as u32, resource: b.as_entire_binding() })
+            .collect();
+        device.create_bind_group(&wgpu::BindGroupDescriptor { label: Some("blur+blend+fx bindings"), layout, entries: &entries })
+    }
+
+    /// Upload this frame's FX discs (growing the buffer if they don't fit); returns
+    /// how many there are and how many of them, at the start, are smoke.
+    fn upload_dabs(&mut self, dabs: &[FxDab]) -> (u32, u32) {
+        if dabs.len() as u64 > self.dab_capacity {
+            self.dab_capacity = (dabs.len() as u64).next_power_of_two();
+            self.dabs = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("fx dabs"),
+                size: self.dab_capacity * DAB_BYTES,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            self.bind_group = Self::bind(&self.device, &self.layout, [
+                &self.params, &self.live, &self.tmp, &self.blurred, &self.mask, &self.out, &self.dabs, &self.discs,
+            ]);
+        }
+        self.upload.clear();
+        for d in dabs {
+            let (offset, radius) = self.disc_at[d.disc];
+            let (r, g, b) = d.rgb;
+            let amount = (d.amount.clamp(0.0, 1.0) * 255.0).round() as u32; // as fx.rs quantises it
+            let fields = [d.x as u32, d.y as u32, radius as u32, offset, (r as u32) << 16 | (g as u32) << 8 | b as u32, amount];
+            self.upload.extend(fields.iter().flat_map(|v| v.to_le_bytes()));
+        }
+        if !self.upload.is_empty() {
+            self.queue.write_buffer(&self.dabs, 0, &self.upload);
+        }
+        (dabs.len() as u32, dabs.iter().take_while(|d| d.smoke).count() as u32)
+    }
+
+    /// BLUR brush on the GPU: `out` = blend(live, boxblur(live, radius), mask), with
+    /// `fx` (this frame's `Fx::dabs`, or none) composited on top.
+    /// What you SEE: the same picture `box_blur_rgb` + `blend_linear_in_place` and
+    /// `Fx::render` would give.
+    pub fn blur_blend(&mut self, live: &FrameBuffer, mask: &Mask, radius: usize, fx: &[FxDab], out: &mut FrameBuffer) -> Result<(), Error> {
+        if live.width != self.width || live.height != self.height
+            || out.width != self.width || out.height != self.height
+            || mask.width != self.width || mask.height != self.height
+        {
+            return Err(Error::Gpu("blur_blend: frame size differs from the GPU buffers".into()));
+        }
+
+        // Upload: FX discs, params, live pixels, mask alpha (little-endian, as WGSL expects).
+        let (dabs, smoke) = self.upload_dabs(fx);
+        let params = [self.width as u32, self.height as u32, radius as u32, dabs, smoke, 0, 0, 0];
+        self.upload.clear();
+        self.upload.extend(params.iter().flat_map(|v| v.to_le_bytes()));
+        self.queue.write_buffer(&self.params, 0, &self.upload);
+        self.upload.clear();
+        self.upload.extend(live.pixels.iter().flat_map(|p| p.to_le_bytes()));
+        self.queue.write_buffer(&self.live, 0, &self.upload);
+        self.upload.clear();
+        // WGSL has no byte arrays, so alpha goes up as f32 0..1.
+        self.upload.extend(mask.alpha.iter().flat_map(|&a| (a as f32 / 255.0).to_le_bytes()));
+        self.queue.write_buffer(&self.mask, 0, &self.upload);
+
+        // Three passes (four with FX), then copy the result somewhere the CPU can read it.
+        let groups = ((self.width as u32).div_ceil(GROUP), (self.height as u32).div_ceil(GROUP));
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("frame") });
+        let passes = [&self.blur_h, &self.blur_v, &self.blend, &self.fx];
+        for pipeline in &passes[..if dabs > 0 { 4 } else { 3 }] {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None, timestamp_writes: None });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups(groups.0, groups.1, 1);
+        }
+        let bytes = (self.width * self.height * 4) as u64;
+        encoder.copy_buffer_to_buffer(&self.out, 0, &self.readback, 0, bytes);
+        self.queue.submit(Some(encoder.finish()));
+
+        // Read back (blocks until the GPU is done with this frame).
+        let slice = self.readback.slice(..);
+        let (tx, rx) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |r| { let _ = tx.send(r); });
+        let _ = self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()
+            .map_err(|e| Error::Gpu(e.to_string()))?
+            .map_err(|e| Error::Gpu(e.to_string()))?;
+        {
+            let data = slice.get_mapped_range();
+            for (p, b) in out.pixels.iter_mut().zip(data.chunks_exact(4)) {
+                *p = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            }
+        }
+        self.readback.unmap();
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+// Composition guides for framing yourself on camera.
+// Visual expectation: F5 cycles thin see-through lines (white by default) over the picture:
+// rule-of-thirds grid → + a centre cross → + safe margins (the inner boxes TV and
+// streaming overlays keep clear) → off. Each can also be ticked in the S panel.
+// Guides are drawn on the window, so they don't move when zooming.
+
+use crate::draw::fill_rect_blend;
+use crate::types::FrameBuffer;
+
+/// Line opacity (see-through, so they never hide what's under them).
+const GUIDE_ALPHA: u8 = 90;
+
+/// Action-safe and title-safe areas as a share of the frame trimmed from each side.
+const ACTION_SAFE: f32 = 0.035;
+const TITLE_SAFE: f32 = 0.05;
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Guides {
+    pub thirds: bool,
+    pub center: bool,
+    pub safe: bool,
+}
+
+impl Guides {
+    /// F5: off → thirds → thirds + centre → everything → off.
+    pub fn cycle(&mut self) {
+        *self = match (self.thirds, self.center, self.safe) {
+            (false, false, false) => Guides { thirds: true, center: false, safe: false },
+            (true, false, false) => Guides { thirds: true, center: true, safe: false },
+            (true, true, false) => Guides { thirds: true, center: true, safe: true },
+            _ => Guides::default(),
+        };
+    }
+
+    pub fn any(&self) -> bool {
+        self.thirds || self.center || self.safe
+    }
+
+    /// Draw the enabled guides over the whole of `fb` in `color`; `scale` is the line width.
+    pub fn draw(&self, fb: &mut FrameBuffer, scale: i32, color: u32) {
+        let (w, h, t) = (fb.width as i32, fb.height as i32, scale.max(1));
+        let hline = |fb: &mut FrameBuffer, x0: i32, x1: i32, y: i32| {
+            fill_rect_blend(fb, x0, y - t / 2, x1 - x0, t, color, GUIDE_ALPHA);
+        };
+        let vline = |fb: &mut FrameBuffer, x: i32, y0: i32, y1: i32| {
+            fill_rect_blend(fb, x - t / 2, y0, t, y1 - y0, color, GUIDE_ALPHA);
+        };
+
+        if self.thirds {
+            // Visual: a # dividing the picture into nine equal parts.
+            for i in 1..3 {
+                vline(fb, w * i / 3, 0, h);
+                hline(fb, 0, w, h * i / 3);
+            }
+        }
+        if self.center {
+            // Visual: a small + in the middle of the frame.
+            let arm = w.min(h) / 20;
+            hline(fb, w / 2 - arm, w / 2 + arm, h / 2);
+            vline(fb, w / 2, h / 2 - arm, h / 2 + arm);
+        }
+        if self.safe {
+            // Visual: two nested boxes; keep faces and text inside the inner one.
+            for share in [ACTION_SAFE, TITLE_SAFE] {
+                let (mx, my) = ((w as f32 * share) as i32, (h as f32 * share) as i32);
+                hline(fb, mx, w - mx, my);
+                hline(fb, mx, w - mx, h - my);
+                vline(fb, mx, my, h - my);
+                vline(fb, w - mx, my, h - my);
+            }
+        }
+    }
+}
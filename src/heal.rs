@@ -0,0 +1,72 @@
+// Clone stamp + healing brush sinks.
+// Visual expectation:
+// - CLONE: painted areas show the frame shifted by a fixed offset (texture copied
+//   from the source point you picked with the right mouse button).
+// - HEAL: same copied texture, but its overall colour/brightness is swapped for the
+//   destination's local colour, so small objects vanish without a visible patch.
+
+use crate::error::Error;
+use crate::gamma::GammaLut;
+use crate::types::FrameBuffer;
+
+/// Read pixel (x,y) with coordinates clamped to the frame (edges extend outward).
+#[inline]
+fn sample_clamped(fb: &FrameBuffer, x: i32, y: i32) -> u32 {
+    let x = x.clamp(0, fb.width as i32 - 1) as usize;
+    let y = y.clamp(0, fb.height as i32 - 1) as usize;
+    fb.pixels[y * fb.width + x]
+}
+
+/// Copy `src` into `dst` shifted so that dst(x,y) = src(x+dx, y+dy).
+/// What you SEE (as sink): painted pixels show texture from the clone source.
+pub fn clone_offset(src: &FrameBuffer, dst: &mut FrameBuffer, dx: i32, dy: i32) -> Result<(), Error> {
+    if src.width != dst.width || src.height != dst.height {
+        return Err(Error::CameraFrame("clone: size mismatch src↔dst".into()));
+    }
+    let w = dst.width;
+    for y in 0..dst.height {
+        for x in 0..w {
+            dst.pixels[y * w + x] = sample_clamped(src, x as i32 + dx, y as i32 + dy);
+        }
+    }
+    Ok(())
+}
+
+/// Healing: clone the *detail* from the source and keep the *local colour* of the destination.
+/// In linear light: heal(p) = src(q) − blur(q) + blur(p), with q = p + (dx,dy).
+/// `src_blur` is the low-pass version of `src` (we reuse the blur sink from the main loop).
+/// What you SEE (as sink): texture from the source point, tinted/lit like the spot you paint.
+pub fn heal_offset(
+    src: &FrameBuffer,
+    src_blur: &FrameBuffer,
+    dst: &mut FrameBuffer,
+    dx: i32,
+    dy: i32,
+    lut: &GammaLut,
+) -> Result<(), Error> {
+    if src.width != dst.width || src.height != dst.height {
+        return Err(Error::CameraFrame("heal: size mismatch src↔dst".into()));
+    }
+    if src_blur.width != src.width || src_blur.height != src.height {
+        return Err(Error::CameraFrame("heal: size mismatch blur".into()));
+    }
+
+    let w = dst.width;
+    for y in 0..dst.height {
+        for x in 0..w {
+            let i = y * w + x;
+            let detail = sample_clamped(src, x as i32 + dx, y as i32 + dy);      // texture we copy
+            let detail_lo = sample_clamped(src_blur, x as i32 + dx, y as i32 + dy); // its local colour
+            let local_lo = src_blur.pixels[i];                                   // colour we keep
+
+            let mut out = 0u32;
+            for shift in [16u32, 8, 0] {
+                let c = |p: u32| lut.srgb_u8_to_linear(((p >> shift) & 0xFF) as u8);
+                let l = c(detail) - c(detail_lo) + c(local_lo);
+                out |= (lut.linear_to_srgb_u8(l) as u32) << shift;
+            }
+            dst.pixels[i] = out; // visual: healed pixel (same texture, destination lighting)
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+// Live RGB + luma histogram of the finished frame.
+// Visual expectation: press I and a small chart appears (bottom right by default):
+// red, green and blue as see-through bars, luma as a white line, dark tones left and
+// bright tones right. A pile-up against the right edge means blown highlights; the
+// label then shows "CLIP n%" in red (and "CRUSH n%" for pure black on the left).
+// Handy while moving the brightness/contrast/white-balance controls.
+
+use crate::draw::{draw_line_aa, draw_text_5x7, fill_rect_blend, put_pixel_blend};
+use crate::theme::Theme;
+use crate::types::FrameBuffer;
+use rayon::prelude::*;
+
+/// Pixels counted per parallel work item.
+const CHUNK_PIXELS: usize = 64 * 1024;
+
+/// Share of pixels at 0 or 255 above which the label warns (0.5 %).
+const CLIP_WARN: f32 = 0.005;
+
+/// 256-bin counts per channel for one frame.
+pub struct Histogram {
+    r: [u32; 256],
+    g: [u32; 256],
+    b: [u32; 256],
+    luma: [u32; 256],
+    clipped: u32, // pixels with any channel at 255
+    crushed: u32, // pixels that are pure black
+    total: u32,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self { r: [0; 256], g: [0; 256], b: [0; 256], luma: [0; 256], clipped: 0, crushed: 0, total: 0 }
+    }
+
+    /// Count every pixel of `fb` (replaces the previous counts).
+    pub fn compute(&mut self, fb: &FrameBuffer) {
+        let parts: Vec<Histogram> = fb
+            .pixels
+            .par_chunks(CHUNK_PIXELS)
+            .map(|chunk| {
+                let mut h = Histogram::new();
+                for &p in chunk {
+                    let (r, g, b) = ((p >> 16) & 0xFF, (p >> 8) & 0xFF, p & 0xFF);
+                    h.r[r as usize] += 1;
+                    h.g[g as usize] += 1;
+                    h.b[b as usize] += 1;
+                    // Rec. 709 weights on the stored (sRGB) values, like a camera's display histogram.
+                    h.luma[((54 * r + 183 * g + 19 * b) >> 8) as usize] += 1;
+                    h.clipped += (r == 255 || g == 255 || b == 255) as u32;
+                    h.crushed += (p & 0x00FF_FFFF == 0) as u32;
+                }
+                h.total = chunk.len() as u32;
+                h
+            })
+            .collect();
+        *self = parts.into_iter().fold(Histogram::new(), Histogram::add);
+    }
+
+    fn add(mut self, other: Histogram) -> Histogram {
+        for i in 0..256 {
+            self.r[i] += other.r[i];
+            self.g[i] += other.g[i];
+            self.b[i] += other.b[i];
+            self.luma[i] += other.luma[i];
+        }
+        self.clipped += other.clipped;
+        self.crushed += other.crushed;
+        self.total += other.total;
+        self
+    }
+
+    /// Width and height in pixels that `draw` covers at `scale` (for HUD placement).
+    pub fn size(scale: i32) -> (i32, i32) {
+        (180 * scale, 74 * scale)
+    }
+
+    /// Draw the chart with its top-left corner at (x, y); `color` is the label/luma colour.
+    pub fn draw(&self, fb: &mut FrameBuffer, x: i32, y: i32, scale: i32, color: u32, theme: &Theme) {
+        let (w, h) = Self::size(scale);
+        fill_rect_blend(fb, x - 4 * scale, y - 3 * scale, w + 8 * scale, h + 6 * scale, theme.backdrop, 140);
+
+        // Label, with clipping warnings in the theme's warning colour (red by default).
+        let total = self.total.max(1) as f32;
+        let (clip, crush) = (self.clipped as f32 / total, self.crushed as f32 / total);
+        draw_text_5x7(fb, x, y, "HIST", color, scale);
+        let mut lx = x + 5 * 6 * scale;
+        for (tag, share) in [("CLIP", clip), ("CRUSH", crush)] {
+            if share > CLIP_WARN {
+                let text = format!("{tag} {:.1}%", share * 100.0);
+                draw_text_5x7(fb, lx, y, &text, theme.warning, scale);
+                lx += (text.len() as i32 + 1) * 6 * scale;
+            }
+        }
+
+        // Scale to the tallest inner bin, so a clipped spike at 0/255 doesn't flatten the rest.
+        let gy = y + 10 * scale;
+        let gh = h - 10 * scale;
+        let peak = [&self.r, &self.g, &self.b, &self.luma]
+            .iter()
+            .flat_map(|c| c[1..255].iter())
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(1) as f32;
+        let height = |count: u32| ((count as f32 / peak).min(1.0) * gh as f32) as i32;
+        let bin = |col: i32| (col * 256 / w) as usize;
+
+        // Channels as translucent columns: where all three overlap the bars look grey-white.
+        for col in 0..w {
+            let i = bin(col);
+            for (counts, tint) in [(&self.r, 0x00_FF_30_30), (&self.g, 0x00_30_FF_30), (&self.b, 0x00_40_60_FF)] {
+                let bar = height(counts[i]);
+                for yy in gy + gh - bar..gy + gh {
+                    put_pixel_blend(fb, x + col, yy, tint, 90);
+                }
+            }
+        }
+
+        // Luma as a line on top.
+        let to_y = |count: u32| (gy + gh - 1 - height(count).min(gh - 1)) as f32;
+        let mut prev = (x as f32, to_y(self.luma[0]));
+        for col in 1..w {
+            let pt = ((x + col) as f32, to_y(self.luma[bin(col)]));
+            draw_line_aa(fb, prev.0, prev.1, pt.0, pt.1, color);
+            prev = pt;
+        }
+    }
+}
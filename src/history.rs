@@ -0,0 +1,186 @@
+// Undo / redo for every change to what you have set up, not just brush strokes.
+// Visual expectation: Ctrl+Z takes back the last change — a brush stroke (one press to
+// release, however long), C clearing the mask, a double-click hole, Tab / 1–9 switching
+// the effect — and Ctrl+Y (or Ctrl+Shift+Z) puts it back. The status bar says what just
+// happened ("UNDO STROKE", "REDO EFFECT HEAL") for a moment. The last HISTORY_LIMIT
+// changes are kept; making a new change after undoing drops what could be redone, as
+// in any editor.
+//
+// Each change is an `Edit` (the command pattern): it knows how to undo and redo itself
+// on the `Doc` — the mask, its tiles and the effect registry. Mask edits keep only the
+// rectangle that changed, before and after, so a small stroke costs a few kilobytes.
+
+use crate::effects::EffectRegistry;
+use crate::types::{Mask, MaskTiles, Rect};
+use std::collections::VecDeque;
+
+/// How many edits Ctrl+Z can go back.
+pub const HISTORY_LIMIT: usize = 64;
+
+/// What an edit can change.
+pub struct Doc<'a> {
+    pub mask: &'a mut Mask,
+    pub tiles: &'a mut MaskTiles,
+    pub effects: &'a mut EffectRegistry,
+}
+
+/// One undoable change.
+pub trait Edit: Send {
+    /// Status bar name, e.g. "STROKE".
+    fn label(&self) -> String;
+    fn undo(&self, doc: &mut Doc);
+    fn redo(&self, doc: &mut Doc);
+}
+
+/// A rectangle of the mask before and after (strokes, clear, holes).
+struct MaskEdit {
+    what: &'static str,
+    rect: Rect,
+    before: Vec<u8>,
+    after: Vec<u8>,
+    bounds: (Option<Rect>, Option<Rect>), // mask bounds before / after
+}
+
+impl MaskEdit {
+    /// The `rect` part of `before` and `after` (masks of the same size).
+    fn between(what: &'static str, before: &Mask, after: &Mask, rect: Rect) -> Self {
+        Self {
+            what,
+            rect,
+            before: crop(before, rect),
+            after: crop(after, rect),
+            bounds: (before.bounds, after.bounds),
+        }
+    }
+
+    fn put(&self, doc: &mut Doc, alpha: &[u8], bounds: Option<Rect>) {
+        let Rect { x0, y0, x1, y1 } = self.rect;
+        let w = x1 - x0;
+        for (row, src) in (y0..y1).zip(alpha.chunks_exact(w)) {
+            let at = row * doc.mask.width + x0;
+            doc.mask.alpha[at..at + w].copy_from_slice(src);
+        }
+        doc.mask.bounds = bounds;
+        doc.tiles.update_rect(doc.mask, x0 as i32, y0 as i32, x1 as i32, y1 as i32); // visual: blend follows
+    }
+}
+
+impl Edit for MaskEdit {
+    fn label(&self) -> String {
+        self.what.to_string()
+    }
+
+    fn undo(&self, doc: &mut Doc) {
+        self.put(doc, &self.before, self.bounds.0);
+    }
+
+    fn redo(&self, doc: &mut Doc) {
+        self.put(doc, &self.after, self.bounds.1);
+    }
+}
+
+/// Switching the brush effect.
+struct EffectEdit {
+    from: usize,
+    to: usize,
+    name: &'static str,
+}
+
+impl Edit for EffectEdit {
+    fn label(&self) -> String {
+        format!("EFFECT {}", self.name)
+    }
+
+    fn undo(&self, doc: &mut Doc) {
+        doc.effects.select(self.from);
+    }
+
+    fn redo(&self, doc: &mut Doc) {
+        doc.effects.select(self.to);
+    }
+}
+
+/// A mask edit still in progress: the mask as it was, and where it has changed since.
+struct OpenMaskEdit {
+    what: &'static str,
+    before: Mask,
+    dirty: Rect,
+}
+
+/// The undo and redo stacks.
+#[derive(Default)]
+pub struct History {
+    done: VecDeque<Box<dyn Edit>>,
+    undone: Vec<Box<dyn Edit>>,
+    open: Option<OpenMaskEdit>,
+}
+
+impl History {
+    fn push(&mut self, edit: Box<dyn Edit>) {
+        if self.done.len() == HISTORY_LIMIT {
+            self.done.pop_front();                             // oldest change can't be undone any more
+        }
+        self.done.push_back(edit);
+        self.undone.clear();
+    }
+
+    /// `mask` is about to change inside the pixel rect [x0,x1)×[y0,y1) as part of the
+    /// edit `what`. Calls with the same `what` in a row (the frames of one stroke) add
+    /// up to one edit; a new `what` first closes the one before.
+    pub fn mask_changing(&mut self, what: &'static str, mask: &Mask, (x0, y0, x1, y1): (i32, i32, i32, i32)) {
+        let (w, h) = (mask.width as i32, mask.height as i32);
+        let clip = |v: i32, max: i32| v.clamp(0, max) as usize;
+        let rect = Rect { x0: clip(x0, w), y0: clip(y0, h), x1: clip(x1, w), y1: clip(y1, h) };
+        match &mut self.open {
+            Some(open) if open.what == what => open.dirty = open.dirty.union(rect),
+            _ => {
+                self.mask_done(mask);
+                self.open = Some(OpenMaskEdit { what, before: mask.clone(), dirty: rect });
+            }
+        }
+    }
+
+    /// Close the open mask edit (if any) and record it; `mask` is how it ended up.
+    pub fn mask_done(&mut self, mask: &Mask) {
+        if let Some(open) = self.open.take() {
+            let rect = open.dirty;
+            if rect.x1 > rect.x0 && rect.y1 > rect.y0 {
+                self.push(Box::new(MaskEdit::between(open.what, &open.before, mask, rect)));
+            }
+        }
+    }
+
+    /// The effect went from index `from` to `to`.
+    pub fn effect_changed(&mut self, from: usize, to: usize, effects: &EffectRegistry) {
+        self.push(Box::new(EffectEdit { from, to, name: effects.name_at(to) }));
+    }
+
+    /// Ctrl+Z: take back the last edit; returns its label for the status bar.
+    pub fn undo(&mut self, doc: &mut Doc) -> Option<String> {
+        self.mask_done(doc.mask);
+        let edit = self.done.pop_back()?;
+        edit.undo(doc);
+        let label = edit.label();
+        self.undone.push(edit);
+        Some(label)
+    }
+
+    /// Ctrl+Y: put back the last undone edit; returns its label.
+    pub fn redo(&mut self, doc: &mut Doc) -> Option<String> {
+        self.mask_done(doc.mask);
+        let edit = self.undone.pop()?;
+        edit.redo(doc);
+        let label = edit.label();
+        self.done.push_back(edit);
+        Some(label)
+    }
+}
+
+/// Copy the `rect` part of `mask`'s alpha, row by row.
+fn crop(mask: &Mask, rect: Rect) -> Vec<u8> {
+    let mut out = Vec::with_capacity((rect.x1 - rect.x0) * (rect.y1 - rect.y0));
+    for y in rect.y0..rect.y1 {
+        out.extend_from_slice(&mask.alpha[y * mask.width + rect.x0..y * mask.width + rect.x1]);
+    }
+    out
+}
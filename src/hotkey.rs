@@ -0,0 +1,36 @@
+// Optional system-wide privacy hotkey, built with `cargo run --release --features hotkey`.
+// Visual expectation: press Ctrl+Shift+P anywhere — in the video-call window, a browser,
+// with Magic Eraser minimised — and the whole picture blurs at once; press it again and
+// the painting you had comes back. For the moment someone walks into the room mid-call.
+
+use crate::error::Error;
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+/// The registered hotkey. Lives on the window thread (the OS delivers the key
+/// through that thread's event loop); dropping it unregisters the key.
+pub struct PrivacyHotkey {
+    _manager: GlobalHotKeyManager,
+    key: HotKey,
+}
+
+impl PrivacyHotkey {
+    /// Register Ctrl+Shift+P system-wide.
+    pub fn register() -> Result<Self, Error> {
+        let manager = GlobalHotKeyManager::new().map_err(|e| Error::Hotkey(format!("Start hotkey manager: {e}")))?;
+        let key = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyP);
+        manager
+            .register(key)
+            .map_err(|e| Error::Hotkey(format!("Register Ctrl+Shift+P (another app may own it): {e}")))?;
+        Ok(Self { _manager: manager, key })
+    }
+
+    /// True if the hotkey went down since the last call.
+    pub fn pressed(&self) -> bool {
+        let mut pressed = false;
+        while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            pressed |= event.id == self.key.id() && event.state == HotKeyState::Pressed;
+        }
+        pressed
+    }
+}
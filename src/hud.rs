@@ -0,0 +1,149 @@
+// HUD layout: which on-screen text blocks appear, in which corner, in which colour.
+// Visual expectation: by default the status line and the colour-controls line sit in
+// the top-left corner as before (P adds the profiler under them) and the frame-time
+// graph sits bottom-left (I adds the histogram bottom-right). H hides all of it for clean demos. `--hud` moves things
+// around, e.g.
+//
+//     magic-eraser --hud status:bl,controls:br:ffcc33,-profiler
+//
+// puts the status line bottom-left, the controls bottom-right in yellow, and never
+// shows the profiler. Items sharing a corner stack instead of overlapping.
+
+use crate::error::Error;
+use crate::theme::{parse_hex_color, Theme};
+
+/// Screen corner an element is anchored to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "tl" | "top-left" => Some(Corner::TopLeft),
+            "tr" | "top-right" => Some(Corner::TopRight),
+            "bl" | "bottom-left" => Some(Corner::BottomLeft),
+            "br" | "bottom-right" => Some(Corner::BottomRight),
+            _ => None,
+        }
+    }
+}
+
+/// One HUD block: shown or not, where, and its text colour (0x00RRGGBB).
+#[derive(Clone, Copy, Debug)]
+pub struct HudElement {
+    pub visible: bool,
+    pub corner: Corner,
+    pub color: u32,
+}
+
+impl HudElement {
+    const fn new(corner: Corner, color: u32) -> Self {
+        Self { visible: true, corner, color }
+    }
+}
+
+/// Layout of every HUD block.
+#[derive(Clone, Copy, Debug)]
+pub struct HudLayout {
+    pub status: HudElement,   // mode, brush effect, blur settings, FPS
+    pub controls: HudElement, // brightness / contrast / saturation / white balance
+    pub profiler: HudElement, // P: per-stage timings + graph
+    pub graph: HudElement,    // T: rolling frame-time line graph
+    pub histogram: HudElement, // I: RGB + luma histogram
+    pub picker: HudElement,   // X: eyedropper readout
+    pub loupe: HudElement,    // Z: zoomed inset around the cursor
+    pub mask: HudElement,     // F4: mask thumbnail
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self::with_color(Theme::DARK.text)
+    }
+}
+
+impl HudLayout {
+    /// The default layout with every block in `color` (the theme's text colour).
+    pub fn with_color(color: u32) -> Self {
+        Self {
+            status: HudElement::new(Corner::TopLeft, color),
+            controls: HudElement::new(Corner::TopLeft, color),
+            profiler: HudElement::new(Corner::TopLeft, color),
+            graph: HudElement::new(Corner::BottomLeft, color),
+            histogram: HudElement::new(Corner::BottomRight, color),
+            picker: HudElement::new(Corner::TopLeft, color),
+            loupe: HudElement::new(Corner::BottomRight, color),
+            mask: HudElement::new(Corner::BottomLeft, color),
+        }
+    }
+
+    /// Parse a `--hud` spec: comma-separated `name[:corner][:RRGGBB]`, or `-name` to hide.
+    /// Names: status, controls, profiler, graph, histogram, picker, loupe, mask.
+    /// Corners: tl, tr, bl, br (or top-left, …). Anything not mentioned keeps its default
+    /// place and `color`.
+    pub fn parse(spec: &str, color: u32) -> Result<Self, Error> {
+        let mut layout = Self::with_color(color);
+        for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (hide, item) = match item.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, item),
+            };
+            let mut parts = item.split(':');
+            let name = parts.next().unwrap_or_default();
+            let el = match name {
+                "status" => &mut layout.status,
+                "controls" => &mut layout.controls,
+                "profiler" => &mut layout.profiler,
+                "graph" => &mut layout.graph,
+                "histogram" => &mut layout.histogram,
+                "picker" => &mut layout.picker,
+                "loupe" => &mut layout.loupe,
+                "mask" => &mut layout.mask,
+                _ => return Err(Error::Config(format!("--hud: unknown element '{name}'"))),
+            };
+            el.visible = !hide;
+            for part in parts {
+                if let Some(corner) = Corner::parse(part) {
+                    el.corner = corner;
+                } else if let Some(color) = parse_hex_color(part) {
+                    el.color = color;
+                } else {
+                    return Err(Error::Config(format!("--hud: '{part}' is neither a corner nor a RRGGBB colour")));
+                }
+            }
+        }
+        Ok(layout)
+    }
+}
+
+/// Hands out positions for HUD blocks, stacking blocks that share a corner.
+pub struct HudPlacer {
+    width: i32,
+    height: i32,
+    margin: i32,
+    gap: i32,
+    used: [i32; 4], // pixels already taken from the top (or bottom) edge, per corner
+}
+
+impl HudPlacer {
+    pub fn new(width: usize, height: usize, scale: i32) -> Self {
+        Self { width: width as i32, height: height as i32, margin: 8, gap: 5 * scale, used: [0; 4] }
+    }
+
+    /// Top-left position for a `w`×`h` block in `corner`, below/above what's already there.
+    pub fn place(&mut self, corner: Corner, w: i32, h: i32) -> (i32, i32) {
+        let used = &mut self.used[corner as usize];
+        let (x, y) = match corner {
+            Corner::TopLeft => (self.margin, self.margin + *used),
+            Corner::TopRight => (self.width - self.margin - w, self.margin + *used),
+            Corner::BottomLeft => (self.margin, self.height - self.margin - *used - h),
+            Corner::BottomRight => (self.width - self.margin - w, self.height - self.margin - *used - h),
+        };
+        *used += h + self.gap;
+        (x, y)
+    }
+}
@@ -0,0 +1,65 @@
+// Idle detection: nobody at the keyboard and nothing moving in front of the camera.
+// Visual expectation: leave the app running and walk away; after a minute of no input
+// and a still picture the window dims with "IDLE" in the middle and stops updating —
+// no blur, no FX, a few cheap frame checks per second, so the laptop cools down.
+// Move the mouse, press a key or step back into view and it is live again on the next
+// frame.
+
+use crate::types::FrameBuffer;
+use std::time::{Duration, Instant};
+
+/// No input and no motion for this long = idle.
+pub const IDLE_AFTER: Duration = Duration::from_secs(60);
+/// While idle, look at a camera frame this often (the rest are dropped).
+pub const IDLE_FRAME_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Motion check on a coarse grid of luma samples.
+const GRID_W: usize = 32;
+const GRID_H: usize = 24;
+/// A cell "moved" when its luma changes by more than this (0..255; above sensor noise)…
+const CELL_CHANGE: i32 = 24;
+/// …and the picture moved when more than this many cells did.
+const MOVED_CELLS: usize = GRID_W * GRID_H / 100;
+
+pub struct IdleWatch {
+    last_activity: Instant,
+    grid: Vec<u8>, // luma samples at the last motion (slow drifts add up against it)
+}
+
+impl IdleWatch {
+    pub fn new() -> Self {
+        Self { last_activity: Instant::now(), grid: Vec::new() }
+    }
+
+    /// Look at this frame and whether there was input; true while idle.
+    pub fn update(&mut self, frame: &FrameBuffer, input_active: bool, now: Instant) -> bool {
+        let moved = self.motion(frame);
+        if input_active || moved {
+            self.last_activity = now;
+        }
+        now.duration_since(self.last_activity) >= IDLE_AFTER
+    }
+
+    /// Sample the grid and compare it with the one kept at the last motion.
+    fn motion(&mut self, frame: &FrameBuffer) -> bool {
+        if frame.width == 0 || frame.height == 0 {
+            return false;
+        }
+        let mut grid = Vec::with_capacity(GRID_W * GRID_H);
+        for gy in 0..GRID_H {
+            let y = (gy * 2 + 1) * frame.height / (GRID_H * 2);
+            for gx in 0..GRID_W {
+                let x = (gx * 2 + 1) * frame.width / (GRID_W * 2);
+                let p = frame.pixels[y * frame.width + x];
+                let (r, g, b) = ((p >> 16) & 0xFF, (p >> 8) & 0xFF, p & 0xFF);
+                grid.push(((r * 77 + g * 150 + b * 29) >> 8) as u8);
+            }
+        }
+        let moved = self.grid.len() == grid.len()
+            && self.grid.iter().zip(&grid).filter(|&(&a, &b)| (a as i32 - b as i32).abs() > CELL_CHANGE).count() > MOVED_CELLS;
+        if moved || self.grid.is_empty() {
+            self.grid = grid;
+        }
+        moved
+    }
+}
@@ -0,0 +1,101 @@
+// Loading still images from disk into our FrameBuffer format, and saving frames back.
+// Visual expectation: a picture file (PNG/JPEG/…) becomes a frame the same size
+// as the camera feed, filling it edge to edge without stretching (it is cropped
+// to the camera's aspect ratio, like a "cover" wallpaper). Masks are saved and read
+// as grey-scale PNGs (white = full effect), so a painted mask can be reused offline.
+
+use crate::error::Error;
+use crate::pixel::{PixelBuffer, PixelFormat};
+use crate::types::{FrameBuffer, Mask, Rect};
+#[cfg(feature = "window")]
+use crate::vision::resize_bilinear_view;
+
+/// Load `path` at its own size.
+pub fn load_image(path: &str) -> Result<FrameBuffer, Error> {
+    let img = image::open(path)
+        .map_err(|e| Error::ImageLoad(format!("Open {path}: {e}")))?
+        .to_rgb8();
+    let (iw, ih) = (img.width() as usize, img.height() as usize);
+    if iw == 0 || ih == 0 {
+        return Err(Error::ImageLoad(format!("{path}: empty image")));
+    }
+    PixelBuffer { width: iw, height: ih, format: PixelFormat::Rgb24, data: img.into_raw() }
+        .to_frame()
+        .map_err(|e| Error::ImageLoad(format!("{path}: {e}")))
+}
+
+/// Load `path` and fit it to `width`×`height` (scale to cover, centre-crop the overflow).
+/// What you SEE (as sink): the picture behind you, same framing as the camera.
+#[cfg(feature = "window")]
+pub fn load_image_cover(path: &str, width: usize, height: usize) -> Result<FrameBuffer, Error> {
+    // 1) The whole picture as 0x00RRGGBB.
+    let full = load_image(path)?;
+    let (iw, ih) = (full.width, full.height);
+    if width == 0 || height == 0 {
+        return Err(Error::ImageLoad(format!("{path}: empty target size")));
+    }
+
+    // 2) Crop rectangle with the target's aspect ratio (as large as possible); a view
+    //    into the picture, nothing copied.
+    let (cw, ch) = if iw * height > ih * width {
+        (ih * width / height, ih)          // source is wider: trim left/right
+    } else {
+        (iw, iw * height / width)          // source is taller: trim top/bottom
+    };
+    let (cx, cy) = ((iw - cw) / 2, (ih - ch) / 2);
+    let cropped = full.sub(Rect { x0: cx, y0: cy, x1: cx + cw.max(1), y1: cy + ch.max(1) });
+
+    // 3) Rescale the crop to the camera resolution.
+    let mut out = FrameBuffer { width, height, pixels: vec![0u32; width * height] };
+    resize_bilinear_view(cropped, out.view_mut());
+    Ok(out)
+}
+
+/// Write `fb` to `path`; the format follows the extension (`.png`, `.jpg`, …).
+/// What you SEE: nothing on screen — the file appears next to where you started the app.
+pub fn save_image(path: &str, fb: &FrameBuffer) -> Result<(), Error> {
+    let rgb = PixelBuffer::from_frame(fb, PixelFormat::Rgb24);
+    let img = image::RgbImage::from_raw(fb.width as u32, fb.height as u32, rgb.data)
+        .ok_or_else(|| Error::ImageSave(format!("{path}: buffer size mismatch")))?;
+    img.save_with_format(path, format_of(path)?).map_err(|e| Error::ImageSave(format!("Write {path}: {e}")))
+}
+
+/// Write `mask` as a grey-scale PNG (white = full effect), as the mask view shows it.
+#[cfg(feature = "window")]
+pub fn save_mask(path: &str, mask: &Mask) -> Result<(), Error> {
+    let img = image::GrayImage::from_raw(mask.width as u32, mask.height as u32, mask.alpha.clone())
+        .ok_or_else(|| Error::ImageSave(format!("{path}: buffer size mismatch")))?;
+    img.save_with_format(path, format_of(path)?).map_err(|e| Error::ImageSave(format!("Write {path}: {e}")))
+}
+
+/// The format `path`'s extension names, looking past a trailing `.partial` (a file
+/// written aside and renamed into place once it is complete).
+fn format_of(path: &str) -> Result<image::ImageFormat, Error> {
+    image::ImageFormat::from_path(path.strip_suffix(".partial").unwrap_or(path))
+        .map_err(|e| Error::ImageSave(format!("Write {path}: {e}")))
+}
+
+/// Load a mask saved by `save_mask` (or any picture: brightness = α), stretched to
+/// `width`×`height` so a mask from a smaller preview still lines up.
+pub fn load_mask(path: &str, width: usize, height: usize) -> Result<Mask, Error> {
+    let mut img = image::open(path)
+        .map_err(|e| Error::ImageLoad(format!("Open {path}: {e}")))?
+        .to_luma8();
+    if (img.width() as usize, img.height() as usize) != (width, height) {
+        img = image::imageops::resize(&img, width as u32, height as u32, image::imageops::FilterType::Triangle);
+    }
+    let mut mask = Mask::new(width, height);
+    mask.alpha = img.into_raw();
+    if mask.alpha.iter().any(|&a| a > 0) {
+        mask.extend_bounds(Rect::full(width, height));
+    }
+    Ok(mask)
+}
+
+/// Where the snapshot's mask goes: `shot.png` → `shot-mask.png`.
+#[cfg(feature = "window")]
+pub fn mask_path(snapshot: &str) -> String {
+    let path = std::path::Path::new(snapshot);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("snapshot");
+    path.with_file_name(format!("{stem}-mask.png")).to_string_lossy().into_owned()
+}
@@ -0,0 +1,201 @@
+// Content-aware fill: the actual "magic eraser".
+// Visual expectation: wherever you painted, the object disappears and is replaced
+// by texture borrowed from the rest of the frame (wall, desk, carpet…), so the hole
+// looks like plausible background instead of a blurry smear.
+//
+// Method: single-scale PatchMatch. Every hole pixel keeps a pointer (the NNF) to a
+// source patch outside the hole; pointers improve by propagation from neighbours and
+// a shrinking random search. After each iteration the hole is repainted from the
+// pointers, so later iterations match against an ever better guess.
+
+use crate::error::Error;
+use crate::fx::Rng32;
+use crate::types::{FrameBuffer, Mask};
+
+/// Mask alpha at or above this counts as "hole" (to be synthesized).
+/// Low on purpose: the feathered brush edge should sit over filled texture, not the object.
+pub const HOLE_THRESHOLD: u8 = 26; // ≈ 10%
+
+const PATCH_RADIUS: i32 = 3; // 7x7 patches: big enough for texture, small enough for speed
+const ITERATIONS: usize = 5; // PatchMatch converges quickly; 4–5 passes are plenty
+
+#[inline]
+fn unpack(p: u32) -> (i32, i32, i32) {
+    (((p >> 16) & 0xFF) as i32, ((p >> 8) & 0xFF) as i32, (p & 0xFF) as i32)
+}
+
+/// Fill every pixel of `src` whose mask alpha ≥ HOLE_THRESHOLD with synthesized texture.
+/// `dst` receives the full frame (untouched pixels are copied from `src`).
+/// What you SEE (when used as the sink): painted objects vanish into their surroundings.
+pub fn inpaint_patchmatch(
+    src: &FrameBuffer,
+    mask: &Mask,
+    dst: &mut FrameBuffer,
+    seed: u32,
+) -> Result<(), Error> {
+    if src.width != dst.width || src.height != dst.height {
+        return Err(Error::CameraFrame("inpaint: size mismatch src↔dst".into()));
+    }
+    if mask.width != src.width || mask.height != src.height {
+        return Err(Error::CameraFrame("inpaint: mask dimension mismatch".into()));
+    }
+
+    let w = src.width as i32;
+    let h = src.height as i32;
+    let len = src.width * src.height;
+    dst.pixels.copy_from_slice(&src.pixels);
+
+    /* 1) Which pixels are holes? Keep a list plus a reverse lookup (pixel → hole slot). */
+    let hole: Vec<bool> = mask.alpha.iter().map(|&a| a >= HOLE_THRESHOLD).collect();
+    let holes: Vec<usize> = (0..len).filter(|&i| hole[i]).collect();
+    if holes.is_empty() { return Ok(()); }
+    let mut slot = vec![u32::MAX; len];
+    for (k, &i) in holes.iter().enumerate() { slot[i] = k as u32; }
+
+    /* 2) Valid source centers: patch fully on screen and touching no hole pixel.
+          A summed-area table of the hole map answers "any hole in this patch?" in O(1). */
+    let sw = src.width + 1;
+    let mut sat = vec![0u32; sw * (src.height + 1)];
+    for y in 0..src.height {
+        let mut row = 0u32;
+        for x in 0..src.width {
+            row += hole[y * src.width + x] as u32;
+            sat[(y + 1) * sw + x + 1] = sat[y * sw + x + 1] + row;
+        }
+    }
+    let pr = PATCH_RADIUS;
+    let is_valid_source = |x: i32, y: i32| -> bool {
+        if x < pr || y < pr || x >= w - pr || y >= h - pr { return false; }
+        let (x0, y0) = ((x - pr) as usize, (y - pr) as usize);
+        let (x1, y1) = ((x + pr + 1) as usize, (y + pr + 1) as usize);
+        sat[y1 * sw + x1] + sat[y0 * sw + x0] == sat[y0 * sw + x1] + sat[y1 * sw + x0]
+    };
+    let sources: Vec<u32> = (0..len as u32)
+        .filter(|&i| is_valid_source(i as i32 % w, i as i32 / w))
+        .collect();
+
+    /* 3) Initial guess: onion-peel the hole from its border inwards, averaging known neighbours.
+          Visual (if shown now): a smooth colour smear that PatchMatch then adds texture to. */
+    let mut known: Vec<bool> = hole.iter().map(|&b| !b).collect();
+    let mut remaining = holes.clone();
+    while !remaining.is_empty() {
+        let mut updates = Vec::new();
+        for &i in &remaining {
+            let (x, y) = (i as i32 % w, i as i32 / w);
+            let (mut sr, mut sg, mut sb, mut n) = (0i32, 0i32, 0i32, 0i32);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 0 || ny < 0 || nx >= w || ny >= h { continue; }
+                    let j = (ny * w + nx) as usize;
+                    if !known[j] { continue; }
+                    let (r, g, b) = unpack(dst.pixels[j]);
+                    sr += r; sg += g; sb += b; n += 1;
+                }
+            }
+            if n > 0 {
+                let px = (((sr / n) as u32) << 16) | (((sg / n) as u32) << 8) | (sb / n) as u32;
+                updates.push((i, px));
+            }
+        }
+        if updates.is_empty() { break; } // whole frame is a hole: nothing to grow from
+        for &(i, px) in &updates {
+            dst.pixels[i] = px;
+            known[i] = true;
+        }
+        remaining.retain(|&i| !known[i]);
+    }
+
+    // No texture anywhere outside the mask (e.g. everything painted): keep the smooth guess.
+    if sources.is_empty() { return Ok(()); }
+
+    /* 4) Random initial NNF: each hole pixel points at some valid source center. */
+    let mut rng = Rng32::from_seed(seed);
+    let mut nnf: Vec<(i32, i32)> = holes
+        .iter()
+        .map(|_| {
+            let s = sources[rng.next_u32() as usize % sources.len()] as i32;
+            (s % w, s / w)
+        })
+        .collect();
+
+    // Patch SSD between the current guess around target (tx,ty) and the source around (sx,sy).
+    // Stops early once `best` is exceeded (most candidates lose quickly).
+    let patch_dist = |img: &[u32], tx: i32, ty: i32, sx: i32, sy: i32, best: i64| -> i64 {
+        let mut d: i64 = 0;
+        for oy in -pr..=pr {
+            let ty2 = ty + oy;
+            if ty2 < 0 || ty2 >= h { continue; }
+            for ox in -pr..=pr {
+                let tx2 = tx + ox;
+                if tx2 < 0 || tx2 >= w { continue; }
+                let (r0, g0, b0) = unpack(img[(ty2 * w + tx2) as usize]);
+                let (r1, g1, b1) = unpack(src.pixels[((sy + oy) * w + sx + ox) as usize]);
+                let (dr, dg, db) = (r0 - r1, g0 - g1, b0 - b1);
+                d += (dr * dr + dg * dg + db * db) as i64;
+            }
+            if d >= best { return d; }
+        }
+        d
+    };
+
+    let mut dist: Vec<i64> = holes
+        .iter()
+        .zip(&nnf)
+        .map(|(&i, &(sx, sy))| patch_dist(&dst.pixels, i as i32 % w, i as i32 / w, sx, sy, i64::MAX))
+        .collect();
+
+    /* 5) PatchMatch iterations: propagate good matches along scan order, then random search. */
+    let max_radius = w.max(h);
+    for iter in 0..ITERATIONS {
+        let forward = iter % 2 == 0;
+        let step: i32 = if forward { 1 } else { -1 };
+
+        for n in 0..holes.len() {
+            let k = if forward { n } else { holes.len() - 1 - n };
+            let i = holes[k];
+            let (tx, ty) = (i as i32 % w, i as i32 / w);
+            let (mut best, mut best_d) = (nnf[k], dist[k]);
+
+            // Propagation: the previous neighbour's match, shifted by one pixel, is a good guess.
+            for (nx, ny) in [(tx - step, ty), (tx, ty - step)] {
+                if nx < 0 || ny < 0 || nx >= w || ny >= h { continue; }
+                let ns = slot[(ny * w + nx) as usize];
+                if ns == u32::MAX { continue; }
+                let (cx, cy) = nnf[ns as usize];
+                let (cx, cy) = (cx + (tx - nx), cy + (ty - ny));
+                if !is_valid_source(cx, cy) { continue; }
+                let d = patch_dist(&dst.pixels, tx, ty, cx, cy, best_d);
+                if d < best_d { best = (cx, cy); best_d = d; }
+            }
+
+            // Random search: try around the current best with an exponentially shrinking window.
+            let mut radius = max_radius;
+            while radius >= 1 {
+                let cx = (best.0 + rng.range(-(radius as f32), radius as f32) as i32).clamp(0, w - 1);
+                let cy = (best.1 + rng.range(-(radius as f32), radius as f32) as i32).clamp(0, h - 1);
+                if is_valid_source(cx, cy) {
+                    let d = patch_dist(&dst.pixels, tx, ty, cx, cy, best_d);
+                    if d < best_d { best = (cx, cy); best_d = d; }
+                }
+                radius /= 2;
+            }
+
+            nnf[k] = best;
+            dist[k] = best_d;
+        }
+
+        // Repaint the hole from the matches so the next pass compares against real texture.
+        for (k, &i) in holes.iter().enumerate() {
+            let (sx, sy) = nnf[k];
+            dst.pixels[i] = src.pixels[(sy * w + sx) as usize];
+        }
+        // The guess changed under every patch, so refresh the scores we compare against.
+        for (k, &i) in holes.iter().enumerate() {
+            let (sx, sy) = nnf[k];
+            dist[k] = patch_dist(&dst.pixels, i as i32 % w, i as i32 / w, sx, sy, i64::MAX);
+        }
+    }
+
+    Ok(())
+}
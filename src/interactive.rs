@@ -0,0 +1,1458 @@
+// The interactive app: a window on the main thread, processing on another.
+// Visual expectation: the window opens on the live camera (or the --source synthetic
+// test pattern). Each frame the processing thread takes what was pressed, clicked or
+// sent since the last one, hands it to the app as events (app.rs), and builds the
+// picture: blur, effect under the mask, polish filters, FX, then the HUD on top.
+// The window thread only shows finished frames and collects input in between.
+//
+// With --half all of that runs on a quarter of the pixels; only the finished picture
+// is scaled up for the window, so the crosshair and HUD text stay sharp. `--bench N`
+// runs the same processing on synthetic frames and prints where the time went.
+//
+// SNAPSHOT FILE in the S panel (`--snapshot FILE` sets the first name) takes a typed name
+// while hotkeys pause; Enter or SAVE SNAPSHOT writes the finished picture without the
+// HUD, the extension picking the format, with the mask beside it for `process --mask`.
+
+use crate::app::App;
+use crate::bench;
+use crate::cli::{Options, Source};
+#[cfg(feature = "winit")]
+use crate::cli::Backend;
+use crate::color::{Adjust, ColorAdjust, WhiteBalance};
+use crate::config::{Config, ConfigWatch, Tap};
+use crate::cursor::CursorStyle;
+use crate::debugwin::{self, DebugLink, DebugView};
+use crate::draw::{draw_crosshair, draw_line_thick, draw_progress_bar, draw_text_5x7, fill_rect, fill_rect_blend, fill_rect_blend_linear, hud_scale, text_size_5x7};
+use crate::effects::EffectCtx;
+use crate::error::{Context, Error};
+use crate::events::{self, Action, Event, EventQueue, EventSender};
+use crate::filter::FilterChain;
+use crate::filters::{self, WhiteBalancePick};
+use crate::gamma::GammaLut;
+use crate::gesture::{Gesture, Gestures};
+use crate::governor::{self, Governor};
+use crate::histogram::Histogram;
+use crate::hud::{HudLayout, HudPlacer};
+use crate::idle::{IdleWatch, IDLE_FRAME_INTERVAL};
+use crate::imageio;
+use crate::keycursor::KeyCursor;
+use crate::keys::{self, KeyMap};
+use crate::loupe::Loupe;
+use crate::maskview::{self, MaskThumb};
+use crate::median::MedianFilter;
+use crate::metrics::MetricsLog;
+use crate::mode::Review;
+use crate::offline::VideoSource;
+use crate::osc;
+use crate::outputs::Outputs;
+#[cfg(feature = "camera")]
+use crate::pipeline::CaptureStage;
+use crate::pipeline::{self, FrameSource, PresentLink};
+use crate::picker::{self, ColorPick};
+use crate::profile::{Profiler, Stage};
+use crate::pyramid::{BlurPyramid, BlurQuality, PYRAMID_MIN_RADIUS};
+use crate::segment;
+use crate::session::{Session, CHECKPOINT_INTERVAL};
+use crate::sprite::Sprite;
+use crate::statusbar::{self, StatusCell};
+use crate::theme::Theme;
+use crate::touch::TouchGestures;
+use crate::types::{FrameBuffer, Mask, MaskTiles, Rect};
+use crate::vision::{self, box_blur_rgb, box_blur_rgb_rect, blend_linear_in_place, downsample_2x, resize_bilinear, stack_blur_rgb, BlurKind, LinearBlur, BG_CAPTURE_COUNT};
+use crate::window::{Drawer, Input, WindowBackend};
+#[cfg(feature = "gpu")]
+use crate::gpu;
+#[cfg(feature = "script")]
+use crate::script;
+#[cfg(feature = "winit")]
+use crate::winit_window;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Softness of portrait mode's subject outline, in processing pixels.
+const PORTRAIT_FEATHER: usize = 6;
+
+/// No subcommand: the window (or `--bench`).
+pub fn run(opts: Options) -> Result<(), Error> {
+    if let Some(frames) = opts.bench_frames {
+        return run_bench(frames, opts);
+    }
+
+    /* --- Camera + window setup ---
+       Visual: window opens with live camera feed (or the test pattern with
+       --source synthetic). The camera runs on its own thread. */
+    let (w, h) = opts.resolution;
+    match opts.source {
+        #[cfg(feature = "camera")]
+        Source::Camera => run_window(CaptureStage::spawn(opts.camera, w, h, opts.fps)?, opts),
+        #[cfg(not(feature = "camera"))]
+        Source::Camera => Err(Error::Config("built without the `camera` feature: use --source synthetic".into())),
+        Source::Synthetic => run_window(bench::SyntheticSource::paced(w, h, opts.fps), opts),
+    }
+}
+
+/// The interactive app: a window on this thread, processing on another.
+fn run_window(capture: impl FrameSource + 'static, opts: Options) -> Result<(), Error> {
+    let (w, h) = capture.resolution();
+    const TITLE: &str = "Magic Eraser — Blur Brush";
+    #[cfg(feature = "winit")]
+    let mut drawer: Box<dyn WindowBackend> = match opts.backend {
+        Backend::Minifb => Box::new(Drawer::new(TITLE, w as usize, h as usize)?),
+        Backend::Winit => Box::new(winit_window::WinitDrawer::new(TITLE, w as usize, h as usize)?),
+    };
+    #[cfg(not(feature = "winit"))]
+    let mut drawer: Box<dyn WindowBackend> = Box::new(Drawer::new(TITLE, w as usize, h as usize)?);
+
+    /* --- Pipeline wiring ---
+       Visual: none. Input goes window → processing; finished frames come back. */
+    let input = Arc::new(Mutex::new(Input::default()));
+    let quit = Arc::new(AtomicBool::new(false));
+    let (link, end) = pipeline::present_channel();
+    let (debug_link, mut debug_windows) = debugwin::debug_channel(&opts.debug_windows); // --debug-windows
+    let (config_path, config_read) = (opts.config_path.clone(), opts.config.clone());
+    drawer.set_keymap(opts.keymap.clone());
+    let mut config_watch = config_path.clone().map(|path| ConfigWatch::new(path, &opts.config));
+    let processing = {
+        let (input, quit) = (Arc::clone(&input), Arc::clone(&quit));
+        thread::Builder::new()
+            .name("process".into())
+            .spawn(move || process(capture, &input, link, debug_link, &quit, &opts))
+            .map_err(|e| Error::WindowInit(format!("Spawn processing thread: {e}")))?
+    };
+
+    /* --- Optional gamepad (built with `--features gamepad`) ---
+       Visual: the left stick moves the cursor; no pad backend just means mouse only. */
+    #[cfg(feature = "gamepad")]
+    let mut pad = match gamepad::Gamepad::new() {
+        Ok(pad) => Some(pad),
+        Err(e) => { log::warn!("{e} — mouse only"); None }
+    };
+
+    /* --- Optional privacy hotkey (built with `--features hotkey`) ---
+       Visual: Ctrl+Shift+P blurs everything, even while another app has focus. */
+    #[cfg(feature = "hotkey")]
+    let privacy_key = match hotkey::PrivacyHotkey::register() {
+        Ok(key) => Some(key),
+        Err(e) => { log::warn!("{e} — no privacy hotkey"); None }
+    };
+
+    /* --- Present stage (the window has to live on the main thread) ---
+       Visual: each finished frame appears; input is collected in between. */
+    let mut key_cursor = KeyCursor::new();                     // arrows move the brush, Space paints
+    let mut cursor_hidden = false;
+    while drawer.is_open() && !drawer.esc_pressed() {
+        if end.os_cursor_hidden() != cursor_hidden {
+            cursor_hidden = !cursor_hidden;
+            drawer.set_cursor_visible(!cursor_hidden);         // visual: OS arrow hides/returns
+        }
+        let mut polled = drawer.poll_input();
+        if let Some(reload) = config_watch.as_mut().and_then(ConfigWatch::poll) {
+            let reload = reload.and_then(|cfg| Ok((cfg.live_settings()?, cfg)));
+            match reload.context("Settings reload") {
+                Ok((live, cfg)) => {
+                    log::info!("settings reloaded");
+                    drawer.set_keymap(live.keymap);                // visual: remapped keys work at once
+                    polled.reloaded = Some(Box::new(cfg));         // processing applies the rest
+                }
+                Err(e) => log::warn!("{e} — keeping the current settings"),
+            }
+        }
+        let (arrows, tapped) = drawer.arrow_keys();
+        key_cursor.apply(&mut polled, arrows, tapped, drawer.space_down(), w as usize, h as usize);
+        #[cfg(feature = "hotkey")]
+        if let Some(key) = &privacy_key {
+            polled.privacy_pressed = key.pressed();
+        }
+        #[cfg(feature = "gamepad")]
+        if let Some(pad) = &mut pad {
+            pad.apply(&mut polled, w as usize, h as usize);
+        }
+        if let Ok(mut shared) = input.lock() {
+            shared.merge(polled);
+        }
+        match end.frames.recv_timeout(Duration::from_millis(5)) {
+            Ok(frame) => {
+                drawer.present(&frame)?;               // visual: the on-screen image updates
+                let _ = end.recycle.send(frame);       // buffer goes back for the next frame
+            }
+            Err(RecvTimeoutError::Timeout) => drawer.update(), // no frame yet: keep the window alive
+            Err(RecvTimeoutError::Disconnected) => break,      // processing stopped (see its error)
+        }
+        debug_windows.update()?;                               // visual: debug windows follow along
+    }
+
+    // Stop processing, let it notice, and report any error it hit.
+    quit.store(true, Ordering::Relaxed);
+    drop(end);
+    let (_, config) = processing
+        .join()
+        .map_err(|_| Error::CameraFrame("Processing thread panicked".into()))??;
+
+    // Keep what changed this session (and write the template on the first run).
+    if let Some(path) = config_path && (config != config_read || !path.exists()) {
+        config.save(&path)?;
+    }
+    Ok(())
+}
+
+/// `--bench N`: the processing stage on synthetic frames, with this thread standing
+/// in for the window (it feeds a scripted brush stroke and drains finished frames).
+fn run_bench(frames: usize, opts: Options) -> Result<(), Error> {
+    let source = bench::SyntheticSource::new(opts.resolution.0, opts.resolution.1);
+    let (w, h) = source.resolution();
+    let input = Arc::new(Mutex::new(Input::default()));
+    let quit = Arc::new(AtomicBool::new(false));
+    let (link, end) = pipeline::present_channel();
+    let processing = {
+        let (input, quit) = (Arc::clone(&input), Arc::clone(&quit));
+        thread::Builder::new()
+            .name("process".into())
+            .spawn(move || process(source, &input, link, debugwin::debug_channel(&[]).0, &quit, &opts))
+            .map_err(|e| Error::WindowInit(format!("Spawn processing thread: {e}")))?
+    };
+
+    let start = Instant::now();
+    for i in 0..frames {
+        if let Ok(mut shared) = input.lock() {
+            shared.merge(bench::brush_input(i, w, h));
+        }
+        match end.frames.recv() {
+            Ok(frame) => { let _ = end.recycle.send(frame); }
+            Err(_) => break,                                   // processing stopped (see its error)
+        }
+    }
+    let elapsed = start.elapsed();
+
+    quit.store(true, Ordering::Relaxed);
+    drop(end);
+    let (profiler, _) = processing
+        .join()
+        .map_err(|_| Error::CameraFrame("Processing thread panicked".into()))??;
+    bench::report(&profiler, elapsed, w, h);
+    Ok(())
+}
+
+/// Processing stage: everything between "camera frame in" and "finished screen out".
+/// Runs on its own thread; reads input snapshots taken by the window thread.
+/// Returns its profiler so `--bench` can print the per-stage totals, and the settings
+/// file updated with this session's brush/blur changes (saved on exit).
+fn process(capture: impl FrameSource + 'static, input_shared: &Mutex<Input>, present: PresentLink, debug: DebugLink,
+           quit: &AtomicBool, opts: &Options) -> Result<(Profiler, Config), Error> {
+    let mut stage = Processor::new(Box::new(capture), input_shared, present, debug, opts)?;
+    while !quit.load(Ordering::Relaxed) {
+        if !stage.frame()? {
+            break;                                             // window closed
+        }
+    }
+    stage.finish()
+}
+
+/// This frame's input once `prepare` has applied it, for the stages after it.
+struct Prepared {
+    input: Input,                              // mouse in frame pixels (through the view)
+    window_mouse: Option<(usize, usize)>,      // crosshair is drawn at window size
+    ui_left_down: bool,                        // the panel sees the real button
+    idle_now: bool,                            // this frame is the last before the IDLE pause
+}
+
+/// Which mask the effect goes under this frame.
+#[derive(Clone, Copy)]
+enum ActiveMask {
+    Painted,   // the app's mask
+    Portrait,  // portrait mode alone
+    Combined,  // portrait mode with painting on top
+}
+
+/// Portrait mode's masks.
+/// Visual: none directly; the subject's outline, alone or with your painting on top.
+struct PortraitMasks {
+    mask: Mask,
+    combined: Mask,
+    tiles: MaskTiles,              // tiles of portrait/combined
+    scratch: Vec<u8>,
+}
+
+impl ActiveMask {
+    /// The mask and its tiles (`painted` / `painted_tiles` are the app's).
+    fn pick<'m>(self, painted: &'m Mask, painted_tiles: &'m MaskTiles, portrait: &'m PortraitMasks) -> (&'m Mask, &'m MaskTiles) {
+        match self {
+            ActiveMask::Painted => (painted, painted_tiles),
+            ActiveMask::Portrait => (&portrait.mask, &portrait.tiles),
+            ActiveMask::Combined => (&portrait.combined, &portrait.tiles),
+        }
+    }
+}
+
+/// What `build_sink` decided for this frame, for compositing and the HUD.
+struct Scene {
+    active: ActiveMask,
+    portrait_active: bool,
+    effect_needed: bool,           // visual: false → plain live image
+    quality: BlurQuality,          // Q setting, or lower under load
+    gpu_frame: bool,               // blur + blend (and maybe FX) on the graphics card
+}
+
+/// The FILE cell in the warning colour.
+fn file_warning(theme: &Theme, what: &str) -> StatusCell {
+    StatusCell { value_color: Some(theme.warning), ..StatusCell::new("FILE", String::from(what)) }
+}
+
+/// Where overlays draw: the scaled-up copy after zoom / --half, else the picture itself.
+fn window_frame<'f>(upscaled: bool, display: &'f mut FrameBuffer, screen: &'f mut FrameBuffer) -> &'f mut FrameBuffer {
+    if upscaled { display } else { screen }
+}
+
+/// The processing stage's state, kept from frame to frame.
+struct Processor<'a> {
+    opts: &'a Options,
+    input_shared: &'a Mutex<Input>,
+    present: PresentLink,
+    debug: DebugLink,
+    w: u32,
+    h: u32,
+
+    /* --- Picture source ---
+       Visual: the camera (or test pattern) until a video is dropped onto the window;
+       the video then plays in its place, at the same size, until it fails. */
+    capture: Box<dyn FrameSource>,
+    replaced: Option<Box<dyn FrameSource>>,    // the camera a video stands in for
+
+    /* --- Processing resolution ---
+       Visual: with --half everything below runs on a quarter of the pixels; only the
+       finished picture is scaled up for the window (crosshair + HUD are drawn after,
+       so they stay sharp). */
+    scale: usize,
+    small_spare: Option<FrameBuffer>,          // --half: downscaled camera frame
+    display: FrameBuffer,                      // --half / zoom: what the window gets
+    screen: FrameBuffer,                       // visual: the image you actually see each frame
+    prev_live: FrameBuffer,                    // last frame, for the "vs previous" heat map
+
+    /* --- What events change (see app.rs) ---
+       Visual: the brush, mask, effect, filters and every on/off the HUD shows. */
+    app: App,
+    mask_thumb: MaskThumb,                     // visual: grey mask inset; click for the full-screen mask
+
+    /* --- Blur buffers (reused every frame) ---
+       Visual: `blur_tmp` is invisible scratch; `blur_sink` becomes BLUR(LIVE). */
+    blur_tmp: FrameBuffer,
+    blur_sink: FrameBuffer,
+    pyramid: BlurPyramid,                      // big radii: blur a small copy, scale back up
+    linear_blur: LinearBlur,                   // L: average light, not sRGB code values
+    governor: Governor,                        // visual: under load the blur softens, FX switch off
+    post_filters: FilterChain,                 // N vignette, G grain (--post), on the finished picture
+    median: MedianFilter,                      // visual: portrait mode's mask loses lone specks
+    portrait: PortraitMasks,
+    lut: GammaLut,                             // visual: seamless linear-light edges
+    #[cfg(feature = "gpu")]
+    gpu: Option<gpu::GpuBlend>,                // visual: same BLUR brush, from the graphics card
+
+    /* --- Input ---
+       Visual: clicks/drags + modifiers → paint, clear, source; remote control and panel
+       clicks arrive as events (see events.rs). */
+    gestures: Gestures,
+    touch: TouchGestures,                      // --touch: scroll read as pinch / two-finger pan
+    queue: EventQueue,
+    panel_events: EventSender,
+    idle: IdleWatch,                           // no input + still picture → low-power pause
+    idle_shown: bool,                          // the dimmed IDLE frame is on screen
+    idle_mouse: Option<(usize, usize)>,
+    pick_at: Option<(i32, i32)>,               // X click waiting to be sampled
+    #[cfg(feature = "script")]
+    script: Option<script::Script>,            // visual: whatever `--script FILE` asks for
+    #[cfg(feature = "script")]
+    script_start: Instant,
+    #[cfg(feature = "script")]
+    script_frame: u64,
+    #[cfg(feature = "script")]
+    script_fps: f32,                           // last second's FPS, for the script's stats
+
+    /* --- Settings config.toml can change while running --- */
+    theme: Theme,
+    hud_layout: HudLayout,
+    keymap: KeyMap,
+    config: Config,
+
+    /* --- HUD ---
+       Visual: mode hints + FPS, status bar, corner blocks, logo. */
+    logo: Option<Sprite>,                      // `--logo logo.png`, top right
+    hud_px: i32,                               // visual: bigger text on 1080p+ cameras
+    color_pick: Option<ColorPick>,             // last picked colour (stays on the HUD)
+    histogram: Histogram,                      // I: RGB/luma histogram
+    last_frame_time: Instant,
+    last_fps_time: Instant,
+    frames_this_second: u32,
+    late_this_second: u32,                     // frames whose processing overran the budget
+    frame_budget: Duration,
+    hud_fps_text: String,
+    profiler: Profiler,                        // P: per-stage milliseconds and a frame-time graph
+    metrics: Option<MetricsLog>,               // --metrics FILE
+    outputs: Outputs,                          // visual: none in the window; each output gets its tap
+
+    /* --- Files ---
+       Visual: the FILE cell of the status bar, and a busy bar while one is written. */
+    snapshot_path: String,                     // visual: SNAPSHOT FILE field
+    snapshot_due: bool,                        // save requested; written next frame
+    saved_tx: mpsc::Sender<Result<String, Error>>, // writer thread → status bar
+    saved_rx: mpsc::Receiver<Result<String, Error>>,
+    file_state: StatusCell,                    // visual: right end of the status bar
+    saving: Option<(String, Instant)>,         // visual: busy bar until the file is written
+
+    /* --- Session (--session FILE) and crash recovery ---
+       Visual: the window opens with the saved mask painted, the saved effect and toggles
+       on, and portrait mode working without another R. After a crash, a prompt offers
+       the last checkpoint instead (ENTER restores, BACKSPACE discards). */
+    restore_due: Option<(Session, bool)>,      // (session, from --session)
+    checkpoint_path: Option<PathBuf>,
+    recovery: Option<Session>,
+    checkpoint_at: Instant,
+    checkpoint_seen: u64,                      // fingerprint of what the last checkpoint holds
+    checkpoint_writer: Option<thread::JoinHandle<()>>,
+}
+
+impl<'a> Processor<'a> {
+    fn new(capture: Box<dyn FrameSource>, input_shared: &'a Mutex<Input>, present: PresentLink, debug: DebugLink,
+           opts: &'a Options) -> Result<Self, Error> {
+        let (w, h) = capture.resolution();
+        let scale: usize = if opts.half_res { 2 } else { 1 };
+        let (pw, ph) = ((w as usize).div_ceil(scale), (h as usize).div_ceil(scale));
+        let blank = |width: usize, height: usize| FrameBuffer { width, height, pixels: vec![0u32; width * height] };
+        let app = App::new(opts, pw, ph, w as usize)?;
+
+        let queue = EventQueue::new();
+        if let Some(port) = opts.osc_port {
+            osc::listen(port, queue.sender())?;               // --osc PORT: remote control
+        }
+        #[cfg(feature = "gpu")]
+        let gpu = match gpu::GpuBlend::new(pw, ph, app.fx.discs()) {
+            Ok(g) => Some(g),
+            Err(e) => { log::warn!("{e} — using the CPU path"); None }
+        };
+        let checkpoint_path = if opts.bench_frames.is_none() { Session::checkpoint_path() } else { None };
+        let recovery = match checkpoint_path.as_deref().filter(|p| p.exists()) {
+            Some(path) => match Session::load(path) {
+                Ok(s) => {
+                    log::warn!("the last run did not exit cleanly; its checkpoint is in {}", path.display());
+                    Some(s)
+                }
+                Err(e) => { log::warn!("{e} — checkpoint ignored"); None }
+            },
+            None => None,
+        };
+        #[cfg(feature = "script")]
+        let script = opts.script.as_deref().map(|path| script::Script::load(path, queue.sender())).transpose()?;
+        let (saved_tx, saved_rx) = mpsc::channel();
+        Ok(Self {
+            opts,
+            input_shared,
+            present,
+            debug,
+            w,
+            h,
+            capture,
+            replaced: None,
+            scale,
+            small_spare: opts.half_res.then(|| blank(pw, ph)),
+            display: if opts.half_res { blank(w as usize, h as usize) } else { blank(0, 0) },
+            screen: blank(pw, ph),
+            prev_live: blank(pw, ph),
+            app,
+            mask_thumb: MaskThumb::new(),
+            blur_tmp: blank(pw, ph),
+            blur_sink: blank(pw, ph),
+            pyramid: BlurPyramid::new(),
+            linear_blur: LinearBlur::new(),
+            governor: Governor::new(governor::frame_budget(opts.fps)),
+            post_filters: filters::build(&opts.post_filters, "--post")?,
+            median: MedianFilter::new(),
+            portrait: PortraitMasks {
+                mask: Mask::new(pw, ph),
+                combined: Mask::new(pw, ph),
+                tiles: MaskTiles::new(pw, ph),
+                scratch: Vec::new(),
+            },
+            lut: GammaLut::new(),
+            #[cfg(feature = "gpu")]
+            gpu,
+            gestures: Gestures::default(),
+            touch: TouchGestures::default(),
+            panel_events: queue.sender(),
+            queue,
+            idle: IdleWatch::new(),
+            idle_shown: false,
+            idle_mouse: None,
+            pick_at: None,
+            #[cfg(feature = "script")]
+            script,
+            #[cfg(feature = "script")]
+            script_start: Instant::now(),
+            #[cfg(feature = "script")]
+            script_frame: 0,
+            #[cfg(feature = "script")]
+            script_fps: 0.0,
+            theme: opts.theme,
+            hud_layout: opts.hud,
+            keymap: opts.keymap.clone(),
+            config: opts.config.clone(),
+            logo: opts.logo_path.as_deref().map(Sprite::load).transpose()?,
+            hud_px: hud_scale(h as usize),
+            color_pick: None,
+            histogram: Histogram::new(),
+            last_frame_time: Instant::now(),
+            last_fps_time: Instant::now(),
+            frames_this_second: 0,
+            late_this_second: 0,
+            frame_budget: governor::frame_budget(opts.fps),
+            hud_fps_text: String::from("FPS: 0.0"),
+            profiler: Profiler::new(governor::frame_budget(opts.fps)),
+            metrics: opts.metrics.as_deref().map(MetricsLog::create).transpose()?,
+            outputs: Outputs::start(&opts.outputs, opts.fps),
+            snapshot_path: opts.snapshot_path.clone(),
+            snapshot_due: false,
+            saved_tx,
+            saved_rx,
+            file_state: StatusCell::new("FILE", String::from("IDLE")),
+            saving: None,
+            restore_due: opts.session.clone().map(|s| (s, true)),
+            checkpoint_path,
+            recovery,
+            checkpoint_at: Instant::now(),
+            checkpoint_seen: 0,
+            checkpoint_writer: None,
+        })
+    }
+
+    /// One frame, camera to window. False once the window has closed.
+    fn frame(&mut self) -> Result<bool, Error> {
+        let now = Instant::now();
+        let dt = (now - self.last_frame_time).as_secs_f32();
+        self.last_frame_time = now;
+
+        /* 1) Grab a fresh live frame (what the camera sees right now).
+           Visual: this is the raw base we’ll start from. */
+        let Some(mut live) = self.grab()? else { return Ok(true) };
+        let work_start = Instant::now();      // governor times processing, not waiting for the camera
+        self.profiler.lap(Stage::Capture);
+
+        /* 2) Inputs (everything pressed since the last frame) */
+        let Some(frame) = self.prepare(&live, now)? else {
+            self.recycle(live);
+            thread::sleep(IDLE_FRAME_INTERVAL.saturating_sub(now.elapsed()));
+            return Ok(true);                                   // visual: the IDLE frame stays up
+        };
+
+        /* 2b–3) Masks and the blurred sink; 3b–6) the picture with the effect and FX. */
+        let scene = self.build_sink(&mut live)?;
+        self.composite(&live, &scene, dt)?;
+        self.read_picture(&frame, &scene);
+
+        /* 6b) Overlays at window size: HUD, panels, prompts. */
+        let upscaled = self.draw_overlays(&frame);
+        self.draw_hud(&frame, &scene, upscaled);
+        self.draw_panels(&frame, &scene, upscaled);
+
+        // Remember this frame for the "vs previous" heat map.
+        if self.app.mode.diff_view() == segment::DiffView::Previous || self.debug.wants(DebugView::Diff) {
+            self.prev_live.pixels.copy_from_slice(&live.pixels);
+        }
+        self.profiler.lap(Stage::Hud);
+        if self.opts.bench_frames.is_none() {
+            let work = work_start.elapsed();
+            if work > self.frame_budget {
+                self.late_this_second += 1;
+                log::trace!("frame over budget: {:.1} ms", work.as_secs_f64() * 1000.0);
+            }
+            self.governor.record(work);                        // visual: AUTO level may change
+        }                                                      // (--bench measures fixed quality)
+
+        /* 7) Hand the frame to the window thread (it appears on its next update). */
+        let out = window_frame(upscaled, &mut self.display, &mut self.screen);
+        self.outputs.send(Tap::Screen, out);
+        if !self.present.send(out) {
+            return Ok(false);                                  // window closed
+        }
+        self.housekeeping(live, now, dt);
+        Ok(true)
+    }
+
+    /// The next picture from the source, downscaled with --half. None skips this frame
+    /// (a dropped camera frame, or a video that just ended and handed back the camera).
+    fn grab(&mut self) -> Result<Option<FrameBuffer>, Error> {
+        let frame = match self.capture.next_frame() {
+            Ok(frame) => frame,
+            Err(e) if e.is_recoverable() => {
+                log::debug!("{e} — frame skipped");              // visual: the last picture stays a moment
+                return Ok(None);
+            }
+            Err(e) => match self.replaced.take() {
+                Some(camera) => {
+                    log::warn!("{e} — back to the camera");          // visual: the camera picture returns
+                    self.capture = camera;
+                    self.file_state = file_warning(&self.theme, "VIDEO STOPPED");
+                    return Ok(None);
+                }
+                None => return Err(e),                            // camera gone: end with the message
+            },
+        };
+        Ok(Some(match self.small_spare.take() {   // corrected in place below; then we copy it into screen
+            Some(mut small) => {
+                downsample_2x(&frame, &mut small)?;          // --half: average 2x2 blocks
+                self.capture.recycle(frame);
+                small
+            }
+            None => frame,
+        }))
+    }
+
+    /// Hand `live` back: downscaled into again next frame (--half), or to the camera.
+    fn recycle(&mut self, live: FrameBuffer) {
+        if self.scale > 1 {
+            self.small_spare = Some(live);
+        } else {
+            self.capture.recycle(live);
+        }
+    }
+
+    /// Take the input since the last frame and apply it: prompts, reloads, dropped files,
+    /// the script, then every key, click and remote message as events (app.rs). None
+    /// while idle with the dimmed frame already up (nothing to do this frame).
+    fn prepare(&mut self, live: &FrameBuffer, now: Instant) -> Result<Option<Prepared>, Error> {
+        let mut input = self.input_shared.lock().map(|mut i| i.take()).unwrap_or_default();
+        if self.app.ui.has_focus() {
+            input.clear_shortcuts();                           // typing a name doesn't fire hotkeys
+        } else if self.recovery.is_some() && (input.enter_pressed || input.backspace_pressed) {
+            // Crash recovery prompt: the answer keys do nothing else this frame.
+            if let Some(checkpoint) = self.recovery.take() {
+                if input.enter_pressed {
+                    self.restore_due = Some((checkpoint, false)); // visual: the mask from before the crash
+                } else if let Some(path) = &self.checkpoint_path {
+                    Session::discard_checkpoint(path);         // visual: prompt gone, nothing changes
+                }
+            }
+            input.enter_pressed = false;
+            input.backspace_pressed = false;
+        }
+        if let Some((s, from_flag)) = self.restore_due.take() {
+            match self.app.restore(&s, from_flag) {
+                Err(e) if from_flag => return Err(e),          // --session was asked for: say why not
+                Err(e) => log::warn!("{e} — checkpoint not restored"),
+                Ok(()) => {}
+            }
+        }
+        self.app.ui.text_events(&input.typed, input.backspace_pressed, input.enter_pressed);
+        if let Some(cfg) = input.reloaded.take() {
+            self.reload(*cfg);
+        }
+        for path in std::mem::take(&mut input.dropped) {
+            self.file_state = self.app.load_dropped(&path, self.theme.warning); // visual: FILE LOADED … / why not
+        }
+        if let Some(path) = self.app.video_due.take() {
+            self.play(&path);
+        }
+        #[cfg(feature = "script")]
+        self.run_script(live);
+        let remote: Vec<Event> = self.queue.drain().collect(); // OSC, script, last frame's panel clicks, …
+        let window_mouse = input.mouse_pos;
+
+        // Idle (no input, nothing moving): keep the dimmed picture and only glance at the
+        // camera a few times a second. Anything at all resumes on this frame.
+        let active = input.has_activity() || !remote.is_empty() || window_mouse != self.idle_mouse;
+        self.idle_mouse = window_mouse;
+        let idle_now = self.opts.bench_frames.is_none() && self.idle.update(live, active, now);
+        if idle_now && self.idle_shown {
+            return Ok(None);
+        }
+
+        let app = &mut self.app;
+        if self.opts.touch {
+            let (dx, dy) = self.touch.apply(&mut input);       // visual: pinch resizes the brush
+            app.view.pan_by(dx, dy);                           // visual: picture follows two fingers
+        }
+        if input.mods.ctrl && input.scroll_steps != 0.0 && let Some(m) = window_mouse {
+            app.view.zoom_by(input.scroll_steps, m);           // visual: picture grows around the mouse
+        }
+        app.view.pan(window_mouse, input.middle_mouse_down);   // visual: picture follows middle-drag
+        input.mouse_pos = window_mouse.map(|m| app.view.to_frame_px(m)); // dabs land under the cursor
+        let ui_left_down = input.left_mouse_down;
+        if app.panel_on && app.ui.wants_mouse(window_mouse) {
+            input.left_mouse_down = false;                     // visual: no painting under the panel
+            input.right_mouse_down = false;
+        }
+        let (thumb_clicked, over_thumb) = self.mask_thumb.handle_mouse(window_mouse, input.left_mouse_down);
+        if thumb_clicked { app.mode = app.mode.toggle_review(Review::Mask); } // visual: full-screen mask on/off
+        if over_thumb {
+            input.left_mouse_down = false;                     // visual: no painting under the thumbnail
+        }
+        if app.compare.on && app.compare.handle_mouse(window_mouse, input.left_mouse_down, &app.view) {
+            input.left_mouse_down = false;                     // visual: dragging the divider doesn't paint
+        }
+        app.mode.gate(&mut input);                             // e.g. the brush waits for a capture
+
+        // A click while W / X is armed samples instead of painting.
+        let picking = (app.picker_armed || app.wb_pick_armed) && input.left_mouse_down;
+        if picking && app.picker_armed {
+            // Visual: this click reads a colour instead of painting (sampled once the frame is done).
+            self.pick_at = input.mouse_pos.map(|(mx, my)| (mx as i32, my as i32));
+            app.picker_armed = false;
+        } else if picking {
+            // Visual: this click samples white balance instead of painting.
+            if let Some(white) = app.live_filters.get_mut::<WhiteBalancePick>() {
+                white.pick_at = input.mouse_pos.map(|(mx, my)| (mx as i32, my as i32));
+            }
+            app.wb_pick_armed = false;
+        }
+
+        /* 2a) Everything asked for this frame, as events (see events.rs): keys and steps,
+           then the queue, then the brush gesture — applied in that order. */
+        let mut events = events::from_input(&input);
+        events.extend(remote);
+        let gesture = self.gestures.interpret(&input, now);
+        if picking {
+            self.gestures.consume_press();                     // visual: no dab while the button stays down
+        }
+        match gesture {
+            Some(Gesture::Paint { .. } | Gesture::ClearAt(_)) if picking => {}
+            Some(gesture) => events.push(gesture.into()),
+            None => {}
+        }
+        let mut stroke = false;
+        for event in events {
+            stroke |= app.apply(event, now);
+        }
+        if !stroke {
+            app.history.mask_done(&app.mask);                  // stroke over: one undo step
+        }
+        app.mode = app.mode.stroke(stroke);
+        Ok(Some(Prepared { input, window_mouse, ui_left_down, idle_now }))
+    }
+
+    /// config.toml (or its theme file) changed on disk.
+    /// Visual: the edits show up within a second.
+    fn reload(&mut self, cfg: Config) {
+        let app = &mut self.app;
+        app.blur_radius = (cfg.brush.blur_radius as usize).clamp(1, 96);
+        app.set_brush(cfg.brush.size as i32 / self.scale as i32, cfg.brush.hardness);
+        app.fx_on = cfg.brush.fx;
+        app.fx.set_params(cfg.fx.params());
+        if let Ok(live) = cfg.live_settings() {                // checked on the window thread
+            self.theme = live.theme;
+            self.hud_layout = live.hud;
+            app.cursor_styles = live.cursor;
+            self.keymap = live.keymap;
+            app.fx.set_colors(self.theme.sparkle, self.theme.bolt, self.theme.smoke);
+            app.ui.set_theme(self.theme);
+        }
+        self.config = cfg;
+    }
+
+    /// A video was dropped: play it instead of the camera.
+    /// Visual: the next frame is the video's (LOAD FAILED in the status bar if it won't open).
+    fn play(&mut self, path: &Path) {
+        match VideoSource::open(path, self.w, self.h) {
+            Ok(video) => {
+                let camera = std::mem::replace(&mut self.capture, Box::new(video));
+                self.replaced.get_or_insert(camera);           // a second video just replaces the first
+            }
+            Err(e) => {
+                log::warn!("{e}");
+                self.file_state = file_warning(&self.theme, "LOAD FAILED");
+            }
+        }
+    }
+
+    /// `--script`: this frame's stats in, its events out (they land this frame).
+    #[cfg(feature = "script")]
+    fn run_script(&mut self, live: &FrameBuffer) {
+        let Some(script) = &mut self.script else { return };
+        self.script_frame += 1;
+        let app = &self.app;
+        script.on_frame(&script::FrameStats {
+            t: self.script_start.elapsed().as_secs_f32(),
+            frame: self.script_frame,
+            fps: self.script_fps,
+            width: live.width,
+            height: live.height,
+            coverage: app.mask.coverage(),
+            brightness: script::brightness(live),
+            blur_radius: app.blur_radius,
+            brush_radius: app.eraser_radius * self.scale as i32,
+            hardness: app.brush_hardness,
+            effect: app.effects.active_name(),
+            portrait: app.portrait_on,
+            background: app.have_background,
+        });
+    }
+
+    /// Correct the live frame, work out the mask, and blur it (BLUR(LIVE)) if anything
+    /// will show the blur.
+    /// Visual: not shown directly unless B is on; used for eraser mixing.
+    fn build_sink(&mut self, live: &mut FrameBuffer) -> Result<Scene, Error> {
+        let app = &mut self.app;
+        if app.compare.on {
+            app.compare.capture_raw(live);                     // before any correction touches it
+        }
+        let painted = app.mask.bounds.is_some();               // visual: if false, we skip blending (faster)
+
+        /* 2b) Colour correction on the live frame, before any sink is built.
+           Visual: both the raw and the painted parts of the image get the same fix. */
+        app.live_filters.set_enabled("levels", app.levels_on);
+        app.live_filters.set_enabled("smooth", app.beauty_on); // visual: softer skin everywhere
+        app.live_filters.apply(live, &self.lut, false)?;       // neutral steps cost next to nothing
+        self.outputs.send(Tap::Live, live);                    // [[pipeline.output]] tap = "live"
+        self.debug.send(DebugView::Live, live.width, live.height, |buf| { buf.pixels.copy_from_slice(&live.pixels); Ok(()) })?;
+
+        /* 2c) Background capture: fold frames into the running per-pixel estimate.
+           Visual: HUD counts up; afterwards portrait mode knows what "empty room" looks like. */
+        if app.mode.capturing() {
+            app.bg_model.update(live)?;
+            if app.bg_model.frames_seen() >= BG_CAPTURE_COUNT {
+                app.have_background = true;
+                app.mode = app.mode.capture_finished();
+            }
+        }
+
+        /* 2d) Portrait mask: subject = differs from background; effect goes everywhere else.
+           Visual: you stay sharp, the room gets the effect; outline is feathered. */
+        let portrait_active = app.portrait_on && app.have_background && !app.mode.capturing();
+        let active = if portrait_active {
+            let p = &mut self.portrait;
+            segment::foreground_mask(live, app.bg_model.frame(), &mut p.mask)?;
+            self.median.apply_mask(&mut p.mask, 2);            // visual: lone noisy specks vanish
+            segment::soften_mask(&mut p.mask, &mut p.scratch, PORTRAIT_FEATHER);
+            vision::invert_mask(&mut p.mask);                  // background = 1 → gets the effect
+            app.bg_model.refresh(live, &p.mask)?;              // visual: room keeps up with lighting
+            if painted {
+                segment::max_masks(&app.mask, &p.mask, &mut p.combined); // painting adds on top
+                p.tiles.rebuild(&p.combined);
+                ActiveMask::Combined
+            } else {
+                p.tiles.rebuild(&p.mask);
+                ActiveMask::Portrait
+            }
+        } else {
+            ActiveMask::Painted
+        };
+        let (active_mask, _) = active.pick(&app.mask, &app.mask_tiles, &self.portrait);
+        let effect_needed = painted || portrait_active;
+        self.debug.send(DebugView::Mask, live.width, live.height, |buf| maskview::mask_to_screen(active_mask, buf))?;
+        self.debug.send(DebugView::Background, live.width, live.height, |buf| {
+            if app.have_background || app.mode.capturing() {
+                buf.pixels.copy_from_slice(&app.bg_model.frame().pixels); // visual: the empty room
+            } else {
+                buf.pixels.fill(0);
+            }
+            Ok(())
+        })?;
+
+        let quality = self.governor.blur_quality(app.blur_quality); // Q setting, or lower under load
+
+        // GPU takes over blur + blend for the plain BLUR brush, but only with the settings
+        // its shader does (full-resolution box blur, sRGB); STACK, LIN, a lower Q quality
+        // or a radius the CPU hands to the pyramid stay on the CPU, as do the debug views.
+        #[cfg(feature = "gpu")]
+        let gpu_frame = self.gpu.is_some() && app.effects.active_is_blur() && effect_needed
+            && app.blur_kind == BlurKind::Box && !app.blur_linear_on && quality == BlurQuality::Full
+            && app.blur_radius <= PYRAMID_MIN_RADIUS
+            && !app.mode.shows_sink() && app.mode.diff_view() == segment::DiffView::Off
+            && !self.debug.wants(DebugView::Blur) && !self.debug.wants(DebugView::Sink);
+        #[cfg(not(feature = "gpu"))]
+        let gpu_frame = false;
+
+        self.profiler.lap(Stage::Prep);
+
+        /* 3) Build the blurred sink from the live frame (BLUR(LIVE)). */
+        let min_depth = quality.depth();
+        // Plain BLUR only needs the pixels the blend reads: the painted box (the blur
+        // reads `radius` around it itself; everything else is copied from live).
+        // (Heal samples the blur at the clone source too, so other effects get it all.)
+        let sink_wanted = app.mode.shows_sink() || self.debug.wants(DebugView::Sink);
+        let blur_wanted = self.debug.wants(DebugView::Blur);
+        let region = match active_mask.bounds {
+            Some(b) if !sink_wanted && !blur_wanted && app.effects.active_is_blur() => b,
+            _ => Rect::full(live.width, live.height),
+        };
+        let blur_used = ((effect_needed || sink_wanted) && app.effects.active_needs_blur() || blur_wanted) && !gpu_frame;
+        let blur_key = (app.blur_radius, app.blur_kind, quality, app.blur_linear_on, region);
+        let (tmp, sink, lut) = (&mut self.blur_tmp, &mut self.blur_sink, &self.lut);
+        if !blur_used {
+            app.blur_cache.invalidate();                       // visual: nothing shows the blur → skip it
+        } else if !app.blur_cache.needs_refresh(blur_key) {
+            // visual: static scene, reuse last frame's blur
+        } else if app.blur_radius > PYRAMID_MIN_RADIUS || min_depth > 0 {
+            // visual: soft blur, cheap; the small copy gets the chosen blur (LIN, STACK or box)
+            let linear_blur = &mut self.linear_blur;
+            self.pyramid.blur(live, sink, app.blur_radius, min_depth, |small, out, tmp, r| {
+                if app.blur_linear_on {
+                    linear_blur.blur(small, out, r, lut)
+                } else if app.blur_kind == BlurKind::Stack {
+                    stack_blur_rgb(small, tmp, out, r, Rect::full(small.width, small.height))
+                } else {
+                    box_blur_rgb(small, tmp, out, r)
+                }
+            })?;
+        } else if app.blur_linear_on {
+            self.linear_blur.blur(live, sink, app.blur_radius, lut)?; // visual: physically plausible blur
+        } else if app.blur_kind == BlurKind::Stack {
+            stack_blur_rgb(live, tmp, sink, app.blur_radius, region)?; // visual: rounder blur
+        } else {
+            box_blur_rgb_rect(live, tmp, sink, app.blur_radius, region)?;
+        }
+        self.profiler.lap(Stage::Blur);
+        Ok(Scene { active, portrait_active, effect_needed, quality, gpu_frame })
+    }
+
+    /// The brush effect under the mask, polish filters and FX: the finished picture in
+    /// `screen` (or a review view in its place).
+    /// Visual: you “paint blur” into the live feed with soft edges.
+    fn composite(&mut self, live: &FrameBuffer, scene: &Scene, dt: f32) -> Result<(), Error> {
+        let app = &mut self.app;
+        let (active_mask, active_tiles) = scene.active.pick(&app.mask, &app.mask_tiles, &self.portrait);
+        let show_sink = app.mode.shows_sink();                 // B: the sink full screen
+        let sink_wanted = show_sink || self.debug.wants(DebugView::Sink);
+
+        /* 3b) The brush effect's sink (only when something will show it).
+           Visual: whatever the active effect looks like; blended in under the mask below. */
+        let sink = if (scene.effect_needed || sink_wanted) && !scene.gpu_frame {
+            let ctx = EffectCtx { live, blur: &self.blur_sink, mask: active_mask, lut: &self.lut, clone_offset: app.clone_offset };
+            app.effects.render(&ctx)?
+        } else {
+            &self.blur_sink
+        };
+        let debug = &mut self.debug;
+        debug.send(DebugView::Blur, live.width, live.height, |buf| { buf.pixels.copy_from_slice(&self.blur_sink.pixels); Ok(()) })?;
+        debug.send(DebugView::Sink, live.width, live.height, |buf| { buf.pixels.copy_from_slice(&sink.pixels); Ok(()) })?;
+        debug.send(DebugView::Diff, live.width, live.height, |buf| segment::diff_heatmap(live, &self.prev_live, buf))?;
+        self.profiler.lap(Stage::Effect);
+
+        /* 4) Choose what to show as the base image this frame. */
+        let screen = &mut self.screen;
+        let diff_ref = match app.mode.diff_view() {
+            segment::DiffView::Background => app.have_background.then(|| app.bg_model.frame()),
+            segment::DiffView::Previous => Some(&self.prev_live),
+            segment::DiffView::Off => None,
+        };
+        if let Some(reference) = diff_ref {
+            // Visual: heat map of what changed (debug view replaces the image entirely)
+            segment::diff_heatmap(live, reference, screen)?;
+        } else if show_sink {
+            // Visual: full-screen sink (blurred camera, or whatever the current brush paints)
+            screen.pixels.copy_from_slice(&sink.pixels);
+        } else {
+            // Visual: raw live camera
+            screen.pixels.copy_from_slice(&live.pixels);
+        }
+
+        /* 5) If we have any painted (or portrait) mask, blend the sink into LIVE where α>0. */
+        self.post_filters.set_enabled("vignette", app.vignette_on);       // visual: darker corners
+        self.post_filters.set_enabled("grain", app.grain_on);             // visual: film grain
+        // The GPU draws the FX too when nothing comes between the blend and them: no
+        // post filter on, no output that wants the picture without FX.
+        #[cfg(feature = "gpu")]
+        let fx_on_gpu = scene.gpu_frame && app.fx_on && !self.governor.skip_fx()
+            && !self.post_filters.any_enabled() && !self.outputs.wants(Tap::Clean);
+        #[cfg(not(feature = "gpu"))]
+        let fx_on_gpu = false;
+        if scene.gpu_frame {
+            #[cfg(feature = "gpu")]
+            if let Some(g) = self.gpu.as_mut() {
+                if fx_on_gpu {
+                    app.fx.update(dt);                                     // visual: glows fade & drift
+                }
+                let fx = if fx_on_gpu { app.fx.dabs() } else { &[] };
+                g.blur_blend(live, active_mask, app.blur_radius, fx, screen)?; // visual: same blur and FX, from the GPU
+            }
+        } else if !show_sink && diff_ref.is_none() && scene.effect_needed {
+            blend_linear_in_place(screen, sink, active_mask, active_tiles, &self.lut)?; // visual: blur/fill appears under brush
+        }
+
+        /* 5b) Polish on the composite (before FX/HUD so those stay crisp). */
+        self.post_filters.apply(screen, &self.lut, self.governor.skip_fx())?; // grain is dropped under load
+        self.outputs.send(Tap::Clean, screen);                           // visual: no FX or HUD in it
+        self.profiler.lap(Stage::Blend);
+
+        /* 6) FX on top (sparkles/bolt) */
+        if fx_on_gpu {
+            // visual: already on the picture (step 5)
+        } else if app.fx_on && !self.governor.skip_fx() {
+            app.fx.update_and_render(screen, dt);                          // visual: glows fade & drift
+        } else if !app.fx_on {
+            app.fx.clear();                                                // off: none left over, no FX cost
+        }
+        self.profiler.lap(Stage::Fx);
+
+        // Full-screen mask (clicked thumbnail) replaces the picture.
+        if app.mode.shows_mask() {
+            maskview::mask_to_screen(active_mask, screen)?;               // visual: white = painted
+        }
+
+        // Split view: the raw camera left of the divider.
+        if app.compare.on {
+            app.compare.apply(screen);                                     // visual: BEFORE | AFTER
+        }
+        Ok(())
+    }
+
+    /// Everything that reads the finished picture before any overlay lands on it: the
+    /// colour picker, a snapshot, the loupe and the histogram.
+    fn read_picture(&mut self, frame: &Prepared, scene: &Scene) {
+        let app = &mut self.app;
+        let screen = &self.screen;
+
+        // Colour picker: read the finished picture (no HUD on it yet) and copy the hex.
+        if let Some((px, py)) = self.pick_at.take() && let Some(pick) = ColorPick::sample(screen, px, py) {
+            let hex = pick.hex();
+            thread::spawn(move || {
+                if let Err(e) = picker::copy_to_clipboard(&hex) {
+                    log::warn!("{e}");                             // picked value still shows in the HUD
+                }
+            });
+            self.color_pick = Some(pick);
+        }
+
+        // Snapshot: the finished picture (no HUD yet), written off the processing thread;
+        // the mask goes next to it (`shot-mask.png`) for `magic-eraser process --mask`.
+        if self.snapshot_due {
+            self.snapshot_due = false;
+            let (active_mask, _) = scene.active.pick(&app.mask, &app.mask_tiles, &self.portrait);
+            let (path, shot, done) = (self.snapshot_path.clone(), screen.clone(), self.saved_tx.clone());
+            let mask_shot = active_mask.bounds.is_some().then(|| active_mask.clone());
+            self.file_state = StatusCell::new("FILE", format!("SAVING {path}"));
+            self.saving = Some((path.clone(), Instant::now()));
+            thread::spawn(move || {
+                let saved = imageio::save_image(&path, &shot).and_then(|()| match &mask_shot {
+                    Some(m) => imageio::save_mask(&imageio::mask_path(&path), m),
+                    None => Ok(()),
+                });
+                let _ = done.send(saved.map(|()| path));
+            });
+        }
+        if let Ok(result) = self.saved_rx.try_recv() {
+            self.saving = None;
+            self.file_state = match result {
+                Ok(path) => StatusCell::new("FILE", format!("SAVED {path}")),
+                Err(e) => {
+                    log::warn!("{e}");
+                    file_warning(&self.theme, "SAVE FAILED")
+                }
+            };
+        }
+
+        // Loupe: copy the pixels around the cursor before any overlay lands on them.
+        if app.hud_on && app.loupe.is_on() && self.hud_layout.loupe.visible {
+            match frame.input.mouse_pos {
+                Some((mx, my)) => app.loupe.capture(screen, mx as i32, my as i32, self.hud_px),
+                None => app.loupe.clear(),
+            }
+        }
+
+        // Histogram of the finished picture (before the HUD is drawn on it).
+        if app.hud_on && app.histogram_on && self.hud_layout.histogram.visible {
+            self.histogram.compute(screen);
+        }
+    }
+
+    /// Scale the picture up for the window if zoom or --half need it, then draw the logo,
+    /// guides, divider and cursor over it. True when it went into `display`.
+    fn draw_overlays(&mut self, frame: &Prepared) -> bool {
+        let (app, theme, hud_px) = (&self.app, &self.theme, self.hud_px);
+        let (w, h) = (self.w as usize, self.h as usize);
+
+        /* Zoom / --half: scale the finished picture up; everything below draws at window size. */
+        let upscaled = if !app.view.is_identity() {
+            app.view.render(&self.screen, &mut self.display, w, h); // visual: crisp magnified pixels
+            true
+        } else if self.scale > 1 {
+            resize_bilinear(&self.screen, &mut self.display);       // visual: slightly softer
+            true
+        } else {
+            false
+        };
+        let out = window_frame(upscaled, &mut self.display, &mut self.screen);
+
+        if let Some(logo) = &self.logo {
+            // At most a sixth of the window wide; never enlarged past its own pixels.
+            let k = (out.width as f32 / 6.0 / logo.width as f32).min(1.0);
+            let (lw, _) = logo.scaled_size(k);
+            logo.draw(out, out.width as i32 - lw - 8, 8, k, 255);          // visual: logo top right
+        }
+        if app.guides.any() {
+            app.guides.draw(out, hud_px, theme.guides);                    // visual: faint framing lines
+        }
+        if app.compare.on {
+            app.compare.draw(out, &app.view, hud_px, theme.divider);       // visual: divider + labels
+        }
+
+        // Cursor in the style of the current mode (a pick click this frame switches back to paint).
+        let cursor_style = app.cursor_styles.get(app.cursor_mode());
+        self.present.hide_os_cursor(cursor_style == CursorStyle::Hidden);
+        if let Some((mx, my)) = frame.window_mouse && cursor_style != CursorStyle::Hidden {
+            let ring = app.stamp.radius as f32 * app.view.window_per_frame(); // brush size in window pixels
+            cursor_style.draw(out, mx as i32, my as i32, ring, theme.accent); // visual: + and/or brush ring
+            if app.effects.active_uses_clone_source() && let Some((fx, fy)) = frame.input.mouse_pos {
+                // Visual: small + (blue by default) where texture is being copied from
+                let src = match app.clone_offset {
+                    Some((dx, dy)) => Some((fx as i32 + dx, fy as i32 + dy)),
+                    None => app.clone_src,
+                };
+                if let Some((sx, sy)) = src.map(|p| app.view.to_window(p)) {
+                    draw_crosshair(out, sx, sy, 8, theme.clone_source);
+                    // Visual: a thin line ties the source to the cursor
+                    draw_line_thick(out, sx as f32, sy as f32, mx as f32, my as f32, 1.0, theme.clone_source);
+                }
+            }
+        }
+        if let Some((a, b)) = self.gestures.line_preview() {
+            // Visual: Shift+drag shows the straight stroke it will paint on release.
+            let ((ax, ay), (bx, by)) = (app.view.to_window(a), app.view.to_window(b));
+            draw_line_thick(out, ax as f32, ay as f32, bx as f32, by as f32, 1.0, theme.accent);
+        }
+        upscaled
+    }
+
+    /// HUD tag for how the blur is computed: GPU, LIN or nothing.
+    fn backend_tag(&self, scene: &Scene) -> &'static str {
+        if scene.gpu_frame { " GPU" } else if self.app.blur_linear_on { " LIN" } else { "" }
+    }
+
+    /// The HUD: the top lines, status bar and corner blocks (H hides them), then the
+    /// progress bars for timed jobs (always shown).
+    fn draw_hud(&mut self, frame: &Prepared, scene: &Scene, upscaled: bool) {
+        let backend_tag = self.backend_tag(scene);
+        let (app, theme, hud_px, lut) = (&self.app, &self.theme, self.hud_px, &self.lut);
+        let (active_mask, _) = scene.active.pick(&app.mask, &app.mask_tiles, &self.portrait);
+        let out = window_frame(upscaled, &mut self.display, &mut self.screen);
+
+        let status = if app.privacy.is_some() {                            // visual: left HUD tag
+            String::from("PRIVACY")
+        } else {
+            app.mode.status(app.bg_model.frames_seen(), scene.portrait_active)
+        };
+        let name = app.effects.active_name();
+        let hint = app.mode.hint(name);                                    // visual: keys for this mode
+        let reuse_tag = if app.blur_cache.every > 1 { format!(" /{}", app.blur_cache.every) } else { String::new() };
+        let res_tag = if self.scale > 1 { " LOW RES" } else { "" };
+        let cursor_style = app.cursor_styles.get(app.cursor_mode());
+        let cursor_tag = if cursor_style != CursorStyle::Both { format!(" | CURSOR {}", cursor_style.label()) }
+                         else { String::new() };                       // visual: F2 choice named
+        let zoom_tag = if app.view.is_identity() { String::new() } else { format!(" | ZOOM {:.1}x", app.view.zoom()) };
+        let hud = format!("{}{} | {} R {}{}{}{}{}{}{}{} | {}", status, hint, app.blur_kind.label(), app.blur_radius, backend_tag,
+                          scene.quality.label(), reuse_tag, self.governor.label(), res_tag, zoom_tag, cursor_tag, self.hud_fps_text);
+
+        // Visual: second line with the colour controls; '>' marks the one -/= changes.
+        let mark = |a: Adjust| if a == app.grade_sel { ">" } else { " " };
+        let grade = app.live_filters.get::<ColorAdjust>().copied().unwrap_or_else(ColorAdjust::new);
+        let white_balance = app.live_filters.get::<WhiteBalancePick>().map_or_else(WhiteBalance::new, |f| f.wb);
+        let wb_text = if app.wb_pick_armed {
+            String::from("PICK WHITE")
+        } else if white_balance.is_picked() {
+            String::from("PICKED")
+        } else {
+            format!("{}TEMP {:.0}K  {}TINT {:+.2}",
+                mark(Adjust::Temperature), white_balance.kelvin,
+                mark(Adjust::Tint), white_balance.tint)
+        };
+        let grade_text = format!(
+            "{}BRIGHT {:+.1}  {}CONTRAST {:.2}  {}SAT {:.1}  {}",
+            mark(Adjust::Brightness), grade.brightness,
+            mark(Adjust::Contrast), grade.contrast,
+            mark(Adjust::Saturation), grade.saturation,
+            wb_text,
+        );
+        // Visual: each HUD line sits on its own smoky see-through strip in its corner;
+        // blocks sharing a corner stack. H hides them all.
+        let mut thumb_drawn = false;
+        if app.hud_on {
+            // Visual: status bar along the bottom; the bottom corners stack above it.
+            let mut cells = vec![
+                StatusCell::new("MODE", status.clone()),
+                StatusCell::new("EFFECT", name.to_string()),
+                StatusCell::new("BRUSH", format!("R {} HARD {:.0}%{}", app.eraser_radius, app.brush_hardness * 100.0,
+                                                 if frame.input.mods.ctrl { " SUBTRACT" } else { "" })),
+                StatusCell::new("MASK", format!("{:.1}%", active_mask.coverage() * 100.0)),
+            ];
+            if let Some((note, at)) = &app.history_note && at.elapsed() < Duration::from_secs(2) {
+                cells.push(StatusCell::new("EDIT", note.clone()));         // visual: "UNDO STROKE" for a moment
+            }
+            statusbar::draw(out, hud_px, &cells, Some(&self.file_state), theme);
+            let bar_h = statusbar::height(hud_px) as usize;
+
+            let layout = &self.hud_layout;
+            let mut placer = HudPlacer::new(out.width, out.height.saturating_sub(bar_h), hud_px);
+            for (el, text) in [(&layout.status, &hud), (&layout.controls, &grade_text)] {
+                if !el.visible { continue; }
+                let (tw, th) = text_size_5x7(text, hud_px);
+                let (x, y) = placer.place(el.corner, tw, th);
+                fill_rect_blend_linear(out, x - 4, y - 4, tw + 8, th + 8, 0, 110, lut);
+                draw_text_5x7(out, x, y, text, el.color, hud_px);
+            }
+            let color_pick = self.color_pick;
+            let pick_text = if app.picker_armed {
+                Some(String::from("PICK: click a pixel"))
+            } else {
+                color_pick.map(|p| p.hud_text(lut))
+            };
+            if let Some(text) = pick_text && layout.picker.visible {
+                let (tw, th) = text_size_5x7(&text, hud_px);
+                let sw = if color_pick.is_some() && !app.picker_armed { 10 * hud_px } else { 0 };
+                let (x, y) = placer.place(layout.picker.corner, sw + tw, th);
+                fill_rect_blend_linear(out, x - 4, y - 4, sw + tw + 8, th + 8, 0, 110, lut);
+                if sw > 0 && let Some(p) = color_pick {
+                    fill_rect(out, x, y, th, th, p.color);                 // visual: swatch of the pick
+                }
+                draw_text_5x7(out, x + sw, y, &text, layout.picker.color, hud_px);
+            }
+            if app.profile_on && layout.profiler.visible {
+                let (gw, gh) = Profiler::overlay_size(hud_px);
+                let (x, y) = placer.place(layout.profiler.corner, gw, gh + 2 * hud_px);
+                self.profiler.draw_overlay(out, x, y + 2 * hud_px, hud_px, layout.profiler.color, theme); // visual: timing table + graph
+            }
+            if app.graph_on && layout.graph.visible {
+                let (gw, gh) = Profiler::graph_size(hud_px);
+                let (x, y) = placer.place(layout.graph.corner, gw, gh + 6 * hud_px);
+                self.profiler.draw_graph(out, x, y + 3 * hud_px, hud_px, layout.graph.color, theme); // visual: frame-time line
+            }
+            if app.loupe.is_on() && layout.loupe.visible {
+                let (lw, lh) = Loupe::size(hud_px);
+                let (x, y) = placer.place(layout.loupe.corner, lw, lh + 4 * hud_px);
+                app.loupe.draw(out, x, y + 2 * hud_px, hud_px, layout.loupe.color); // visual: zoomed pixels
+            }
+            if app.histogram_on && layout.histogram.visible {
+                let (hw, hh) = Histogram::size(hud_px);
+                let (x, y) = placer.place(layout.histogram.corner, hw, hh + 6 * hud_px);
+                self.histogram.draw(out, x, y + 3 * hud_px, hud_px, layout.histogram.color, theme); // visual: RGB bars + luma line
+            }
+            if app.thumb_on && layout.mask.visible && (scene.effect_needed || app.mode.shows_mask()) {
+                let (tw, th) = MaskThumb::size(active_mask, hud_px);
+                let (x, y) = placer.place(layout.mask.corner, tw, th + 2 * hud_px);
+                self.mask_thumb.draw(out, active_mask, x, y + hud_px, hud_px, layout.mask.color); // visual: grey mask inset
+                thumb_drawn = true;
+            }
+        }
+        if !thumb_drawn {
+            self.mask_thumb.hide();                            // nothing to click this frame
+        }
+
+        // Timed jobs get a progress bar in the lower middle (shown even with the HUD off),
+        // so a capture or a slow write never looks like a hang.
+        let mut jobs: Vec<(String, Option<f32>, f32)> = Vec::new();   // label, progress, phase
+        if let Some((label, done)) = app.mode.progress(app.bg_model.frames_seen()) {
+            jobs.push((String::from(label), Some(done), 0.0));
+        }
+        if let Some((path, since)) = &self.saving {
+            jobs.push((format!("SAVING {path}"), None, since.elapsed().as_secs_f32()));
+        }
+        let (bar_w, bar_h) = (out.width as i32 * 2 / 5, 6 * hud_px);
+        let mut bar_y = out.height as i32 * 2 / 3;
+        for (label, progress, phase) in &jobs {
+            let bar_x = (out.width as i32 - bar_w) / 2;
+            let (tw, th) = text_size_5x7(label, hud_px);
+            draw_text_5x7(out, (out.width as i32 - tw) / 2, bar_y - th - 4 * hud_px, label, theme.text, hud_px);
+            draw_progress_bar(out, bar_x, bar_y, bar_w, bar_h, *progress, *phase,
+                              theme.accent, theme.backdrop);  // visual: fills / sweeps
+            bar_y += bar_h + th + 12 * hud_px;
+        }
+    }
+
+    /// On top of the HUD: the settings panel, the help sheet, the IDLE dimming and the
+    /// crash recovery prompt.
+    fn draw_panels(&mut self, frame: &Prepared, scene: &Scene, upscaled: bool) {
+        let backend_tag = self.backend_tag(scene);
+        let (app, theme, hud_px) = (&mut self.app, &self.theme, self.hud_px);
+        let out = window_frame(upscaled, &mut self.display, &mut self.screen);
+
+        // Settings panel (changes take effect from the next frame).
+        if app.panel_on {
+            let pw = 150 * hud_px;
+            let effect_text = format!("EFFECT: {}", app.effects.active_name());
+            let (brush_lo, brush_hi) = app.brush_limits();
+            let panel_events = &self.panel_events;
+            let mut p = app.ui.panel(out, out.width as i32 - pw - 12, 40 * hud_px, pw, hud_px, frame.window_mouse, frame.ui_left_down);
+            p.label("SETTINGS (S closes)");
+            let mut r = app.blur_radius as i32;
+            if p.slider("BLUR RADIUS", &mut r, 1, 96) { app.blur_radius = r as usize; }
+            let mut size = app.eraser_radius;
+            if p.slider("BRUSH SIZE", &mut size, brush_lo, brush_hi) {
+                panel_events.send(Event::SetBrushRadius((size * self.scale as i32) as f32)); // App::set_brush
+            }
+            if p.button(&effect_text) { panel_events.send(Event::Press(Action::NextEffect)); } // visual: next effect
+            let mut showing = app.mode.shows_sink();
+            if p.checkbox("SHOW BLUR", &mut showing) { panel_events.send(Event::Press(Action::ShowBlur)); }
+            p.checkbox("LINEAR BLUR", &mut app.blur_linear_on);
+            p.checkbox("SKIN SMOOTHING", &mut app.beauty_on);
+            p.checkbox("FILM GRAIN", &mut app.grain_on);
+            p.checkbox("VIGNETTE", &mut app.vignette_on);
+            p.checkbox("SPARKLES", &mut app.fx_on);
+            let mut fx_params = *app.fx.params();
+            let mut per_dab = fx_params.per_dab as i32;
+            let mut bolt_pct = (fx_params.bolt_chance * 100.0).round() as i32;
+            let per_dab_moved = p.slider("SPARKLES / DAB", &mut per_dab, 0, 40);
+            if p.slider("BOLT CHANCE %", &mut bolt_pct, 0, 30) || per_dab_moved {
+                fx_params.per_dab = per_dab as usize;
+                fx_params.bolt_chance = bolt_pct as f32 / 100.0;
+                app.fx.set_params(fx_params);                                // visual: next dabs follow
+            }
+            p.checkbox("THIRDS GRID", &mut app.guides.thirds);
+            p.checkbox("CENTER CROSS", &mut app.guides.center);
+            p.checkbox("SAFE MARGINS", &mut app.guides.safe);
+            if p.text_input("SNAPSHOT FILE", &mut self.snapshot_path) { self.snapshot_due = true; }
+            if p.button("SAVE SNAPSHOT") { self.snapshot_due = true; }   // visual: file written next frame
+            if p.button("SAVE SESSION") { panel_events.send(Event::Press(Action::SaveSession)); }
+            p.end();
+        }
+
+        // Help on top of everything (generated from keys::KEY_BINDINGS).
+        if app.help_on {
+            let on_off = |b: bool| if b { "ON" } else { "OFF" };
+            let settings = [
+                format!("EFFECT {}  BRUSH {} PX", app.effects.active_name(), app.eraser_radius),
+                format!("BLUR {} R {}{}{}", app.blur_kind.label(), app.blur_radius, scene.quality.label(), backend_tag),
+                format!("PORTRAIT {}  SKIN {}  GRAIN {}  VIGNETTE {}  FX {}",
+                        on_off(app.portrait_on), on_off(app.beauty_on), on_off(app.grain_on), on_off(app.vignette_on), on_off(app.fx_on)),
+                format!("CURSOR {}  ZOOM {:.1}x", app.cursor_styles.get(app.cursor_mode()).label(), app.view.zoom()),
+            ];
+            keys::draw_help(out, hud_px, &settings, theme, &self.keymap);              // visual: cheat sheet
+        }
+
+        // Going idle: this last frame is dimmed, then updates stop until there is activity.
+        if frame.idle_now {
+            fill_rect_blend(out, 0, 0, out.width as i32, out.height as i32, theme.backdrop, 120);
+            let label = "IDLE - MOVE THE MOUSE TO RESUME";
+            let (tw, th) = text_size_5x7(label, hud_px);
+            draw_text_5x7(out, (out.width as i32 - tw) / 2, (out.height as i32 - th) / 2, label, theme.text, hud_px);
+        }
+        self.idle_shown = frame.idle_now;
+
+        // Crash recovery: the question stays up until it is answered.
+        if self.recovery.is_some() {
+            let lines = ["THE LAST RUN DID NOT EXIT CLEANLY", "ENTER: RESTORE ITS MASK AND SETTINGS", "BACKSPACE: START FRESH"];
+            let (line_h, pad) = (10 * hud_px, 8 * hud_px);
+            let box_w = lines.iter().map(|l| text_size_5x7(l, hud_px).0).max().unwrap_or(0) + 2 * pad;
+            let box_h = lines.len() as i32 * line_h + 2 * pad;
+            let (x, y) = ((out.width as i32 - box_w) / 2, (out.height as i32 - box_h) / 2);
+            fill_rect_blend(out, x, y, box_w, box_h, theme.backdrop, 200);  // visual: centred prompt box
+            for (i, line) in lines.iter().enumerate() {
+                let color = if i == 0 { theme.accent } else { theme.text };
+                draw_text_5x7(out, x + pad, y + pad + i as i32 * line_h, line, color, hud_px);
+            }
+        }
+    }
+
+    /// After the frame is on its way: the session file (F6 / SAVE SESSION and the
+    /// checkpoint), the live frame back to its source, the FPS counter and metrics.
+    fn housekeeping(&mut self, live: FrameBuffer, now: Instant, dt: f32) {
+        /* 7b) Session file: F6 / SAVE SESSION, and the checkpoint every so often.
+           Visual: FILE SAVING → SAVED session.toml in the status bar. */
+        let app = &mut self.app;
+        let requested = app.session_due.take();
+        let (painted, effect) = app.painting();                 // privacy on: keep the real painting
+        // Checkpoint: every CHECKPOINT_INTERVAL if anything changed, quietly, never over
+        // a checkpoint still waiting for its recovery answer.
+        let mut checkpoint_due = false;
+        if self.recovery.is_none() && self.checkpoint_path.is_some() && requested.is_none()
+            && self.checkpoint_at.elapsed() >= CHECKPOINT_INTERVAL
+            && self.checkpoint_writer.as_ref().is_none_or(|t| t.is_finished())
+        {
+            self.checkpoint_at = now;
+            let mut hasher = std::hash::DefaultHasher::new();
+            (&painted.alpha, effect, app.portrait_on, app.filters_on(), app.have_background).hash(&mut hasher);
+            (app.blur_radius, app.eraser_radius, app.brush_hardness.to_bits(), app.fx_on).hash(&mut hasher);
+            let seen = hasher.finish();
+            checkpoint_due = seen != self.checkpoint_seen;
+            self.checkpoint_seen = seen;
+        }
+        let target = match requested {
+            Some(path) => Some((path, false)),
+            None if checkpoint_due => self.checkpoint_path.clone().map(|path| (path, true)),
+            None => None,
+        };
+        if let Some((path, checkpoint)) = target {
+            let session = app.session(self.opts);
+            let painted = painted.bounds.is_some().then(|| painted.clone());
+            let room = app.have_background.then(|| app.bg_model.frame().clone());
+            if checkpoint {
+                self.checkpoint_writer = Some(thread::spawn(move || match session.save(&path, painted.as_ref(), room.as_ref()) {
+                    Ok(file) => log::debug!("checkpoint written to {file}"),
+                    Err(e) => log::warn!("checkpoint: {e}"),
+                }));
+            } else {
+                let file = path.display().to_string();
+                self.file_state = StatusCell::new("FILE", format!("SAVING {file}"));
+                self.saving = Some((file, Instant::now()));
+                let done = self.saved_tx.clone();
+                thread::spawn(move || {
+                    let _ = done.send(session.save(&path, painted.as_ref(), room.as_ref()));
+                });
+            }
+        }
+        self.recycle(live);
+
+        /* 8) FPS counter (logged + HUD once per second) */
+        self.frames_this_second += 1;
+        if now.duration_since(self.last_fps_time) >= Duration::from_secs(1) {
+            let secs = now.duration_since(self.last_fps_time).as_secs_f32();
+            let fps = self.frames_this_second as f32 / secs;
+            log::info!("fps={fps:.1} late={} quality={}", self.late_this_second, self.governor.level()); // terminal / --log-file
+            self.hud_fps_text = format!("FPS: {:.1}", fps);     // HUD part
+            #[cfg(feature = "script")]
+            { self.script_fps = fps; }
+            self.frames_this_second = 0;
+            self.late_this_second = 0;
+            self.last_fps_time = now;
+            if let Some(m) = &mut self.metrics {
+                m.flush();
+            }
+        }
+        self.profiler.lap(Stage::Present);
+        self.profiler.end_frame();
+        if let Some(m) = &mut self.metrics {
+            m.record(&self.profiler, dt, self.governor.level());
+        }
+    }
+
+    /// The loop has ended (window closed, Esc, even while IDLE): autosave, drop the
+    /// checkpoint, and write back the settings that changed.
+    fn finish(mut self) -> Result<(Profiler, Config), Error> {
+        let (app, opts) = (&self.app, self.opts);
+
+        /* Autosave on the way out.
+           Visual: none; `--session` with the autosave file picks up from here. */
+        if opts.bench_frames.is_none() && let Some(path) = Session::autosave_path() {
+            let (painted, _) = app.painting();                      // privacy on: keep the real painting
+            let session = app.session(opts);
+            let room = app.have_background.then(|| app.bg_model.frame());
+            match session.save(&path, painted.bounds.is_some().then_some(painted), room) {
+                Ok(file) => log::info!("session saved to {file}"),
+                Err(e) => log::warn!("{e}"),
+            }
+        }
+        // A clean exit: the checkpoint goes (unless its recovery question is still open).
+        if let Some(writer) = self.checkpoint_writer.take() {
+            let _ = writer.join();
+        }
+        if let Some(path) = &self.checkpoint_path && self.recovery.is_none() {
+            Session::discard_checkpoint(path);
+        }
+
+        // Write back what changed while running; a value a flag set for this run only
+        // (--blur-radius, --brush-size, --snapshot) keeps what the file says.
+        let config = &mut self.config;
+        if app.blur_radius != opts.blur_radius {
+            config.brush.blur_radius = app.blur_radius as u32;
+        }
+        let (lo, hi) = app.brush_limits();
+        if app.eraser_radius != (opts.brush_size / self.scale as i32).clamp(lo, hi) {
+            config.brush.size = (app.eraser_radius * self.scale as i32) as u32;
+        }
+        config.brush.hardness = app.brush_hardness;
+        config.fx.sparkles_per_dab = app.fx.params().per_dab as u32; // the two the panel sets
+        config.fx.bolt_chance = app.fx.params().bolt_chance;
+        if self.snapshot_path != opts.snapshot_path {
+            config.display.snapshot = self.snapshot_path;
+        }
+        Ok((self.profiler, self.config))
+    }
+}
@@ -0,0 +1,70 @@
+// Keyboard brush cursor: arrow keys move the brush, Space paints.
+// Visual expectation: tap an arrow and the cursor steps one pixel; hold it and the
+// cursor glides off, speeding up the longer it is held (up to about a window width
+// in two seconds); hold Space to paint where it is — alone or while gliding. Touch
+// the mouse and it takes over again. An accessibility path for anyone who can't hold
+// a mouse steady, and handy for exact straight strokes.
+
+use crate::window::Input;
+use std::time::Instant;
+
+/// Cursor speed in window pixels per second when an arrow goes down…
+const START_SPEED: f32 = 40.0;
+/// …and after holding it for ACCEL_TIME seconds (it ramps up in between).
+const TOP_SPEED: f32 = 600.0;
+const ACCEL_TIME: f32 = 1.5;
+
+/// Virtual cursor driven by the arrow keys, in window pixels.
+pub struct KeyCursor {
+    cursor: (f32, f32),
+    held_since: Option<Instant>, // when the current run of arrow presses started
+    driving: bool,               // the keys moved last (not the mouse)
+    mouse_seen: Option<(usize, usize)>,
+    last: Instant,
+}
+
+impl KeyCursor {
+    pub fn new() -> Self {
+        Self { cursor: (0.0, 0.0), held_since: None, driving: false, mouse_seen: None, last: Instant::now() }
+    }
+
+    /// Fold the keys into this window-thread input snapshot (`width` × `height` window).
+    /// `arrows` = held direction (-1/0/1 each), `tapped` = an arrow went down this poll.
+    pub fn apply(&mut self, input: &mut Input, arrows: (i32, i32), tapped: bool, space: bool, width: usize, height: usize) {
+        let now = Instant::now();
+        let dt = now.duration_since(self.last).as_secs_f32().min(0.1);
+        self.last = now;
+
+        // The mouse moved: it is in charge again, and the keys continue from there.
+        if input.mouse_pos != self.mouse_seen {
+            self.mouse_seen = input.mouse_pos;
+            if let Some((x, y)) = input.mouse_pos {
+                self.cursor = (x as f32, y as f32);
+            }
+            self.driving = false;
+        }
+        if arrows != (0, 0) {
+            self.driving = true;
+            let since = *self.held_since.get_or_insert(now);
+            if tapped {
+                // Visual: a single tap moves exactly one pixel.
+                self.cursor.0 += arrows.0 as f32;
+                self.cursor.1 += arrows.1 as f32;
+            } else {
+                let ramp = (now.duration_since(since).as_secs_f32() / ACCEL_TIME).min(1.0);
+                let speed = START_SPEED + (TOP_SPEED - START_SPEED) * ramp * ramp;
+                self.cursor.0 += arrows.0 as f32 * speed * dt;
+                self.cursor.1 += arrows.1 as f32 * speed * dt;
+            }
+        } else {
+            self.held_since = None;
+        }
+        if !self.driving || width == 0 || height == 0 {
+            return;
+        }
+        self.cursor.0 = self.cursor.0.clamp(0.0, (width - 1) as f32);
+        self.cursor.1 = self.cursor.1.clamp(0.0, (height - 1) as f32);
+        input.mouse_pos = Some((self.cursor.0.round() as usize, self.cursor.1.round() as usize)); // visual: cursor moves
+        input.left_mouse_down |= space;                                                           // visual: paints
+    }
+}
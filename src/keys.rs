@@ -0,0 +1,237 @@
+// The keybinding table, and the F1 / ? help overlay generated from it.
+// Visual expectation: press F1 (or ?) and the picture dims behind a cheat sheet of
+// every key with what it does, followed by the mouse controls and the current
+// settings. Press F1 / ? again to close it.
+//
+// The window's `poll_input` reads exactly the keys listed in `KEY_BINDINGS`, so a key
+// that works is always on the sheet and the sheet never lists a key that doesn't.
+//
+// Keys can be moved in config.toml by binding name (`KeyBinding::name`):
+//
+//     [keys]
+//     show_blur = "J"      # full-screen effect on J instead of B
+//     profiler = "F8"
+//
+// A `KeyMap` holds the keys actually in use; the sheet shows those labels.
+
+use crate::draw::{draw_text_5x7, fill_rect_blend};
+use crate::theme::Theme;
+use crate::types::FrameBuffer;
+use crate::window::Input;
+use minifb::Key;
+
+/// One single-press key: its config name, which physical key(s), how the sheet names
+/// it, what it does, and the `Input` field it sets.
+pub struct KeyBinding {
+    pub name: &'static str,
+    pub keys: &'static [Key],
+    pub label: &'static str,
+    pub help: &'static str,
+    pub flag: fn(&mut Input) -> &mut bool,
+}
+
+/// Every single-press key the app reacts to, in cheat-sheet order.
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    KeyBinding { name: "help", keys: &[Key::F1, Key::Slash], label: "F1 ?", help: "this help", flag: |i| &mut i.help_pressed },
+    KeyBinding { name: "next_effect", keys: &[Key::Tab], label: "TAB", help: "next brush effect", flag: |i| &mut i.tab_pressed },
+    KeyBinding { name: "clear", keys: &[Key::C], label: "C", help: "clear the painted mask", flag: |i| &mut i.c_pressed },
+    KeyBinding { name: "show_blur", keys: &[Key::B], label: "B", help: "show the effect full screen", flag: |i| &mut i.b_pressed },
+    KeyBinding { name: "skin", keys: &[Key::U], label: "U", help: "skin smoothing on the whole frame", flag: |i| &mut i.u_pressed },
+    KeyBinding { name: "median", keys: &[Key::M], label: "M", help: "median denoise: off / 3x3 / 5x5", flag: |i| &mut i.m_pressed },
+    KeyBinding { name: "levels", keys: &[Key::A], label: "A", help: "auto-levels", flag: |i| &mut i.a_pressed },
+    KeyBinding { name: "colour_control", keys: &[Key::V], label: "V", help: "pick the colour control -/= change", flag: |i| &mut i.v_pressed },
+    KeyBinding { name: "colour_reset", keys: &[Key::Backspace], label: "BKSP", help: "colour controls back to neutral", flag: |i| &mut i.backspace_pressed },
+    KeyBinding { name: "white_balance", keys: &[Key::W], label: "W", help: "white balance: click a grey spot", flag: |i| &mut i.w_pressed },
+    KeyBinding { name: "picker", keys: &[Key::X], label: "X", help: "colour picker: click a pixel", flag: |i| &mut i.x_pressed },
+    KeyBinding { name: "grain", keys: &[Key::G], label: "G", help: "film grain", flag: |i| &mut i.g_pressed },
+    KeyBinding { name: "vignette", keys: &[Key::N], label: "N", help: "vignette", flag: |i| &mut i.n_pressed },
+    KeyBinding { name: "fx", keys: &[Key::F], label: "F", help: "sparkles and lightning on / off", flag: |i| &mut i.f_pressed },
+    KeyBinding { name: "blur_kind", keys: &[Key::K], label: "K", help: "blur algorithm: BOX / STACK", flag: |i| &mut i.k_pressed },
+    KeyBinding { name: "blur_quality", keys: &[Key::Q], label: "Q", help: "blur quality: FULL / HALF / QUARTER", flag: |i| &mut i.q_pressed },
+    KeyBinding { name: "blur_reuse", keys: &[Key::E], label: "E", help: "blur reuse: every 1st / 2nd / 4th frame", flag: |i| &mut i.e_pressed },
+    KeyBinding { name: "linear_blur", keys: &[Key::L], label: "L", help: "linear-light blur", flag: |i| &mut i.l_pressed },
+    KeyBinding { name: "capture_bg", keys: &[Key::R], label: "R", help: "capture the background (step out first)", flag: |i| &mut i.r_pressed },
+    KeyBinding { name: "portrait", keys: &[Key::O], label: "O", help: "portrait mode", flag: |i| &mut i.o_pressed },
+    KeyBinding { name: "diff", keys: &[Key::D], label: "D", help: "difference heat map: BG / previous / off", flag: |i| &mut i.d_pressed },
+    KeyBinding { name: "settings", keys: &[Key::S], label: "S", help: "settings panel", flag: |i| &mut i.s_pressed },
+    KeyBinding { name: "profiler", keys: &[Key::P], label: "P", help: "profiler", flag: |i| &mut i.p_pressed },
+    KeyBinding { name: "graph", keys: &[Key::T], label: "T", help: "frame-time graph", flag: |i| &mut i.t_pressed },
+    KeyBinding { name: "histogram", keys: &[Key::I], label: "I", help: "histogram", flag: |i| &mut i.i_pressed },
+    KeyBinding { name: "loupe", keys: &[Key::Z], label: "Z", help: "loupe: 4x / 8x / off (CTRL+Z: undo)", flag: |i| &mut i.z_pressed },
+    KeyBinding { name: "redo", keys: &[Key::Y], label: "Y", help: "with CTRL: redo what CTRL+Z undid", flag: |i| &mut i.y_pressed },
+    KeyBinding { name: "cursor_style", keys: &[Key::F2], label: "F2", help: "cursor style", flag: |i| &mut i.f2_pressed },
+    KeyBinding { name: "compare", keys: &[Key::F3], label: "F3", help: "before / after split", flag: |i| &mut i.f3_pressed },
+    KeyBinding { name: "mask_thumb", keys: &[Key::F4], label: "F4", help: "mask thumbnail", flag: |i| &mut i.f4_pressed },
+    KeyBinding { name: "guides", keys: &[Key::F5], label: "F5", help: "guides: thirds / centre / safe", flag: |i| &mut i.f5_pressed },
+    KeyBinding { name: "save_session", keys: &[Key::F6], label: "F6", help: "save the session (mask, brush, background)", flag: |i| &mut i.f6_pressed },
+    KeyBinding { name: "zoom_reset", keys: &[Key::Home], label: "HOME", help: "zoom back to the whole frame", flag: |i| &mut i.home_pressed },
+    KeyBinding { name: "hud", keys: &[Key::H], label: "H", help: "hide / show the HUD", flag: |i| &mut i.h_pressed },
+    KeyBinding { name: "confirm", keys: &[Key::Enter, Key::NumPadEnter], label: "ENTER", help: "confirm a text field", flag: |i| &mut i.enter_pressed },
+];
+
+/// Controls that aren't single presses (held keys, ramps, the mouse). The window reads
+/// these itself; they are listed here so the sheet covers them too.
+pub const OTHER_CONTROLS: &[(&str, &str)] = &[
+    ("LMB", "paint the effect"),
+    ("CTRL+LMB", "remove paint (subtract brush)"),
+    ("SHIFT+LMB", "straight line (click: from the last stroke)"),
+    ("ALT+LMB", "clone / heal source"),
+    ("2x LMB", "clear the paint under the cursor"),
+    ("CTRL+Z", "undo: strokes, clear, holes, effect changes"),
+    ("CTRL+Y", "redo (also CTRL+SHIFT+Z)"),
+    ("RMB", "clone / heal source"),
+    ("1-9", "jump to a brush effect"),
+    ("- =", "lower / raise the colour control"),
+    (", .", "blur radius down / up"),
+    ("CTRL+WHL", "zoom around the mouse"),
+    ("MMB", "drag to pan while zoomed"),
+    ("ARROWS", "move the brush cursor (hold to speed up)"),
+    ("SPACE", "paint at the brush cursor"),
+    ("ESC", "quit"),
+];
+
+/// Key names accepted in `[keys]` (letters, digits, F-keys and a few named keys).
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("A", Key::A),
+    ("B", Key::B),
+    ("C", Key::C),
+    ("D", Key::D),
+    ("E", Key::E),
+    ("F", Key::F),
+    ("G", Key::G),
+    ("H", Key::H),
+    ("I", Key::I),
+    ("J", Key::J),
+    ("K", Key::K),
+    ("L", Key::L),
+    ("M", Key::M),
+    ("N", Key::N),
+    ("O", Key::O),
+    ("P", Key::P),
+    ("Q", Key::Q),
+    ("R", Key::R),
+    ("S", Key::S),
+    ("T", Key::T),
+    ("U", Key::U),
+    ("V", Key::V),
+    ("W", Key::W),
+    ("X", Key::X),
+    ("Y", Key::Y),
+    ("Z", Key::Z),
+    ("0", Key::Key0),
+    ("1", Key::Key1),
+    ("2", Key::Key2),
+    ("3", Key::Key3),
+    ("4", Key::Key4),
+    ("5", Key::Key5),
+    ("6", Key::Key6),
+    ("7", Key::Key7),
+    ("8", Key::Key8),
+    ("9", Key::Key9),
+    ("F1", Key::F1),
+    ("F2", Key::F2),
+    ("F3", Key::F3),
+    ("F4", Key::F4),
+    ("F5", Key::F5),
+    ("F6", Key::F6),
+    ("F7", Key::F7),
+    ("F8", Key::F8),
+    ("F9", Key::F9),
+    ("F10", Key::F10),
+    ("F11", Key::F11),
+    ("F12", Key::F12),
+    ("TAB", Key::Tab),
+    ("ENTER", Key::Enter),
+    ("BKSP", Key::Backspace),
+    ("BACKSPACE", Key::Backspace),
+    ("HOME", Key::Home),
+    ("END", Key::End),
+    ("INSERT", Key::Insert),
+    ("DELETE", Key::Delete),
+    ("PAGEUP", Key::PageUp),
+    ("PAGEDOWN", Key::PageDown),
+    ("/", Key::Slash),
+    ("?", Key::Slash),
+    (";", Key::Semicolon),
+    ("'", Key::Apostrophe),
+    ("[", Key::LeftBracket),
+    ("]", Key::RightBracket),
+    ("\\", Key::Backslash),
+    ("`", Key::Backquote),
+];
+
+/// The keys in use for each entry of `KEY_BINDINGS` (same order), and their labels.
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+    keys: Vec<Vec<Key>>,
+    labels: Vec<String>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            keys: KEY_BINDINGS.iter().map(|b| b.keys.to_vec()).collect(),
+            labels: KEY_BINDINGS.iter().map(|b| b.label.to_string()).collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// The default keys with `[keys]` overrides (binding name → key name) applied.
+    pub fn from_overrides<'a>(overrides: impl IntoIterator<Item = (&'a String, &'a String)>) -> Result<Self, String> {
+        let mut map = KeyMap::default();
+        for (name, key_name) in overrides {
+            let index = KEY_BINDINGS
+                .iter()
+                .position(|b| b.name == name.as_str())
+                .ok_or_else(|| format!("[keys] unknown binding '{name}'"))?;
+            let label = key_name.trim().to_ascii_uppercase();
+            let key = KEY_NAMES
+                .iter()
+                .find(|(n, _)| *n == label)
+                .map(|&(_, k)| k)
+                .ok_or_else(|| format!("[keys] {name}: unknown key '{key_name}'"))?;
+            map.keys[index] = vec![key];
+            map.labels[index] = label;
+        }
+        Ok(map)
+    }
+
+    /// Physical keys for `KEY_BINDINGS[index]`.
+    pub fn keys(&self, index: usize) -> &[Key] {
+        &self.keys[index]
+    }
+}
+
+/// Draw the cheat sheet over `fb`, followed by `settings` (one line each).
+/// Visual: see-through backdrop, key names in the accent colour, descriptions in the
+/// text colour, in as many columns as the window height needs.
+pub fn draw_help(fb: &mut FrameBuffer, scale: i32, settings: &[String], theme: &Theme, keymap: &KeyMap) {
+    let (key_color, text_color) = (theme.accent, theme.text);
+    let s = scale.max(1);
+    fill_rect_blend(fb, 0, 0, fb.width as i32, fb.height as i32, theme.backdrop, 190);
+
+    let (line_h, label_w, margin) = (10 * s, 9 * 6 * s, 16 * s);
+    let mut rows: Vec<(&str, &str)> = Vec::new();
+    rows.extend(KEY_BINDINGS.iter().zip(&keymap.labels).map(|(b, label)| (label.as_str(), b.help)));
+    rows.push(("", ""));
+    rows.extend(OTHER_CONTROLS.iter().copied());
+    rows.push(("", ""));
+    rows.push(("NOW", ""));
+    rows.extend(settings.iter().map(|line| ("", line.as_str())));
+
+    let widest = rows.iter().map(|(_, help)| help.chars().count()).max().unwrap_or(0) as i32;
+    let col_w = label_w + widest * 6 * s + margin;
+    draw_text_5x7(fb, margin, margin, "KEYS  (F1 or ? closes)", key_color, s);
+    let top = margin + 2 * line_h;
+    let (mut x, mut y) = (margin, top);
+    for (label, help) in rows {
+        if y + line_h > fb.height as i32 - margin {
+            x += col_w;                                           // next column
+            y = top;
+        }
+        draw_text_5x7(fb, x, y, label, key_color, s);
+        draw_text_5x7(fb, x + label_w, y, help, text_color, s);
+        y += line_h;
+    }
+}
@@ -0,0 +1,113 @@
+// Log output: what the app is doing, for the terminal and for bug reports.
+// Visual expectation: instead of bare "FPS: 29.9" prints, the terminal shows one line
+// per event, with the time since start, the level and the module it came from:
+//
+//      13.001s INFO  main: fps=29.9 late=1 quality=0
+//      41.530s WARN  pipeline: camera stopped after 30 failed frames (...); reconnecting
+//      43.552s INFO  pipeline: camera 0 reconnected (attempt 2)
+//
+// `--log SPEC` (or the MAGIC_ERASER_LOG environment variable) picks how much: a level
+// (off, error, warn, info — the default —, debug, trace), optionally per module:
+// `--log warn,pipeline=debug,governor=debug` keeps the terminal quiet except for
+// dropped camera frames and quality changes. `--log-file FILE` also appends every line
+// to FILE, to attach to a bug report.
+
+use crate::error::Error;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Environment variable read when `--log` is not given.
+pub const ENV_VAR: &str = "MAGIC_ERASER_LOG";
+
+/// Prefix of every target in this crate (stripped on output, optional in specs).
+const CRATE: &str = "magic_eraser";
+
+struct Logger {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>, // full target prefix → level, longest first
+    file: Option<Mutex<File>>,
+    start: Instant,
+}
+
+impl Logger {
+    /// The level for `target`: the longest matching module directive, else the default.
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .find(|(prefix, _)| target == prefix || target.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.starts_with("::")))
+            .map_or(self.default, |&(_, level)| level)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let target = record.target();
+        let module = target.strip_prefix(CRATE).map_or(target, |t| t.trim_start_matches("::"));
+        let module = if module.is_empty() { "main" } else { module };
+        let line = format!("{:>9.3}s {:<5} {module}: {}\n", self.start.elapsed().as_secs_f64(), record.level(), record.args());
+        eprint!("{line}");
+        if let Some(file) = &self.file && let Ok(mut file) = file.lock() {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file && let Ok(mut file) = file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// "warn,pipeline=debug" → (default level, [(target prefix, level)]).
+fn parse(spec: &str) -> Result<(LevelFilter, Vec<(String, LevelFilter)>), Error> {
+    let level = |s: &str| s.trim().parse::<LevelFilter>()
+        .map_err(|_| Error::Config(format!("--log: '{s}' is not a level (off, error, warn, info, debug, trace)")));
+    let mut default = LevelFilter::Info;
+    let mut modules = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('=') {
+            None => default = level(part)?,
+            Some((module, lvl)) => {
+                let module = module.trim();
+                let prefix = if module == CRATE || module.starts_with(&format!("{CRATE}::")) {
+                    module.to_string()
+                } else {
+                    format!("{CRATE}::{module}")
+                };
+                modules.push((prefix, level(lvl)?));
+            }
+        }
+    }
+    modules.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+    Ok((default, modules))
+}
+
+/// Install the logger: `spec` from --log (else $MAGIC_ERASER_LOG, else "info"),
+/// mirrored to `file` if given.
+pub fn init(spec: Option<&str>, file: Option<&Path>) -> Result<(), Error> {
+    let spec = spec.map(str::to_string).or_else(|| std::env::var(ENV_VAR).ok()).unwrap_or_default();
+    let (default, modules) = parse(&spec)?;
+    let file = match file {
+        Some(path) => Some(Mutex::new(
+            OpenOptions::new().create(true).append(true).open(path)
+                .map_err(|e| Error::Io(format!("Open log file {}", path.display()), e))?,
+        )),
+        None => None,
+    };
+    let max = modules.iter().map(|&(_, level)| level).fold(default, Ord::max);
+    log::set_boxed_logger(Box::new(Logger { default, modules, file, start: Instant::now() }))
+        .map_err(|e| Error::Config(format!("--log: {e}")))?;
+    log::set_max_level(max);
+    Ok(())
+}
@@ -0,0 +1,105 @@
+// Grading LUTs: 3D colour lookup tables read from `.cube` files (the format Resolve,
+// Premiere, OBS and most LUT packs use).
+// Visual expectation: drop a `.cube` file onto the window and the whole live picture
+// takes on that look at once — film stock, teal-and-orange, faded black-and-white —
+// and the brush effects, built from the live frame, match it. Dropping another LUT
+// swaps the look; the status bar names the file.
+//
+// Only 3D tables are read (`LUT_3D_SIZE`, optional `DOMAIN_MIN` / `DOMAIN_MAX` or
+// `LUT_3D_INPUT_RANGE`). They are looked up on the frame's sRGB values, as LUT packs
+// expect, blending the eight nearest grid points (trilinear), so even a 17-point
+// table grades without banding.
+
+use crate::error::Error;
+use crate::types::FrameBuffer;
+use std::path::Path;
+
+/// Largest `LUT_3D_SIZE` accepted (256³ entries is already 200 MB of floats).
+const MAX_SIZE: usize = 256;
+
+/// A 3D LUT: `size`³ output colours, red changing fastest, then green, then blue.
+pub struct CubeLut {
+    size: usize,
+    min: [f32; 3],   // input value mapped to the first grid point (DOMAIN_MIN)
+    max: [f32; 3],   // … and to the last (DOMAIN_MAX)
+    table: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// Read and check the `.cube` file at `path`.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path).map_err(|e| Error::Io(format!("Read {}", path.display()), e))?;
+        Self::parse(&text).map_err(|e| Error::Config(format!("{}: {e}", path.display())))
+    }
+
+    /// The table in `text` (the contents of a `.cube` file).
+    fn parse(text: &str) -> Result<Self, String> {
+        let (mut size, mut min, mut max) = (None, [0.0; 3], [1.0; 3]);
+        let mut table = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let mut words = line.split_whitespace().peekable();
+            let numbers = |words: &mut dyn Iterator<Item = &str>| -> Result<Vec<f32>, String> {
+                words.map(|w| w.parse::<f32>().ok().filter(|v| v.is_finite()))
+                    .collect::<Option<Vec<f32>>>()
+                    .ok_or_else(|| format!("line {}: not a number", n + 1))
+            };
+            let triple = |v: Vec<f32>| -> Result<[f32; 3], String> {
+                <[f32; 3]>::try_from(v).map_err(|_| format!("line {}: expected three numbers", n + 1))
+            };
+            match words.peek().copied() {
+                None => {}
+                Some(w) if w.starts_with('#') => {}
+                Some("LUT_1D_SIZE") => return Err(String::from("1D LUTs are not supported, only LUT_3D_SIZE")),
+                Some("LUT_3D_SIZE") => {
+                    let s = words.nth(1).and_then(|w| w.parse::<usize>().ok()).filter(|s| (2..=MAX_SIZE).contains(s));
+                    size = Some(s.ok_or_else(|| format!("line {}: LUT_3D_SIZE must be 2 to {MAX_SIZE}", n + 1))?);
+                }
+                Some("DOMAIN_MIN") => min = triple(numbers(&mut words.skip(1))?)?,
+                Some("DOMAIN_MAX") => max = triple(numbers(&mut words.skip(1))?)?,
+                Some("LUT_3D_INPUT_RANGE") => match numbers(&mut words.skip(1))?[..] {
+                    [lo, hi] => (min, max) = ([lo; 3], [hi; 3]),
+                    _ => return Err(format!("line {}: expected two numbers", n + 1)),
+                },
+                Some(w) if w.starts_with(|c: char| c.is_ascii_alphabetic()) => {} // TITLE and the like
+                Some(_) => table.push(triple(numbers(&mut words)?)?),
+            }
+        }
+        let size = size.ok_or("no LUT_3D_SIZE line")?;
+        if table.len() != size * size * size {
+            return Err(format!("LUT_3D_SIZE {size} needs {} entries, found {}", size * size * size, table.len()));
+        }
+        if (0..3).any(|c| max[c] <= min[c]) {
+            return Err(String::from("DOMAIN_MAX must be above DOMAIN_MIN"));
+        }
+        Ok(Self { size, min, max, table })
+    }
+
+    /// Grade every pixel of `frame` in place.
+    /// Visual: the whole frame takes on the LUT's look.
+    pub fn apply(&self, frame: &mut FrameBuffer) {
+        let n = self.size;
+        let last = (n - 1) as f32;
+        // Per channel: grid cell and position inside it for each 8-bit value.
+        let axis = |c: usize| -> Vec<(usize, f32)> {
+            (0..256)
+                .map(|v| {
+                    let t = ((v as f32 / 255.0 - self.min[c]) / (self.max[c] - self.min[c])).clamp(0.0, 1.0) * last;
+                    let i = (t as usize).min(n - 2);
+                    (i, t - i as f32)
+                })
+                .collect()
+        };
+        let (ax_r, ax_g, ax_b) = (axis(0), axis(1), axis(2));
+        let at = |r: usize, g: usize, b: usize| self.table[(b * n + g) * n + r];
+        let mix = |a: [f32; 3], b: [f32; 3], t: f32| [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t];
+        for p in &mut frame.pixels {
+            let (r, fr) = ax_r[((*p >> 16) & 0xFF) as usize];
+            let (g, fg) = ax_g[((*p >> 8) & 0xFF) as usize];
+            let (b, fb) = ax_b[(*p & 0xFF) as usize];
+            let lo = mix(mix(at(r, g, b), at(r + 1, g, b), fr), mix(at(r, g + 1, b), at(r + 1, g + 1, b), fr), fg);
+            let hi = mix(mix(at(r, g, b + 1), at(r + 1, g, b + 1), fr), mix(at(r, g + 1, b + 1), at(r + 1, g + 1, b + 1), fr), fg);
+            let [r, g, b] = mix(lo, hi, fb).map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u32);
+            *p = (r << 16) | (g << 8) | b;
+        }
+    }
+}
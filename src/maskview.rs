@@ -0,0 +1,88 @@
+// Mask thumbnail (picture-in-picture) and the full-screen mask view.
+// Visual expectation: as soon as anything is painted (or portrait mode runs), a small
+// grey-scale copy of the mask sits in a corner (bottom left by default): white = full
+// effect, black = untouched. It shows the strokes even when the blur is too subtle to
+// spot. Click the thumbnail to fill the whole window with the mask; click again (or
+// press F4, which also hides the thumbnail) to go back.
+
+use crate::draw::{draw_drop_shadow, draw_rounded_rect, fill_rect, Shadow};
+use crate::error::Error;
+use crate::types::{FrameBuffer, Mask};
+
+/// Thumbnail width in pixels at HUD scale 1 (height follows the frame's aspect ratio).
+const THUMB_WIDTH: i32 = 120;
+
+/// α → grey pixel.
+#[inline]
+fn gray(a: u8) -> u32 {
+    let v = a as u32;
+    (v << 16) | (v << 8) | v
+}
+
+/// Replace `screen` with the mask in grey-scale.
+/// Visual: the whole window shows the mask (white = full effect).
+pub fn mask_to_screen(mask: &Mask, screen: &mut FrameBuffer) -> Result<(), Error> {
+    if mask.width != screen.width || mask.height != screen.height {
+        return Err(Error::CameraFrame("mask_to_screen: size mismatch".into()));
+    }
+    for (p, &a) in screen.pixels.iter_mut().zip(&mask.alpha) {
+        *p = gray(a);
+    }
+    Ok(())
+}
+
+/// Clickable thumbnail state (where it was drawn last frame, for hit-testing).
+pub struct MaskThumb {
+    area: Option<(i32, i32, i32, i32)>, // x0, y0, x1, y1 in window pixels
+    was_down: bool,
+}
+
+impl MaskThumb {
+    pub fn new() -> Self {
+        Self { area: None, was_down: false }
+    }
+
+    /// Width and height in pixels that `draw` covers at `scale`, for a `mask` of this size.
+    pub fn size(mask: &Mask, scale: i32) -> (i32, i32) {
+        let w = THUMB_WIDTH * scale;
+        (w, (w as usize * mask.height / mask.width.max(1)) as i32)
+    }
+
+    /// Forget the thumbnail (call when it is not drawn this frame).
+    pub fn hide(&mut self) {
+        self.area = None;
+    }
+
+    /// Check the mouse against last frame's thumbnail.
+    /// Returns (clicked this frame, mouse is over it) — the caller shouldn't paint in either case.
+    pub fn handle_mouse(&mut self, mouse: Option<(usize, usize)>, left_down: bool) -> (bool, bool) {
+        let pressed = left_down && !self.was_down;
+        self.was_down = left_down;
+        let over = match (self.area, mouse) {
+            (Some((x0, y0, x1, y1)), Some((mx, my))) => {
+                let (mx, my) = (mx as i32, my as i32);
+                mx >= x0 && mx < x1 && my >= y0 && my < y1
+            }
+            _ => false,
+        };
+        (over && pressed, over)
+    }
+
+    /// Draw the downscaled mask with its top-left corner at (x, y); `color` is the frame.
+    /// Visual: a small grey picture of the mask with a thin rounded border and a soft shadow.
+    pub fn draw(&mut self, fb: &mut FrameBuffer, mask: &Mask, x: i32, y: i32, scale: i32, color: u32) {
+        let (w, h) = Self::size(mask, scale);
+        let (bx, by, bw, bh) = (x - scale, y - scale, w + 2 * scale, h + 2 * scale);
+        draw_drop_shadow(fb, bx, by, bw, bh, 2 * scale, Shadow::hud(scale));
+        draw_rounded_rect(fb, bx, by, bw, bh, 2 * scale, scale, color);
+        for ty in 0..h {
+            let my = (ty as usize * mask.height / h.max(1) as usize).min(mask.height.saturating_sub(1));
+            let row = &mask.alpha[my * mask.width..(my + 1) * mask.width];
+            for tx in 0..w {
+                let mx = (tx as usize * mask.width / w.max(1) as usize).min(mask.width - 1);
+                fill_rect(fb, x + tx, y + ty, 1, 1, gray(row[mx]));
+            }
+        }
+        self.area = Some((x, y, x + w, y + h));
+    }
+}
@@ -0,0 +1,100 @@
+// Per-frame performance trace (`--metrics FILE`), for attaching numbers to a
+// "it feels slow" issue.
+// Visual expectation: nothing changes on screen; FILE fills with one row per frame —
+// the milliseconds of each profiler stage (capture, prep, blur, effect, blend, FX, HUD,
+// present), the frame total, the instantaneous FPS and the adaptive quality level:
+//
+//     frame,t,capture_ms,prep_ms,blur_ms,effect_ms,blend_ms,fx_ms,hud_ms,present_ms,total_ms,fps,quality
+//     1,0.034,21.402,1.210,3.954,0.002,1.871,0.120,0.933,0.041,29.533,29.4,0
+//
+// A name ending in `.json` or `.jsonl` gets the same as one JSON object per line
+// (JSON Lines) instead, so a trace cut short by a crash still parses up to the end.
+// Rows are buffered and flushed once a second (and on exit).
+
+use crate::error::Error;
+use crate::profile::{Profiler, Stage};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Format {
+    Csv,
+    JsonLines,
+}
+
+pub struct MetricsLog {
+    out: BufWriter<File>,
+    format: Format,
+    path: String,
+    start: Instant,
+    frame: u64,
+    failed: bool, // a write failed: warned once, then the trace stops
+}
+
+impl MetricsLog {
+    /// Create (or truncate) `path`; CSV unless it ends in .json / .jsonl.
+    pub fn create(path: &Path) -> Result<Self, Error> {
+        let json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json") || e.eq_ignore_ascii_case("jsonl"));
+        let format = if json { Format::JsonLines } else { Format::Csv };
+        let name = path.display().to_string();
+        let file = File::create(path).map_err(|e| Error::Io(format!("Create {name}"), e))?;
+        let mut log = Self { out: BufWriter::new(file), format, path: name, start: Instant::now(), frame: 0, failed: false };
+        if format == Format::Csv {
+            let stages: String = Stage::ALL.iter().map(|s| format!("{}_ms,", s.label().to_ascii_lowercase())).collect();
+            let header = format!("frame,t,{stages}total_ms,fps,quality\n");
+            log.write(&header);
+        }
+        Ok(log)
+    }
+
+    /// One row for the frame `profiler` just closed; `dt` is the time since the
+    /// previous frame started (seconds), `quality` the governor's level.
+    pub fn record(&mut self, profiler: &Profiler, dt: f32, quality: u8) {
+        self.frame += 1;
+        let t = self.start.elapsed().as_secs_f64();
+        let stages = Stage::ALL.map(|s| profiler.last_ms(s));
+        let total: f32 = stages.iter().sum();
+        let fps = if dt > 0.0 { 1.0 / dt } else { 0.0 };
+        let row = match self.format {
+            Format::Csv => {
+                let cells: String = stages.iter().map(|ms| format!("{ms:.3},")).collect();
+                format!("{},{t:.3},{cells}{total:.3},{fps:.1},{quality}\n", self.frame)
+            }
+            Format::JsonLines => {
+                let cells: String = Stage::ALL
+                    .iter()
+                    .zip(stages)
+                    .map(|(s, ms)| format!("\"{}_ms\":{ms:.3},", s.label().to_ascii_lowercase()))
+                    .collect();
+                format!("{{\"frame\":{},\"t\":{t:.3},{cells}\"total_ms\":{total:.3},\"fps\":{fps:.1},\"quality\":{quality}}}\n", self.frame)
+            }
+        };
+        self.write(&row);
+    }
+
+    /// Push buffered rows to the file (once a second, so a crash loses at most that).
+    pub fn flush(&mut self) {
+        if !self.failed && let Err(e) = self.out.flush() {
+            self.fail(e);
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if !self.failed && let Err(e) = self.out.write_all(text.as_bytes()) {
+            self.fail(e);
+        }
+    }
+
+    fn fail(&mut self, e: std::io::Error) {
+        log::warn!("metrics: writing {} failed ({e}) — trace stopped", self.path);
+        self.failed = true;
+    }
+}
+
+impl Drop for MetricsLog {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
@@ -0,0 +1,166 @@
+// What the app is doing right now, as one explicit state instead of loose flags.
+// Visual expectation: the MODE cell of the status bar and the hint at the top name the
+// state you are in, and keys that make no sense there do nothing:
+//
+//   LIVE       the camera with whatever is painted (or portrait) blended in
+//   PAINTING   a brush stroke is landing this frame ("LMB: painting BLUR...")
+//   CAPTURING  R (or O with no background yet): ~1 s of the empty room is recorded
+//              while a bar fills; the brush and the review views wait until it is done
+//   REVIEW     an inspection view replaces the picture — B the full-screen effect,
+//              D the difference heat maps, a click on the thumbnail the mask — and
+//              the same key (or click) goes back to LIVE; painting still works
+//
+// Portrait mode and the privacy hotkey are settings on top of these, not states: they
+// change what LIVE shows, not how input is handled. What each state lets through
+// (`gate`), names (`label`, `hint`) and shows as progress is decided here; the frame
+// loop (interactive.rs) only asks.
+
+use crate::segment::DiffView;
+use crate::vision::BG_CAPTURE_COUNT;
+use crate::window::Input;
+
+/// An inspection view that replaces the finished picture.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Review {
+    Blur,           // B: the effect's sink over the whole frame
+    Mask,           // thumbnail click: the mask in grey
+    Diff(DiffView), // D: heat map (never `DiffView::Off`)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Live,
+    Painting,
+    CapturingBackground,
+    Reviewing(Review),
+}
+
+impl Mode {
+    /// B / thumbnail click: open `review`, or close it if it is the one showing.
+    /// Ignored while capturing (the picture must stay what it is).
+    pub fn toggle_review(self, review: Review) -> Self {
+        match self {
+            Mode::CapturingBackground => self,
+            Mode::Reviewing(r) if r == review => Mode::Live,
+            _ => Mode::Reviewing(review),
+        }
+    }
+
+    /// Leave `review` if it is the one showing (F4 hides the thumbnail with its view).
+    pub fn close_review(self, review: Review) -> Self {
+        if self == Mode::Reviewing(review) { Mode::Live } else { self }
+    }
+
+    /// D: background diff → previous-frame diff → back to LIVE. Without a captured
+    /// background the first step is skipped (there is nothing to compare against).
+    pub fn cycle_diff(self, have_background: bool) -> Self {
+        if self == Mode::CapturingBackground {
+            return self;
+        }
+        let mut next = self.diff_view().next();
+        if next == DiffView::Background && !have_background {
+            next = next.next();
+        }
+        match next {
+            DiffView::Off => Mode::Live,
+            view => Mode::Reviewing(Review::Diff(view)),
+        }
+    }
+
+    /// R (or O with no background): start recording the empty room. Any review closes.
+    pub fn start_capture(self) -> Self {
+        Mode::CapturingBackground
+    }
+
+    /// The background model has seen enough frames.
+    pub fn capture_finished(self) -> Self {
+        match self {
+            Mode::CapturingBackground => Mode::Live,
+            _ => self,
+        }
+    }
+
+    /// Whether a brush stroke landed this frame; only LIVE and PAINTING switch on it
+    /// (painting inside a review view keeps the view).
+    pub fn stroke(self, painting: bool) -> Self {
+        match self {
+            Mode::Live | Mode::Painting if painting => Mode::Painting,
+            Mode::Live | Mode::Painting => Mode::Live,
+            _ => self,
+        }
+    }
+
+    /// Drop the input this state ignores, before it turns into events: while capturing,
+    /// the left button neither paints nor picks (the brush waits for the capture).
+    pub fn gate(self, input: &mut Input) {
+        if self == Mode::CapturingBackground {
+            input.left_mouse_down = false;
+        }
+    }
+
+    pub fn capturing(self) -> bool {
+        self == Mode::CapturingBackground
+    }
+
+    /// B view: the effect over the whole frame instead of the blended picture.
+    pub fn shows_sink(self) -> bool {
+        self == Mode::Reviewing(Review::Blur)
+    }
+
+    /// Clicked thumbnail: the mask full screen.
+    pub fn shows_mask(self) -> bool {
+        self == Mode::Reviewing(Review::Mask)
+    }
+
+    /// Which heat map is showing (`Off` outside the D views).
+    pub fn diff_view(self) -> DiffView {
+        match self {
+            Mode::Reviewing(Review::Diff(view)) => view,
+            _ => DiffView::Off,
+        }
+    }
+
+    /// MODE cell / left HUD tag with portrait mode on top: LIVE reads PORTRAIT while it
+    /// is working.
+    pub fn status(self, frames_seen: usize, portrait: bool) -> String {
+        match self {
+            Mode::Live if portrait => String::from("PORTRAIT"),
+            _ => self.label(frames_seen),
+        }
+    }
+
+    /// The progress bar this state shows (even with the HUD off): label and 0..1.
+    pub fn progress(self, frames_seen: usize) -> Option<(&'static str, f32)> {
+        match self {
+            Mode::CapturingBackground => {
+                Some(("CAPTURING BACKGROUND - STEP OUT OF VIEW", frames_seen as f32 / BG_CAPTURE_COUNT as f32))
+            }
+            _ => None,
+        }
+    }
+
+    /// MODE cell / left HUD tag; `frames_seen` counts the capture.
+    pub fn label(self, frames_seen: usize) -> String {
+        match self {
+            Mode::Live => String::from("LIVE"),
+            Mode::Painting => String::from("PAINTING"),
+            Mode::CapturingBackground => format!("CAPTURING BG {frames_seen}/{BG_CAPTURE_COUNT}"),
+            Mode::Reviewing(Review::Blur) => String::from("BLUR (Showing)"),
+            Mode::Reviewing(Review::Mask) => String::from("MASK"),
+            Mode::Reviewing(Review::Diff(DiffView::Previous)) => String::from("DIFF vs PREV"),
+            Mode::Reviewing(Review::Diff(_)) => String::from("DIFF vs BG"),
+        }
+    }
+
+    /// The key hint after the label, for what this state responds to.
+    pub fn hint(self, effect: &str) -> String {
+        match self {
+            Mode::Live => format!(" | LMB: paint {effect}     C: clear  B: show BLUR  Tab: effect  RMB: source  A: levels"),
+            Mode::Painting => format!(" | LMB: painting {effect}... C: clear  B: show BLUR  Tab: effect  RMB: source  A: levels"),
+            Mode::CapturingBackground => String::from(" | step out of view...  R: start over"),
+            Mode::Reviewing(Review::Blur) => format!(" | B: back to LIVE  Tab: effect ({effect})  LMB: paint"),
+            Mode::Reviewing(Review::Mask) => String::from(" | click the thumbnail: back to LIVE  LMB: paint  C: clear"),
+            Mode::Reviewing(Review::Diff(_)) => String::from(" | D: next view / back to LIVE  R: capture background"),
+        }
+    }
+}
@@ -0,0 +1,586 @@
+// `magic-eraser process IN OUT` / `magic-eraser batch IMAGES --out DIR`: the brush
+// pipeline run over a video file or a pile of still images, no window.
+// Visual expectation: nothing opens. The terminal counts frames, and OUT plays like
+// IN — same size, frame rate and sound — with the masked area blurred (or pixelated,
+// filled, … with --effect) in every frame:
+//
+//   magic-eraser process talk.mp4 talk-redacted.mp4 --mask snapshot-mask.png
+//   magic-eraser process talk.mp4 out.mp4 --auto --effect pixelate
+//
+// --mask takes the grey-scale PNG that SAVE SNAPSHOT writes next to the picture
+// (white = full effect; stretched to the video size). --auto is portrait mode: the
+// first frames teach the empty room and whatever stays still gets the effect;
+// --invert flips either mask (e.g. the moving subject instead of the room).
+// --filters / --post run filters.rs chains before / after the effect, all listed on.
+//
+// `batch` takes files, folders and `*` / `?` patterns (quoted, or let the shell expand
+// them), needs --mask, and redacts several images at once (one per core); each result
+// keeps its file name (and so its format) in --out:
+//
+//   magic-eraser batch "dumps/*.png" --mask header-mask.png --effect pixelate --out clean/
+//
+// `stream` is `process` with the webcam as IN: no window, so a build without one
+// (`--no-default-features --features camera`) runs it on a server, e.g.
+//
+//   magic-eraser stream room.mkv --auto --frames 9000
+//   magic-eraser --camera 1 stream udp://10.0.0.5:1234 --format mpegts --mask desk-mask.png
+//
+// Video goes through ffmpeg and ffprobe, which must be on PATH: ffmpeg decodes IN to
+// raw RGB24 on a pipe, and a second ffmpeg encodes the processed frames (codec from
+// OUT's extension, audio copied from IN). A video dropped onto the window plays through
+// the same decoder, looped, in place of the camera (`VideoSource`).
+
+use crate::cli::{BatchArgs, EffectArgs, ProcessArgs};
+#[cfg(feature = "camera")]
+use crate::cli::{Options, StreamArgs};
+use crate::effects::{EffectCtx, EffectRegistry};
+use crate::error::Error;
+use crate::filter::FilterChain;
+use crate::filters;
+use crate::gamma::GammaLut;
+use crate::imageio;
+use crate::median::MedianFilter;
+#[cfg(feature = "camera")]
+use crate::pipeline::CaptureStage;
+#[cfg(any(feature = "window", feature = "camera"))]
+use crate::pipeline::FrameSource;
+use crate::pixel::{PixelBuffer, PixelFormat};
+use crate::segment;
+use crate::types::{FrameBuffer, Mask, MaskTiles};
+#[cfg(feature = "window")]
+use crate::types::FramePool;
+use crate::vision::{self, BackgroundModel, BG_CAPTURE_COUNT};
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+#[cfg(feature = "window")]
+use std::sync::Mutex;
+
+/// Softness of the --auto mask's outline (as in portrait mode).
+const AUTO_FEATHER: usize = 6;
+
+/// The per-frame work without a window: filters, the effect's sink blended in under a
+/// mask, polish. One per stream; its buffers follow the frame size.
+pub struct Redactor {
+    live_filters: FilterChain,
+    post_filters: FilterChain,
+    effects: EffectRegistry,
+    blur_radius: usize,
+    lut: GammaLut,
+    tmp: FrameBuffer,
+    blur: FrameBuffer,
+    out: FrameBuffer,
+    tiles: MaskTiles,
+}
+
+impl Redactor {
+    /// The effect and filter lists from `--effect` / `--filters` / `--post`.
+    pub fn new(args: &EffectArgs, blur_radius: usize) -> Result<Self, Error> {
+        let effect = args.effect.as_str();
+        let mut effects = EffectRegistry::new(None);
+        if !effects.select_by_name(effect) {
+            return Err(Error::Config(format!("--effect: unknown effect '{effect}'")));
+        }
+        if effects.active_uses_clone_source() {
+            return Err(Error::Config(format!("--effect {effect}: needs a clone source, which only the window can set")));
+        }
+        let empty = || FrameBuffer { width: 0, height: 0, pixels: Vec::new() };
+        Ok(Self {
+            live_filters: filters::build(&args.filters, "--filters")?,
+            post_filters: filters::build(&args.post, "--post")?,
+            effects,
+            blur_radius,
+            lut: GammaLut::new(),
+            tmp: empty(),
+            blur: empty(),
+            out: empty(),
+            tiles: MaskTiles::new(0, 0),
+        })
+    }
+
+    /// Step 1: the live-frame filters (what the mask, if computed, should see).
+    pub fn correct(&mut self, frame: &mut FrameBuffer) -> Result<(), Error> {
+        self.live_filters.apply(frame, &self.lut, false)
+    }
+
+    /// Step 2: the effect where `mask` says, then the post filters.
+    /// Visual: the painted / masked area shows the effect, the rest is untouched.
+    pub fn finish(&mut self, frame: &mut FrameBuffer, mask: &Mask) -> Result<(), Error> {
+        if mask.bounds.is_some() {
+            for buf in [&mut self.tmp, &mut self.blur, &mut self.out] {
+                if buf.width != frame.width || buf.height != frame.height {
+                    *buf = FrameBuffer { width: frame.width, height: frame.height, pixels: vec![0u32; frame.pixels.len()] };
+                }
+            }
+            if self.effects.active_needs_blur() {
+                vision::box_blur_rgb(frame, &mut self.tmp, &mut self.blur, self.blur_radius)?;
+            }
+            self.tiles = MaskTiles::new(mask.width, mask.height);
+            self.tiles.rebuild(mask);
+            let ctx = EffectCtx { live: frame, blur: &self.blur, mask, lut: &self.lut, clone_offset: None };
+            let sink = self.effects.render(&ctx)?;
+            self.out.pixels.copy_from_slice(&frame.pixels);
+            vision::blend_linear_in_place(&mut self.out, sink, mask, &self.tiles, &self.lut)?;
+            std::mem::swap(frame, &mut self.out);
+        }
+        self.post_filters.apply(frame, &self.lut, false)
+    }
+}
+
+/// --auto: portrait mode's mask from a background model (α = 255 on the room).
+struct AutoMask {
+    model: BackgroundModel,
+    median: MedianFilter,
+    scratch: Vec<u8>,
+}
+
+impl AutoMask {
+    fn new(width: usize, height: usize) -> Self {
+        Self { model: BackgroundModel::new(width, height), median: MedianFilter::new(), scratch: Vec::new() }
+    }
+
+    /// The room in `live` (everything that matches the learned background) into `mask`.
+    fn build(&mut self, live: &FrameBuffer, mask: &mut Mask) -> Result<(), Error> {
+        segment::foreground_mask(live, self.model.frame(), mask)?;
+        self.median.apply_mask(mask, 2);
+        segment::soften_mask(mask, &mut self.scratch, AUTO_FEATHER);
+        vision::invert_mask(mask);
+        self.model.refresh(live, mask) // the room keeps up with lighting, as in the window
+    }
+}
+
+/// Size and frame rate ("30000/1001") of the first video stream in `path`.
+fn probe(path: &str) -> Result<(usize, usize, String), Error> {
+    let out = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "stream=width,height,r_frame_rate", "-of", "csv=p=0", path])
+        .output()
+        .map_err(|e| Error::Io("Run ffprobe (is ffmpeg installed?)".into(), e))?;
+    if !out.status.success() {
+        return Err(Error::Video(format!("ffprobe {path}: {}", String::from_utf8_lossy(&out.stderr).trim())));
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    let mut fields = text.trim().split(',');
+    let mut dim = || fields.next().and_then(|v| v.trim().parse::<usize>().ok()).filter(|&n| n > 0);
+    match (dim(), dim(), text.trim().rsplit(',').next()) {
+        (Some(w), Some(h), Some(rate)) => Ok((w, h, rate.trim().to_string())),
+        _ => Err(Error::Video(format!("{path}: no video stream"))),
+    }
+}
+
+/// Raw RGB24 frames from a running `ffmpeg` decoder.
+struct Decoder {
+    child: Child,
+    stdout: ChildStdout,
+    raw: PixelBuffer,
+}
+
+impl Decoder {
+    /// `input`: ffmpeg's arguments up to the output (at least `-i FILE`); the frames
+    /// must come out `width`×`height`.
+    fn spawn(input: &[&str], width: usize, height: usize) -> Result<Self, Error> {
+        let mut child = Command::new("ffmpeg")
+            .args(["-v", "error"])
+            .args(input)
+            .args(["-f", "rawvideo", "-pix_fmt", "rgb24", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Io("Run ffmpeg (is it installed?)".into(), e))?;
+        let stdout = child.stdout.take().ok_or_else(|| Error::Video("ffmpeg decoder: no output pipe".into()))?;
+        Ok(Self { child, stdout, raw: PixelBuffer::new(width, height, PixelFormat::Rgb24) })
+    }
+
+    /// Read the next frame into `raw`; false at the end of the video.
+    fn read(&mut self) -> Result<bool, Error> {
+        match self.stdout.read_exact(&mut self.raw.data) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(Error::Io("Read decoded frames from ffmpeg".into(), e)),
+        }
+    }
+
+    /// The next frame, or `None` at the end of the video.
+    fn next(&mut self) -> Result<Option<FrameBuffer>, Error> {
+        if self.read()? { self.raw.to_frame().map(Some) } else { Ok(None) }
+    }
+}
+
+/// A video file as the window's picture instead of the camera (dropped onto it): played
+/// in real time and looped, scaled to cover the camera's size and centre-cropped, so
+/// every buffer behind it still fits.
+#[cfg(feature = "window")]
+pub struct VideoSource {
+    decoder: Mutex<Decoder>,
+    pool: FramePool,
+    name: String,
+    width: u32,
+    height: u32,
+}
+
+#[cfg(feature = "window")]
+impl VideoSource {
+    /// Start decoding `path` at `width`×`height`; fails before anything switches if
+    /// ffmpeg is missing or the file has no video.
+    pub fn open(path: &Path, width: u32, height: u32) -> Result<Self, Error> {
+        let file = path.to_string_lossy();
+        probe(&file)?;
+        let (w, h) = (width as usize, height as usize);
+        let fit = format!("scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1");
+        let decoder = Decoder::spawn(&["-re", "-stream_loop", "-1", "-i", &file, "-an", "-vf", &fit], w, h)?;
+        Ok(Self { decoder: Mutex::new(decoder), pool: FramePool::new(w, h), name: file.into_owned(), width, height })
+    }
+}
+
+#[cfg(feature = "window")]
+impl FrameSource for VideoSource {
+    /// The next frame of the video (ffmpeg's `-re` paces it to the video's frame rate).
+    fn next_frame(&self) -> Result<FrameBuffer, Error> {
+        let mut decoder = self.decoder.lock().map_err(|_| Error::Video(format!("{}: decoder poisoned", self.name)))?;
+        if !decoder.read()? {
+            return Err(Error::Video(format!("ffmpeg stopped decoding {}", self.name)));
+        }
+        let mut frame = self.pool.get();
+        decoder.raw.read_frame(&mut frame)?;
+        Ok(frame)
+    }
+
+    fn recycle(&self, frame: FrameBuffer) {
+        self.pool.put(frame);
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+#[cfg(feature = "window")]
+impl Drop for VideoSource {
+    /// Stop ffmpeg with the source (it loops forever otherwise).
+    fn drop(&mut self) {
+        if let Ok(decoder) = self.decoder.get_mut() {
+            let _ = decoder.child.kill();
+            let _ = decoder.child.wait();
+        }
+    }
+}
+
+/// A running `ffmpeg` encoder fed raw RGB24 frames; the audio (if any) is taken from `audio`.
+/// Also behind the `[[pipeline.output]]` entries of config.toml (see outputs.rs).
+pub struct Encoder {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    raw: PixelBuffer,
+}
+
+impl Encoder {
+    /// `format` is ffmpeg's `-f` for `path` (None: from the extension).
+    pub fn spawn(path: &str, audio: Option<&str>, format: Option<&str>, width: usize, height: usize, rate: &str) -> Result<Self, Error> {
+        let size = format!("{width}x{height}");
+        let mut ffmpeg = Command::new("ffmpeg");
+        ffmpeg.args(["-v", "error", "-y", "-f", "rawvideo", "-pix_fmt", "rgb24", "-s", &size, "-r", rate, "-i", "-"]);
+        if let Some(source) = audio {
+            ffmpeg.args(["-i", source, "-map", "0:v", "-map", "1:a?", "-c:a", "copy"]);
+        }
+        if let Some(format) = format {
+            ffmpeg.args(["-f", format]);
+        }
+        let mut child = ffmpeg
+            .args(["-pix_fmt", "yuv420p", path])
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Io("Run ffmpeg (is it installed?)".into(), e))?;
+        let stdin = child.stdin.take();
+        Ok(Self { child, stdin, raw: PixelBuffer::new(width, height, PixelFormat::Rgb24) })
+    }
+
+    pub fn write(&mut self, frame: &FrameBuffer) -> Result<(), Error> {
+        self.raw.write_frame(frame)?;
+        let stdin = self.stdin.as_mut().ok_or_else(|| Error::Video("ffmpeg encoder: no input pipe".into()))?;
+        stdin.write_all(&self.raw.data).map_err(|e| Error::Io("Write frames to ffmpeg".into(), e))
+    }
+
+    /// Close the pipe and wait for the file to be finished.
+    pub fn finish(mut self, path: &str) -> Result<(), Error> {
+        drop(self.stdin.take());
+        let status = self.child.wait().map_err(|e| Error::Io("Wait for ffmpeg".into(), e))?;
+        if status.success() { Ok(()) } else { Err(Error::Video(format!("ffmpeg could not write {path} ({status})"))) }
+    }
+}
+
+/// `magic-eraser process`: read `args.input`, redact every frame, write `args.output`.
+pub fn run(args: &ProcessArgs, blur_radius: usize) -> Result<(), Error> {
+    let (input, output) = (args.input.as_str(), args.output.as_str());
+    if same_file(Path::new(input), Path::new(output))? {
+        return Err(Error::Config("process: OUT must be a different file from IN".into()));
+    }
+    let mut redactor = Redactor::new(&args.effect, blur_radius)?;
+    let (w, h, rate) = probe(input)?;
+
+    let mut mask = match &args.mask {
+        Some(path) => imageio::load_mask(path, w, h)?,
+        None => Mask::new(w, h),
+    };
+    if args.mask.is_some() && args.effect.invert {
+        vision::invert_mask(&mut mask);
+    }
+
+    let mut decoder = Decoder::spawn(&["-i", input], w, h)?;
+    let mut encoder = Encoder::spawn(output, Some(input), None, w, h, &rate)?;
+
+    // --auto learns the room from the first frames before any of them is written, so
+    // the start of the video is redacted like the rest.
+    let mut auto = args.auto.then(|| AutoMask::new(w, h));
+    let mut learned = VecDeque::new();
+    if let Some(auto) = auto.as_mut() {
+        while learned.len() < BG_CAPTURE_COUNT && let Some(mut frame) = decoder.next()? {
+            redactor.correct(&mut frame)?;
+            auto.model.update(&frame)?;
+            learned.push_back(frame);
+        }
+    }
+
+    let mut frames = 0usize;
+    loop {
+        let mut frame = match learned.pop_front() {
+            Some(frame) => frame,
+            None => match decoder.next()? {
+                Some(mut frame) => { redactor.correct(&mut frame)?; frame }
+                None => break,
+            },
+        };
+        if let Some(auto) = auto.as_mut() {
+            auto.build(&frame, &mut mask)?;
+            if args.effect.invert {
+                vision::invert_mask(&mut mask);
+            }
+        }
+        redactor.finish(&mut frame, &mask)?;
+        encoder.write(&frame)?;
+        frames += 1;
+        if frames.is_multiple_of(30) {
+            eprint!("\rprocess: {frames} frames");
+        }
+    }
+    // A decoder that gives up mid-file (corrupt input, unknown codec) looks like the end
+    // of the video on the pipe; only its exit status tells the two apart.
+    let status = decoder.child.wait().map_err(|e| Error::Io("Wait for ffmpeg".into(), e))?;
+    encoder.finish(output)?;
+    if !status.success() {
+        return Err(Error::Video(format!("ffmpeg could not decode all of {input} ({status}); {output} stops after {frames} frames")));
+    }
+    eprintln!("\rprocess: {frames} frames → {output}");
+    Ok(())
+}
+
+/// Whether writing `output` would replace `input`, however either is spelled
+/// (`talk.mp4 ./talk.mp4`, an absolute path, a symlink). As in `batch_jobs`: the
+/// output's folder is resolved and its file name put back, since OUT needn't exist yet.
+fn same_file(input: &Path, output: &Path) -> Result<bool, Error> {
+    let input = std::fs::canonicalize(input).map_err(|e| Error::Io(format!("Resolve {}", input.display()), e))?;
+    if let Ok(existing) = std::fs::canonicalize(output) {
+        return Ok(existing == input);
+    }
+    let parent = output.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    match (std::fs::canonicalize(parent), output.file_name()) {
+        (Ok(dir), Some(name)) => Ok(dir.join(name) == input),
+        _ => Ok(false), // no such folder: OUT can't be IN (the encoder reports it)
+    }
+}
+
+/// `magic-eraser stream OUT`: the camera, redacted frame by frame, into ffmpeg — no
+/// window, so it runs on a server or over SSH. Ctrl+C (or --frames) ends it; ffmpeg
+/// finishes the file on its way out.
+#[cfg(feature = "camera")]
+pub fn run_stream(args: &StreamArgs, opts: &Options) -> Result<(), Error> {
+    let output = args.output.as_str();
+    let mut redactor = Redactor::new(&args.effect, opts.blur_radius)?;
+    let (cw, ch) = opts.resolution;
+    let camera = CaptureStage::spawn(opts.camera, cw, ch, opts.fps)?;
+    let (w, h) = camera.resolution();
+    let (w, h) = (w as usize, h as usize);
+
+    let mut mask = match &args.mask {
+        Some(path) => imageio::load_mask(path, w, h)?,
+        None => Mask::new(w, h),
+    };
+    if args.mask.is_some() && args.effect.invert {
+        vision::invert_mask(&mut mask);
+    }
+    let mut encoder = Encoder::spawn(output, None, args.format.as_deref(), w, h, &opts.fps.to_string())?;
+
+    // --auto: the first frames teach the room (step out of view meanwhile); they are
+    // not written, so the stream starts already redacted.
+    let mut auto = args.auto.then(|| AutoMask::new(w, h));
+    if let Some(auto) = auto.as_mut() {
+        eprintln!("stream: learning the room ({BG_CAPTURE_COUNT} frames) — step out of view");
+        while auto.model.frames_seen() < BG_CAPTURE_COUNT {
+            let mut frame = match camera.next_frame() {
+                Ok(frame) => frame,
+                Err(e) if e.is_recoverable() => continue,
+                Err(e) => return Err(e),
+            };
+            redactor.correct(&mut frame)?;
+            auto.model.update(&frame)?;
+            camera.recycle(frame);
+        }
+    }
+
+    let mut frames = 0usize;
+    while args.frames.is_none_or(|n| frames < n) {
+        let mut frame = match camera.next_frame() {
+            Ok(frame) => frame,
+            Err(e) if e.is_recoverable() => { log::debug!("{e} — frame skipped"); continue; }
+            Err(e) => return Err(e),                              // camera gone: end with the message
+        };
+        redactor.correct(&mut frame)?;
+        if let Some(auto) = auto.as_mut() {
+            auto.build(&frame, &mut mask)?;
+            if args.effect.invert {
+                vision::invert_mask(&mut mask);
+            }
+        }
+        redactor.finish(&mut frame, &mask)?;
+        encoder.write(&frame)?;
+        camera.recycle(frame);
+        frames += 1;
+        if frames.is_multiple_of(30) {
+            eprint!("\rstream: {frames} frames");
+        }
+    }
+    encoder.finish(output)?;
+    eprintln!("\rstream: {frames} frames → {output}");
+    Ok(())
+}
+
+/// File extensions `batch` picks up from a folder.
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "bmp", "gif", "tif", "tiff"];
+
+/// `*` (any run) and `?` (any one character) against a whole file name.
+fn wildcard(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => wildcard(&pattern[1..], name) || (!name.is_empty() && wildcard(pattern, &name[1..])),
+        (Some('?'), Some(_)) => wildcard(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) => p == n && wildcard(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// Every file `input` names: the file itself, the images in a folder, or the files in
+/// its folder whose names match a `*` / `?` pattern (in the last part only).
+fn expand(input: &str) -> Result<Vec<PathBuf>, Error> {
+    let path = Path::new(input);
+    let list = |dir: &Path| std::fs::read_dir(dir)
+        .map_err(|e| Error::Io(format!("Read folder {}", dir.display()), e));
+    let mut files = Vec::new();
+    if path.is_dir() {
+        for entry in list(path)?.flatten() {
+            let ext = entry.path().extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+            if ext.is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.as_str())) {
+                files.push(entry.path());
+            }
+        }
+    } else if let Some(name) = path.file_name().and_then(|n| n.to_str()).filter(|n| n.contains(['*', '?'])) {
+        let pattern: Vec<char> = name.chars().collect();
+        let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        for entry in list(dir)?.flatten() {
+            let name: Vec<char> = entry.file_name().to_string_lossy().chars().collect();
+            if entry.path().is_file() && wildcard(&pattern, &name) {
+                files.push(entry.path());
+            }
+        }
+        if files.is_empty() {
+            return Err(Error::ImageLoad(format!("{input}: no files match")));
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Redact one image into `dst`; `mask` is reloaded when the image size changes.
+fn redact_image(redactor: &mut Redactor, mask: &mut Option<Mask>, args: &BatchArgs, src: &Path, dst: &Path) -> Result<(), Error> {
+    let mut frame = imageio::load_image(&src.to_string_lossy())?;
+    let mask = match mask {
+        Some(m) if (m.width, m.height) == (frame.width, frame.height) => m,
+        slot => {
+            let mut m = imageio::load_mask(&args.mask, frame.width, frame.height)?;
+            if args.effect.invert {
+                vision::invert_mask(&mut m);
+            }
+            slot.insert(m)
+        }
+    };
+    redactor.correct(&mut frame)?;
+    redactor.finish(&mut frame, mask)?;
+    imageio::save_image(&dst.to_string_lossy(), &frame)
+}
+
+/// Pair every input with its output in `out` (same file name). The same file named twice
+/// (`a.png ./a.png`) is done once; an output that would replace an input, or that two
+/// inputs would both write (`x/a.png y/a.png`), is an error before anything is written.
+fn batch_jobs(files: Vec<PathBuf>, out: &Path) -> Result<Vec<(PathBuf, PathBuf)>, Error> {
+    let canonical = |p: &Path| std::fs::canonicalize(p).map_err(|e| Error::Io(format!("Resolve {}", p.display()), e));
+    let mut files = files.iter().map(|f| canonical(f)).collect::<Result<Vec<_>, _>>()?;
+    files.sort();
+    files.dedup();
+    let out = canonical(out)?;
+    let mut jobs: Vec<(PathBuf, PathBuf)> = files
+        .into_iter()
+        .map(|src| {
+            let dst = out.join(src.file_name().unwrap_or_default());
+            (src, dst)
+        })
+        .collect();
+    if let Some((src, _)) = jobs.iter().find(|(src, dst)| src == dst) {
+        return Err(Error::Config(format!("{}: --out would overwrite it", src.display())));
+    }
+    jobs.sort_by(|a, b| a.1.cmp(&b.1));
+    if let Some(pair) = jobs.windows(2).find(|pair| pair[0].1 == pair[1].1) {
+        return Err(Error::Config(format!(
+            "{} and {} would both be written to {}", pair[0].0.display(), pair[1].0.display(), pair[0].1.display()
+        )));
+    }
+    jobs.sort();
+    Ok(jobs)
+}
+
+/// `magic-eraser batch`: redact every listed image into `args.out`, several at a time.
+pub fn run_batch(args: &BatchArgs, blur_radius: usize) -> Result<(), Error> {
+    Redactor::new(&args.effect, blur_radius)?; // bad --effect / --filters: fail before any work
+    let mut files = Vec::new();
+    for input in &args.inputs {
+        files.extend(expand(input)?);
+    }
+    std::fs::create_dir_all(&args.out)
+        .map_err(|e| Error::Io(format!("Create {}", args.out.display()), e))?;
+    let jobs = batch_jobs(files, &args.out)?;
+
+    // One Redactor (and mask) per worker; the blur inside still splits across cores too.
+    let failed = jobs
+        .par_iter()
+        .map_init(
+            || (Redactor::new(&args.effect, blur_radius), None),
+            |(redactor, mask), (src, dst)| {
+                let result = match redactor {
+                    Ok(redactor) => redact_image(redactor, mask, args, src, dst),
+                    Err(e) => Err(Error::Config(e.to_string())),
+                };
+                match result {
+                    Ok(()) => false,
+                    Err(e) => { log::error!("{}: {e}", src.display()); true }
+                }
+            },
+        )
+        .filter(|&failed| failed)
+        .count();
+
+    println!("batch: {} of {} images → {}", jobs.len() - failed, jobs.len(), args.out.display());
+    if failed > 0 {
+        return Err(Error::ImageSave(format!("batch: {failed} images not written")));
+    }
+    Ok(())
+}
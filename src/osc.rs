@@ -0,0 +1,76 @@
+// OSC remote control (`--osc PORT`), for TouchOSC, Max/MSP, TouchDesigner, Ableton.
+// Visual expectation: a fader on a tablet or a beat in a music set drives the brush
+// live — the blur swells, the effect switches, the mask clears on the drop — while
+// the window shows the change on the next frame, exactly as if the key had been hit
+// (messages become the same `Event`s the keyboard makes; see events.rs).
+//
+// Addresses (UDP, one value each; ints and floats are both accepted):
+//   /eraser/brush/radius   PX     brush radius in window pixels (4..160)
+//   /eraser/brush/hardness 0..1   0 = very soft edge, 1 = crisp
+//   /eraser/blur/radius    PX     blur radius (1..96)
+//   /eraser/effect         N|NAME brush effect: 1-based number (as the digit keys) or name ("pixelate")
+//   /eraser/clear                 clear the painted mask
+//   /eraser/portrait       0|1    portrait mode off / on
+//   /eraser/fx             0|1    sparkles and lightning off / on
+
+use crate::error::Error;
+use crate::events::{Action, EffectRef, Event, EventSender};
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::UdpSocket;
+use std::thread;
+
+/// Listen on UDP `port` (all interfaces) on a background thread, passing what it
+/// understands to `events`; the thread ends when processing stops.
+pub fn listen(port: u16, events: EventSender) -> Result<(), Error> {
+    let socket = UdpSocket::bind(("0.0.0.0", port)).map_err(|e| Error::Osc(format!("Listen on UDP {port}: {e}")))?;
+    thread::Builder::new()
+        .name("osc".into())
+        .spawn(move || {
+            let mut buf = [0u8; rosc::decoder::MTU];
+            while let Ok(n) = socket.recv(&mut buf) {
+                let Ok((_, packet)) = rosc::decoder::decode_udp(&buf[..n]) else {
+                    continue;                                  // not OSC: ignore
+                };
+                let mut received = Vec::new();
+                collect(packet, &mut received);
+                if received.into_iter().any(|e| !events.send(e)) {
+                    break;                                     // processing stopped
+                }
+            }
+        })
+        .map_err(|e| Error::Osc(format!("Spawn OSC thread: {e}")))?;
+    Ok(())
+}
+
+/// Flatten bundles and keep the messages we understand (unknown addresses are ignored).
+fn collect(packet: OscPacket, out: &mut Vec<Event>) {
+    match packet {
+        OscPacket::Message(msg) => out.extend(command(msg)),
+        OscPacket::Bundle(bundle) => bundle.content.into_iter().for_each(|p| collect(p, out)),
+    }
+}
+
+fn command(msg: OscMessage) -> Option<Event> {
+    let arg = msg.args.into_iter().next();
+    let number = || match &arg {
+        Some(OscType::Float(v)) => Some(*v),
+        Some(OscType::Double(v)) => Some(*v as f32),
+        Some(OscType::Int(v)) => Some(*v as f32),
+        Some(OscType::Long(v)) => Some(*v as f32),
+        Some(OscType::Bool(b)) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    };
+    Some(match msg.addr.as_str() {
+        "/eraser/brush/radius" => Event::SetBrushRadius(number()?),
+        "/eraser/brush/hardness" => Event::SetBrushHardness(number()?),
+        "/eraser/blur/radius" => Event::SetBlurRadius(number()?),
+        "/eraser/effect" => match &arg {
+            Some(OscType::String(name)) => Event::SelectEffect(EffectRef::Name(name.clone())),
+            _ => Event::SelectEffect(EffectRef::Index((number()?.round() as usize).checked_sub(1)?)),
+        },
+        "/eraser/clear" => Event::Press(Action::Clear),
+        "/eraser/portrait" => Event::SetPortrait(number()? >= 0.5),
+        "/eraser/fx" => Event::SetFx(number()? >= 0.5),
+        _ => return None,
+    })
+}
@@ -0,0 +1,159 @@
+// Extra outputs from `[[pipeline.output]]` in config.toml: copies of the picture, taken
+// at some point of the pipeline, going to a file, a stream or a virtual camera while
+// the window runs.
+// Visual expectation: the window looks as always. Each output gets the frame at its
+// tap — `live` (the camera after the live filters, before the brush), `clean` (the
+// finished picture before FX, guides and HUD) or `screen` (what the window shows).
+// E.g. a clean feed on a v4l2loopback camera for video calls, overlays only on screen:
+//
+//     [[pipeline.output]]
+//     to = "/dev/video10"
+//     format = "v4l2"
+//     tap = "clean"
+//
+// Each output runs its own ffmpeg (see `offline::Encoder`) on its own thread; with
+// `format = "shm"` the frames go to a shared-memory file instead, for OBS (see shm.rs;
+// `--obs` adds one of those). One that falls behind drops frames instead of slowing the
+// app; one that fails is logged and closed while the others go on.
+
+use crate::config::{OutputConfig, Tap};
+use crate::error::Error;
+use crate::offline::Encoder;
+use crate::shm::{self, ShmWriter};
+use crate::types::FrameBuffer;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+
+/// Frame buffers per output: one being encoded, one waiting, one being filled.
+const BUFFERS: usize = 3;
+
+struct Output {
+    tap: Tap,
+    frames: Option<SyncSender<FrameBuffer>>, // None once the encoder thread has stopped
+    spare: Receiver<FrameBuffer>,            // buffers the encoder is done with
+    free: Option<FrameBuffer>,               // filled but not taken last time
+    buffers: usize,
+    thread: Option<JoinHandle<()>>,
+}
+
+pub struct Outputs {
+    outputs: Vec<Output>,
+}
+
+impl Outputs {
+    /// One encoder thread per entry; ffmpeg starts with the first frame (its size).
+    pub fn start(config: &[OutputConfig], fps: u32) -> Self {
+        let outputs = config
+            .iter()
+            .map(|out| {
+                let (frames, inbox) = mpsc::sync_channel(1);
+                let (done, spare) = mpsc::channel();
+                let (to, format) = (out.to.clone(), out.format.clone());
+                let thread = thread::spawn(move || encode(&to, format.as_deref(), fps, inbox, done));
+                log::info!("output: {:?} picture to {}", out.tap, out.to);
+                Output { tap: out.tap, frames: Some(frames), spare, free: None, buffers: 0, thread: Some(thread) }
+            })
+            .collect();
+        Self { outputs }
+    }
+
+    /// True if a running output takes the picture at `tap`.
+    #[cfg(feature = "gpu")]
+    pub fn wants(&self, tap: Tap) -> bool {
+        self.outputs.iter().any(|o| o.tap == tap && o.frames.is_some())
+    }
+
+    /// Copy `frame` to every output tapping `tap`; one still busy misses this frame.
+    pub fn send(&mut self, tap: Tap, frame: &FrameBuffer) {
+        for out in self.outputs.iter_mut().filter(|o| o.tap == tap) {
+            let Some(frames) = &out.frames else { continue };
+            let mut buf = match out.free.take().or_else(|| out.spare.try_recv().ok()) {
+                Some(buf) => buf,
+                None if out.buffers < BUFFERS => {
+                    out.buffers += 1;
+                    FrameBuffer { width: 0, height: 0, pixels: Vec::new() }
+                }
+                None => continue,                               // encoder behind: drop this one
+            };
+            buf.clone_from(frame);
+            match frames.try_send(buf) {
+                Ok(()) => {}
+                Err(TrySendError::Full(buf)) => out.free = Some(buf),
+                Err(TrySendError::Disconnected(_)) => out.frames = None, // failed (logged there)
+            }
+        }
+    }
+}
+
+impl Drop for Outputs {
+    /// Close every pipe and wait, so files are finished properly.
+    fn drop(&mut self) {
+        for out in &mut self.outputs {
+            out.frames = None;
+            if let Some(thread) = out.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+/// What an output writes to.
+enum Sink {
+    Ffmpeg(Encoder),
+    Shared(ShmWriter),
+}
+
+impl Sink {
+    fn open(to: &str, format: Option<&str>, fps: u32, width: usize, height: usize) -> Result<Self, Error> {
+        match format {
+            Some(shm::OUTPUT_FORMAT) => ShmWriter::create(Path::new(to), width, height, fps).map(Sink::Shared),
+            _ => Encoder::spawn(to, None, format, width, height, &fps.to_string()).map(Sink::Ffmpeg),
+        }
+    }
+
+    fn write(&mut self, frame: &FrameBuffer) -> Result<(), Error> {
+        match self {
+            Sink::Ffmpeg(e) => e.write(frame),
+            Sink::Shared(w) => w.write(frame),
+        }
+    }
+
+    fn finish(self, to: &str) -> Result<(), Error> {
+        match self {
+            Sink::Ffmpeg(e) => e.finish(to),
+            Sink::Shared(_) => Ok(()),                          // the file goes with the writer
+        }
+    }
+}
+
+/// Output thread: write frames until the app closes the channel or a write fails.
+fn encode(to: &str, format: Option<&str>, fps: u32, inbox: Receiver<FrameBuffer>, done: Sender<FrameBuffer>) {
+    let mut encoder: Option<(Sink, usize, usize)> = None;
+    for frame in inbox {
+        if encoder.is_none() {
+            match Sink::open(to, format, fps, frame.width, frame.height) {
+                Ok(e) => encoder = Some((e, frame.width, frame.height)),
+                Err(e) => {
+                    log::warn!("output {to}: {e}");
+                    return;
+                }
+            }
+        }
+        let Some((e, w, h)) = &mut encoder else { return };
+        if (frame.width, frame.height) != (*w, *h) {
+            log::warn!("output {to}: frame size changed from {w}x{h} to {}x{} — stopped", frame.width, frame.height);
+            break;
+        }
+        if let Err(err) = e.write(&frame) {
+            log::warn!("output {to}: {err}");
+            break;
+        }
+        let _ = done.send(frame);
+    }
+    if let Some((e, _, _)) = encoder
+        && let Err(err) = e.finish(to)
+    {
+        log::warn!("output {to}: {err}");
+    }
+}
@@ -0,0 +1,80 @@
+// Eyedropper: read back the exact colour of one pixel of the finished picture.
+// Visual expectation: press X, click anywhere, and a HUD line shows a swatch plus the
+// pixel's hex code, its sRGB bytes and its linear-light values (what the blur and
+// blends actually average). The hex code is also put on the system clipboard, ready to
+// paste as a chroma-key colour.
+//
+// Clipboard: no extra crate — we pipe the text into the platform's own tool
+// (pbcopy, clip, wl-copy, xclip or xsel), on a short-lived thread so a slow or
+// missing tool never stalls a frame.
+
+use crate::error::Error;
+use crate::gamma::GammaLut;
+use crate::types::FrameBuffer;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// A sampled pixel (0x00RRGGBB, as stored in the frame).
+#[derive(Clone, Copy, Debug)]
+pub struct ColorPick {
+    pub color: u32,
+}
+
+impl ColorPick {
+    /// Sample `fb` at (x, y); None outside the frame.
+    pub fn sample(fb: &FrameBuffer, x: i32, y: i32) -> Option<Self> {
+        if x < 0 || y < 0 || x as usize >= fb.width || y as usize >= fb.height {
+            return None;
+        }
+        Some(Self { color: fb.pixels[y as usize * fb.width + x as usize] & 0x00FF_FFFF })
+    }
+
+    /// "#RRGGBB".
+    pub fn hex(&self) -> String {
+        format!("#{:06X}", self.color)
+    }
+
+    /// HUD line: hex, sRGB bytes, linear values.
+    /// Visual: "#FF8000  SRGB 255 128 0  LIN 1.000 0.216 0.000".
+    pub fn hud_text(&self, lut: &GammaLut) -> String {
+        let [_, r, g, b] = self.color.to_be_bytes();
+        let (lr, lg, lb) = (lut.srgb_u8_to_linear(r), lut.srgb_u8_to_linear(g), lut.srgb_u8_to_linear(b));
+        format!("{}  SRGB {r} {g} {b}  LIN {lr:.3} {lg:.3} {lb:.3}", self.hex())
+    }
+}
+
+/// Put `text` on the system clipboard using whichever command-line tool this OS has.
+pub fn copy_to_clipboard(text: &str) -> Result<(), Error> {
+    let tools: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    };
+    let mut last_err = String::from("no clipboard tool found");
+    for (tool, args) in tools {
+        let child = Command::new(tool)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(c) => c,
+            Err(e) => {
+                last_err = format!("{tool}: {e}");
+                continue; // not installed: try the next one
+            }
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).map_err(|e| Error::Clipboard(format!("{tool}: {e}")))?;
+        } // stdin dropped here, so the tool sees end of input
+        let status = child.wait().map_err(|e| Error::Clipboard(format!("{tool}: {e}")))?;
+        if status.success() {
+            return Ok(());
+        }
+        last_err = format!("{tool} exited with {status}");
+    }
+    Err(Error::Clipboard(last_err))
+}
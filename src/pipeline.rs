@@ -0,0 +1,216 @@
+// Three-stage pipeline: capture → process → present, each on its own thread.
+// Visual expectation: the same picture as before, but a slow camera read, a heavy
+// blur and waiting for the window no longer add up — each stage works on a
+// different frame at the same time, so FPS goes up on multi-core machines.
+//
+// Stages are joined by bounded channels (at most one frame waiting between two
+// stages), so latency stays at a frame or two and memory never grows.
+// Presented screen buffers travel back to the processing stage for reuse, and
+// camera frames come from a shared `FramePool` the processing stage returns them to.
+//
+// If the camera stops delivering (unplugged, driver hiccup), the capture thread closes
+// it and tries to open it again a few times before giving up; the picture freezes
+// meanwhile and the log says what happened.
+
+#[cfg(feature = "camera")]
+use crate::camera::CameraCapture;
+use crate::error::Error;
+use crate::types::FrameBuffer;
+#[cfg(feature = "camera")]
+use crate::types::FramePool;
+#[cfg(feature = "window")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+#[cfg(feature = "window")]
+use std::sync::mpsc::{Sender, SyncSender};
+#[cfg(feature = "window")]
+use std::sync::Arc;
+#[cfg(feature = "camera")]
+use std::thread::{self, JoinHandle};
+#[cfg(feature = "camera")]
+use std::time::Duration;
+
+/// Where the processing stage gets its frames: the camera thread, or synthetic
+/// frames for `--bench` (see `bench.rs`).
+pub trait FrameSource: Send {
+    /// The next frame (may block until one is ready).
+    fn next_frame(&self) -> Result<FrameBuffer, Error>;
+    /// Return a finished frame so its buffer can be filled again.
+    fn recycle(&self, frame: FrameBuffer);
+    /// Size of the frames this source delivers.
+    fn resolution(&self) -> (u32, u32);
+}
+
+/// Failed camera reads in a row before the capture thread gives up (about a second).
+#[cfg(feature = "camera")]
+const MAX_FAILED_FRAMES: u32 = 30;
+
+/// Attempts to reopen a camera that stopped, and the pause before each.
+#[cfg(feature = "camera")]
+const RECONNECT_TRIES: u32 = 5;
+#[cfg(feature = "camera")]
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Frames waiting between two stages (1 = the next stage sees at most one frame of lag).
+pub const STAGE_QUEUE: usize = 1;
+
+/// Capture stage: a thread that owns the camera and keeps one decoded frame ready.
+#[cfg(feature = "camera")]
+pub struct CaptureStage {
+    frames: Receiver<Result<FrameBuffer, Error>>,
+    pool: FramePool,
+    width: u32,
+    height: u32,
+    _thread: JoinHandle<()>,
+}
+
+#[cfg(feature = "camera")]
+impl CaptureStage {
+    /// Open the camera on a new thread (it is created there, so it never crosses threads)
+    /// and wait until it reports the resolution it actually delivers.
+    pub fn spawn(index: u32, width: u32, height: u32, fps: u32) -> Result<Self, Error> {
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(u32, u32, FramePool), Error>>();
+        let (frame_tx, frames) = mpsc::sync_channel(STAGE_QUEUE);
+
+        let thread = thread::Builder::new()
+            .name("capture".into())
+            .spawn(move || {
+                let cam = match CameraCapture::new(index, width, height, fps) {
+                    Ok(cam) => cam,
+                    Err(e) => { let _ = ready_tx.send(Err(e)); return; }
+                };
+                let (w, h) = cam.resolution();
+                let pool = FramePool::new(w as usize, h as usize);
+                let _ = ready_tx.send(Ok((w, h, pool.clone())));
+                // Blocks while the processing stage is busy; stops once it hangs up.
+                // A bad frame is passed on (processing skips it); many in a row, or a
+                // fatal error, and the camera is reopened — or counts as lost.
+                let mut cam = Some(cam);
+                let mut failed = 0;
+                while let Some(c) = cam.as_mut() {
+                    let mut frame = pool.get();
+                    let result = match c.next_frame_into(&mut frame) {
+                        Ok(()) => { failed = 0; Ok(frame) }
+                        Err(e) if e.is_recoverable() && failed < MAX_FAILED_FRAMES => {
+                            failed += 1;
+                            log::debug!("camera frame dropped: {e}");
+                            Err(e)
+                        }
+                        Err(e) => {
+                            log::warn!("camera stopped after {failed} failed frames ({e}); reconnecting");
+                            drop(cam.take());                     // release the device first
+                            cam = reconnect(index, width, height, fps, (w, h));
+                            failed = 0;
+                            match cam {
+                                Some(_) => continue,
+                                None => Err(Error::CameraLost(format!("{e}; {RECONNECT_TRIES} reconnect attempts failed"))),
+                            }
+                        }
+                    };
+                    if frame_tx.send(result).is_err() { break; }
+                }
+            })
+            .map_err(|e| Error::CameraInit(format!("Spawn capture thread: {e}")))?;
+
+        let (width, height, pool) = ready_rx
+            .recv()
+            .map_err(|_| Error::CameraInit("Capture thread exited during setup".into()))??;
+        Ok(Self { frames, pool, width, height, _thread: thread })
+    }
+}
+
+/// Reopen camera `index` after it stopped. Only a camera delivering the old `size`
+/// counts: every buffer downstream is sized for it.
+#[cfg(feature = "camera")]
+fn reconnect(index: u32, width: u32, height: u32, fps: u32, size: (u32, u32)) -> Option<CameraCapture> {
+    for attempt in 1..=RECONNECT_TRIES {
+        thread::sleep(RECONNECT_DELAY);
+        match CameraCapture::new(index, width, height, fps) {
+            Ok(cam) if cam.resolution() == size => {
+                log::info!("camera {index} reconnected (attempt {attempt})");
+                return Some(cam);
+            }
+            Ok(cam) => {
+                let (w, h) = cam.resolution();
+                log::warn!("camera {index} came back at {w}x{h} instead of {}x{}", size.0, size.1);
+            }
+            Err(e) => log::warn!("reconnect attempt {attempt}/{RECONNECT_TRIES}: {e}"),
+        }
+    }
+    None
+}
+
+#[cfg(feature = "camera")]
+impl FrameSource for CaptureStage {
+    /// The next camera frame (blocks until the capture thread has one).
+    fn next_frame(&self) -> Result<FrameBuffer, Error> {
+        self.frames
+            .recv()
+            .map_err(|_| Error::CameraLost("Capture thread stopped".into()))?
+    }
+
+    /// Return a finished camera frame so the capture thread can decode into it again.
+    fn recycle(&self, frame: FrameBuffer) {
+        self.pool.put(frame);
+    }
+
+    /// Report the actual resolution the camera is delivering.
+    fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+/// Processing side of the present stage: send finished screens, get old ones back.
+#[cfg(feature = "window")]
+pub struct PresentLink {
+    pub frames: SyncSender<FrameBuffer>,
+    pub recycled: Receiver<FrameBuffer>,
+    hide_cursor: Arc<AtomicBool>,
+}
+
+/// Window side of the present stage.
+#[cfg(feature = "window")]
+pub struct PresentEnd {
+    pub frames: Receiver<FrameBuffer>,
+    pub recycle: Sender<FrameBuffer>,
+    hide_cursor: Arc<AtomicBool>,
+}
+
+/// Bounded channel for finished screens plus the return path for their buffers.
+#[cfg(feature = "window")]
+pub fn present_channel() -> (PresentLink, PresentEnd) {
+    let (frame_tx, frame_rx) = mpsc::sync_channel(STAGE_QUEUE);
+    let (recycle_tx, recycle_rx) = mpsc::channel();
+    let hide_cursor = Arc::new(AtomicBool::new(false));
+    (
+        PresentLink { frames: frame_tx, recycled: recycle_rx, hide_cursor: Arc::clone(&hide_cursor) },
+        PresentEnd { frames: frame_rx, recycle: recycle_tx, hide_cursor },
+    )
+}
+
+#[cfg(feature = "window")]
+impl PresentLink {
+    /// Hand `screen` to the window and swap in a recycled buffer (or a fresh one).
+    /// Returns false once the window side has gone away (time to stop).
+    pub fn send(&self, screen: &mut FrameBuffer) -> bool {
+        let next = self.recycled.try_recv().unwrap_or_else(|_| FrameBuffer {
+            width: screen.width,
+            height: screen.height,
+            pixels: vec![0u32; screen.pixels.len()],
+        });
+        self.frames.send(std::mem::replace(screen, next)).is_ok()
+    }
+
+    /// Ask the window to hide (true) or show the OS mouse cursor over it.
+    pub fn hide_os_cursor(&self, hide: bool) {
+        self.hide_cursor.store(hide, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "window")]
+impl PresentEnd {
+    /// Whether the processing stage wants the OS mouse cursor hidden.
+    pub fn os_cursor_hidden(&self) -> bool {
+        self.hide_cursor.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,266 @@
+// Pixel formats other than our 0x00RRGGBB, for handing frames to (or taking them from)
+// the outside world: encoders, virtual-camera sinks, other window backends.
+// Visual expectation: none on screen — whoever receives a `PixelBuffer` sees exactly
+// the picture in the window, in the byte layout it asked for (RGBA for a GPU texture,
+// BGRA for a desktop surface, I420 / NV12 for a video encoder).
+//
+// Packed formats convert pixel for pixel. The YUV 4:2:0 formats use the same integer
+// BT.601 video-range maths as the camera's YUYV decode (convert.rs), so a frame that
+// goes out as I420 and comes back differs only by chroma subsampling and rounding.
+// Both directions split the work into row bands for rayon.
+
+use crate::error::Error;
+use crate::types::FrameBuffer;
+use rayon::prelude::*;
+
+/// Pixels per parallel work item (about 32 rows at 640 px; same as convert.rs).
+const BAND_PIXELS: usize = 640 * 32;
+
+/// Byte layouts a `PixelBuffer` can hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// R,G,B per pixel (image files, most encoders' RGB input).
+    Rgb24,
+    /// R,G,B,A per pixel, A = 255 (GPU textures, web canvases).
+    Rgba8,
+    /// B,G,R,A per pixel, A = 255 (Windows / macOS surfaces, most virtual cameras).
+    Bgra8,
+    /// Planar YUV 4:2:0: a full-size Y plane, then a U and a V plane at half width and height.
+    I420,
+    /// Semi-planar YUV 4:2:0: a full-size Y plane, then one half-size plane of U,V pairs.
+    Nv12,
+}
+
+impl PixelFormat {
+    /// Short name for messages and the HUD.
+    pub fn label(self) -> &'static str {
+        match self {
+            PixelFormat::Rgb24 => "RGB24",
+            PixelFormat::Rgba8 => "RGBA8",
+            PixelFormat::Bgra8 => "BGRA8",
+            PixelFormat::I420 => "I420",
+            PixelFormat::Nv12 => "NV12",
+        }
+    }
+
+    /// Bytes per pixel of a packed format; None for the planar ones.
+    pub fn bytes_per_pixel(self) -> Option<usize> {
+        match self {
+            PixelFormat::Rgb24 => Some(3),
+            PixelFormat::Rgba8 | PixelFormat::Bgra8 => Some(4),
+            PixelFormat::I420 | PixelFormat::Nv12 => None,
+        }
+    }
+
+    /// Bytes a tightly packed `width`×`height` image takes (odd sizes round the chroma up).
+    pub fn buffer_len(self, width: usize, height: usize) -> usize {
+        match self.bytes_per_pixel() {
+            Some(bpp) => width * height * bpp,
+            None => width * height + 2 * chroma_size(width, height),
+        }
+    }
+}
+
+/// Width × height of a 4:2:0 chroma plane (one sample per 2×2 block, edges rounded up).
+fn chroma_size(width: usize, height: usize) -> usize {
+    width.div_ceil(2) * height.div_ceil(2)
+}
+
+/// A frame in any `PixelFormat`, as one tightly packed byte buffer (planes back to back,
+/// no row padding). Converts to and from `FrameBuffer`; reuse one per stream so steady
+/// frames allocate nothing.
+#[derive(Clone, Debug)]
+pub struct PixelBuffer {
+    pub width: usize,
+    pub height: usize,
+    pub format: PixelFormat,
+    pub data: Vec<u8>, // format.buffer_len(width, height) bytes
+}
+
+impl PixelBuffer {
+    /// All-zero buffer of this size and format.
+    pub fn new(width: usize, height: usize, format: PixelFormat) -> Self {
+        Self { width, height, format, data: vec![0; format.buffer_len(width, height)] }
+    }
+
+    /// `frame` converted to `format`.
+    pub fn from_frame(frame: &FrameBuffer, format: PixelFormat) -> Self {
+        let mut out = Self::new(frame.width, frame.height, format);
+        encode(frame, format, &mut out.data);
+        out
+    }
+
+    /// Overwrite this buffer with `frame` (same size required; the format stays).
+    pub fn write_frame(&mut self, frame: &FrameBuffer) -> Result<(), Error> {
+        self.check_size(frame)?;
+        encode(frame, self.format, &mut self.data);
+        Ok(())
+    }
+
+    /// Decode this buffer into `frame` (same size required).
+    pub fn read_frame(&self, frame: &mut FrameBuffer) -> Result<(), Error> {
+        self.check_size(frame)?;
+        decode(&self.data, self.format, frame);
+        Ok(())
+    }
+
+    /// Decode into a new `FrameBuffer`.
+    pub fn to_frame(&self) -> Result<FrameBuffer, Error> {
+        let mut frame = FrameBuffer { width: self.width, height: self.height, pixels: vec![0; self.width * self.height] };
+        self.read_frame(&mut frame)?;
+        Ok(frame)
+    }
+
+    /// The planes in order: one for packed formats, Y,U,V for I420, Y,UV for NV12.
+    pub fn planes(&self) -> Vec<&[u8]> {
+        let luma = self.width * self.height;
+        let chroma = chroma_size(self.width, self.height);
+        match self.format {
+            PixelFormat::I420 => {
+                let (y, uv) = self.data.split_at(luma);
+                let (u, v) = uv.split_at(chroma);
+                vec![y, u, v]
+            }
+            PixelFormat::Nv12 => {
+                let (y, uv) = self.data.split_at(luma);
+                vec![y, uv]
+            }
+            _ => vec![&self.data],
+        }
+    }
+
+    fn check_size(&self, frame: &FrameBuffer) -> Result<(), Error> {
+        if frame.width != self.width || frame.height != self.height || frame.pixels.len() != self.width * self.height {
+            return Err(Error::CameraFrame(format!(
+                "{} buffer: frame is {}x{}, buffer is {}x{}",
+                self.format.label(), frame.width, frame.height, self.width, self.height
+            )));
+        }
+        if self.data.len() != self.format.buffer_len(self.width, self.height) {
+            return Err(Error::CameraFrame(format!("{} buffer: {} bytes, expected {}",
+                self.format.label(), self.data.len(), self.format.buffer_len(self.width, self.height))));
+        }
+        Ok(())
+    }
+}
+
+/* ---------------------- FrameBuffer → bytes ---------------------- */
+
+fn encode(frame: &FrameBuffer, format: PixelFormat, data: &mut [u8]) {
+    let (w, h) = (frame.width, frame.height);
+    if w == 0 || h == 0 {
+        return;
+    }
+    match format {
+        PixelFormat::Rgb24 => pack(&frame.pixels, data, 3, |p, o| o.copy_from_slice(&[(p >> 16) as u8, (p >> 8) as u8, p as u8])),
+        PixelFormat::Rgba8 => pack(&frame.pixels, data, 4, |p, o| o.copy_from_slice(&[(p >> 16) as u8, (p >> 8) as u8, p as u8, 255])),
+        PixelFormat::Bgra8 => pack(&frame.pixels, data, 4, |p, o| o.copy_from_slice(&[p as u8, (p >> 8) as u8, (p >> 16) as u8, 255])),
+        PixelFormat::I420 | PixelFormat::Nv12 => {
+            let (y, chroma) = data.split_at_mut(w * h);
+            y.par_chunks_mut(BAND_PIXELS)
+                .zip(frame.pixels.par_chunks(BAND_PIXELS))
+                .for_each(|(d, s)| d.iter_mut().zip(s).for_each(|(o, &p)| *o = luma(p)));
+            let cw = w.div_ceil(2);
+            if format == PixelFormat::I420 {
+                let (u, v) = chroma.split_at_mut(chroma_size(w, h));
+                u.par_chunks_mut(cw).zip(v.par_chunks_mut(cw)).enumerate().for_each(|(cy, (ur, vr))| {
+                    for cx in 0..cw {
+                        (ur[cx], vr[cx]) = block_chroma(frame, cx, cy);
+                    }
+                });
+            } else {
+                chroma.par_chunks_mut(cw * 2).enumerate().for_each(|(cy, row)| {
+                    for (cx, uv) in row.chunks_exact_mut(2).enumerate() {
+                        (uv[0], uv[1]) = block_chroma(frame, cx, cy);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Packed formats: `put` writes one pixel's `bpp` bytes.
+fn pack(pixels: &[u32], data: &mut [u8], bpp: usize, put: impl Fn(u32, &mut [u8]) + Sync) {
+    data.par_chunks_mut(BAND_PIXELS * bpp)
+        .zip(pixels.par_chunks(BAND_PIXELS))
+        .for_each(|(d, s)| d.chunks_exact_mut(bpp).zip(s).for_each(|(o, &p)| put(p, o)));
+}
+
+#[inline]
+fn channels(p: u32) -> (i32, i32, i32) {
+    (((p >> 16) & 0xFF) as i32, ((p >> 8) & 0xFF) as i32, (p & 0xFF) as i32)
+}
+
+/// BT.601 video-range Y (16..235) of one pixel.
+#[inline]
+fn luma(p: u32) -> u8 {
+    let (r, g, b) = channels(p);
+    (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8
+}
+
+/// U,V of the 2×2 block (cx, cy) in chroma samples: the block's average colour
+/// (blocks on an odd right / bottom edge use the pixels they have).
+fn block_chroma(frame: &FrameBuffer, cx: usize, cy: usize) -> (u8, u8) {
+    let (x0, y0) = (cx * 2, cy * 2);
+    let (x1, y1) = ((x0 + 1).min(frame.width - 1), (y0 + 1).min(frame.height - 1));
+    let (mut r, mut g, mut b) = (0, 0, 0);
+    for (x, y) in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)] {
+        let (pr, pg, pb) = channels(frame.pixels[y * frame.width + x]);
+        (r, g, b) = (r + pr, g + pg, b + pb);
+    }
+    let (r, g, b) = ((r + 2) / 4, (g + 2) / 4, (b + 2) / 4);
+    let u = ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128;
+    let v = ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128;
+    (u.clamp(0, 255) as u8, v.clamp(0, 255) as u8)
+}
+
+/* ---------------------- bytes → FrameBuffer ---------------------- */
+
+fn decode(data: &[u8], format: PixelFormat, frame: &mut FrameBuffer) {
+    let (w, h) = (frame.width, frame.height);
+    if w == 0 || h == 0 {
+        return;
+    }
+    match format {
+        PixelFormat::Rgb24 => unpack(data, &mut frame.pixels, 3, |c| rgb(c[0], c[1], c[2])),
+        PixelFormat::Rgba8 => unpack(data, &mut frame.pixels, 4, |c| rgb(c[0], c[1], c[2])),
+        PixelFormat::Bgra8 => unpack(data, &mut frame.pixels, 4, |c| rgb(c[2], c[1], c[0])),
+        PixelFormat::I420 | PixelFormat::Nv12 => {
+            let (y, chroma) = data.split_at(w * h);
+            let cw = w.div_ceil(2);
+            let (u, v) = chroma.split_at(chroma_size(w, h));
+            frame.pixels.par_chunks_mut(w).enumerate().for_each(|(row, out)| {
+                let c = (row / 2) * cw;
+                for (x, o) in out.iter_mut().enumerate() {
+                    let (cu, cv) = match format {
+                        PixelFormat::I420 => (u[c + x / 2], v[c + x / 2]),
+                        _ => (chroma[(c + x / 2) * 2], chroma[(c + x / 2) * 2 + 1]),
+                    };
+                    *o = yuv(y[row * w + x], cu, cv);
+                }
+            });
+        }
+    }
+}
+
+/// Packed formats: `get` reads one pixel from its `bpp` bytes.
+fn unpack(data: &[u8], pixels: &mut [u32], bpp: usize, get: impl Fn(&[u8]) -> u32 + Sync) {
+    pixels.par_chunks_mut(BAND_PIXELS)
+        .zip(data.par_chunks(BAND_PIXELS * bpp))
+        .for_each(|(d, s)| d.iter_mut().zip(s.chunks_exact(bpp)).for_each(|(p, c)| *p = get(c)));
+}
+
+#[inline]
+fn rgb(r: u8, g: u8, b: u8) -> u32 {
+    ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+}
+
+/// BT.601 video-range YUV → 0x00RRGGBB (the formula of convert.rs's YUYV decode).
+#[inline]
+fn yuv(y: u8, u: u8, v: u8) -> u32 {
+    let (c, d, e) = (298 * (y as i32 - 16), u as i32 - 128, v as i32 - 128);
+    let r = ((c + 409 * e + 128) >> 8).clamp(0, 255) as u8;
+    let g = ((c - 100 * d - 208 * e + 128) >> 8).clamp(0, 255) as u8;
+    let b = ((c + 516 * d + 128) >> 8).clamp(0, 255) as u8;
+    rgb(r, g, b)
+}
@@ -0,0 +1,94 @@
+// Post effects applied to the composited frame (after blending, before FX/HUD).
+// What you SEE on screen:
+// - Vignette: corners gently darken, pulling the eye to the centre.
+// - Film grain: fine flickering noise over the image, like old film stock.
+
+use crate::fx::Rng32;
+use crate::types::FrameBuffer;
+
+/// Multiply a packed 0x00RRGGBB pixel by factor/256 (factor ≤ 256).
+#[inline]
+fn scale_rgb(p: u32, factor: u32) -> u32 {
+    let r = (((p >> 16) & 0xFF) * factor) >> 8;
+    let g = (((p >> 8) & 0xFF) * factor) >> 8;
+    let b = ((p & 0xFF) * factor) >> 8;
+    (r << 16) | (g << 8) | b
+}
+
+/* -------------------- vignette (precomputed radial falloff) -------------------- */
+
+/// Darkens the frame towards the corners.
+/// The per-pixel falloff is built once for the frame size, so applying it is one
+/// integer multiply per channel.
+pub struct Vignette {
+    width: usize,
+    height: usize,
+    factors: Vec<u16>, // 256 = untouched, lower = darker; one per pixel
+}
+
+impl Vignette {
+    /// Build the falloff for a `width`×`height` frame.
+    /// `strength` 0..1: how dark the very corners get (0.5 = half brightness).
+    pub fn new(width: usize, height: usize, strength: f32) -> Self {
+        let (cx, cy) = (width as f32 * 0.5, height as f32 * 0.5);
+        let max_r2 = cx * cx + cy * cy; // distance² to a corner
+        let strength = strength.clamp(0.0, 1.0);
+
+        let mut factors = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                let t = ((dx * dx + dy * dy) / max_r2).clamp(0.0, 1.0); // 0 centre → 1 corner
+                // Smooth start so the middle stays fully bright; darkening ramps up outward.
+                let fall = t * t * (3.0 - 2.0 * t);
+                factors.push(((1.0 - strength * fall) * 256.0).round() as u16);
+            }
+        }
+        Self { width, height, factors }
+    }
+
+    /// Darken `fb` in place. Visual: soft dark edges; skipped if sizes disagree.
+    pub fn apply(&self, fb: &mut FrameBuffer) {
+        if fb.width != self.width || fb.height != self.height { return; }
+        for (p, &f) in fb.pixels.iter_mut().zip(&self.factors) {
+            *p = scale_rgb(*p, f as u32);
+        }
+    }
+}
+
+/* -------------------- film grain (animated noise) -------------------- */
+
+/// Monochrome grain that changes every frame.
+pub struct Grain {
+    rng: Rng32,
+    amount: i32, // max ± brightness change per pixel (0..255)
+}
+
+impl Grain {
+    /// `amount` is the peak noise in 8-bit steps (8–16 looks like film; 30+ looks like a bad TV).
+    pub fn new(amount: i32) -> Self {
+        Self { rng: Rng32::from_seed(0x5EED_F11E), amount: amount.clamp(0, 255) }
+    }
+
+    /// Peak noise in 8-bit steps.
+    pub fn amount(&self) -> i32 {
+        self.amount
+    }
+
+    /// Add fresh grain to `fb` in place. Visual: fine crawling noise, same on all channels.
+    pub fn apply(&mut self, fb: &mut FrameBuffer) {
+        if self.amount == 0 { return; }
+        let span = (2 * self.amount + 1) as u32;
+        for p in &mut fb.pixels {
+            // Sum of two uniforms ≈ triangular distribution: mostly small, occasionally strong.
+            let a = (self.rng.next_u32() >> 8) % span;
+            let b = (self.rng.next_u32() >> 8) % span;
+            let n = (a + b) as i32 / 2 - self.amount;
+
+            let r = (((*p >> 16) & 0xFF) as i32 + n).clamp(0, 255) as u32;
+            let g = (((*p >> 8) & 0xFF) as i32 + n).clamp(0, 255) as u32;
+            let bl = ((*p & 0xFF) as i32 + n).clamp(0, 255) as u32;
+            *p = (r << 16) | (g << 8) | bl;
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// Per-stage profiler + on-screen overlay.
+// Visual expectation: press P and a small table appears under the HUD with how
+// many milliseconds each stage of the frame took (smoothed), plus a rolling bar
+// graph of total frame time. Bars above the white budget line are red: those
+// frames were too slow for 30 FPS. Handy to see exactly which stage is slow.
+// Without P, a compact line graph of the same frame times stays in a corner (T hides
+// it): a single slow frame shows up as a spike instead of vanishing into the FPS
+// average.
+//
+// How: the processing loop calls `lap(stage)` after each stage; the time since
+// the previous lap is charged to that stage.
+
+use crate::draw::{draw_line_aa, draw_text_5x7, fill_rect, fill_rect_blend};
+use crate::theme::Theme;
+use crate::types::FrameBuffer;
+use std::time::{Duration, Instant};
+
+/// The parts of one frame, in the order the loop runs them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stage {
+    Capture, // waiting for the camera thread
+    Prep,    // input, colour correction, background + masks
+    Blur,    // blurred sink
+    Effect,  // the brush effect's sink
+    Blend,   // base image + sink under the mask + polish
+    Fx,      // sparkles / bolts
+    Hud,     // crosshair + text (+ this overlay)
+    Present, // handing the frame to the window thread
+}
+
+impl Stage {
+    pub const ALL: [Stage; 8] = [
+        Stage::Capture, Stage::Prep, Stage::Blur, Stage::Effect,
+        Stage::Blend, Stage::Fx, Stage::Hud, Stage::Present,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Capture => "CAPTURE",
+            Stage::Prep => "PREP",
+            Stage::Blur => "BLUR",
+            Stage::Effect => "EFFECT",
+            Stage::Blend => "BLEND",
+            Stage::Fx => "FX",
+            Stage::Hud => "HUD",
+            Stage::Present => "PRESENT",
+        }
+    }
+}
+
+/// Frames kept for the rolling graph (2 px per bar at scale 1).
+const HISTORY: usize = 120;
+
+/// Smoothing factor for the per-stage averages.
+const EMA_ALPHA: f32 = 0.1;
+
+pub struct Profiler {
+    checkpoint: Instant,
+    frame: [f32; Stage::ALL.len()],  // this frame, ms
+    last: [f32; Stage::ALL.len()],   // the frame closed last, ms (for --metrics)
+    avg: [f32; Stage::ALL.len()],    // smoothed, ms
+    history: [f32; HISTORY],         // total ms per frame (ring buffer)
+    next: usize,
+    totals: [f64; Stage::ALL.len()], // ms since start (for --bench)
+    frames: u64,
+    budget_ms: f32,                  // frame-time budget (red above it)
+}
+
+impl Profiler {
+    /// `budget` is the frame time to stay under (see `governor::frame_budget`).
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            checkpoint: Instant::now(),
+            frame: [0.0; Stage::ALL.len()],
+            last: [0.0; Stage::ALL.len()],
+            avg: [0.0; Stage::ALL.len()],
+            history: [0.0; HISTORY],
+            next: 0,
+            totals: [0.0; Stage::ALL.len()],
+            frames: 0,
+            budget_ms: budget.as_secs_f32() * 1000.0,
+        }
+    }
+
+    /// Charge the time since the previous lap to `stage`.
+    pub fn lap(&mut self, stage: Stage) {
+        let now = Instant::now();
+        self.frame[stage as usize] += (now - self.checkpoint).as_secs_f32() * 1000.0;
+        self.checkpoint = now;
+    }
+
+    /// Close the frame: update the averages and push the total into the graph.
+    pub fn end_frame(&mut self) {
+        self.last = self.frame;
+        let mut total = 0.0;
+        for ((avg, sum), ms) in self.avg.iter_mut().zip(self.totals.iter_mut()).zip(self.frame.iter_mut()) {
+            *avg += (*ms - *avg) * EMA_ALPHA;
+            *sum += *ms as f64;
+            total += *ms;
+            *ms = 0.0;
+        }
+        self.frames += 1;
+        self.history[self.next] = total;
+        self.next = (self.next + 1) % HISTORY;
+    }
+
+    /// Smoothed time of one stage in milliseconds.
+    pub fn stage_ms(&self, stage: Stage) -> f32 {
+        self.avg[stage as usize]
+    }
+
+    /// Time of one stage in the frame closed last, in milliseconds (unsmoothed).
+    pub fn last_ms(&self, stage: Stage) -> f32 {
+        self.last[stage as usize]
+    }
+
+    /// Total time of one stage since the profiler was created, in milliseconds.
+    pub fn total_ms(&self, stage: Stage) -> f64 {
+        self.totals[stage as usize]
+    }
+
+    /// Frames closed with `end_frame` so far.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Longest frame in the history, in milliseconds.
+    fn peak_ms(&self) -> f32 {
+        self.history.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Width and height in pixels that `draw_graph` covers at `scale` (for HUD placement).
+    pub fn graph_size(scale: i32) -> (i32, i32) {
+        (HISTORY as i32 * scale, 40 * scale)
+    }
+
+    /// Compact frame-time graph with the top-left corner at (x, y).
+    /// Visual: a line of the last 120 frame times over a dark see-through box, the budget
+    /// as a faint grey line, and "FRAME 12.3 MAX 48.0 MS" above. The vertical scale fits
+    /// the worst frame (at least 2× the budget), so a spike always stays on screen.
+    pub fn draw_graph(&self, fb: &mut FrameBuffer, x: i32, y: i32, scale: i32, color: u32, theme: &Theme) {
+        let (w, h) = Self::graph_size(scale);
+        let budget_ms = self.budget_ms;
+        let latest = self.history[(self.next + HISTORY - 1) % HISTORY];
+        let peak = self.peak_ms();
+        fill_rect_blend(fb, x - 4 * scale, y - 3 * scale, w + 8 * scale, h + 6 * scale, theme.backdrop, 140);
+        draw_text_5x7(fb, x, y, &format!("FRAME {latest:.1} MAX {peak:.1} MS"), color, scale);
+
+        let gy = y + 10 * scale;
+        let gh = (h - 10 * scale) as f32;
+        let top_ms = peak.max(2.0 * budget_ms);
+        let to_y = |ms: f32| gy as f32 + gh - 1.0 - (ms / top_ms).clamp(0.0, 1.0) * (gh - 1.0);
+        let by = to_y(budget_ms);
+        draw_line_aa(fb, x as f32, by, (x + w - 1) as f32, by, 0x00_80_80_80);
+
+        let step = w as f32 / (HISTORY - 1) as f32;
+        let mut prev = (x as f32, to_y(self.history[self.next]));
+        for i in 1..HISTORY {
+            let ms = self.history[(self.next + i) % HISTORY]; // oldest on the left
+            let pt = (x as f32 + i as f32 * step, to_y(ms));
+            let line_color = if ms > budget_ms { theme.warning } else { color };
+            draw_line_aa(fb, prev.0, prev.1, pt.0, pt.1, line_color);
+            prev = pt;
+        }
+    }
+
+    /// Width and height in pixels that `draw_overlay` covers at `scale` (for HUD placement).
+    pub fn overlay_size(scale: i32) -> (i32, i32) {
+        let table_h = Stage::ALL.len() as i32 * 10 * scale;
+        (HISTORY as i32 * 2 * scale, table_h + 4 * scale + 60 * scale)
+    }
+
+    /// Draw the table and graph with the top-left corner at (x, y), `scale` like the HUD text.
+    /// Visual: one line per stage ("BLUR  4.2 ms") in `color`, then the frame-time bars.
+    pub fn draw_overlay(&self, fb: &mut FrameBuffer, x: i32, y: i32, scale: i32, color: u32, theme: &Theme) {
+        // Visual: a see-through dark panel keeps the numbers readable on any background.
+        let table_h = Stage::ALL.len() as i32 * 10 * scale;
+        fill_rect_blend(fb, x - 4 * scale, y - 3 * scale, 100 * scale, table_h + 3 * scale, theme.backdrop, 140);
+        let mut ty = y;
+        for stage in Stage::ALL {
+            let line = format!("{:<8}{:>6.1} ms", stage.label(), self.stage_ms(stage));
+            draw_text_5x7(fb, x, ty, &line, color, scale);
+            ty += 10 * scale;
+        }
+
+        // Graph: 2× the budget fills the box; the budget itself is a white line.
+        let (bw, gh, budget_ms) = (2 * scale, 60 * scale, self.budget_ms);
+        let gy = ty + 4 * scale;
+        fill_rect_blend(fb, x, gy, HISTORY as i32 * bw, gh, 0x00_10_10_10, 180);
+        for i in 0..HISTORY {
+            let ms = self.history[(self.next + i) % HISTORY]; // oldest on the left
+            let h = ((ms / (2.0 * budget_ms)) * gh as f32).clamp(0.0, gh as f32) as i32;
+            let color = if ms > budget_ms { theme.warning } else { theme.good };
+            fill_rect(fb, x + i as i32 * bw, gy + gh - h, bw, h, color);
+        }
+        fill_rect(fb, x, gy + gh / 2, HISTORY as i32 * bw, scale, color);
+    }
+}
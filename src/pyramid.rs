@@ -0,0 +1,136 @@
+// Blur pyramid: approximate very large blur radii cheaply.
+// Visual expectation: at big radii (> ~16 px) the blur looks like a smooth Gaussian
+// haze, but costs about the same as a small blur because most of the work happens
+// on a much smaller copy of the frame.
+//
+// How: halve the frame until the remaining radius is small, blur there (with the
+// caller's blur, so STACK and LIN keep their look), then stretch the result back up with
+// bilinear filtering.
+//
+// The same machinery doubles as the "fast" blur quality: forcing one or two
+// halvings computes any blur at half/quarter resolution.
+
+use crate::error::Error;
+use crate::types::FrameBuffer;
+use crate::vision::{downsample_2x, resize_bilinear};
+
+/// Radii above this go through the pyramid; below, a direct box blur is both fast and sharper.
+pub const PYRAMID_MIN_RADIUS: usize = 16;
+
+/// Blur radius we aim for at the smallest pyramid level.
+const LEVEL_RADIUS: usize = 4;
+
+/// Resolution the BLUR sink is computed at (Q cycles at runtime).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlurQuality {
+    Full,    // every pixel: sharpest small blurs
+    Half,    // ¼ of the pixels: visually identical for radius ≳ 4
+    Quarter, // 1/16 of the pixels: for 720p+ cameras and slow machines
+}
+
+impl BlurQuality {
+    pub fn next(self) -> Self {
+        match self {
+            BlurQuality::Full => BlurQuality::Half,
+            BlurQuality::Half => BlurQuality::Quarter,
+            BlurQuality::Quarter => BlurQuality::Full,
+        }
+    }
+
+    /// How many times the frame is halved before blurring.
+    pub fn depth(self) -> usize {
+        match self {
+            BlurQuality::Full => 0,
+            BlurQuality::Half => 1,
+            BlurQuality::Quarter => 2,
+        }
+    }
+
+    /// Short HUD tag ("" for full quality).
+    pub fn label(self) -> &'static str {
+        match self {
+            BlurQuality::Full => "",
+            BlurQuality::Half => " HALF",
+            BlurQuality::Quarter => " QUARTER",
+        }
+    }
+}
+
+fn blank(width: usize, height: usize) -> FrameBuffer {
+    FrameBuffer { width, height, pixels: vec![0u32; width * height] }
+}
+
+/// Scratch buffers for the pyramid, reused every frame (rebuilt only when size/depth changes).
+pub struct BlurPyramid {
+    levels: Vec<FrameBuffer>, // levels[0] = half size, levels[1] = quarter size, …
+    tmp: FrameBuffer,         // blur scratch at the smallest level
+    small: FrameBuffer,       // blur result at the smallest level
+}
+
+impl BlurPyramid {
+    pub fn new() -> Self {
+        Self { levels: Vec::new(), tmp: blank(0, 0), small: blank(0, 0) }
+    }
+
+    /// Make sure we hold `depth` halvings of a `width`×`height` frame.
+    fn ensure(&mut self, width: usize, height: usize, depth: usize) {
+        let (mut w, mut h) = (width, height);
+        let mut sizes = Vec::with_capacity(depth);
+        for _ in 0..depth {
+            w = w.div_ceil(2);
+            h = h.div_ceil(2);
+            sizes.push((w, h));
+        }
+        let same = self.levels.len() == depth
+            && self.levels.iter().zip(&sizes).all(|(l, &(w, h))| l.width == w && l.height == h);
+        if !same {
+            self.levels = sizes.iter().map(|&(w, h)| blank(w, h)).collect();
+            self.tmp = blank(w, h);
+            self.small = blank(w, h);
+        }
+    }
+
+    /// Blur `src` into `dst` with (approximately) the given radius, halving at least
+    /// `min_depth` times (0 = only as much as the radius needs). `level_blur(src, dst,
+    /// scratch, radius)` blurs the smallest level, e.g. `box_blur_rgb`.
+    /// What you SEE: same look as `level_blur` at that radius, just softer and much cheaper.
+    pub fn blur(
+        &mut self,
+        src: &FrameBuffer,
+        dst: &mut FrameBuffer,
+        radius: usize,
+        min_depth: usize,
+        mut level_blur: impl FnMut(&FrameBuffer, &mut FrameBuffer, &mut FrameBuffer, usize) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        if src.width != dst.width || src.height != dst.height {
+            return Err(Error::CameraFrame("pyramid: size mismatch src↔dst".into()));
+        }
+
+        // Each halving also halves the radius we still need; stop once it is small.
+        let fits = |d: usize| (src.width >> d) > 1 && (src.height >> d) > 1;
+        let mut depth = 0;
+        while ((radius >> depth) > LEVEL_RADIUS || depth < min_depth) && fits(depth + 1) {
+            depth += 1;
+        }
+        if depth == 0 {
+            let mut tmp = blank(src.width, src.height);
+            return level_blur(src, dst, &mut tmp, radius);
+        }
+        self.ensure(src.width, src.height, depth);
+
+        // 1) Down: src → ½ → ¼ → … (each 2x2 average is itself a little blur).
+        downsample_2x(src, &mut self.levels[0])?;
+        for i in 1..depth {
+            let (done, rest) = self.levels.split_at_mut(i);
+            downsample_2x(&done[i - 1], &mut rest[0])?;
+        }
+
+        // 2) Blur the tiny image with what is left of the radius.
+        let level_radius = (radius >> depth).max(1);
+        level_blur(&self.levels[depth - 1], &mut self.small, &mut self.tmp, level_radius)?;
+
+        // 3) Up: bilinear straight back to full size (the tent filter smooths box edges).
+        resize_bilinear(&self.small, dst);
+        Ok(())
+    }
+}
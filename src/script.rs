@@ -0,0 +1,162 @@
+// Optional scripting (rhai), built with `cargo run --release --features script`.
+// Visual expectation: `magic-eraser --script pulse.rhai` runs the app as usual, but the
+// script gets a look at every frame and can do what a key, a click or OSC could: the
+// blur breathing in and out, the background recaptured every five minutes, the mask
+// cleared when the room goes dark — without forking the crate.
+//
+//     // pulse.rhai: blur radius follows a slow sine wave; fresh background every 5 min
+//     fn on_frame(frame) {
+//         set_blur_radius(24.0 + 16.0 * sin(frame.t));
+//         if this.recaptured == () || frame.t - this.recaptured > 300.0 {
+//             recapture_background();
+//             this.recaptured = frame.t;
+//         }
+//     }
+//
+// Top-level statements run once at start; `on_frame(frame)` (if defined) runs every
+// frame, with `this` a map that keeps its values between frames. `frame` has
+//     t (seconds since start), frame (count), fps, width, height,
+//     coverage (0..1 of the mask painted), brightness (0..1 mean luma of the camera),
+//     blur_radius, brush_radius (window px), hardness, effect (name), portrait, background.
+// What a script can do (applied like the same key or click, at once, undo and all):
+//     paint(x, y), erase(x, y)        one dab at frame pixel (x, y)
+//     clear_mask(), recapture_background()
+//     set_blur_radius(px), set_brush_radius(px), set_hardness(0..1)
+//     select_effect("HEAL"), set_portrait(true), set_fx(false)
+//     press("next_effect")            any action by its keys.rs name
+//     print(...)                      to the log
+// A script error is logged once and the script stops; the app carries on without it.
+
+use crate::error::Error;
+use crate::events::{Action, EffectRef, Event, EventSender};
+use crate::types::FrameBuffer;
+use rhai::{CallFnOptions, Dynamic, Engine, ImmutableString, Map, Scope, AST};
+use std::path::Path;
+
+/// What `on_frame` sees (the `frame` map).
+pub struct FrameStats<'a> {
+    pub t: f32,
+    pub frame: u64,
+    pub fps: f32,
+    pub width: usize,
+    pub height: usize,
+    pub coverage: f32,
+    pub brightness: f32,
+    pub blur_radius: usize,
+    pub brush_radius: i32,
+    pub hardness: f32,
+    pub effect: &'a str,
+    pub portrait: bool,
+    pub background: bool,
+}
+
+/// A loaded script: its compiled code, globals and `this`.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    this: Dynamic,
+    name: String,
+    has_on_frame: bool,
+    stopped: bool,
+}
+
+impl Script {
+    /// Compile `path`, register the host functions (which send to `events`) and run
+    /// the top-level statements once.
+    pub fn load(path: &Path, events: EventSender) -> Result<Self, Error> {
+        let name = path.display().to_string();
+        let text = std::fs::read_to_string(path).map_err(|e| Error::Io(format!("Read {name}"), e))?;
+        let mut engine = Engine::new();
+        register(&mut engine, events);
+        let ast = engine.compile(&text).map_err(|e| Error::Script(format!("{name}: {e}")))?;
+        let mut scope = Scope::new();
+        engine.run_ast_with_scope(&mut scope, &ast).map_err(|e| Error::Script(format!("{name}: {e}")))?;
+        let has_on_frame = ast.iter_functions().any(|f| f.name == "on_frame" && f.params.len() == 1);
+        log::info!("script {name} loaded{}", if has_on_frame { "" } else { " (no on_frame: ran once)" });
+        Ok(Self { engine, ast, scope, this: Map::new().into(), name, has_on_frame, stopped: false })
+    }
+
+    /// Run `on_frame(frame)`; its events reach the queue before this frame's are drained.
+    pub fn on_frame(&mut self, stats: &FrameStats) {
+        if self.stopped || !self.has_on_frame {
+            return;
+        }
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut self.this);
+        let result = self.engine.call_fn_with_options::<Dynamic>(options, &mut self.scope, &self.ast, "on_frame", (frame_map(stats),));
+        if let Err(e) = result {
+            log::error!("script {}: {e} — script stopped", self.name);
+            self.stopped = true;
+        }
+    }
+}
+
+/// The `frame` map handed to `on_frame`.
+fn frame_map(s: &FrameStats) -> Map {
+    let mut m = Map::new();
+    let mut put = |key: &str, value: Dynamic| { m.insert(key.into(), value); };
+    put("t", Dynamic::from_float(s.t as f64));
+    put("frame", Dynamic::from_int(s.frame as i64));
+    put("fps", Dynamic::from_float(s.fps as f64));
+    put("width", Dynamic::from_int(s.width as i64));
+    put("height", Dynamic::from_int(s.height as i64));
+    put("coverage", Dynamic::from_float(s.coverage as f64));
+    put("brightness", Dynamic::from_float(s.brightness as f64));
+    put("blur_radius", Dynamic::from_int(s.blur_radius as i64));
+    put("brush_radius", Dynamic::from_int(s.brush_radius as i64));
+    put("hardness", Dynamic::from_float(s.hardness as f64));
+    put("effect", s.effect.into());
+    put("portrait", s.portrait.into());
+    put("background", s.background.into());
+    m
+}
+
+/// A number argument, whether the script wrote `12` or `12.0`.
+fn number(value: Dynamic) -> f32 {
+    value.as_float().or_else(|_| value.as_int().map(|i| i as f64)).unwrap_or(0.0) as f32
+}
+
+/// The functions scripts can call. Each one queues the event a key or click would.
+fn register(engine: &mut Engine, events: EventSender) {
+    engine.on_print(|text| log::info!(target: "script", "{text}"));
+    let send = move |event: Event| { events.send(event); };
+    let s = send.clone();
+    engine.register_fn("paint", move |x: i64, y: i64| {
+        s(Event::Paint { from: (x as i32, y as i32), to: (x as i32, y as i32), subtract: false, start: true });
+    });
+    let s = send.clone();
+    engine.register_fn("erase", move |x: i64, y: i64| {
+        s(Event::Paint { from: (x as i32, y as i32), to: (x as i32, y as i32), subtract: true, start: true });
+    });
+    let s = send.clone();
+    engine.register_fn("clear_mask", move || s(Event::Press(Action::Clear)));
+    let s = send.clone();
+    engine.register_fn("recapture_background", move || s(Event::Press(Action::CaptureBackground)));
+    let s = send.clone();
+    engine.register_fn("set_blur_radius", move |px: Dynamic| s(Event::SetBlurRadius(number(px))));
+    let s = send.clone();
+    engine.register_fn("set_brush_radius", move |px: Dynamic| s(Event::SetBrushRadius(number(px))));
+    let s = send.clone();
+    engine.register_fn("set_hardness", move |h: Dynamic| s(Event::SetBrushHardness(number(h))));
+    let s = send.clone();
+    engine.register_fn("select_effect", move |name: ImmutableString| s(Event::SelectEffect(EffectRef::Name(name.to_string()))));
+    let s = send.clone();
+    engine.register_fn("set_portrait", move |on: bool| s(Event::SetPortrait(on)));
+    let s = send.clone();
+    engine.register_fn("set_fx", move |on: bool| s(Event::SetFx(on)));
+    engine.register_fn("press", move |name: ImmutableString| match Action::from_binding(&name) {
+        Some(action) => send(Event::Press(action)),
+        None => log::warn!(target: "script", "press(\"{name}\"): no such action (see keys.rs)"),
+    });
+}
+
+/// Mean brightness (0..1) of `frame`, from every 7th pixel — plenty for a script's "is it dark?".
+pub fn brightness(frame: &FrameBuffer) -> f32 {
+    let (mut sum, mut n) = (0u64, 0u64);
+    for &p in frame.pixels.iter().step_by(7) {
+        let (r, g, b) = ((p >> 16) & 0xFF, (p >> 8) & 0xFF, p & 0xFF);
+        sum += ((77 * r + 150 * g + 29 * b) >> 8) as u64;
+        n += 1;
+    }
+    sum as f32 / (255.0 * n.max(1) as f32)
+}
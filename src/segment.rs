@@ -0,0 +1,135 @@
+// Subject segmentation against a captured background.
+// Visual expectation: after you capture the empty scene (R), anything that differs
+// from it — you, your hands, a cup you put down — lights up as "foreground" (α≈1),
+// the unchanged room stays "background" (α≈0). Edges are softened so effects
+// built on this mask don't show a hard cut-out line.
+
+use crate::error::Error;
+use crate::types::{FrameBuffer, Mask, Rect};
+
+/// Colour difference (0..255, largest channel) below which a pixel is surely background.
+pub const FG_LO: i32 = 18;
+/// Difference above which a pixel is surely foreground.
+pub const FG_HI: i32 = 48;
+
+/// Per-pixel foreground likelihood from |live − background|, written into `out` (0..255).
+/// What you SEE (if visualized): a white silhouette of whatever is new in the scene.
+pub fn foreground_mask(live: &FrameBuffer, bg: &FrameBuffer, out: &mut Mask) -> Result<(), Error> {
+    if live.width != bg.width || live.height != bg.height {
+        return Err(Error::CameraFrame("foreground_mask: size mismatch live↔bg".into()));
+    }
+    if out.width != live.width || out.height != live.height {
+        return Err(Error::CameraFrame("foreground_mask: mask dimension mismatch".into()));
+    }
+
+    let span = FG_HI - FG_LO;
+    for ((a, &p), &q) in out.alpha.iter_mut().zip(&live.pixels).zip(&bg.pixels) {
+        let dr = (((p >> 16) & 0xFF) as i32 - ((q >> 16) & 0xFF) as i32).abs();
+        let dg = (((p >> 8) & 0xFF) as i32 - ((q >> 8) & 0xFF) as i32).abs();
+        let db = ((p & 0xFF) as i32 - (q & 0xFF) as i32).abs();
+        let d = dr.max(dg).max(db);
+        *a = ((d - FG_LO) * 255 / span).clamp(0, 255) as u8;
+    }
+    out.bounds = Some(Rect::full(out.width, out.height));
+    Ok(())
+}
+
+/// Box-blur the mask in place (separable, edges extended), using `scratch` as the row buffer.
+/// Visual: speckles shrink away and the silhouette gets a soft feathered border.
+pub fn soften_mask(mask: &mut Mask, scratch: &mut Vec<u8>, radius: usize) {
+    let (w, h) = (mask.width, mask.height);
+    if w == 0 || h == 0 || radius == 0 { return; }
+    let r = radius as isize;
+    let win = (2 * radius + 1) as u32;
+    scratch.resize(w * h, 0);
+
+    // Horizontal: mask → scratch (integer running sums, rounded average)
+    for y in 0..h {
+        let row = &mask.alpha[y * w..(y + 1) * w];
+        let at = |i: isize| row[i.clamp(0, w as isize - 1) as usize] as u32;
+        let mut sum: u32 = (-r..=r).map(at).sum();
+        for x in 0..w {
+            scratch[y * w + x] = ((sum + win / 2) / win) as u8;
+            sum = sum + at(x as isize + r + 1) - at(x as isize - r);
+        }
+    }
+    // Vertical: scratch → mask
+    for x in 0..w {
+        let at = |i: isize| scratch[i.clamp(0, h as isize - 1) as usize * w + x] as u32;
+        let mut sum: u32 = (-r..=r).map(at).sum();
+        for y in 0..h {
+            mask.alpha[y * w + x] = ((sum + win / 2) / win) as u8;
+            sum = sum + at(y as isize + r + 1) - at(y as isize - r);
+        }
+    }
+    mask.bounds = mask.bounds.map(|b| b.expand(radius, w, h)); // feathering spreads outwards
+}
+
+/// Combine two masks into `dst` by taking the larger alpha per pixel.
+/// Visual: both painted areas and automatic areas get the effect.
+#[cfg(feature = "window")]
+pub fn max_masks(a: &Mask, b: &Mask, dst: &mut Mask) {
+    for ((d, &x), &y) in dst.alpha.iter_mut().zip(&a.alpha).zip(&b.alpha) {
+        *d = x.max(y);
+    }
+    dst.bounds = match (a.bounds, b.bounds) {
+        (Some(p), Some(q)) => Some(p.union(q)),
+        (p, q) => p.or(q),
+    };
+}
+
+/* -------------------- frame-difference debug view -------------------- */
+
+/// What the D debug view compares the live frame against.
+#[cfg(feature = "window")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiffView {
+    Off,
+    Background, // |live − captured background|: shows what portrait mode will treat as "you"
+    Previous,   // |live − previous frame|: shows motion and camera noise
+}
+
+#[cfg(feature = "window")]
+impl DiffView {
+    pub fn next(self) -> Self {
+        match self {
+            DiffView::Off => DiffView::Background,
+            DiffView::Background => DiffView::Previous,
+            DiffView::Previous => DiffView::Off,
+        }
+    }
+}
+
+/// Map 0..255 to a "heat" colour: black → blue → red → yellow → white.
+#[cfg(feature = "window")]
+#[inline]
+fn heat_color(v: u32) -> u32 {
+    let v = v.min(255);
+    let (r, g, b) = match v {
+        0..=63 => (0, 0, v * 4),                           // black → blue
+        64..=127 => ((v - 64) * 4, 0, 255 - (v - 64) * 4), // blue → red
+        128..=191 => (255, (v - 128) * 4, 0),              // red → yellow
+        _ => (255, 255, (v - 192) * 4),                    // yellow → white
+    };
+    (r << 16) | (g << 8) | b
+}
+
+/// Paint |a − b| (largest channel) into `dst` as a heat map.
+/// Differences below FG_LO are shown dimmed, so the current threshold is visible:
+/// anything clearly blue/red or hotter counts as foreground in portrait mode.
+#[cfg(feature = "window")]
+pub fn diff_heatmap(a: &FrameBuffer, b: &FrameBuffer, dst: &mut FrameBuffer) -> Result<(), Error> {
+    if a.width != b.width || a.height != b.height || a.width != dst.width || a.height != dst.height {
+        return Err(Error::CameraFrame("diff_heatmap: size mismatch".into()));
+    }
+    for ((d, &p), &q) in dst.pixels.iter_mut().zip(&a.pixels).zip(&b.pixels) {
+        let dr = (((p >> 16) & 0xFF) as i32 - ((q >> 16) & 0xFF) as i32).unsigned_abs();
+        let dg = (((p >> 8) & 0xFF) as i32 - ((q >> 8) & 0xFF) as i32).unsigned_abs();
+        let db = ((p & 0xFF) as i32 - (q & 0xFF) as i32).unsigned_abs();
+        let diff = dr.max(dg).max(db);
+        // Stretch ×4 so small differences are visible; halve below-threshold ones.
+        let v = if (diff as i32) < FG_LO { diff * 2 } else { diff * 4 };
+        *d = heat_color(v);
+    }
+    Ok(())
+}
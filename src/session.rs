@@ -0,0 +1,186 @@
+// Sessions: the whole working setup in one file, so a careful mask survives a restart.
+// Visual expectation: F6 (or SAVE SESSION in the S panel) writes `session.toml` with the
+// painted mask and the captured background as PNGs beside it (`session-mask.png`,
+// `session-background.png`); the status bar says SAVED. `magic-eraser --session
+// session.toml` then starts exactly there: same camera, brush, effect, filters and
+// portrait mode, the mask already painted, and portrait working without another R.
+// Every clean exit also writes the same to an autosave slot in the settings folder;
+// `--session autosave` picks up where the last run stopped.
+// While the app runs, a checkpoint of the same goes to a `checkpoint` folder beside it
+// every half minute (when something changed); a clean exit deletes it. If it is still
+// there at the next start, the last run crashed: the window asks whether to restore it
+// (ENTER) or throw it away (BACKSPACE), and a restored mask is one Ctrl+Z away from
+// the blank one.
+//
+//     effect = "HEAL"
+//     portrait = true
+//     mask = "session-mask.png"            # next to this file
+//     background = "session-background.png"
+//
+//     [camera]
+//     index = 1
+//     resolution = "1280x720"
+//     fps = 30
+//
+//     [brush]                              # as in config.toml
+//     blur_radius = 12
+//     size = 30
+//     hardness = 0.5
+//     fx = true
+//
+//     [filters]
+//     live = "median,white-balance,levels,grade,smooth"
+//     post = "vignette,grain"
+//     on = ["levels", "vignette"]          # the toggles (A, U, N, G) that were on
+//     denoise = 1                          # M: 0 off, 1 = 3x3, 2 = 5x5
+//
+// Command-line flags still win over the session for that run; the session wins over
+// config.toml.
+
+use crate::config::{BrushConfig, CameraConfig};
+use crate::error::Error;
+#[cfg(feature = "window")]
+use crate::imageio;
+#[cfg(feature = "window")]
+use crate::types::{FrameBuffer, Mask};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "window")]
+use std::time::Duration;
+
+/// `--session autosave` means the slot written on exit.
+pub const AUTOSAVE: &str = "autosave";
+
+/// Where F6 writes when no --session file was given.
+#[cfg(feature = "window")]
+pub const DEFAULT_PATH: &str = "session.toml";
+
+/// How often the running app may write its checkpoint (only when something changed).
+#[cfg(feature = "window")]
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct Session {
+    pub effect: String,             // brush effect by name (`EffectRegistry::select_by_name`)
+    pub portrait: bool,
+    pub mask: Option<String>,       // PNG next to the session file; none = nothing painted
+    pub background: Option<String>, // PNG of the captured room; none = no capture yet
+    pub camera: CameraConfig,
+    pub brush: BrushConfig,
+    pub filters: SessionFilters,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct SessionFilters {
+    pub live: String,     // the chains, as for --filters / --post
+    pub post: String,
+    pub on: Vec<String>,  // filter names switched on ("levels", "smooth", "vignette", "grain")
+    pub denoise: usize,
+}
+
+impl Session {
+    /// `<platform config dir>/magic-eraser/autosave/session.toml`, if the platform has one.
+    pub fn autosave_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("magic-eraser").join("autosave").join("session.toml"))
+    }
+
+    /// `<platform config dir>/magic-eraser/checkpoint/session.toml`: the running app's
+    /// checkpoint, deleted on a clean exit (so finding it at startup means a crash).
+    #[cfg(feature = "window")]
+    pub fn checkpoint_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("magic-eraser").join("checkpoint").join("session.toml"))
+    }
+
+    /// Delete the checkpoint at `path` with its PNGs (clean exit, or restoring was declined).
+    #[cfg(feature = "window")]
+    pub fn discard_checkpoint(path: &Path) {
+        if let Some(dir) = path.parent().filter(|d| d.exists())
+            && let Err(e) = std::fs::remove_dir_all(dir)
+        {
+            log::warn!("could not delete the checkpoint {}: {e}", dir.display());
+        }
+    }
+
+    /// The file `--session ARG` names (`autosave` → the autosave slot).
+    pub fn resolve(arg: &str) -> Result<PathBuf, Error> {
+        if arg == AUTOSAVE {
+            Self::autosave_path().ok_or_else(|| Error::Config("--session autosave: no settings folder on this platform".into()))
+        } else {
+            Ok(PathBuf::from(arg))
+        }
+    }
+
+    /// Read `path`; `mask` and `background` come back as paths usable from here.
+    pub fn load(path: &Path) -> Result<Session, Error> {
+        let text = std::fs::read_to_string(path).map_err(|e| Error::Io(format!("Read {}", path.display()), e))?;
+        let mut session: Session = toml::from_str(&text).map_err(|e| Error::Config(format!("{}: {e}", path.display())))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let beside = |name: &String| dir.join(name).to_string_lossy().into_owned();
+        session.mask = session.mask.as_ref().map(beside);
+        session.background = session.background.as_ref().map(beside);
+        Ok(session)
+    }
+
+    /// The painted mask, fitted to `width`×`height` (None if the session has none).
+    #[cfg(feature = "window")]
+    pub fn load_mask(&self, width: usize, height: usize) -> Result<Option<Mask>, Error> {
+        self.mask.as_deref().map(|path| imageio::load_mask(path, width, height)).transpose()
+    }
+
+    /// The captured background, fitted to `width`×`height` (None if there was none).
+    #[cfg(feature = "window")]
+    pub fn load_background(&self, width: usize, height: usize) -> Result<Option<FrameBuffer>, Error> {
+        self.background.as_deref().map(|path| imageio::load_image_cover(path, width, height)).transpose()
+    }
+
+    /// Write to `path` (creating its folder), with `mask` and `background` as PNGs
+    /// beside it; returns the path for the status bar.
+    #[cfg(feature = "window")]
+    pub fn save(mut self, path: &Path, mask: Option<&Mask>, background: Option<&FrameBuffer>) -> Result<String, Error> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| Error::Io(format!("Create {}", dir.display()), e))?;
+        }
+        let file = path.to_string_lossy().into_owned();
+        let name = |p: String| Path::new(&p).file_name().map(|n| n.to_string_lossy().into_owned());
+        // Everything is written aside and renamed over once complete, the TOML last, so a
+        // crash mid-write leaves the previous files whole and never a TOML that names a
+        // half-written picture.
+        let aside = |p: &str| format!("{p}.partial");
+        let mut written = Vec::new();
+        self.mask = match mask {
+            Some(m) => {
+                let png = imageio::mask_path(&file);
+                imageio::save_mask(&aside(&png), m)?;
+                written.push(png.clone());
+                name(png)
+            }
+            None => None,
+        };
+        self.background = match background {
+            Some(bg) => {
+                let png = sibling(&file, "background");
+                imageio::save_image(&aside(&png), bg)?;
+                written.push(png.clone());
+                name(png)
+            }
+            None => None,
+        };
+        let text = toml::to_string_pretty(&self).map_err(|e| Error::Encode(format!("Session: {e}")))?;
+        std::fs::write(aside(&file), text).map_err(|e| Error::Io(format!("Write {}", aside(&file)), e))?;
+        written.push(file.clone());
+        for done in &written {
+            std::fs::rename(aside(done), done).map_err(|e| Error::Io(format!("Write {done}"), e))?;
+        }
+        Ok(file)
+    }
+}
+
+/// `session.toml` → `session-<what>.png`, in the same folder.
+#[cfg(feature = "window")]
+fn sibling(file: &str, what: &str) -> String {
+    let path = Path::new(file);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("session");
+    path.with_file_name(format!("{stem}-{what}.png")).to_string_lossy().into_owned()
+}
@@ -0,0 +1,123 @@
+// Shared-memory frame exchange (`--obs`, or `format = "shm"` in a [[pipeline.output]]):
+// finished frames go into one file in RAM that another program on the same machine maps
+// and reads, e.g. the OBS source in examples/obs/. No encoder, no virtual camera driver:
+// OBS shows the frame as soon as it is written.
+// Visual expectation: an OBS "Magic Eraser" source shows the same clean picture as the
+// window (no HUD, FX or guides), within a frame of it.
+//
+// The file (/dev/shm/magic-eraser.frame on Linux, the temp folder elsewhere) is a
+// 64-byte header followed by the pixels; all numbers little-endian (include/magic_eraser_shm.h):
+//
+//     offset size  field
+//      0      8    magic "MEFRAME1"
+//      8      4    version (1)
+//     12      4    width
+//     16      4    height
+//     20      4    stride (bytes per row)
+//     24      4    format (1 = BGRA8)
+//     28      4    frames per second
+//     32      8    sequence
+//     40      8    time written (ns since the Unix epoch)
+//     48     16    reserved
+//     64           pixels: height rows of stride bytes
+//
+// The sequence is odd while a frame is being written and even once it is complete:
+// a reader copies the pixels and keeps them only if the sequence was the same even
+// number before and after. The file is removed when the app exits.
+
+use crate::error::Error;
+use crate::types::FrameBuffer;
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const MAGIC: &[u8; 8] = b"MEFRAME1";
+pub const VERSION: u32 = 1;
+pub const HEADER_LEN: u64 = 64;
+/// `format` value: B, G, R, A bytes per pixel.
+pub const FORMAT_BGRA8: u32 = 1;
+/// `[[pipeline.output]] format` that picks this writer instead of ffmpeg.
+pub const OUTPUT_FORMAT: &str = "shm";
+
+/// Where `--obs` writes (and the OBS example reads by default).
+pub fn default_path() -> PathBuf {
+    let shm = Path::new("/dev/shm");
+    let dir = if shm.is_dir() { shm.to_path_buf() } else { std::env::temp_dir() };
+    dir.join("magic-eraser.frame")
+}
+
+pub struct ShmWriter {
+    file: File,
+    path: PathBuf,
+    width: usize,
+    height: usize,
+    sequence: u64,
+    bytes: Vec<u8>, // the frame as BGRA, reused
+}
+
+impl ShmWriter {
+    /// Create (or take over) `path` for `width`×`height` frames and write the header.
+    pub fn create(path: &Path, width: usize, height: usize, fps: u32) -> Result<Self, Error> {
+        let name = path.display().to_string();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|e| Error::Io(format!("Create {name}"), e))?;
+        file.set_len(HEADER_LEN + (width * height * 4) as u64).map_err(|e| Error::Io(format!("Size {name}"), e))?;
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        header.extend_from_slice(MAGIC);
+        for field in [VERSION, width as u32, height as u32, (width * 4) as u32, FORMAT_BGRA8, fps] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        header.resize(HEADER_LEN as usize, 0);                    // sequence 0: no frame yet
+        let mut writer =
+            Self { file, path: path.to_path_buf(), width, height, sequence: 0, bytes: vec![0; width * height * 4] };
+        writer.write_at(0, &header)?;
+        Ok(writer)
+    }
+
+    /// Publish `frame` (same size as at `create`).
+    pub fn write(&mut self, frame: &FrameBuffer) -> Result<(), Error> {
+        if (frame.width, frame.height) != (self.width, self.height) {
+            return Err(Error::Video(format!(
+                "{}: frame is {}x{}, not {}x{}", self.path.display(), frame.width, frame.height, self.width, self.height
+            )));
+        }
+        for (px, out) in frame.pixels.iter().zip(self.bytes.chunks_exact_mut(4)) {
+            out.copy_from_slice(&(px | 0xFF00_0000).to_le_bytes()); // 0x00RRGGBB → B, G, R, 255
+        }
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64);
+        // Odd: readers keep off. `|= 1` rather than `+= 1`: after a failed write it is still
+        // odd, and adding one would mark these pixels complete before they are written.
+        self.sequence |= 1;
+        self.write_at(32, &self.sequence.to_le_bytes())?;
+        let bytes = std::mem::take(&mut self.bytes);
+        let written = self.write_at(HEADER_LEN, &bytes);
+        self.bytes = bytes;
+        written?;
+        self.sequence += 1;                                      // even: complete
+        let mut stamp = [0u8; 16];
+        stamp[..8].copy_from_slice(&self.sequence.to_le_bytes());
+        stamp[8..].copy_from_slice(&nanos.to_le_bytes());
+        self.write_at(32, &stamp)
+    }
+
+    fn write_at(&mut self, offset: u64, data: &[u8]) -> Result<(), Error> {
+        self.file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| self.file.write_all(data))
+            .map_err(|e| Error::Io(format!("Write {}", self.path.display()), e))
+    }
+}
+
+impl Drop for ShmWriter {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log::debug!("shm: remove {}: {e}", self.path.display());
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Sprites: small pictures with transparency (PNG alpha) composited over the frame.
+// Visual expectation: `magic-eraser --logo logo.png` puts your logo in the top-right
+// corner of the window, with its see-through parts showing the video behind it —
+// a watermark for streams and recordings. The same `Sprite` can draw custom cursors
+// or icons in the HUD.
+
+use crate::error::Error;
+use crate::types::FrameBuffer;
+
+/// An RGBA picture, stored premultiplied (0xAARRGGBB with colour already × alpha), so
+/// scaling can mix neighbouring pixels without dark fringes around transparent edges.
+pub struct Sprite {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<u32>,
+}
+
+impl Sprite {
+    /// Load a PNG (or any format `image` reads; no alpha = fully opaque).
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let img = image::open(path)
+            .map_err(|e| Error::ImageLoad(format!("Open {path}: {e}")))?
+            .into_rgba8();
+        let (width, height) = (img.width() as usize, img.height() as usize);
+        if width == 0 || height == 0 {
+            return Err(Error::ImageLoad(format!("{path}: empty image")));
+        }
+        let pixels = img
+            .into_raw()
+            .chunks_exact(4)
+            .map(|p| {
+                let a = p[3] as u32;
+                let pm = |c: u8| (c as u32 * a + 127) / 255;
+                (a << 24) | (pm(p[0]) << 16) | (pm(p[1]) << 8) | pm(p[2])
+            })
+            .collect();
+        Ok(Self { width, height, pixels })
+    }
+
+    /// Size in pixels when drawn at `scale`.
+    pub fn scaled_size(&self, scale: f32) -> (i32, i32) {
+        ((self.width as f32 * scale).round() as i32, (self.height as f32 * scale).round() as i32)
+    }
+
+    /// Bilinear sample at sprite coordinates (u, v) → premultiplied (a, r, g, b) in 0..=255.
+    fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        let (u, v) = (u.clamp(0.0, (self.width - 1) as f32), v.clamp(0.0, (self.height - 1) as f32));
+        let (x0, y0) = (u as usize, v as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (u - x0 as f32, v - y0 as f32);
+        let mut out = [0.0f32; 4];
+        for (x, y, w) in [(x0, y0, (1.0 - fx) * (1.0 - fy)), (x1, y0, fx * (1.0 - fy)),
+                          (x0, y1, (1.0 - fx) * fy), (x1, y1, fx * fy)] {
+            let p = self.pixels[y * self.width + x];
+            for (i, o) in out.iter_mut().enumerate() {
+                *o += ((p >> (24 - 8 * i)) & 0xFF) as f32 * w;
+            }
+        }
+        out
+    }
+
+    /// Composite over `fb` with the top-left corner at (x, y), `scale`× the original size,
+    /// at `opacity` (255 = as the file says). Clipped to the frame.
+    /// Visual: the picture appears with soft, see-through edges where the PNG has them.
+    pub fn draw(&self, fb: &mut FrameBuffer, x: i32, y: i32, scale: f32, opacity: u8) {
+        let scale = scale.max(1e-3);
+        let (w, h) = self.scaled_size(scale);
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = (x + w).min(fb.width as i32);
+        let y1 = (y + h).min(fb.height as i32);
+        let k = opacity as f32 / 255.0;
+        for py in y0..y1 {
+            let v = (py - y) as f32 / scale + 0.5 / scale - 0.5;
+            let row = &mut fb.pixels[py as usize * fb.width..(py as usize + 1) * fb.width];
+            for px in x0..x1 {
+                let u = (px - x) as f32 / scale + 0.5 / scale - 0.5;
+                let [a, r, g, b] = self.sample(u, v).map(|c| c * k);
+                if a <= 0.0 {
+                    continue;
+                }
+                // Premultiplied "over": out = src + dst × (1 − α).
+                let dst = row[px as usize];
+                let keep = 1.0 - a / 255.0;
+                let mix = |s: f32, shift: u32| {
+                    ((s + ((dst >> shift) & 0xFF) as f32 * keep).round() as u32).min(255) << shift
+                };
+                row[px as usize] = mix(r, 16) | mix(g, 8) | mix(b, 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// Persistent status bar along the bottom edge.
+// Visual expectation: a thin see-through strip across the bottom of the window with
+// what you'd otherwise have to remember — MODE LIVE | EFFECT BLUR | BRUSH R 22 HARD 50% |
+// MASK 12.4% — and, on the far right, whether a file is being written. It stays put
+// while the hint line at the top changes; H hides it with the rest of the HUD, and the
+// bottom HUD corners stack above it.
+
+use crate::draw::{draw_text_5x7, fill_rect, fill_rect_blend, text_size_5x7};
+use crate::theme::Theme;
+use crate::types::FrameBuffer;
+
+/// One "LABEL value" cell; `value_color` lets a cell stand out (e.g. a warning).
+pub struct StatusCell {
+    pub label: &'static str,
+    pub value: String,
+    pub value_color: Option<u32>,
+}
+
+impl StatusCell {
+    pub fn new(label: &'static str, value: String) -> Self {
+        Self { label, value, value_color: None }
+    }
+}
+
+/// Height in pixels of the bar at `scale` (bottom HUD blocks sit above this).
+pub fn height(scale: i32) -> i32 {
+    13 * scale.max(1)
+}
+
+/// Draw the bar over the bottom of `fb`: `cells` from the left, `right` pinned to the right.
+/// Visual: labels in the accent colour, values in the text colour, thin dividers between.
+pub fn draw(fb: &mut FrameBuffer, scale: i32, cells: &[StatusCell], right: Option<&StatusCell>, theme: &Theme) {
+    let s = scale.max(1);
+    let (w, h) = (fb.width as i32, height(s));
+    let top = fb.height as i32 - h;
+    let text_y = top + 3 * s;
+    fill_rect_blend(fb, 0, top, w, h, theme.backdrop, 160);
+    fill_rect_blend(fb, 0, top, w, s, theme.text, 60);                 // visual: faint top edge
+
+    let draw_cell = |fb: &mut FrameBuffer, x: i32, cell: &StatusCell| -> i32 {
+        let (lw, _) = text_size_5x7(cell.label, s);
+        draw_text_5x7(fb, x, text_y, cell.label, theme.accent, s);
+        let vx = x + lw + 6 * s;
+        draw_text_5x7(fb, vx, text_y, &cell.value, cell.value_color.unwrap_or(theme.text), s);
+        vx + text_size_5x7(&cell.value, s).0
+    };
+
+    let mut x = 6 * s;
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            fill_rect(fb, x, top + 3 * s, s, h - 5 * s, theme.face_hot); // visual: divider
+            x += 6 * s;
+        }
+        x = draw_cell(fb, x, cell) + 6 * s;
+    }
+    if let Some(cell) = right {
+        let cw = text_size_5x7(cell.label, s).0 + 6 * s + text_size_5x7(&cell.value, s).0;
+        draw_cell(fb, (w - cw - 6 * s).max(x), cell);
+    }
+}
@@ -0,0 +1,169 @@
+// Colour theme for everything drawn on top of the picture (HUD, cursor, panel, FX).
+// Visual expectation: `--theme dark` (default) is white text with yellow accents, as
+// always; `--theme light` uses dark text on pale panels for bright rooms and light
+// footage; `--theme high-vis` uses pure yellow / cyan / magenta that stay readable on
+// anything. `--theme my.theme` loads a file of `key = RRGGBB` lines:
+//
+//     base = light          # start from a built-in theme (optional)
+//     accent = ff00aa
+//     sparkle = 80c0ff      # blue sparkles instead of gold
+//
+// `--hud` colours still win for the blocks they name.
+
+use crate::error::Error;
+
+/// Every overlay colour, as 0x00RRGGBB.
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    pub text: u32,         // HUD text, help descriptions, panel labels
+    pub accent: u32,       // cursor, slider knobs, ticks, help key names
+    pub clone_source: u32, // clone / heal source marker and its link to the brush
+    pub warning: u32,      // over-budget frames, clipping
+    pub good: u32,         // frames within budget (profiler bars)
+    pub face: u32,         // panel buttons / slider tracks
+    pub face_hot: u32,     // …under the mouse
+    pub backdrop: u32,     // see-through boxes behind text
+    pub guides: u32,       // F5 composition guides
+    pub divider: u32,      // F3 before/after divider and labels
+    pub sparkle: u32,      // FX sparkles
+    pub bolt: u32,         // FX lightning
+    pub smoke: u32,        // FX smoke trail
+}
+
+impl Theme {
+    /// White text and yellow accents on dark see-through boxes (the original look).
+    pub const DARK: Theme = Theme {
+        text: 0x00_FF_FF_FF,
+        accent: 0x00_FF_CC_33,
+        clone_source: 0x00_33_99_FF,
+        warning: 0x00_FF_44_44,
+        good: 0x00_44_DD_66,
+        face: 0x00_44_44_50,
+        face_hot: 0x00_66_66_78,
+        backdrop: 0x00_00_00_00,
+        guides: 0x00_FF_FF_FF,
+        divider: 0x00_FF_FF_FF,
+        sparkle: 0x00_FF_C8_50,
+        bolt: 0x00_D2_E6_FF,
+        smoke: 0x00_C8_C8_D8,
+    };
+
+    /// Dark text on pale boxes.
+    pub const LIGHT: Theme = Theme {
+        text: 0x00_18_18_20,
+        accent: 0x00_D0_50_00,
+        clone_source: 0x00_00_50_D0,
+        warning: 0x00_C0_00_00,
+        good: 0x00_10_90_30,
+        face: 0x00_C8_C8_D0,
+        face_hot: 0x00_E0_E0_EA,
+        backdrop: 0x00_F0_F0_F0,
+        guides: 0x00_20_20_20,
+        divider: 0x00_20_20_20,
+        sparkle: 0x00_FF_A0_30,
+        bolt: 0x00_A0_C0_FF,
+        smoke: 0x00_70_70_80,
+    };
+
+    /// Saturated colours on black: readable over any footage, and for low vision.
+    pub const HIGH_VIS: Theme = Theme {
+        text: 0x00_FF_FF_00,
+        accent: 0x00_00_FF_FF,
+        clone_source: 0x00_FF_00_FF,
+        warning: 0x00_FF_00_00,
+        good: 0x00_00_FF_00,
+        face: 0x00_00_00_00,
+        face_hot: 0x00_40_40_40,
+        backdrop: 0x00_00_00_00,
+        guides: 0x00_00_FF_FF,
+        divider: 0x00_FF_FF_00,
+        sparkle: 0x00_FF_FF_00,
+        bolt: 0x00_00_FF_FF,
+        smoke: 0x00_FF_FF_FF,
+    };
+
+    /// A built-in theme by name: dark, light, high-vis.
+    pub fn builtin(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::DARK),
+            "light" => Some(Theme::LIGHT),
+            "high-vis" | "highvis" | "high-visibility" => Some(Theme::HIGH_VIS),
+            _ => None,
+        }
+    }
+
+    /// `--theme` argument: a built-in name, else a theme file.
+    pub fn from_arg(arg: &str) -> Result<Theme, Error> {
+        match Theme::builtin(arg) {
+            Some(theme) => Ok(theme),
+            None => Theme::load(arg),
+        }
+    }
+
+    /// Read a theme file (see the top of this file for the format).
+    pub fn load(path: &str) -> Result<Theme, Error> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("--theme {path}: {e} (built-ins: dark, light, high-vis)")))?;
+        Theme::parse(&text).map_err(|e| Error::Config(format!("{path}: {e}")))
+    }
+
+    /// Parse theme file contents; keys not mentioned keep the base theme's colour.
+    pub fn parse(text: &str) -> Result<Theme, String> {
+        let mut theme = Theme::DARK;
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=').map(|(k, v)| (k.trim(), v.trim())) else {
+                return Err(format!("line {}: expected key = value", n + 1));
+            };
+            if key == "base" {
+                theme = Theme::builtin(value).ok_or_else(|| format!("line {}: unknown base theme '{value}'", n + 1))?;
+                continue;
+            }
+            let color = parse_hex_color(value).ok_or_else(|| format!("line {}: '{value}' is not RRGGBB", n + 1))?;
+            *theme.slot(key).ok_or_else(|| format!("line {}: unknown colour '{key}'", n + 1))? = color;
+        }
+        Ok(theme)
+    }
+
+    fn slot(&mut self, key: &str) -> Option<&mut u32> {
+        Some(match key {
+            "text" => &mut self.text,
+            "accent" => &mut self.accent,
+            "clone_source" => &mut self.clone_source,
+            "warning" => &mut self.warning,
+            "good" => &mut self.good,
+            "face" => &mut self.face,
+            "face_hot" => &mut self.face_hot,
+            "backdrop" => &mut self.backdrop,
+            "guides" => &mut self.guides,
+            "divider" => &mut self.divider,
+            "sparkle" => &mut self.sparkle,
+            "bolt" => &mut self.bolt,
+            "smoke" => &mut self.smoke,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DARK
+    }
+}
+
+/// 0x00RRGGBB → (r, g, b).
+pub fn rgb(c: u32) -> (u8, u8, u8) {
+    ((c >> 16) as u8, (c >> 8) as u8, c as u8)
+}
+
+/// "ffcc33" (optionally "#ffcc33") → 0x00FFCC33.
+pub fn parse_hex_color(s: &str) -> Option<u32> {
+    let s = s.strip_prefix('#').unwrap_or(s);
+    if s.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(s, 16).ok()
+}
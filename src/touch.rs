@@ -0,0 +1,45 @@
+// Touch screens (`--touch`), e.g. convertible laptops and kiosks.
+// Visual expectation: tap or drag with one finger to paint, pinch to make the brush
+// bigger or smaller, drag with two fingers to move around a zoomed picture.
+//
+// minifb only knows about a mouse, so this works with what the OS turns touches into:
+// one finger already arrives as the left button; a two-finger drag arrives as scroll
+// and a pinch as Ctrl + scroll (how Windows, macOS and most Linux desktops deliver
+// them). With `--touch` those two are read as gestures instead of wheel zoom; Home
+// still shows the whole frame. The winit window (`--backend winit`) gets touches
+// itself and turns the first finger into the left button; pinch and pan work as above
+// wherever the OS still sends them as scroll.
+
+use crate::window::Input;
+
+/// Window pixels the picture moves per scroll step of a two-finger drag.
+const PAN_PX_PER_STEP: f32 = 24.0;
+
+/// Pinch (Ctrl + scroll) steps per brush size step.
+const PINCH_PER_BRUSH_STEP: f32 = 0.5;
+
+#[derive(Default)]
+pub struct TouchGestures {
+    pinch: f32, // pinch travel not yet turned into a whole brush step
+}
+
+impl TouchGestures {
+    /// Turn this frame's scroll into gestures: a pinch becomes `input.brush_steps`,
+    /// a two-finger drag is returned as a pan in window pixels. The scroll is used up,
+    /// so Ctrl + wheel zoom doesn't also fire.
+    pub fn apply(&mut self, input: &mut Input) -> (f32, f32) {
+        let (dx, dy) = (input.scroll_x_steps, input.scroll_steps);
+        input.scroll_steps = 0.0;
+        input.scroll_x_steps = 0.0;
+        if input.mods.ctrl {
+            self.pinch += dy / PINCH_PER_BRUSH_STEP;              // spread = bigger brush
+            let whole = self.pinch.trunc();
+            self.pinch -= whole;
+            input.brush_steps += whole as i32;
+            (0.0, 0.0)
+        } else {
+            self.pinch = 0.0;
+            (dx * PAN_PX_PER_STEP, dy * PAN_PX_PER_STEP)
+        }
+    }
+}
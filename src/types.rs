@@ -1,5 +1,7 @@
 // Core types used by Steps 1–4.
 
+use std::sync::{Arc, Mutex};
+
 #[derive(Clone)]
 pub struct FrameBuffer {
     pub width: usize,      // how wide the frame is on screen (pixels)
@@ -7,17 +9,341 @@ pub struct FrameBuffer {
     pub pixels: Vec<u32>,  // each entry is 0x00RRGGBB for minifb
 }
 
-/// Alpha mask in [0,1] per pixel; 1 = use background, 0 = use live foreground.
+/// Most buffers a `FramePool` keeps around; extras are dropped (bounds memory after bursts).
+const POOL_MAX_FREE: usize = 8;
+
+/// Shared free-list of frame pixel buffers, so steady-state frames allocate nothing.
+/// Clones share the same pool (e.g. capture thread takes, processing thread gives back).
+/// Visual: none — it only removes allocation hiccups from the frame rate.
+#[derive(Clone)]
+pub struct FramePool {
+    width: usize,
+    height: usize,
+    free: Arc<Mutex<Vec<Vec<u32>>>>,
+}
+
+impl FramePool {
+    /// Pool of `width`×`height` frames (starts empty; buffers appear as frames are returned).
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, free: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// A frame of the pool's size: a recycled buffer if one is free, else a new one.
+    /// Contents are whatever the previous user left; callers overwrite every pixel.
+    pub fn get(&self) -> FrameBuffer {
+        let reused = self.free.lock().ok().and_then(|mut f| f.pop());
+        let pixels = reused.unwrap_or_else(|| vec![0u32; self.width * self.height]);
+        FrameBuffer { width: self.width, height: self.height, pixels }
+    }
+
+    /// Give a frame back for reuse (frames of another size are simply dropped).
+    pub fn put(&self, frame: FrameBuffer) {
+        if frame.pixels.len() != self.width * self.height { return; }
+        if let Ok(mut free) = self.free.lock()
+            && free.len() < POOL_MAX_FREE
+        {
+            free.push(frame.pixels);
+        }
+    }
+}
+
+/// Axis-aligned pixel rectangle [x0,x1)×[y0,y1).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rect {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+}
+
+impl Rect {
+    /// The whole `width`×`height` frame.
+    pub fn full(width: usize, height: usize) -> Self {
+        Self { x0: 0, y0: 0, x1: width, y1: height }
+    }
+
+    /// Smallest rectangle covering both.
+    pub fn union(self, o: Rect) -> Self {
+        Self { x0: self.x0.min(o.x0), y0: self.y0.min(o.y0), x1: self.x1.max(o.x1), y1: self.y1.max(o.y1) }
+    }
+
+    /// Grow by `r` pixels on every side, clipped to a `width`×`height` frame.
+    pub fn expand(self, r: usize, width: usize, height: usize) -> Self {
+        Self {
+            x0: self.x0.saturating_sub(r),
+            y0: self.y0.saturating_sub(r),
+            x1: (self.x1 + r).min(width),
+            y1: (self.y1 + r).min(height),
+        }
+    }
+}
+
+/* ---------------------- borrowed views: sub-rectangles without copying ---------------------- */
+
+/// Pixels a view of `height` rows `stride` apart, each `width` long, spans in its slice
+/// (the last row needs no padding after it).
+fn view_len(width: usize, height: usize, stride: usize) -> usize {
+    if width == 0 || height == 0 { 0 } else { (height - 1) * stride + width }
+}
+
+/// `rect` clipped to a `width`×`height` view, and where it starts in the view's slice.
+fn clip_sub(rect: Rect, width: usize, height: usize, stride: usize) -> (usize, usize, usize) {
+    let (x1, y1) = (rect.x1.min(width), rect.y1.min(height));
+    let (x0, y0) = (rect.x0.min(x1), rect.y0.min(y1));
+    (y0 * stride + x0, x1 - x0, y1 - y0)
+}
+
+/// Read-only window onto a rectangle of 0x00RRGGBB pixels: `height` rows of `width`,
+/// each row starting `stride` pixels after the one above (stride ≥ width). Made from a
+/// `FrameBuffer` (`view`, `sub`) or any pixel slice (`from_raw`), and cut down further
+/// with `sub`; nothing is copied. Visual: none — lets an algorithm work on part of a frame.
+#[derive(Clone, Copy)]
+pub struct FrameView<'a> {
+    pub width: usize,
+    pub height: usize,
+    pub stride: usize,
+    pixels: &'a [u32], // starts at the view's top-left pixel
+}
+
+impl<'a> FrameView<'a> {
+    /// View into `pixels` starting `offset` pixels in; None if it would run past the end.
+    pub fn from_raw(pixels: &'a [u32], offset: usize, width: usize, height: usize, stride: usize) -> Option<Self> {
+        if stride < width { return None; }
+        let pixels = pixels.get(offset..)?.get(..view_len(width, height, stride))?;
+        Some(Self { width, height, stride, pixels })
+    }
+
+    /// Row `y` (`width` pixels). Panics past the last row, like slice indexing.
+    #[inline]
+    pub fn row(&self, y: usize) -> &'a [u32] {
+        &self.pixels[y * self.stride..y * self.stride + self.width]
+    }
+
+    /// Rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u32]> + 'a {
+        let width = self.width;
+        self.pixels.chunks(self.stride.max(1)).map(move |r| &r[..width]).take(self.height)
+    }
+
+    /// The part of this view inside `rect` (in view coordinates, clipped to the view).
+    pub fn sub(&self, rect: Rect) -> FrameView<'a> {
+        let (offset, width, height) = clip_sub(rect, self.width, self.height, self.stride);
+        let pixels = &self.pixels[offset.min(self.pixels.len())..][..view_len(width, height, self.stride)];
+        FrameView { width, height, stride: self.stride, pixels }
+    }
+
+    /// A tightly packed copy (the one place a view allocates).
+    pub fn to_frame(&self) -> FrameBuffer {
+        FrameBuffer { width: self.width, height: self.height, pixels: self.rows().flatten().copied().collect() }
+    }
+}
+
+/// Writable counterpart of `FrameView`: the same layout over `&mut` pixels.
+pub struct FrameViewMut<'a> {
+    pub width: usize,
+    pub height: usize,
+    pub stride: usize,
+    pixels: &'a mut [u32],
+}
+
+impl<'a> FrameViewMut<'a> {
+    /// View into `pixels` starting `offset` pixels in; None if it would run past the end.
+    pub fn from_raw(pixels: &'a mut [u32], offset: usize, width: usize, height: usize, stride: usize) -> Option<Self> {
+        if stride < width { return None; }
+        let pixels = pixels.get_mut(offset..)?.get_mut(..view_len(width, height, stride))?;
+        Some(Self { width, height, stride, pixels })
+    }
+
+    /// Read-only view of the same pixels.
+    pub fn as_view(&self) -> FrameView<'_> {
+        FrameView { width: self.width, height: self.height, stride: self.stride, pixels: self.pixels }
+    }
+
+    /// Row `y` (`width` pixels), writable.
+    #[inline]
+    pub fn row_mut(&mut self, y: usize) -> &mut [u32] {
+        &mut self.pixels[y * self.stride..y * self.stride + self.width]
+    }
+
+    /// Rows from top to bottom, writable (disjoint, so they can go to different threads).
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u32]> {
+        let width = self.width;
+        self.pixels.chunks_mut(self.stride.max(1)).map(move |r| &mut r[..width]).take(self.height)
+    }
+
+    /// The part of this view inside `rect` (view coordinates, clipped), writable.
+    pub fn sub_mut(&mut self, rect: Rect) -> FrameViewMut<'_> {
+        let (offset, width, height) = clip_sub(rect, self.width, self.height, self.stride);
+        let len = self.pixels.len();
+        let pixels = &mut self.pixels[offset.min(len)..][..view_len(width, height, self.stride)];
+        FrameViewMut { width, height, stride: self.stride, pixels }
+    }
+
+    /// Copy `src` in (same size required; returns false and leaves the pixels alone otherwise).
+    pub fn copy_from(&mut self, src: &FrameView) -> bool {
+        if src.width != self.width || src.height != self.height { return false; }
+        for (d, s) in self.rows_mut().zip(src.rows()) {
+            d.copy_from_slice(s);
+        }
+        true
+    }
+
+    /// Set every pixel of the view to `color`.
+    pub fn fill(&mut self, color: u32) {
+        self.rows_mut().for_each(|r| r.fill(color));
+    }
+}
+
+impl FrameBuffer {
+    /// The whole frame as a view.
+    pub fn view(&self) -> FrameView<'_> {
+        FrameView { width: self.width, height: self.height, stride: self.width, pixels: &self.pixels }
+    }
+
+    /// The whole frame as a writable view.
+    pub fn view_mut(&mut self) -> FrameViewMut<'_> {
+        FrameViewMut { width: self.width, height: self.height, stride: self.width, pixels: &mut self.pixels }
+    }
+
+    /// The pixels inside `rect` (clipped to the frame), without copying.
+    pub fn sub(&self, rect: Rect) -> FrameView<'_> {
+        self.view().sub(rect)
+    }
+
+    /// The pixels inside `rect` (clipped to the frame), writable, without copying.
+    pub fn sub_mut(&mut self, rect: Rect) -> FrameViewMut<'_> {
+        let (offset, width, height) = clip_sub(rect, self.width, self.height, self.width);
+        let stride = self.width;
+        let pixels = &mut self.pixels[offset.min(self.width * self.height)..][..view_len(width, height, stride)];
+        FrameViewMut { width, height, stride, pixels }
+    }
+}
+
+/// Alpha mask, one byte per pixel: 255 = use background, 0 = use live foreground.
 /// Visual: unseen directly; it controls how much “erase” happens at each pixel.
+#[derive(Clone)]
 pub struct Mask {
     pub width: usize,
     pub height: usize,
-    pub alpha: Vec<f32>,   // length = width * height, values clamped to [0.0, 1.0]
+    pub alpha: Vec<u8>,    // length = width * height, 0..=255 (255 = full effect)
+    pub bounds: Option<Rect>, // every α > 0 lies inside this box; None = mask is empty
+}
+
+impl Mask {
+    /// Empty (all-zero) mask.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, alpha: vec![0; width * height], bounds: None }
+    }
+
+    /// Widen `bounds` to include `r` (clipped to the mask).
+    pub fn extend_bounds(&mut self, r: Rect) {
+        let r = Rect { x0: r.x0, y0: r.y0, x1: r.x1.min(self.width), y1: r.y1.min(self.height) };
+        if r.x0 >= r.x1 || r.y0 >= r.y1 { return; }
+        self.bounds = Some(self.bounds.map_or(r, |b| b.union(r)));
+    }
+
+    /// Share of the frame under the effect, 0..=1 (α-weighted: half-painted counts half).
+    /// Only `bounds` is scanned, so an empty or small mask costs next to nothing.
+    pub fn coverage(&self) -> f32 {
+        let Some(b) = self.bounds else { return 0.0 };
+        let mut sum: u64 = 0;
+        for y in b.y0..b.y1 {
+            sum += self.alpha[y * self.width + b.x0..y * self.width + b.x1].iter().map(|&a| a as u64).sum::<u64>();
+        }
+        sum as f32 / (255.0 * (self.width * self.height).max(1) as f32)
+    }
+}
+
+/// Side length of a `MaskTiles` tile in pixels.
+pub const MASK_TILE: usize = 32;
+
+/// Coarse summary of a Mask: min/max alpha per 32×32 tile.
+/// Visual: none — lets the blend skip untouched tiles and copy fully painted ones.
+pub struct MaskTiles {
+    pub tiles_x: usize,
+    pub tiles_y: usize,
+    pub min: Vec<u8>,      // smallest alpha in each tile (255 → tile is fully painted)
+    pub max: Vec<u8>,      // largest alpha in each tile (0 → tile is untouched)
+}
+
+impl MaskTiles {
+    /// Summary for an all-zero mask of `width`×`height`.
+    pub fn new(width: usize, height: usize) -> Self {
+        let (tiles_x, tiles_y) = (width.div_ceil(MASK_TILE), height.div_ceil(MASK_TILE));
+        Self { tiles_x, tiles_y, min: vec![0; tiles_x * tiles_y], max: vec![0; tiles_x * tiles_y] }
+    }
+
+    /// Forget everything (matches a freshly cleared mask).
+    pub fn clear(&mut self) {
+        self.min.fill(0);
+        self.max.fill(0);
+    }
+
+    /// Rescan every tile (for masks rebuilt wholesale each frame, e.g. portrait).
+    pub fn rebuild(&mut self, mask: &Mask) {
+        self.update_rect(mask, 0, 0, mask.width as i32, mask.height as i32);
+    }
+
+    /// Rescan only the tiles overlapping the pixel rect [x0,x1)×[y0,y1) (e.g. one brush dab).
+    pub fn update_rect(&mut self, mask: &Mask, x0: i32, y0: i32, x1: i32, y1: i32) {
+        let (w, h) = (mask.width as i32, mask.height as i32);
+        let (x0, y0, x1, y1) = (x0.clamp(0, w), y0.clamp(0, h), x1.clamp(0, w), y1.clamp(0, h));
+        if x0 >= x1 || y0 >= y1 { return; }
+        let t = MASK_TILE as i32;
+        for ty in (y0 / t) as usize..=((y1 - 1) / t) as usize {
+            for tx in (x0 / t) as usize..=((x1 - 1) / t) as usize {
+                let (px0, py0) = (tx * MASK_TILE, ty * MASK_TILE);
+                let (px1, py1) = ((px0 + MASK_TILE).min(mask.width), (py0 + MASK_TILE).min(mask.height));
+                let (mut lo, mut hi) = (u8::MAX, u8::MIN);
+                for y in py0..py1 {
+                    for &a in &mask.alpha[y * mask.width + px0..y * mask.width + px1] {
+                        lo = lo.min(a);
+                        hi = hi.max(a);
+                    }
+                }
+                self.min[ty * self.tiles_x + tx] = lo;
+                self.max[ty * self.tiles_x + tx] = hi;
+            }
+        }
+    }
 }
 
 /// Precomputed circular Gaussian “stamp” we dab into the Mask at the pointer.
 /// Visual: makes the erase edge soft/feathered.
 pub struct Stamp {
     pub radius: i32,       // pixels from center to edge
-    pub weights: Vec<f32>, // (2r+1)*(2r+1), centered kernel, already normalized to peak 1.0
+    pub weights: Vec<u8>,  // (2r+1)*(2r+1), centered kernel, peak 255 at the center
+}
+
+/// Small square convolution kernel with integer weights.
+/// result = (Σ weight·pixel) / divisor + bias, per channel, clamped to 0..255.
+/// Visual: depends on the weights — sharpen, emboss, edge outlines, …
+pub struct Kernel {
+    pub size: usize,      // odd width = height (3, 5, …)
+    pub weights: Vec<i32>, // size*size, row-major, centre in the middle
+    pub divisor: i32,     // normalisation (sum of weights for smoothing kernels; never 0)
+    pub bias: i32,        // added after dividing (128 makes signed results visible as grey)
+}
+
+impl Kernel {
+    /// Build a kernel; panics in debug if the shape is inconsistent (a programming error).
+    pub fn new(size: usize, weights: Vec<i32>, divisor: i32, bias: i32) -> Self {
+        debug_assert!(size % 2 == 1 && weights.len() == size * size && divisor != 0);
+        Self { size, weights, divisor, bias }
+    }
+
+    /// Crisper details: centre boosted, neighbours subtracted.
+    pub fn sharpen() -> Self {
+        Self::new(3, vec![0, -1, 0, -1, 5, -1, 0, -1, 0], 1, 0)
+    }
+
+    /// Relief look: lit from the top-left, flat areas turn mid-grey.
+    pub fn emboss() -> Self {
+        Self::new(3, vec![-2, -1, 0, -1, 1, 1, 0, 1, 2], 1, 128)
+    }
+
+    /// Edge outlines: flat areas go black, edges glow.
+    pub fn edge() -> Self {
+        Self::new(3, vec![-1, -1, -1, -1, 8, -1, -1, -1, -1], 1, 0)
+    }
 }
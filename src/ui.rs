@@ -0,0 +1,272 @@
+// Minimal immediate-mode widgets (labels, buttons, checkboxes, sliders, text fields) on
+// top of draw.rs.
+// Visual expectation: S opens a see-through settings panel in the top-right corner.
+// Click a button or checkbox, or drag a slider, and the setting changes right away —
+// no hotkeys to memorize. While the mouse is over the panel the brush doesn't paint.
+//
+// "Immediate mode": there are no widget objects. Every frame the caller lays the panel
+// out again, and each call both draws its widget and reports what the mouse did to it:
+//
+//     let mut p = ui.panel(fb, x, y, width, scale, mouse, down);
+//     if p.button("NEXT") { … }
+//     p.slider("RADIUS", &mut radius, 1, 96);
+//     p.end();
+
+use crate::draw::{draw_text_5x7, fill_rect, fill_rect_blend, fill_rounded_rect};
+use crate::theme::Theme;
+use crate::types::FrameBuffer;
+
+/// Widget state that has to survive from one frame to the next.
+pub struct Ui {
+    was_down: bool,          // left button state last frame (click = up → down)
+    active: Option<usize>,   // widget that owns the mouse (slider being dragged)
+    area: Option<(i32, i32, i32, i32)>, // panel rectangle last frame (x0, y0, x1, y1)
+    focus: Option<usize>,    // text field that owns the keyboard
+    typed: String,           // this frame's keyboard input for the focused field
+    backspace: bool,
+    enter: bool,
+    blink: u32,              // frame counter for the caret
+    theme: Theme,            // text, face, face_hot (under the mouse), accent (ticks, knobs, fills)
+}
+
+impl Ui {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            was_down: false,
+            active: None,
+            area: None,
+            focus: None,
+            typed: String::new(),
+            backspace: false,
+            enter: false,
+            blink: 0,
+            theme,
+        }
+    }
+
+    /// Draw with these colours from now on (the theme was reloaded).
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// True while a text field has the keyboard; the caller should then keep typed keys
+    /// away from the hotkeys (`Input::clear_shortcuts`) and pass them in `text_events`.
+    pub fn has_focus(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    /// This frame's keyboard input for the focused text field (call before `panel`).
+    pub fn text_events(&mut self, typed: &str, backspace: bool, enter: bool) {
+        self.typed.clear();
+        self.typed.push_str(typed);
+        self.backspace = backspace;
+        self.enter = enter;
+    }
+
+    /// True if the mouse belongs to the UI (over last frame's panel, or dragging a widget),
+    /// so the caller should not paint with it this frame.
+    pub fn wants_mouse(&self, mouse: Option<(usize, usize)>) -> bool {
+        if self.active.is_some() {
+            return true;
+        }
+        match (self.area, mouse) {
+            (Some((x0, y0, x1, y1)), Some((mx, my))) => {
+                let (mx, my) = (mx as i32, my as i32);
+                mx >= x0 && mx < x1 && my >= y0 && my < y1
+            }
+            _ => false,
+        }
+    }
+
+    /// Forget the panel (call when it is hidden, so its area no longer blocks painting).
+    pub fn hide(&mut self) {
+        self.area = None;
+        self.active = None;
+        self.focus = None;
+    }
+
+    /// Start laying out a panel `width` pixels wide with its top-left corner at (x, y).
+    #[allow(clippy::too_many_arguments)]
+    pub fn panel<'a>(
+        &'a mut self,
+        fb: &'a mut FrameBuffer,
+        x: i32,
+        y: i32,
+        width: i32,
+        scale: i32,
+        mouse: Option<(usize, usize)>,
+        down: bool,
+    ) -> Panel<'a> {
+        let pressed = down && !self.was_down;
+        self.was_down = down;
+        if !down {
+            self.active = None; // drag ends when the button is released
+        }
+        self.blink = self.blink.wrapping_add(1);
+        let scale = scale.max(1);
+        Panel {
+            ui: self,
+            fb,
+            x,
+            y0: y,
+            y,
+            width,
+            scale,
+            mouse: mouse.map(|(mx, my)| (mx as i32, my as i32)),
+            down,
+            pressed,
+            next_id: 0,
+        }
+    }
+}
+
+/// One frame's worth of a panel: widgets stack top to bottom.
+pub struct Panel<'a> {
+    ui: &'a mut Ui,
+    fb: &'a mut FrameBuffer,
+    x: i32,
+    y0: i32,
+    y: i32,
+    width: i32,
+    scale: i32,
+    mouse: Option<(i32, i32)>,
+    down: bool,
+    pressed: bool,
+    next_id: usize,
+}
+
+impl Panel<'_> {
+    /// Reserve the next row (`h` pixels tall), draw its background, and return
+    /// (widget id, row top, whether the mouse is over the row).
+    fn row(&mut self, h: i32) -> (usize, i32, bool) {
+        let (id, top, pad) = (self.next_id, self.y, 4 * self.scale);
+        self.next_id += 1;
+        fill_rect_blend(self.fb, self.x - pad, top, self.width + 2 * pad, h, self.ui.theme.backdrop, 150);
+        self.y += h;
+        let hot = self.mouse.is_some_and(|(mx, my)| {
+            mx >= self.x && mx < self.x + self.width && my >= top && my < top + h
+        });
+        (id, top, hot)
+    }
+
+    fn face(&self, hot: bool) -> u32 {
+        if hot { self.ui.theme.face_hot } else { self.ui.theme.face }
+    }
+
+    fn text(&mut self, x: i32, y: i32, text: &str) {
+        draw_text_5x7(self.fb, x, y, text, self.ui.theme.text, self.scale);
+    }
+
+    /// Plain text line.
+    /// Visual: white text on the panel.
+    pub fn label(&mut self, text: &str) {
+        let s = self.scale;
+        let (_, top, _) = self.row(12 * s);
+        self.text(self.x, top + 3 * s, text);
+    }
+
+    /// A push button; true on the frame it is clicked.
+    /// Visual: a grey rounded box that lightens under the mouse.
+    pub fn button(&mut self, text: &str) -> bool {
+        let s = self.scale;
+        let (_, top, hot) = self.row(14 * s);
+        let face = self.face(hot);
+        fill_rounded_rect(self.fb, self.x, top + s, self.width, 12 * s, 3 * s, face, 255);
+        self.text(self.x + 3 * s, top + 4 * s, text);
+        hot && self.pressed
+    }
+
+    /// A checkbox bound to `value`; true on the frame it is toggled.
+    /// Visual: a small square, filled with the accent colour (yellow) while on, followed by the label.
+    pub fn checkbox(&mut self, text: &str, value: &mut bool) -> bool {
+        let s = self.scale;
+        let (_, top, hot) = self.row(12 * s);
+        let clicked = hot && self.pressed;
+        if clicked {
+            *value = !*value;
+        }
+        fill_rect(self.fb, self.x, top + 2 * s, 8 * s, 8 * s, self.face(hot));
+        if *value {
+            fill_rect(self.fb, self.x + 2 * s, top + 4 * s, 4 * s, 4 * s, self.ui.theme.accent);
+        }
+        self.text(self.x + 12 * s, top + 3 * s, text);
+        clicked
+    }
+
+    /// A horizontal slider for `value` in `min..=max`; true on frames where it changed.
+    /// Visual: label and value on top, a track with an accent fill and knob below;
+    /// press anywhere on it and drag.
+    pub fn slider(&mut self, text: &str, value: &mut i32, min: i32, max: i32) -> bool {
+        let s = self.scale;
+        let (id, top, hot) = self.row(22 * s);
+        if hot && self.pressed {
+            self.ui.active = Some(id);
+        }
+        let before = *value;
+        let track_y = top + 13 * s;
+        if self.ui.active == Some(id) && self.down && let Some((mx, _)) = self.mouse {
+            let t = ((mx - self.x) as f32 / self.width.max(1) as f32).clamp(0.0, 1.0);
+            *value = min + ((max - min) as f32 * t).round() as i32;
+        }
+        *value = (*value).clamp(min, max);
+
+        self.text(self.x, top + 3 * s, &format!("{text} {}", *value));
+        fill_rect(self.fb, self.x, track_y, self.width, 4 * s, self.face(hot));
+        let fill = ((*value - min) as f32 / (max - min).max(1) as f32 * self.width as f32) as i32;
+        fill_rect(self.fb, self.x, track_y, fill, 4 * s, self.ui.theme.accent);
+        fill_rect(self.fb, self.x + fill - 2 * s, track_y - 2 * s, 4 * s, 8 * s, self.ui.theme.accent);
+        *value != before
+    }
+
+    /// A one-line text field editing `value`; true on the frame Enter confirms it.
+    /// Click the field to type into it (hotkeys pause meanwhile); Backspace deletes,
+    /// Enter or a click elsewhere finishes.
+    /// Visual: label on top, a dark box below with the text and a blinking accent caret;
+    /// the box lightens while it has the keyboard. Long text scrolls to keep its end in view.
+    pub fn text_input(&mut self, text: &str, value: &mut String) -> bool {
+        let s = self.scale;
+        let (id, top, hot) = self.row(24 * s);
+        let box_y = top + 11 * s;
+        let box_hot = hot && self.mouse.is_some_and(|(_, my)| my >= box_y);
+        if self.pressed {
+            if box_hot {
+                self.ui.focus = Some(id);
+            } else if self.ui.focus == Some(id) {
+                self.ui.focus = None; // clicked somewhere else
+            }
+        }
+
+        let focused = self.ui.focus == Some(id);
+        let mut confirmed = false;
+        if focused {
+            value.push_str(&self.ui.typed);
+            if self.ui.backspace {
+                value.pop();
+            }
+            if self.ui.enter {
+                self.ui.focus = None;
+                confirmed = true;
+            }
+            self.ui.typed.clear();
+            self.ui.backspace = false;
+            self.ui.enter = false;
+        }
+
+        self.text(self.x, top + 2 * s, text);
+        fill_rect(self.fb, self.x, box_y, self.width, 11 * s, self.face(focused));
+        let fits = ((self.width - 6 * s) / (6 * s)).max(1) as usize;
+        let shown: String = value.chars().skip(value.chars().count().saturating_sub(fits - focused as usize)).collect();
+        self.text(self.x + 2 * s, box_y + 2 * s, &shown);
+        if focused && self.ui.blink % 30 < 15 {
+            let caret_x = self.x + 2 * s + shown.chars().count() as i32 * 6 * s;
+            fill_rect(self.fb, caret_x, box_y + 2 * s, s, 7 * s, self.ui.theme.accent);
+        }
+        confirmed
+    }
+
+    /// Finish the panel and remember where it was (for `Ui::wants_mouse`).
+    pub fn end(self) {
+        let pad = 4 * self.scale;
+        self.ui.area = Some((self.x - pad, self.y0, self.x + self.width + pad, self.y));
+    }
+}
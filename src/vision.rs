@@ -1,66 +1,109 @@
-// Background median builder for Step 3.
+// Background estimator for Step 3.
 // Visual expectation: after you record N frames, the computed background looks
 // like your empty scene without moving subjects (hands/you/etc.).
 use crate::gamma::GammaLut;
 use crate::error::Error;
-use crate::types::{FrameBuffer, Mask, Stamp};
+use crate::types::{FrameBuffer, FrameView, FrameViewMut, Kernel, Mask, MaskTiles, Rect, Stamp, MASK_TILE};
+use rayon::prelude::*;
+
+pub const BG_CAPTURE_COUNT: usize = 35; // frames folded in before the estimate is used (~1–2 s)
+
+/// Running per-pixel background estimate (approximate temporal median).
+/// What you *see* afterward: a clean background image with moving objects removed,
+/// built from one frame of memory instead of a stack of stored frames.
+///
+/// How: every channel of every pixel steps toward the live value by a fixed amount,
+/// never past it. Values that are "usually" seen win, so a hand waving through for a
+/// few frames barely moves it — the same outcome as a median over those frames.
+/// Steps start large (fast convergence) and shrink to 1 as frames accumulate.
+pub struct BackgroundModel {
+    frame: FrameBuffer,
+    frames_seen: usize,
+}
 
-pub const BG_CAPTURE_COUNT: usize = 35; // ~1–2 seconds of frames at 30 FPS
+impl BackgroundModel {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            frame: FrameBuffer { width, height, pixels: vec![0u32; width * height] },
+            frames_seen: 0,
+        }
+    }
 
-/// Compute per-pixel median across the provided frames (all same size).
-/// What you *see* afterward: a clean background image with moving objects removed.
-pub fn median_background(frames: &[FrameBuffer]) -> Result<FrameBuffer, Error> {
-    // 1) Must have at least 1 frame; otherwise we cannot build a background.
-    if frames.is_empty() {
-        return Err(Error::CameraFrame("median_background: no frames".into()));
+    /// Forget everything (the next `update` starts a fresh capture).
+    pub fn reset(&mut self) {
+        self.frames_seen = 0;
     }
 
-    // 2) Verify all frames share the same resolution, else drawing will look scrambled.
-    let w = frames[0].width;
-    let h = frames[0].height;
-    for f in frames.iter() {
-        if f.width != w || f.height != h {
-            return Err(Error::CameraFrame(
-                "median_background: frames must share identical dimensions".into(),
-            ));
-        }
+    /// Frames folded in since the last reset (HUD shows n/35 while capturing).
+    pub fn frames_seen(&self) -> usize {
+        self.frames_seen
+    }
+
+    /// The current background estimate.
+    pub fn frame(&self) -> &FrameBuffer {
+        &self.frame
+    }
+
+    /// Take `frame` as a finished estimate (a background saved earlier), as if a full
+    /// capture had just run.
+    pub fn restore(&mut self, frame: &FrameBuffer) -> Result<(), Error> {
+        self.check_size(frame)?;
+        self.frame.pixels.copy_from_slice(&frame.pixels);
+        self.frames_seen = BG_CAPTURE_COUNT;
+        Ok(())
     }
 
-    // 3) Prepare an output buffer of the same size (what we'll show as BG).
-    let mut out = Vec::with_capacity(w * h);
-
-    // 4) We'll compute median per pixel, channel by channel (R,G,B).
-    //    For speed (and to avoid heap allocs per pixel), we use fixed-size arrays
-    //    sized by the capture count, and slice them to the actual length.
-    let k = frames.len();
-    let mut rbuf = vec![0u8; k];
-    let mut gbuf = vec![0u8; k];
-    let mut bbuf = vec![0u8; k];
-
-    // 5) For each pixel index, collect all Rs, all Gs, all Bs, then sort and pick the middle.
-    for idx in 0..(w * h) {
-        // Gather channel values across all frames
-        for (i, f) in frames.iter().enumerate() {
-            let px = f.pixels[idx];
-            // px = 0x00RRGGBB
-            rbuf[i] = ((px >> 16) & 0xFF) as u8;
-            gbuf[i] = ((px >> 8) & 0xFF) as u8;
-            bbuf[i] = (px & 0xFF) as u8;
+    /// Fold one frame into the estimate everywhere.
+    pub fn update(&mut self, live: &FrameBuffer) -> Result<(), Error> {
+        self.check_size(live)?;
+        if self.frames_seen == 0 {
+            self.frame.pixels.copy_from_slice(&live.pixels); // first frame: start from it
+        } else {
+            let step = (32u32 >> (self.frames_seen / 4).min(5)).max(1);
+            self.frame.pixels.par_iter_mut()
+                .zip(live.pixels.par_iter())
+                .for_each(|(bg, &px)| *bg = step_toward(*bg, px, step));
         }
+        self.frames_seen += 1;
+        Ok(())
+    }
 
-        // Sort in place and pick median (k is small ~35; this is fine for learning)
-        rbuf[..k].sort_unstable();
-        gbuf[..k].sort_unstable();
-        bbuf[..k].sort_unstable();
-        let mid = k / 2;
-        let r = rbuf[mid] as u32;
-        let g = gbuf[mid] as u32;
-        let b = bbuf[mid] as u32;
+    /// Keep the estimate fresh while it is in use: only pixels `room` marks as fully
+    /// background (α = 255) move, by one level per frame, so slow lighting changes are
+    /// followed but the subject never bleeds into the background.
+    pub fn refresh(&mut self, live: &FrameBuffer, room: &Mask) -> Result<(), Error> {
+        self.check_size(live)?;
+        if room.width != live.width || room.height != live.height {
+            return Err(Error::CameraFrame("BackgroundModel::refresh: mask size mismatch".into()));
+        }
+        self.frame.pixels.par_iter_mut()
+            .zip(live.pixels.par_iter())
+            .zip(room.alpha.par_iter())
+            .for_each(|((bg, &px), &a)| if a == 255 { *bg = step_toward(*bg, px, 1) });
+        Ok(())
+    }
 
-        out.push((r << 16) | (g << 8) | b); // pack back as 0x00RRGGBB
+    fn check_size(&self, live: &FrameBuffer) -> Result<(), Error> {
+        if live.width != self.frame.width || live.height != self.frame.height {
+            return Err(Error::CameraFrame(
+                "BackgroundModel: frames must share identical dimensions".into(),
+            ));
+        }
+        Ok(())
     }
+}
 
-    Ok(FrameBuffer { width: w, height: h, pixels: out })
+/// Move each 8-bit channel of `bg` toward `px` by at most `step`.
+#[inline]
+fn step_toward(bg: u32, px: u32, step: u32) -> u32 {
+    let mut out = 0u32;
+    for shift in [16u32, 8, 0] {
+        let b = (bg >> shift) & 0xFF;
+        let p = (px >> shift) & 0xFF;
+        let v = if p > b { b + (p - b).min(step) } else { b - (b - p).min(step) };
+        out |= v << shift;
+    }
+    out
 }
 
 /// Make a circular Gaussian stamp with peak 1.0 at the center.
@@ -80,14 +123,20 @@ pub fn make_gaussian_stamp(radius: i32, sigma: f32) -> Stamp {
             weights.push(w);
         }
     }
-    // Normalize to peak 1.0 (not sum=1); we want full strength at the center
-    if maxw > 0.0 {
-        for w in &mut weights { *w /= maxw; }
-    }
+    // Normalize to peak 255 (not sum=1); we want full strength at the center
+    let scale = if maxw > 0.0 { 255.0 / maxw } else { 0.0 };
+    let weights = weights.iter().map(|&w| (w * scale).round() as u8).collect();
 
     Stamp { radius, weights }
 }
 
+/// The brush's dab for a radius and hardness 0..1: σ = radius × (1 − hardness), but at
+/// least half a pixel, so hardness 1 is a crisp disc rather than nothing at all.
+/// Visual: 0 = very soft edge, 1 = hard edge.
+pub fn brush_stamp(radius: i32, hardness: f32) -> Stamp {
+    make_gaussian_stamp(radius, (radius as f32 * (1.0 - hardness.clamp(0.0, 1.0))).max(0.5))
+}
+
 /// Add (dab) the stamp into the alpha mask at (cx, cy).
 /// Visual: increases erase strength under the cursor, with soft edges.
 pub fn dab_mask(mask: &mut Mask, cx: i32, cy: i32, stamp: &Stamp) {
@@ -104,16 +153,84 @@ pub fn dab_mask(mask: &mut Mask, cx: i32, cy: i32, stamp: &Stamp) {
             let idx = sy as usize * mask.width + sx as usize;
             let kidx = ky as usize * d as usize + kx as usize;
 
-            // Add stamp weight; clamp to 1.0 (full erase).
-            let a = mask.alpha[idx] + stamp.weights[kidx];
-            mask.alpha[idx] = if a > 1.0 { 1.0 } else { a };
+            // Add stamp weight; saturates at 255 (full erase).
+            mask.alpha[idx] = mask.alpha[idx].saturating_add(stamp.weights[kidx]);
         }
     }
+
+    // Remember the dabbed square so blur/blend can stay inside the painted area.
+    let (x0, y0) = ((cx - r).clamp(0, w) as usize, (cy - r).clamp(0, h) as usize);
+    let (x1, y1) = ((cx + r + 1).clamp(0, w) as usize, (cy + r + 1).clamp(0, h) as usize);
+    mask.extend_bounds(Rect { x0, y0, x1, y1 });
+}
+
+/// Take the stamp back out of the mask at (cx, cy) (the subtract brush).
+/// Visual: the effect fades out under the cursor, with the same soft edge it went on with.
+pub fn lift_mask(mask: &mut Mask, cx: i32, cy: i32, stamp: &Stamp) {
+    let w = mask.width as i32;
+    let h = mask.height as i32;
+    let r = stamp.radius;
+    let d = 2 * r + 1;
+
+    for ky in 0..d {
+        for kx in 0..d {
+            let sx = cx + kx - r;
+            let sy = cy + ky - r;
+            if sx < 0 || sy < 0 || sx >= w || sy >= h { continue; }
+            let idx = sy as usize * mask.width + sx as usize;
+            let kidx = ky as usize * d as usize + kx as usize;
+            mask.alpha[idx] = mask.alpha[idx].saturating_sub(stamp.weights[kidx]);
+        }
+    }
+    // Bounds stay as they are: they only need to cover every non-zero alpha.
+}
+
+/// Clear the mask inside a circle of radius `r` around (cx, cy) (double-click).
+/// Visual: a clean round hole in the paint; everything outside it stays.
+pub fn clear_circle(mask: &mut Mask, cx: i32, cy: i32, r: i32) {
+    let (w, h) = (mask.width as i32, mask.height as i32);
+    for y in (cy - r).max(0)..(cy + r + 1).min(h) {
+        for x in (cx - r).max(0)..(cx + r + 1).min(w) {
+            if (x - cx) * (x - cx) + (y - cy) * (y - cy) <= r * r {
+                mask.alpha[y as usize * mask.width + x as usize] = 0;
+            }
+        }
+    }
+}
+
+/// Fill the whole mask with 255 (the effect everywhere; the privacy hotkey).
+pub fn fill_mask(mask: &mut Mask) {
+    mask.alpha.fill(255);
+    mask.bounds = Some(Rect { x0: 0, y0: 0, x1: mask.width, y1: mask.height });
+}
+
+/// Centre of the paint, weighted by α (None if nothing is painted).
+/// Visual: where the clear burst starts — the middle of what you painted.
+pub fn mask_centroid(mask: &Mask) -> Option<(f32, f32)> {
+    let b = mask.bounds?;
+    let (mut sum, mut sx, mut sy) = (0u64, 0u64, 0u64);
+    for y in b.y0..b.y1 {
+        let row = &mask.alpha[y * mask.width + b.x0..y * mask.width + b.x1];
+        for (i, &a) in row.iter().enumerate() {
+            sum += a as u64;
+            sx += a as u64 * (b.x0 + i) as u64;
+            sy += a as u64 * y as u64;
+        }
+    }
+    (sum > 0).then(|| (sx as f32 / sum as f32 + 0.5, sy as f32 / sum as f32 + 0.5))
 }
 
 /// Clear the mask to 0 (no erase anywhere).
 pub fn clear_mask(mask: &mut Mask) {
-    for a in &mut mask.alpha { *a = 0.0; }
+    mask.alpha.fill(0);
+    mask.bounds = None;
+}
+
+/// Flip the mask (α → 1−α): erased areas become untouched and vice versa.
+/// Visual: the effect jumps from "where you painted" to "everywhere else".
+pub fn invert_mask(mask: &mut Mask) {
+    for a in &mut mask.alpha { *a = 255 - *a; }
+    mask.bounds = Some(Rect::full(mask.width, mask.height));
 }
 
 // ---------------------- sRGB <-> Linear helpers (gamma correct) ----------------------
@@ -132,11 +249,36 @@ pub fn clear_mask(mask: &mut Mask) {
 }
 
 
+/// Rows per parallel task in the horizontal pass of `box_blur_rgb` (big enough that
+/// scheduling overhead vanishes).
+const BLUR_BAND_ROWS: usize = 16;
+/// Columns per parallel task in the vertical pass: 256 bytes of each row, so a stripe
+/// walks down the frame touching a few whole cache lines per row.
+const BLUR_STRIPE_COLS: usize = 64;
+
+/// Separable box blur (edges extended), both passes spread across CPU cores with rayon.
+/// The horizontal pass splits the rows into bands; the vertical pass splits the columns
+/// into stripes, each sliding one running sum per column from the top of the region to
+/// the bottom, so no task re-reads rows another already summed.
+/// What you SEE: the same blur as before, only cheaper per frame on multi-core machines.
 pub fn box_blur_rgb(
     src: &FrameBuffer,      // input (live camera for this frame)
     tmp: &mut FrameBuffer,  // horizontal pass result (scratch)
     dst: &mut FrameBuffer,  // final blurred output
     radius: usize,          // blur amount; bigger = softer (and slightly slower)
+) -> Result<(), Error> {
+    box_blur_rgb_rect(src, tmp, dst, radius, Rect::full(src.width, src.height))
+}
+
+/// `box_blur_rgb`, but only pixels inside `region` are blurred; the rest of `dst` is
+/// a plain copy of `src`. Used with the mask's bounding box: only the rows and columns
+/// the blend will read are blurred (reading `radius` pixels around them).
+pub fn box_blur_rgb_rect(
+    src: &FrameBuffer,
+    tmp: &mut FrameBuffer,
+    dst: &mut FrameBuffer,
+    radius: usize,
+    region: Rect,
 ) -> Result<(), Error> {
     if src.width != dst.width || src.height != dst.height {
         return Err(Error::CameraFrame("box_blur: size mismatch src↔dst".into()));
@@ -144,102 +286,425 @@ pub fn box_blur_rgb(
     if tmp.width != src.width || tmp.height != src.height {
         return Err(Error::CameraFrame("box_blur: size mismatch tmp".into()));
     }
-    let w = src.width as i32;     // screen width in pixels
-    let h = src.height as i32;    // screen height in pixels
-    let r = radius as i32;        // blur radius
-    let win = (2 * r + 1) as u32; // window width for averaging (constant everywhere)
-
-    /* ---- Pass 1: Horizontal (store averaged rows in tmp) ----
+    let (w, h) = (src.width, src.height);
+    let Rect { x0, y0, x1, y1 } = region;
+    let (x1, y1) = (x1.min(w), y1.min(h));
+    copy_outside(src, dst, Rect { x0, y0, x1, y1 });
+    if x0 >= x1 || y0 >= y1 { return Ok(()); }
+    let cols = x1 - x0;
+    let r = radius as isize;      // blur radius
+    let win = (2 * radius + 1) as u32; // window width for averaging (constant everywhere)
+    let band = w * BLUR_BAND_ROWS;
+    // The vertical pass reads `radius` rows above and below the output rows.
+    let (t0, t1) = (y0.saturating_sub(radius), (y1 + radius).min(h));
+
+    /* ---- Pass 1: Horizontal (store averaged rows in tmp), one band of rows per task ----
        What you SEE: nothing yet (tmp is off-screen), but we prepare row averages. */
-    for y in 0..h {
-        // Grab row start index once (faster indexing)
-        let row_ofs = (y as usize) * (w as usize);
-
-        // Edge pixel value at x=0 (we "extend" edges to avoid dark borders)
-        let px0 = src.pixels[row_ofs + 0];
-        let (mut sr, mut sg, mut sb) = (
-            (((px0 >> 16) & 0xFF) as u32) * (r as u32 + 1),
-            (((px0 >>  8) & 0xFF) as u32) * (r as u32 + 1),
-            (((px0      ) & 0xFF) as u32) * (r as u32 + 1),
-        );
-
-        // Prime the right side of the initial window [0..r]
-        for x in 1..=r {
-            let xr = x.min(w - 1) as usize;        // clamp at right edge
-            let p = src.pixels[row_ofs + xr];
-            sr += ((p >> 16) & 0xFF) as u32;
-            sg += ((p >>  8) & 0xFF) as u32;
-            sb += ((p      ) & 0xFF) as u32;
+    // Each row segment (plus `radius` on both sides) is copied once into a padded scratch
+    // row (edges "extended" by repeating the border pixel, to avoid dark borders), so the
+    // sliding loop below is plain iterator zips: no index clamping and no bounds checks.
+    let pad = radius;
+    let (lo, hi) = (x0 as isize - r, (x1 + radius + 1) as isize); // ext covers row[lo..hi)
+    let left_n = (-lo).max(0) as usize;
+    let right_n = (hi - w as isize).max(0) as usize;
+    let (src_lo, src_hi) = (lo.max(0) as usize, (hi as usize).min(w));
+    let ext_len = cols + 2 * pad + 1;
+    tmp.pixels[t0 * w..t1 * w].par_chunks_mut(band).enumerate().for_each_init(|| vec![0u32; ext_len], |ext, (b, out)| {
+        for (i, out_row) in out.chunks_mut(w).enumerate() {
+            let y = t0 + b * BLUR_BAND_ROWS + i;
+            let row = &src.pixels[y * w..(y + 1) * w];
+            let (left, rest) = ext.split_at_mut(left_n);
+            let (mid, right) = rest.split_at_mut(src_hi - src_lo);
+            left.fill(row[0]);
+            mid.copy_from_slice(&row[src_lo..src_hi]);
+            right[..right_n].fill(row[w - 1]);
+
+            // Prime the window centred on x0 (ext[0..=2r] = row[x0-r..=x0+r]).
+            let (mut sr, mut sg, mut sb) = (0u32, 0u32, 0u32);
+            for &p in &ext[..=2 * pad] {
+                sr += (p >> 16) & 0xFF; sg += (p >> 8) & 0xFF; sb += p & 0xFF;
+            }
+
+            // Slide the window across the segment (add right, remove left).
+            for ((o, &p_sub), &p_add) in out_row[x0..x1].iter_mut().zip(ext.iter()).zip(&ext[2 * pad + 1..]) {
+                *o = ((sr / win) << 16) | ((sg / win) << 8) | (sb / win);
+                sr = sr + ((p_add >> 16) & 0xFF) - ((p_sub >> 16) & 0xFF);
+                sg = sg + ((p_add >>  8) & 0xFF) - ((p_sub >>  8) & 0xFF);
+                sb = sb + ( p_add        & 0xFF) - ( p_sub        & 0xFF);
+            }
+        }
+    });
+
+    /* ---- Pass 2: Vertical (read tmp, write dst), one stripe of columns per task ----
+       What you SEE: `dst` becomes a blurred copy of `src` inside the region. */
+    // Hand each stripe its piece of every output row, so tasks write disjoint slices.
+    let mut stripes: Vec<Vec<&mut [u32]>> = (0..cols.div_ceil(BLUR_STRIPE_COLS)).map(|_| Vec::with_capacity(y1 - y0)).collect();
+    for out_row in dst.pixels[y0 * w..y1 * w].chunks_mut(w) {
+        for (stripe, piece) in stripes.iter_mut().zip(out_row[x0..x1].chunks_mut(BLUR_STRIPE_COLS)) {
+            stripe.push(piece);
+        }
+    }
+    let tmp = &*tmp;
+    stripes.into_par_iter().enumerate().for_each(|(k, mut pieces)| {
+        let c0 = x0 + k * BLUR_STRIPE_COLS;
+        let n = pieces.first().map_or(0, |p| p.len());
+        let row = |y: isize| &tmp.pixels[y.clamp(0, h as isize - 1) as usize * w + c0..][..n];
+
+        // Per-column running sums, primed with the window centred on the region's first row.
+        let mut sums = [[0u32; 3]; BLUR_STRIPE_COLS];
+        let sums = &mut sums[..n];
+        let top = y0 as isize;
+        for y in top - r..=top + r {
+            for (s, &p) in sums.iter_mut().zip(row(y)) {
+                s[0] += (p >> 16) & 0xFF; s[1] += (p >> 8) & 0xFF; s[2] += p & 0xFF;
+            }
+        }
+
+        // Slide the window down the whole stripe.
+        for (i, out) in pieces.iter_mut().enumerate() {
+            let y = top + i as isize;
+            for (o, s) in out.iter_mut().zip(sums.iter()) {
+                *o = ((s[0] / win) << 16) | ((s[1] / win) << 8) | (s[2] / win);
+            }
+            for ((s, &p_sub), &p_add) in sums.iter_mut().zip(row(y - r)).zip(row(y + r + 1)) {
+                s[0] = s[0] + ((p_add >> 16) & 0xFF) - ((p_sub >> 16) & 0xFF);
+                s[1] = s[1] + ((p_add >>  8) & 0xFF) - ((p_sub >>  8) & 0xFF);
+                s[2] = s[2] + ( p_add        & 0xFF) - ( p_sub        & 0xFF);
+            }
         }
+    });
+
+    Ok(())
+}
+
+/// Copy `src` into `dst` everywhere outside `region` (whole rows above/below, then the
+/// left/right strips), so a region-limited blur still leaves a complete frame behind.
+fn copy_outside(src: &FrameBuffer, dst: &mut FrameBuffer, region: Rect) {
+    let w = src.width;
+    let Rect { x0, y0, x1, y1 } = region;
+    if x0 >= x1 || y0 >= y1 {
+        dst.pixels.copy_from_slice(&src.pixels);
+        return;
+    }
+    dst.pixels[..y0 * w].copy_from_slice(&src.pixels[..y0 * w]);
+    dst.pixels[y1 * w..].copy_from_slice(&src.pixels[y1 * w..]);
+    if x0 == 0 && x1 == w { return; }
+    for (d, s) in dst.pixels[y0 * w..y1 * w].chunks_mut(w).zip(src.pixels[y0 * w..y1 * w].chunks(w)) {
+        d[..x0].copy_from_slice(&s[..x0]);
+        d[x1..].copy_from_slice(&s[x1..]);
+    }
+}
+
+/* ---------------------- lazy blur / temporal reuse ---------------------- */
+
+/// Remembers what the blurred frame was computed with, so it is only redone when needed.
+/// `K` is whatever describes the blur settings (radius, algorithm, region, …).
+/// With `every` > 1 the blur is reused for that many frames while settings stay the same:
+/// fine for static scenes, slightly laggy ghosting when you move fast.
+pub struct BlurCache<K> {
+    pub every: u32,     // recompute at least every N consumed frames (1 = always)
+    age: u32,           // frames the current blur has been reused for
+    key: Option<K>,     // settings of the current blur; None = nothing valid cached
+}
+
+impl<K: PartialEq + Copy> Default for BlurCache<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: PartialEq + Copy> BlurCache<K> {
+    pub fn new() -> Self {
+        Self { every: 1, age: 0, key: None }
+    }
+
+    /// Call on frames that will use the blur: true = blur now, false = reuse the last one.
+    pub fn needs_refresh(&mut self, key: K) -> bool {
+        if self.key == Some(key) && self.age + 1 < self.every {
+            self.age += 1;
+            return false;
+        }
+        self.key = Some(key);
+        self.age = 0;
+        true
+    }
 
-        // Slide the window across the row
+    /// Call on frames that skip the blur: the cached one is now out of date.
+    pub fn invalidate(&mut self) {
+        self.key = None;
+    }
+
+    /// Cycle the reuse interval: every frame → every 2nd → every 4th → every frame.
+    pub fn next_every(&mut self) {
+        self.every = match self.every { 1 => 2, 2 => 4, _ => 1 };
+        self.key = None;
+    }
+}
+
+/* ---------------------- generic convolution (sharpen / emboss / edge / …) ---------------------- */
+
+/// Convolve `src` with `kernel` into `dst`. Edges extend (same clamped-edge rule as the blurs),
+/// so borders never darken or wrap around.
+/// What you SEE: whatever the kernel does (see `Kernel::sharpen`, `emboss`, `edge`).
+pub fn convolve(src: &FrameBuffer, dst: &mut FrameBuffer, kernel: &Kernel) -> Result<(), Error> {
+    convolve_view(src.view(), dst.view_mut(), kernel)
+}
+
+/// `convolve` on views: only the rectangle `src` covers is read (its border is the
+/// edge that extends), and `dst` — same size — is written. E.g. sharpen just a face box.
+pub fn convolve_view(src: FrameView, mut dst: FrameViewMut, kernel: &Kernel) -> Result<(), Error> {
+    if src.width != dst.width || src.height != dst.height {
+        return Err(Error::CameraFrame("convolve: size mismatch src↔dst".into()));
+    }
+    if kernel.size.is_multiple_of(2) || kernel.weights.len() != kernel.size * kernel.size || kernel.divisor == 0 {
+        return Err(Error::CameraFrame("convolve: kernel must be odd-sized, square, divisor ≠ 0".into()));
+    }
+    let (w, h) = (src.width as i32, src.height as i32);
+    let r = (kernel.size / 2) as i32;
+
+    for y in 0..h {
+        let out = dst.row_mut(y as usize);
         for x in 0..w {
-            // Average = sum / window; pack back to 0x00RRGGBB
-            let r8 = (sr / win) as u32;
-            let g8 = (sg / win) as u32;
-            let b8 = (sb / win) as u32;
-            tmp.pixels[row_ofs + x as usize] = (r8 << 16) | (g8 << 8) | b8;
-
-            // Update sums for next column (add right, remove left)
-            let left_x  = (x - r).max(0) as usize;     // clamped left index
-            let right_x = (x + r + 1).min(w - 1) as usize; // clamped new right index
-
-            let p_sub = src.pixels[row_ofs + left_x];
-            let p_add = src.pixels[row_ofs + right_x];
-
-            sr = sr + (((p_add >> 16) & 0xFF) as u32) - (((p_sub >> 16) & 0xFF) as u32);
-            sg = sg + (((p_add >>  8) & 0xFF) as u32) - (((p_sub >>  8) & 0xFF) as u32);
-            sb = sb + (((p_add      ) & 0xFF) as u32) - (((p_sub      ) & 0xFF) as u32);
+            let (mut sr, mut sg, mut sb) = (0i32, 0i32, 0i32);
+            for (k, &wt) in kernel.weights.iter().enumerate() {
+                if wt == 0 { continue; }
+                let kx = (k % kernel.size) as i32 - r;
+                let ky = (k / kernel.size) as i32 - r;
+                let sx = (x + kx).clamp(0, w - 1);
+                let sy = (y + ky).clamp(0, h - 1);
+                let p = src.row(sy as usize)[sx as usize];
+                sr += wt * ((p >> 16) & 0xFF) as i32;
+                sg += wt * ((p >> 8) & 0xFF) as i32;
+                sb += wt * (p & 0xFF) as i32;
+            }
+            let f = |s: i32| (s / kernel.divisor + kernel.bias).clamp(0, 255) as u32;
+            out[x as usize] = (f(sr) << 16) | (f(sg) << 8) | f(sb);
         }
     }
+    Ok(())
+}
 
-    /* ---- Pass 2: Vertical (read tmp, write dst) ----
-       What you SEE: `dst` becomes a blurred copy of `src`. */
-    for x in 0..w {
-        // Edge pixel at y=0 for this column
-        let p0 = tmp.pixels[x as usize];
-        let (mut sr, mut sg, mut sb) = (
-            (((p0 >> 16) & 0xFF) as u32) * (r as u32 + 1),
-            (((p0 >>  8) & 0xFF) as u32) * (r as u32 + 1),
-            (((p0      ) & 0xFF) as u32) * (r as u32 + 1),
-        );
-
-        // Prime the initial window [0..r] downwards
-        for y in 1..=r {
-            let yr = y.min(h - 1);
-            let p = tmp.pixels[(yr as usize) * (w as usize) + x as usize];
-            sr += ((p >> 16) & 0xFF) as u32;
-            sg += ((p >>  8) & 0xFF) as u32;
-            sb += ((p      ) & 0xFF) as u32;
+/* ---------------------- stack blur (triangle kernel, box-like cost) ---------------------- */
+
+/// Which blur algorithm builds the BLUR sink (K cycles at runtime).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlurKind {
+    Box,   // flat window: fastest, but bright points turn into visible squares
+    Stack, // triangle-weighted window: rounder, closer to Gaussian, nearly as fast
+}
+
+impl BlurKind {
+    pub fn next(self) -> Self {
+        match self {
+            BlurKind::Box => BlurKind::Stack,
+            BlurKind::Stack => BlurKind::Box,
         }
+    }
 
-        // Slide the window down the column
-        for y in 0..h {
-            let idx = (y as usize) * (w as usize) + x as usize;
-            let r8 = (sr / win) as u32;
-            let g8 = (sg / win) as u32;
-            let b8 = (sb / win) as u32;
-            dst.pixels[idx] = (r8 << 16) | (g8 << 8) | b8;
+    /// Short HUD tag.
+    pub fn label(self) -> &'static str {
+        match self {
+            BlurKind::Box => "BOX",
+            BlurKind::Stack => "STACK",
+        }
+    }
+}
 
-            let top_y    = (y - r).max(0);
-            let bottom_y = (y + r + 1).min(h - 1);
+/// Stack-blur one line of `n` packed pixels read at `src[s0 + i*stride]`,
+/// writing `dst[d0 + i*stride]`. Edges extend (same as `box_blur_rgb`).
+/// The window weights rise 1,2,…,r+1 and fall back to 1, so each pixel's
+/// neighbours count less the further away they are.
+fn stack_line(src: &[u32], dst: &mut [u32], start: usize, stride: usize, n: usize, r: usize, stack: &mut [[u32; 3]]) {
+    let unpack = |p: u32| [(p >> 16) & 0xFF, (p >> 8) & 0xFF, p & 0xFF];
+    let at = |i: usize| unpack(src[start + i.min(n - 1) * stride]);
+    let div = ((r + 1) * (r + 1)) as u32;
+    let len = 2 * r + 1;
+
+    let mut sum = [0u32; 3];     // weighted window sum
+    let mut sum_in = [0u32; 3];  // pixels on the rising (right) side
+    let mut sum_out = [0u32; 3]; // pixels on the falling (left) side
+
+    // Prime: left half repeats the edge pixel, right half reads ahead.
+    let first = at(0);
+    for (i, slot) in stack.iter_mut().enumerate().take(r + 1) {
+        *slot = first;
+        for c in 0..3 {
+            sum[c] += first[c] * (i as u32 + 1);
+            sum_out[c] += first[c];
+        }
+    }
+    for i in 1..=r {
+        let p = at(i);
+        stack[i + r] = p;
+        for c in 0..3 {
+            sum[c] += p[c] * (r + 1 - i) as u32;
+            sum_in[c] += p[c];
+        }
+    }
 
-            let p_sub = tmp.pixels[(top_y as usize)    * (w as usize) + x as usize];
-            let p_add = tmp.pixels[(bottom_y as usize) * (w as usize) + x as usize];
+    let mut sp = r; // stack pointer at the window centre
+    for x in 0..n {
+        dst[start + x * stride] = ((sum[0] / div) << 16) | ((sum[1] / div) << 8) | (sum[2] / div);
+
+        // Slide one step: the whole window loses its falling side…
+        for c in 0..3 { sum[c] -= sum_out[c]; }
+        let oldest = (sp + len - r) % len;
+        for c in 0..3 { sum_out[c] -= stack[oldest][c]; }
+
+        // …the new far-right pixel enters the rising side…
+        let p = at(x + r + 1);
+        stack[oldest] = p;
+        for c in 0..3 {
+            sum_in[c] += p[c];
+            sum[c] += sum_in[c];
+        }
 
-            sr = sr + (((p_add >> 16) & 0xFF) as u32) - (((p_sub >> 16) & 0xFF) as u32);
-            sg = sg + (((p_add >>  8) & 0xFF) as u32) - (((p_sub >>  8) & 0xFF) as u32);
-            sb = sb + (((p_add      ) & 0xFF) as u32) - (((p_sub      ) & 0xFF) as u32);
+        // …and the new centre moves from the rising side to the falling side.
+        sp = (sp + 1) % len;
+        let centre = stack[sp];
+        for c in 0..3 {
+            sum_out[c] += centre[c];
+            sum_in[c] -= centre[c];
         }
     }
+}
 
+/// Stack blur: quality between box and Gaussian at roughly box-blur cost.
+/// Same buffers/sizes contract as `box_blur_rgb_rect`: only `region` is blurred
+/// (rows it needs, then its columns), the rest of `dst` is copied from `src`.
+/// What you SEE: bright points blur into round soft blobs instead of squares.
+pub fn stack_blur_rgb(
+    src: &FrameBuffer,
+    tmp: &mut FrameBuffer,
+    dst: &mut FrameBuffer,
+    radius: usize,
+    region: Rect,
+) -> Result<(), Error> {
+    if src.width != dst.width || src.height != dst.height {
+        return Err(Error::CameraFrame("stack_blur: size mismatch src↔dst".into()));
+    }
+    if tmp.width != src.width || tmp.height != src.height {
+        return Err(Error::CameraFrame("stack_blur: size mismatch tmp".into()));
+    }
+    let (w, h) = (src.width, src.height);
+    if w == 0 || h == 0 { return Ok(()); }
+    let r = radius.max(1);
+    let mut stack = vec![[0u32; 3]; 2 * r + 1];
+    let (x1, y1) = (region.x1.min(w), region.y1.min(h));
+    let (x0, y0) = (region.x0.min(x1), region.y0.min(y1));
+
+    // Pass 1: rows the region's columns will read (src → tmp).
+    // Pass 2: the region's columns (tmp → dst); rows outside it are then restored.
+    for y in y0.saturating_sub(r)..(y1 + r).min(h) {
+        stack_line(&src.pixels, &mut tmp.pixels, y * w, 1, w, r, &mut stack);
+    }
+    for x in x0..x1 {
+        stack_line(&tmp.pixels, &mut dst.pixels, x, w, h, r, &mut stack);
+    }
+    copy_outside(src, dst, Rect { x0, y0, x1, y1 });
     Ok(())
 }
 
+/* ---------------------- linear-light blur (physically plausible edges) ---------------------- */
+
+/// Sliding-window box average over one line of 12-bit linear RGB, edges extended.
+fn box_line_linear(input: &[[u16; 3]], output: &mut [[u16; 3]], radius: usize) {
+    let n = input.len();
+    if n == 0 { return; }
+    let r = radius as isize;
+    let win = (2 * radius + 1) as u32;
+    let at = |i: isize| input[i.clamp(0, n as isize - 1) as usize];
+
+    // Prime the window centred on index 0: left half repeats the edge pixel.
+    let mut sum = [0u32; 3];
+    for i in -r..=r {
+        let p = at(i);
+        for c in 0..3 { sum[c] += p[c] as u32; }
+    }
+    for (i, out) in output.iter_mut().enumerate() {
+        for c in 0..3 { out[c] = ((sum[c] + win / 2) / win) as u16; }
+        // Slide: drop the leftmost, add the next on the right.
+        let (sub, add) = (at(i as isize - r), at(i as isize + r + 1));
+        for c in 0..3 { sum[c] = sum[c] + add[c] as u32 - sub[c] as u32; }
+    }
+}
+
+/// Box blur that averages *light* instead of sRGB code values.
+/// Averaging sRGB numbers darkens bright/dark edges (a white line on black turns grey
+/// and dim); averaging in linear light keeps the glow right, like a real defocus.
+/// Holds its own 12-bit scratch planes so nothing is allocated per frame.
+pub struct LinearBlur {
+    lin: Vec<[u16; 3]>,  // frame converted to 12-bit linear
+    tmp: Vec<[u16; 3]>,  // horizontal pass result
+    line_in: Vec<[u16; 3]>,
+    line_out: Vec<[u16; 3]>,
+}
+
+impl Default for LinearBlur {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinearBlur {
+    pub fn new() -> Self {
+        Self { lin: Vec::new(), tmp: Vec::new(), line_in: Vec::new(), line_out: Vec::new() }
+    }
+
+    /// Blur `src` into `dst` in linear light (same radius meaning as `box_blur_rgb`).
+    /// What you SEE: a blur where highlights stay bright and edges don't grow dark halos.
+    pub fn blur(&mut self, src: &FrameBuffer, dst: &mut FrameBuffer, radius: usize, lut: &GammaLut) -> Result<(), Error> {
+        if src.width != dst.width || src.height != dst.height {
+            return Err(Error::CameraFrame("linear blur: size mismatch src↔dst".into()));
+        }
+        let (w, h) = (src.width, src.height);
+        self.lin.resize(w * h, [0; 3]);
+        self.tmp.resize(w * h, [0; 3]);
+
+        // 1) sRGB → 12-bit linear via the LUT (one lookup per channel).
+        for (l, &p) in self.lin.iter_mut().zip(&src.pixels) {
+            *l = [
+                lut.srgb_u8_to_linear12(((p >> 16) & 0xFF) as u8),
+                lut.srgb_u8_to_linear12(((p >> 8) & 0xFF) as u8),
+                lut.srgb_u8_to_linear12((p & 0xFF) as u8),
+            ];
+        }
+
+        // 2) Horizontal pass, row by row (rows are contiguous: no copying needed).
+        for y in 0..h {
+            let row = y * w..(y + 1) * w;
+            box_line_linear(&self.lin[row.clone()], &mut self.tmp[row], radius);
+        }
+
+        // 3) Vertical pass: gather each column, blur it, scatter back into `lin`.
+        self.line_in.resize(h, [0; 3]);
+        self.line_out.resize(h, [0; 3]);
+        for x in 0..w {
+            for y in 0..h { self.line_in[y] = self.tmp[y * w + x]; }
+            box_line_linear(&self.line_in, &mut self.line_out, radius);
+            for y in 0..h { self.lin[y * w + x] = self.line_out[y]; }
+        }
+
+        // 4) Linear → sRGB via the LUT and pack 0x00RRGGBB.
+        for (d, l) in dst.pixels.iter_mut().zip(&self.lin) {
+            let r = lut.linear12_to_srgb_u8(l[0]) as u32;
+            let g = lut.linear12_to_srgb_u8(l[1]) as u32;
+            let b = lut.linear12_to_srgb_u8(l[2]) as u32;
+            *d = (r << 16) | (g << 8) | b;
+        }
+        Ok(())
+    }
+}
+
+/// Mix `sink` into `fg_live` by the mask, in linear light, one 32×32 tile at a time.
+/// `tiles` must summarize `mask`: untouched tiles (max α = 0) are skipped outright and
+/// fully painted tiles (min α = 1) are a plain row copy, so only edge tiles pay for gamma.
+/// Only tiles inside `mask.bounds` are visited; an empty mask costs nothing.
 pub fn blend_linear_in_place(
     fg_live: &mut FrameBuffer,
     sink: &FrameBuffer,     // NOTE: was `bg` before; now it's BLUR(LIVE)
     mask: &Mask,
+    tiles: &MaskTiles,
     lut: &GammaLut,
 ) -> Result<(), Error> {
     if fg_live.width != sink.width || fg_live.height != sink.height {
@@ -248,44 +713,126 @@ pub fn blend_linear_in_place(
     if mask.width != fg_live.width || mask.height != fg_live.height {
         return Err(Error::CameraFrame("blend: mask dimension mismatch".into()));
     }
+    if tiles.tiles_x != mask.width.div_ceil(MASK_TILE) || tiles.tiles_y != mask.height.div_ceil(MASK_TILE) {
+        return Err(Error::CameraFrame("blend: tile summary does not match mask".into()));
+    }
 
-    let len = fg_live.width * fg_live.height;
-    for i in 0..len {
-        let a = mask.alpha[i];
-        if a <= 0.0 { continue; }            // visual: keep raw live
-        if a >= 1.0 {                        // visual: fully blurred at this pixel
-            fg_live.pixels[i] = sink.pixels[i];
-            continue;
+    let Some(b) = mask.bounds else { return Ok(()) }; // visual: nothing painted → raw live
+    let w = fg_live.width;
+    for ty in b.y0 / MASK_TILE..b.y1.div_ceil(MASK_TILE) {
+        for tx in b.x0 / MASK_TILE..b.x1.div_ceil(MASK_TILE) {
+            let t = ty * tiles.tiles_x + tx;
+            if tiles.max[t] == 0 { continue; }    // visual: whole tile stays raw live
+            let (x0, y0) = (tx * MASK_TILE, ty * MASK_TILE);
+            let (x1, y1) = ((x0 + MASK_TILE).min(w), (y0 + MASK_TILE).min(fg_live.height));
+
+            if tiles.min[t] == 255 {              // visual: whole tile shows the sink
+                for y in y0..y1 {
+                    fg_live.pixels[y * w + x0..y * w + x1].copy_from_slice(&sink.pixels[y * w + x0..y * w + x1]);
+                }
+                continue;
+            }
+
+            for y in y0..y1 {
+                for i in y * w + x0..y * w + x1 {
+                    let a = mask.alpha[i];
+                    if a == 0 { continue; }              // visual: keep raw live
+                    if a == 255 {                        // visual: fully blurred at this pixel
+                        fg_live.pixels[i] = sink.pixels[i];
+                        continue;
+                    }
+                    fg_live.pixels[i] = mix_linear(fg_live.pixels[i], sink.pixels[i], a, lut); // visual: blurred mix
+                }
+            }
         }
+    }
+    Ok(())
+}
 
-        let pf = fg_live.pixels[i];
-        let ps = sink.pixels[i];
-
-        let rf = ((pf >> 16) & 0xFF) as u8;  // live R
-        let gf = ((pf >>  8) & 0xFF) as u8;  // live G
-        let bf = ( pf        & 0xFF) as u8;  // live B
+/// One pixel of the linear-light blend: `a`/255 of the sink over the rest of live.
+/// Pure table lookups: `GammaLut` has the whole gamma-correct mix precomputed per alpha level.
+#[inline]
+fn mix_linear(pf: u32, ps: u32, a: u8, lut: &GammaLut) -> u32 {
+    let table = lut.blend_table(a);
+    let mix = |shift: u32| table[((((ps >> shift) & 0xFF) << 8) | ((pf >> shift) & 0xFF)) as usize] as u32;
+    (mix(16) << 16) | (mix(8) << 8) | mix(0)
+}
 
-        let rs = ((ps >> 16) & 0xFF) as u8;  // sink (blurred) R
-        let gs = ((ps >>  8) & 0xFF) as u8;  // sink (blurred) G
-        let bs = ( ps        & 0xFF) as u8;  // sink (blurred) B
+/* ---------------------- resampling helpers (pyramid / scaled processing) ---------------------- */
 
-        let rf_lin = lut.srgb_u8_to_linear(rf);
-        let gf_lin = lut.srgb_u8_to_linear(gf);
-        let bf_lin = lut.srgb_u8_to_linear(bf);
+/// Halve a frame by averaging 2x2 blocks (odd edges repeat the last pixel).
+/// `dst` must be ((w+1)/2) x ((h+1)/2). Visual: a smaller, slightly smoother copy.
+pub fn downsample_2x(src: &FrameBuffer, dst: &mut FrameBuffer) -> Result<(), Error> {
+    downsample_2x_view(src.view(), dst.view_mut())
+}
 
-        let rs_lin = lut.srgb_u8_to_linear(rs);
-        let gs_lin = lut.srgb_u8_to_linear(gs);
-        let bs_lin = lut.srgb_u8_to_linear(bs);
+/// `downsample_2x` on views (e.g. halve just a region, or write into part of a frame).
+pub fn downsample_2x_view(src: FrameView, mut dst: FrameViewMut) -> Result<(), Error> {
+    if dst.width != src.width.div_ceil(2) || dst.height != src.height.div_ceil(2) {
+        return Err(Error::CameraFrame("downsample_2x: dst must be half size (rounded up)".into()));
+    }
+    let (sw, sh) = (src.width, src.height);
+    for y in 0..dst.height {
+        let row0 = src.row(2 * y);
+        let row1 = src.row((2 * y + 1).min(sh - 1));
+        for (x, out) in dst.row_mut(y).iter_mut().enumerate() {
+            let x0 = 2 * x;
+            let x1 = (2 * x + 1).min(sw - 1);
+            let quad = [row0[x0], row0[x1], row1[x0], row1[x1]];
+            let (mut r, mut g, mut b) = (0u32, 0u32, 0u32);
+            for p in quad {
+                r += (p >> 16) & 0xFF;
+                g += (p >> 8) & 0xFF;
+                b += p & 0xFF;
+            }
+            // +2 rounds to nearest when dividing by 4
+            *out = (((r + 2) / 4) << 16) | (((g + 2) / 4) << 8) | ((b + 2) / 4);
+        }
+    }
+    Ok(())
+}
 
-        let inv = 1.0 - a;
-        let r_lin = a * rs_lin + inv * rf_lin;
-        let g_lin = a * gs_lin + inv * gf_lin;
-        let b_lin = a * bs_lin + inv * bf_lin;
+/// Resize `src` into `dst` (any sizes) with bilinear filtering, pixel centres aligned.
+/// Visual: a smooth stretched/shrunk copy; used to bring small blurred images back up.
+pub fn resize_bilinear(src: &FrameBuffer, dst: &mut FrameBuffer) {
+    resize_bilinear_view(src.view(), dst.view_mut());
+}
 
-        let r = lut.linear_to_srgb_u8(r_lin) as u32;
-        let g = lut.linear_to_srgb_u8(g_lin) as u32;
-        let b = lut.linear_to_srgb_u8(b_lin) as u32;
-        fg_live.pixels[i] = (r << 16) | (g << 8) | b; // visual: blurred mix at this pixel
+/// `resize_bilinear` on views: scale a crop of one frame into a rectangle of another
+/// (zoom, picture-in-picture) without copying either out first.
+pub fn resize_bilinear_view(src: FrameView, mut dst: FrameViewMut) {
+    if src.width == 0 || src.height == 0 { return; }
+    let sx = src.width as f32 / dst.width as f32;
+    let sy = src.height as f32 / dst.height as f32;
+
+    // Column lookups are the same for every row: compute once (index pair + 8-bit weight).
+    let cols: Vec<(usize, usize, u32)> = (0..dst.width)
+        .map(|x| {
+            let u = ((x as f32 + 0.5) * sx - 0.5).max(0.0);
+            let x0 = (u as usize).min(src.width - 1);
+            let x1 = (x0 + 1).min(src.width - 1);
+            (x0, x1, ((u - x0 as f32) * 256.0) as u32)
+        })
+        .collect();
+
+    for (y, out_row) in dst.rows_mut().enumerate() {
+        let v = ((y as f32 + 0.5) * sy - 0.5).max(0.0);
+        let y0 = (v as usize).min(src.height - 1);
+        let y1 = (y0 + 1).min(src.height - 1);
+        let fy = ((v - y0 as f32) * 256.0) as u32;
+        let (row0, row1) = (src.row(y0), src.row(y1));
+
+        for (o, &(x0, x1, fx)) in out_row.iter_mut().zip(&cols) {
+            let (a, b, c, d) = (row0[x0], row0[x1], row1[x0], row1[x1]);
+            let mut out = 0u32;
+            for shift in [16u32, 8, 0] {
+                let ch = |p: u32| (p >> shift) & 0xFF;
+                let top = ch(a) * (256 - fx) + ch(b) * fx;   // 8.8 fixed point
+                let bot = ch(c) * (256 - fx) + ch(d) * fx;
+                let val = (top * (256 - fy) + bot * fy + (1 << 15)) >> 16;
+                out |= val.min(255) << shift;
+            }
+            *o = out;
+        }
     }
-    Ok(())
-}
\ No newline at end of file
+}
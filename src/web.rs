@@ -0,0 +1,206 @@
+// Browser build: the webcam from getUserMedia, the picture on a <canvas>, everything in
+// between the same `vision` / `gamma` / `fx` code the desktop app runs.
+// Visual expectation: open web/index.html (served over http; see the notes at its top),
+// allow the camera, and the canvas shows your live picture; drag on it to paint blur in
+// (sparkles follow the brush), Ctrl+drag removes paint, the sliders set blur and brush.
+//
+// Built with `wasm-pack build --target web --no-default-features --features web`.
+// Rust owns the camera and the canvas; the page only forwards pointer events and calls
+// `frame` from requestAnimationFrame. Without threads in the page, rayon runs every
+// parallel loop on the calling thread: same result, one core.
+
+use crate::error::Error;
+use crate::fx::Fx;
+use crate::gamma::GammaLut;
+use crate::pixel::{PixelBuffer, PixelFormat};
+use crate::types::{FrameBuffer, Mask, MaskTiles, Stamp};
+use crate::vision;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::{Clamped, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlVideoElement, ImageData, MediaStream, MediaStreamConstraints};
+
+fn js_error(e: Error) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+/// The 2D context of `canvas`.
+fn context_2d(canvas: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d, JsValue> {
+    canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("canvas: no 2d context"))?
+        .dyn_into::<CanvasRenderingContext2d>()
+        .map_err(|_| JsValue::from_str("canvas: not a 2d context"))
+}
+
+/// Buffers sized to the camera, made once its first frame has a size.
+struct Frames {
+    rgba: PixelBuffer,   // canvas bytes in and out
+    live: FrameBuffer,   // this frame; the blend and FX go on top of it
+    tmp: FrameBuffer,
+    blur: FrameBuffer,
+    mask: Mask,
+    tiles: MaskTiles,
+}
+
+impl Frames {
+    fn new(width: usize, height: usize) -> Self {
+        let blank = || FrameBuffer { width, height, pixels: vec![0u32; width * height] };
+        Self {
+            rgba: PixelBuffer::new(width, height, PixelFormat::Rgba8),
+            live: blank(),
+            tmp: blank(),
+            blur: blank(),
+            mask: Mask::new(width, height),
+            tiles: MaskTiles::new(width, height),
+        }
+    }
+}
+
+/// The blur brush on a webcam in the page.
+#[wasm_bindgen]
+pub struct WebEraser {
+    video: HtmlVideoElement,
+    grab: CanvasRenderingContext2d,   // hidden canvas the video is drawn into, to read pixels
+    grab_canvas: HtmlCanvasElement,
+    canvas: HtmlCanvasElement,
+    screen: CanvasRenderingContext2d, // the visible canvas
+    frames: Option<Frames>,
+    lut: GammaLut,
+    fx: Fx,
+    fx_on: bool,
+    blur_radius: usize,
+    stamp: Stamp,
+    last_dab: Option<(i32, i32)>,
+}
+
+#[wasm_bindgen]
+impl WebEraser {
+    /// Ask for the camera and draw into the `<canvas id="canvas_id">` (resized to the
+    /// camera). Resolves once the camera is playing; rejects if it was refused.
+    pub async fn start(canvas_id: &str) -> Result<WebEraser, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+        let document = window.document().ok_or_else(|| JsValue::from_str("no document"))?;
+        let canvas = document
+            .get_element_by_id(canvas_id)
+            .ok_or_else(|| JsValue::from_str(&format!("no element #{canvas_id}")))?
+            .dyn_into::<HtmlCanvasElement>()?;
+
+        let constraints = MediaStreamConstraints::new();
+        constraints.set_video(&JsValue::TRUE);
+        constraints.set_audio(&JsValue::FALSE);
+        let request = window.navigator().media_devices()?.get_user_media_with_constraints(&constraints)?;
+        let stream: MediaStream = JsFuture::from(request).await?.dyn_into()?;
+
+        let video = document.create_element("video")?.dyn_into::<HtmlVideoElement>()?;
+        video.set_muted(true);
+        video.set_attribute("playsinline", "")?;                  // iOS: no full-screen player
+        video.set_src_object(Some(&stream));
+        JsFuture::from(video.play()?).await?;
+
+        let grab_canvas = document.create_element("canvas")?.dyn_into::<HtmlCanvasElement>()?;
+        Ok(WebEraser {
+            grab: context_2d(&grab_canvas)?,
+            grab_canvas,
+            screen: context_2d(&canvas)?,
+            canvas,
+            video,
+            frames: None,
+            lut: GammaLut::new(),
+            fx: Fx::new(600),
+            fx_on: true,
+            blur_radius: 12,
+            stamp: vision::brush_stamp(22, 0.5),
+            last_dab: None,
+        })
+    }
+
+    /// One frame: grab the camera, blend the blur in under the mask, FX, show it.
+    /// Call from requestAnimationFrame with the seconds since the last call.
+    pub fn frame(&mut self, dt: f32) -> Result<(), JsValue> {
+        let (w, h) = (self.video.video_width() as usize, self.video.video_height() as usize);
+        if w == 0 || h == 0 {
+            return Ok(());                                        // camera not delivering yet
+        }
+        if self.frames.as_ref().is_none_or(|f| f.live.width != w || f.live.height != h) {
+            for canvas in [&self.canvas, &self.grab_canvas] {
+                canvas.set_width(w as u32);
+                canvas.set_height(h as u32);
+            }
+            self.frames = Some(Frames::new(w, h));                // visual: canvas takes the camera's size
+        }
+        let Some(f) = self.frames.as_mut() else { return Ok(()) };
+
+        self.grab.draw_image_with_html_video_element(&self.video, 0.0, 0.0)?;
+        let pixels = self.grab.get_image_data(0.0, 0.0, w as f64, h as f64)?.data();
+        f.rgba.data.copy_from_slice(&pixels);
+        f.rgba.read_frame(&mut f.live).map_err(js_error)?;
+
+        if let Some(bounds) = f.mask.bounds {
+            vision::box_blur_rgb_rect(&f.live, &mut f.tmp, &mut f.blur, self.blur_radius, bounds).map_err(js_error)?;
+            vision::blend_linear_in_place(&mut f.live, &f.blur, &f.mask, &f.tiles, &self.lut).map_err(js_error)?;
+        }
+        self.fx.update_and_render(&mut f.live, dt);              // visual: sparkles fade
+
+        f.rgba.write_frame(&f.live).map_err(js_error)?;
+        let image = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&f.rgba.data), w as u32, h as u32)?;
+        self.screen.put_image_data(&image, 0.0, 0.0)
+    }
+
+    /// Paint at canvas pixel (x, y); dabs fill the gap from the last call of the stroke.
+    /// `subtract` removes paint instead (Ctrl+drag, as on the desktop).
+    pub fn paint(&mut self, x: i32, y: i32, subtract: bool) {
+        let Some(f) = self.frames.as_mut() else { return };
+        let (x0, y0) = self.last_dab.unwrap_or((x, y));
+        let r = self.stamp.radius;
+        let steps = (((x - x0) as f32).hypot((y - y0) as f32) / (r / 4).max(1) as f32).ceil() as i32;
+        let dab: fn(&mut Mask, i32, i32, &Stamp) = if subtract { vision::lift_mask } else { vision::dab_mask };
+        for i in 0..=steps {
+            let t = if steps == 0 { 1.0 } else { i as f32 / steps as f32 };
+            dab(&mut f.mask, x0 + ((x - x0) as f32 * t).round() as i32, y0 + ((y - y0) as f32 * t).round() as i32, &self.stamp);
+        }
+        f.tiles.update_rect(&f.mask, x0.min(x) - r, y0.min(y) - r, x0.max(x) + r + 1, y0.max(y) + r + 1);
+        self.last_dab = Some((x, y));
+        if !subtract && self.fx_on {
+            self.fx.brush_moved((x - x0) as f32, (y - y0) as f32);
+            self.fx.spawn_sparkles(x as f32, y as f32, self.fx.sparkles_per_dab()); // visual: glows appear
+            self.fx.maybe_spawn_bolt(x as f32, y as f32);
+            self.fx.spawn_smoke(x as f32, y as f32, (x - x0) as f32, (y - y0) as f32); // visual: a trail of haze
+        }
+    }
+
+    /// The pointer went up: the next `paint` starts a new stroke.
+    pub fn end_stroke(&mut self) {
+        self.last_dab = None;
+    }
+
+    /// Remove all paint (with a confetti burst where it was, if FX are on).
+    pub fn clear(&mut self) {
+        if let Some(f) = self.frames.as_mut() {
+            if self.fx_on && let (Some(b), Some((cx, cy))) = (f.mask.bounds, vision::mask_centroid(&f.mask)) {
+                self.fx.burst(cx, cy, (b.x1 - b.x0).max(b.y1 - b.y0) as f32 * 0.5);
+            }
+            vision::clear_mask(&mut f.mask);
+            f.tiles.clear();
+        }
+    }
+
+    pub fn set_blur_radius(&mut self, radius: u32) {
+        self.blur_radius = radius.clamp(1, 96) as usize;
+    }
+
+    /// Brush radius in canvas pixels and hardness 0..1 (as on the desktop).
+    pub fn set_brush(&mut self, radius: u32, hardness: f32) {
+        let radius = radius.clamp(4, 160) as i32;
+        self.stamp = vision::brush_stamp(radius, hardness);
+    }
+
+    pub fn set_fx(&mut self, on: bool) {
+        self.fx_on = on;
+    }
+
+    /// Fraction of the picture painted (0..1), for the page's readout.
+    pub fn coverage(&self) -> f32 {
+        self.frames.as_ref().map_or(0.0, |f| f.mask.coverage())
+    }
+}
@@ -0,0 +1,357 @@
+// The window and its input: the on-screen window (minifb), and one `Input` snapshot of
+// keyboard and mouse per poll, handed to the processing thread.
+// Visual expectation: a window sized to the camera that shows the live picture; every
+// key in keys.rs and every mouse gesture reaches the processing loop on the next frame.
+// Drawing into the picture itself lives in the library (draw.rs).
+//
+// The app talks to a `WindowBackend`, not to minifb: `Drawer` (minifb, the default) and,
+// built with `--features winit`, `WinitDrawer` (winit + softbuffer, `--backend winit`;
+// see winit_window.rs). minifb reports keys, mouse, scroll and typed text, but has no
+// resize, touch, drop or high-DPI events; the winit window is resizable (the picture is
+// letterboxed into it), sharp on high-DPI screens and turns a finger into the left button.
+// Both speak in minifb `Key`s, so keys.rs and [keys] in config.toml work with either.
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::keys::{KeyMap, KEY_BINDINGS};
+use crate::types::FrameBuffer;
+use minifb::{InputCallback, Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// What the app needs from a window: show finished frames, report keyboard and mouse.
+pub trait WindowBackend {
+    /// Use these keys for `KEY_BINDINGS` from the next poll on ([keys] in config.toml).
+    fn set_keymap(&mut self, keymap: KeyMap);
+
+    /// Push the pixels for this frame to the screen.
+    /// Visual: the window immediately displays the new image (live video).
+    fn present(&mut self, framebuffer: &FrameBuffer) -> Result<(), Error>;
+
+    /// Process window events without new pixels (keeps input and the close button alive
+    /// while no frame is ready). Visual: the window keeps showing the last image.
+    fn update(&mut self);
+
+    /// Show or hide the OS mouse cursor while it is over the window.
+    /// Visual: with `false` the arrow disappears inside the window (it returns outside).
+    fn set_cursor_visible(&mut self, visible: bool);
+
+    /// Returns false when the user closes the window (so we can stop the loop).
+    fn is_open(&self) -> bool;
+
+    /// True while ESC is held down (we’ll exit when this is pressed).
+    fn esc_pressed(&self) -> bool;
+
+    /// Read every key/mouse control the app uses (call once per window update).
+    /// Mouse positions are in frame pixels, whatever size the window is.
+    fn poll_input(&self) -> Input;
+
+    /// Arrow keys held right now as a direction (-1, 0 or 1 on each axis; +y is down),
+    /// and whether one of them went down since the last update.
+    /// Visual: they move the keyboard brush cursor (see keycursor.rs).
+    fn arrow_keys(&self) -> ((i32, i32), bool);
+
+    /// Visual: while held, Space paints at the keyboard cursor.
+    fn space_down(&self) -> bool;
+}
+
+pub struct Drawer {
+    window: Window,            // the on-screen window you see
+    typed: Arc<Mutex<String>>, // characters typed since the last poll (for text fields)
+    keymap: KeyMap,            // which physical key sets each KEY_BINDINGS flag
+}
+
+/// Collects typed characters from the window (minifb calls this as keys are typed).
+struct TypedChars(Arc<Mutex<String>>);
+
+impl InputCallback for TypedChars {
+    fn add_char(&mut self, uni_char: u32) {
+        // Printable characters only; editing keys (Backspace, Enter) arrive as key presses.
+        if let Some(ch) = char::from_u32(uni_char).filter(|c| !c.is_control())
+            && let Ok(mut typed) = self.0.lock()
+        {
+            typed.push(ch);
+        }
+    }
+}
+
+impl Drawer {
+    /// Create a window sized to the camera feed.
+    /// Visual: a new empty window appears with your chosen title.
+    pub fn new(title: &str, width: usize, height: usize) -> Result<Self, Error> {
+        let mut window = Window::new(title, width, height, WindowOptions::default())
+            .map_err(|e| Error::WindowInit(e.to_string()))?;
+        let typed = Arc::new(Mutex::new(String::new()));
+        window.set_input_callback(Box::new(TypedChars(Arc::clone(&typed))));
+        Ok(Self { window, typed, keymap: KeyMap::default() })
+    }
+
+    /// Current mouse position in window pixel coordinates (clamped to the window).
+    /// Visual: when this returns Some(x,y), your crosshair will be drawn at that pixel.
+    pub fn mouse_pos(&self) -> Option<(usize, usize)> {
+        self.window
+            .get_mouse_pos(MouseMode::Clamp)
+            .map(|(x, y)| (x.max(0.0) as usize, y.max(0.0) as usize))
+    }
+
+    /// Visual: when true, dabbing occurs at the mouse position (you see erase happening).
+    pub fn left_mouse_down(&self) -> bool {
+        self.window.get_mouse_down(MouseButton::Left)
+    }
+
+    /// -1 for '-', +1 for '=' (auto-repeats while held), 0 otherwise.
+    /// Visual: the selected colour control ramps down/up as you hold the key.
+    pub fn adjust_steps(&self) -> i32 {
+        let mut steps = 0;
+        if self.window.is_key_pressed(Key::Minus, KeyRepeat::Yes) { steps -= 1; }
+        if self.window.is_key_pressed(Key::Equal, KeyRepeat::Yes) { steps += 1; }
+        steps
+    }
+
+    /// -1 for ',', +1 for '.' (auto-repeats while held), 0 otherwise.
+    /// Visual: the blur under your brush gets weaker/stronger.
+    pub fn blur_radius_steps(&self) -> i32 {
+        let mut steps = 0;
+        if self.window.is_key_pressed(Key::Comma, KeyRepeat::Yes) { steps -= 1; }
+        if self.window.is_key_pressed(Key::Period, KeyRepeat::Yes) { steps += 1; }
+        steps
+    }
+
+    /// Visual: while held, the right button picks the clone/heal source point.
+    pub fn right_mouse_down(&self) -> bool {
+        self.window.get_mouse_down(MouseButton::Right)
+    }
+
+    /// Visual: while held, dragging the middle button pans the zoomed view.
+    pub fn middle_mouse_down(&self) -> bool {
+        self.window.get_mouse_down(MouseButton::Middle)
+    }
+
+    /// Shift / Ctrl / Alt held right now (either side).
+    /// Visual: they change what the mouse does — Ctrl+wheel zooms, Shift+click draws a
+    /// straight stroke, Alt+drag removes paint.
+    pub fn modifiers(&self) -> Modifiers {
+        let down = |a, b| self.window.is_key_down(a) || self.window.is_key_down(b);
+        Modifiers {
+            shift: down(Key::LeftShift, Key::RightShift),
+            ctrl: down(Key::LeftCtrl, Key::RightCtrl),
+            alt: down(Key::LeftAlt, Key::RightAlt),
+        }
+    }
+
+    /// Scroll wheel notches since the last update (positive = away from you).
+    pub fn scroll_steps(&self) -> f32 {
+        self.window.get_scroll_wheel().map_or(0.0, |(_, dy)| dy)
+    }
+
+    /// Sideways scroll since the last update (tilt wheels, touchpads, two-finger drags).
+    pub fn scroll_x_steps(&self) -> f32 {
+        self.window.get_scroll_wheel().map_or(0.0, |(dx, _)| dx)
+    }
+
+    /// Which digit key (1..9) went down this frame, if any.
+    /// Visual: used to jump straight to a brush effect (the HUD tag changes).
+    pub fn digit_pressed_once(&self) -> Option<u32> {
+        const DIGITS: [Key; 9] = [
+            Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5,
+            Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+        ];
+        DIGITS
+            .iter()
+            .position(|&k| self.window.is_key_pressed(k, KeyRepeat::No))
+            .map(|i| i as u32 + 1)
+    }
+}
+
+/// Modifier keys held together with the mouse buttons.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+/// One snapshot of the keyboard and mouse, taken on the window thread and
+/// handed to the processing thread (see `pipeline.rs`).
+/// Key fields are "went down since the last `take`" (set from `keys::KEY_BINDINGS`);
+/// mouse fields are the latest state.
+#[derive(Clone, Default, Debug)]
+pub struct Input {
+    pub mouse_pos: Option<(usize, usize)>,
+    pub left_mouse_down: bool,
+    pub right_mouse_down: bool,
+    pub middle_mouse_down: bool,
+    pub mods: Modifiers,
+    pub a_pressed: bool,
+    pub b_pressed: bool,
+    pub c_pressed: bool,
+    pub d_pressed: bool,
+    pub e_pressed: bool,
+    pub f_pressed: bool,
+    pub g_pressed: bool,
+    pub h_pressed: bool,
+    pub i_pressed: bool,
+    pub k_pressed: bool,
+    pub l_pressed: bool,
+    pub m_pressed: bool,
+    pub n_pressed: bool,
+    pub o_pressed: bool,
+    pub p_pressed: bool,
+    pub q_pressed: bool,
+    pub r_pressed: bool,
+    pub s_pressed: bool,
+    pub t_pressed: bool,
+    pub u_pressed: bool,
+    pub v_pressed: bool,
+    pub w_pressed: bool,
+    pub x_pressed: bool,
+    pub y_pressed: bool,
+    pub z_pressed: bool,
+    pub tab_pressed: bool,
+    pub f2_pressed: bool,
+    pub f3_pressed: bool,
+    pub f4_pressed: bool,
+    pub f5_pressed: bool,
+    pub f6_pressed: bool,
+    pub backspace_pressed: bool,
+    pub home_pressed: bool,
+    pub help_pressed: bool,
+    pub enter_pressed: bool,
+    pub digit: Option<u32>,
+    pub adjust_steps: i32,
+    pub blur_radius_steps: i32,
+    pub brush_steps: i32, // brush size down (-) / up (+), from gamepad bumpers
+    pub scroll_steps: f32,
+    pub scroll_x_steps: f32,
+    pub privacy_pressed: bool, // system-wide privacy hotkey (see hotkey.rs)
+    pub reloaded: Option<Box<Config>>, // the settings file was edited (see config.rs)
+    pub typed: String, // text typed since the last `take` (see `ui::Panel::text_input`)
+    pub dropped: Vec<PathBuf>, // files dropped onto the window (winit backend only)
+}
+
+impl Input {
+    /// Fold a newer snapshot in: presses and steps pile up until taken, mouse state is replaced.
+    pub fn merge(&mut self, mut newer: Input) {
+        self.mouse_pos = newer.mouse_pos;
+        self.left_mouse_down = newer.left_mouse_down;
+        self.right_mouse_down = newer.right_mouse_down;
+        self.middle_mouse_down = newer.middle_mouse_down;
+        self.mods = newer.mods;
+        for b in KEY_BINDINGS {
+            *(b.flag)(self) |= *(b.flag)(&mut newer);
+        }
+        self.digit = newer.digit.or(self.digit);
+        self.adjust_steps += newer.adjust_steps;
+        self.blur_radius_steps += newer.blur_radius_steps;
+        self.brush_steps += newer.brush_steps;
+        self.scroll_steps += newer.scroll_steps;
+        self.scroll_x_steps += newer.scroll_x_steps;
+        self.privacy_pressed |= newer.privacy_pressed;
+        if newer.reloaded.is_some() {
+            self.reloaded = newer.reloaded;
+        }
+        self.typed.push_str(&newer.typed);
+        self.dropped.append(&mut newer.dropped);
+    }
+
+    /// Anything pressed, held, scrolled or typed in this snapshot (mouse moves aside).
+    pub fn has_activity(&self) -> bool {
+        let mut probe = self.clone();
+        KEY_BINDINGS.iter().any(|b| *(b.flag)(&mut probe))
+            || self.left_mouse_down || self.right_mouse_down || self.middle_mouse_down
+            || self.digit.is_some() || self.adjust_steps != 0 || self.blur_radius_steps != 0
+            || self.brush_steps != 0 || self.scroll_steps != 0.0 || self.scroll_x_steps != 0.0
+            || self.privacy_pressed || !self.typed.is_empty() || !self.dropped.is_empty()
+    }
+
+    /// Forget every keyboard shortcut in this snapshot (a text field is taking the keys).
+    /// Typed text, Backspace and Enter stay for the field; mouse state is untouched.
+    pub fn clear_shortcuts(&mut self) {
+        let (backspace, enter) = (self.backspace_pressed, self.enter_pressed);
+        for b in KEY_BINDINGS {
+            *(b.flag)(self) = false;
+        }
+        self.backspace_pressed = backspace;
+        self.enter_pressed = enter;
+        self.digit = None;
+        self.adjust_steps = 0;
+        self.blur_radius_steps = 0;
+        self.brush_steps = 0;
+    }
+
+    /// Everything collected so far; presses and steps start over, mouse state stays.
+    pub fn take(&mut self) -> Input {
+        let out = self.clone();
+        *self = Input {
+            mouse_pos: self.mouse_pos,
+            left_mouse_down: self.left_mouse_down,
+            right_mouse_down: self.right_mouse_down,
+            middle_mouse_down: self.middle_mouse_down,
+            mods: self.mods,
+            ..Input::default()
+        };
+        out
+    }
+}
+
+impl WindowBackend for Drawer {
+    fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
+    fn present(&mut self, framebuffer: &FrameBuffer) -> Result<(), Error> {
+        self.window
+            .update_with_buffer(&framebuffer.pixels, framebuffer.width, framebuffer.height)
+            .map_err(|e| Error::WindowUpdate(e.to_string()))?;
+        Ok(())
+    }
+
+    fn update(&mut self) {
+        self.window.update();
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) {
+        self.window.set_cursor_visibility(visible);
+    }
+
+    fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    fn esc_pressed(&self) -> bool {
+        self.window.is_key_down(Key::Escape)
+    }
+
+    fn poll_input(&self) -> Input {
+        let mut input = Input {
+            mouse_pos: self.mouse_pos(),
+            left_mouse_down: self.left_mouse_down(),
+            right_mouse_down: self.right_mouse_down(),
+            middle_mouse_down: self.middle_mouse_down(),
+            mods: self.modifiers(),
+            digit: self.digit_pressed_once(),
+            adjust_steps: self.adjust_steps(),
+            blur_radius_steps: self.blur_radius_steps(),
+            scroll_steps: self.scroll_steps(),
+            scroll_x_steps: self.scroll_x_steps(),
+            typed: self.typed.lock().map(|mut t| std::mem::take(&mut *t)).unwrap_or_default(),
+            ..Input::default()
+        };
+        for (i, b) in KEY_BINDINGS.iter().enumerate() {
+            if self.keymap.keys(i).iter().any(|&k| self.window.is_key_pressed(k, KeyRepeat::No)) {
+                *(b.flag)(&mut input) = true;
+            }
+        }
+        input
+    }
+    fn arrow_keys(&self) -> ((i32, i32), bool) {
+        let held = |k| self.window.is_key_down(k) as i32;
+        let dir = (held(Key::Right) - held(Key::Left), held(Key::Down) - held(Key::Up));
+        let tapped = [Key::Left, Key::Right, Key::Up, Key::Down].iter().any(|&k| self.window.is_key_pressed(k, KeyRepeat::No));
+        (dir, tapped)
+    }
+
+    fn space_down(&self) -> bool {
+        self.window.is_key_down(Key::Space)
+    }
+}
@@ -0,0 +1,406 @@
+// The winit + softbuffer window (`--features winit`, then `--backend winit`).
+// Visual expectation: the same window as the minifb one, but it can be resized or
+// maximised (the picture scales to fit, black bars keep its shape), it is sized in
+// points so it isn't tiny on a high-DPI screen, and a finger on a touch screen paints
+// like the left mouse button.
+//
+// Files dropped onto the window load (minifb has no drop events): a PNG / JPG becomes
+// the BACKGROUND effect, a `*-mask.png` (as SAVE SNAPSHOT writes) becomes the mask,
+// Ctrl+Z takes it back; a `.cube` file grades the picture (lut.rs), and a video plays
+// instead of the camera, looped, until ffmpeg stops (offline.rs). Anything else shows
+// UNSUPPORTED FILE in the status bar.
+//
+// winit wants to own the thread's event loop; the app's present loop (interactive.rs)
+// owns it instead and pumps it once per `present` / `update`, so both backends look the
+// same to `run_window`. Keys are translated to minifb `Key`s (see `key_of`) for keys.rs.
+// Dropped files go to the app as `Input::dropped` (`App::load_dropped` loads them).
+
+use crate::error::Error;
+use crate::keys::{KeyMap, KEY_BINDINGS};
+use crate::types::FrameBuffer;
+use crate::window::{Input, Modifiers, WindowBackend};
+use minifb::Key;
+use softbuffer::{Context, Surface};
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+use winit::application::ApplicationHandler;
+use winit::dpi::{LogicalSize, PhysicalPosition};
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+use winit::window::{Window, WindowId};
+
+/// Touchpad pixels that count as one wheel notch (minifb reports notches).
+const PIXELS_PER_SCROLL_STEP: f64 = 40.0;
+
+/// Where the frame sits in the window: scaled by `scale`, `(x, y)` from the top-left.
+#[derive(Clone, Copy)]
+struct Fit {
+    scale: f64,
+    x: f64,
+    y: f64,
+}
+
+impl Fit {
+    /// The largest `frame` that fits in `window` with its shape kept, centred.
+    fn new(frame: (usize, usize), window: (u32, u32)) -> Self {
+        let (fw, fh) = (frame.0.max(1) as f64, frame.1.max(1) as f64);
+        let (ww, wh) = (window.0 as f64, window.1 as f64);
+        let scale = (ww / fw).min(wh / fh);
+        Self { scale, x: (ww - fw * scale) / 2.0, y: (wh - fh * scale) / 2.0 }
+    }
+}
+
+/// Window events collected between pumps (winit hands them to an `ApplicationHandler`).
+#[derive(Default)]
+struct State {
+    title: String,
+    size: (usize, usize),                                  // the frame size asked for
+    window: Option<Rc<Window>>,
+    surface: Option<Surface<Rc<Window>, Rc<Window>>>,
+    error: Option<Error>,                                  // window creation failed
+    closed: bool,
+    held: Vec<Key>,
+    pressed: Vec<(Key, bool)>,                             // (key, auto-repeat) since the last pump
+    mods: Modifiers,
+    cursor: Option<PhysicalPosition<f64>>,
+    buttons: [bool; 3],                                    // left, right, middle
+    finger: Option<u64>,                                   // the touch that acts as the left button
+    scroll: (f32, f32),
+    typed: String,
+    dropped: Vec<PathBuf>,                                 // files dropped since the last pump
+}
+
+impl State {
+    fn key(&mut self, key: Key, down: bool, repeat: bool) {
+        self.held.retain(|&k| k != key);
+        if down {
+            self.held.push(key);
+            self.pressed.push((key, repeat));
+        }
+    }
+
+    fn pressed_once(&self, key: Key) -> bool {
+        self.pressed.iter().any(|&(k, repeat)| k == key && !repeat)
+    }
+
+    /// Presses of `key` including auto-repeats (for the ramps on - = , .).
+    fn presses(&self, key: Key) -> i32 {
+        self.pressed.iter().filter(|&&(k, _)| k == key).count() as i32
+    }
+}
+
+impl ApplicationHandler for State {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_some() {
+            return;
+        }
+        let attributes = Window::default_attributes()
+            .with_title(self.title.as_str())
+            .with_inner_size(LogicalSize::new(self.size.0 as f64, self.size.1 as f64));
+        let opened = event_loop
+            .create_window(attributes)
+            .map_err(|e| Error::WindowInit(e.to_string()))
+            .map(Rc::new)
+            .and_then(|window| {
+                let context = Context::new(Rc::clone(&window)).map_err(|e| Error::WindowInit(format!("softbuffer: {e}")))?;
+                let surface = Surface::new(&context, Rc::clone(&window)).map_err(|e| Error::WindowInit(format!("softbuffer: {e}")))?;
+                Ok((window, surface))
+            });
+        match opened {
+            Ok((window, surface)) => {
+                self.window = Some(window);
+                self.surface = Some(surface);
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn window_event(&mut self, _event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::CloseRequested => self.closed = true,
+            WindowEvent::KeyboardInput { event, .. } => {
+                let down = event.state == ElementState::Pressed;
+                if let PhysicalKey::Code(code) = event.physical_key
+                    && let Some(key) = key_of(code)
+                {
+                    self.key(key, down, event.repeat);
+                }
+                // Printable characters only; editing keys (Backspace, Enter) arrive as key presses.
+                if down && let Some(text) = &event.text {
+                    self.typed.extend(text.chars().filter(|c| !c.is_control()));
+                }
+            }
+            WindowEvent::ModifiersChanged(mods) => {
+                let state = mods.state();
+                self.mods = Modifiers { shift: state.shift_key(), ctrl: state.control_key(), alt: state.alt_key() };
+            }
+            WindowEvent::CursorMoved { position, .. } => self.cursor = Some(position),
+            WindowEvent::MouseInput { state, button, .. } => {
+                let index = match button {
+                    MouseButton::Left => 0,
+                    MouseButton::Right => 1,
+                    MouseButton::Middle => 2,
+                    _ => return,
+                };
+                self.buttons[index] = state == ElementState::Pressed;
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (dx, dy) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x, y),
+                    MouseScrollDelta::PixelDelta(p) => ((p.x / PIXELS_PER_SCROLL_STEP) as f32, (p.y / PIXELS_PER_SCROLL_STEP) as f32),
+                };
+                self.scroll.0 += dx;
+                self.scroll.1 += dy;
+            }
+            // One finger paints (like minifb, which gets it as the mouse from the OS).
+            WindowEvent::Touch(touch) if self.finger.is_none_or(|id| id == touch.id) => {
+                let on = matches!(touch.phase, TouchPhase::Started | TouchPhase::Moved);
+                self.finger = on.then_some(touch.id);
+                self.cursor = Some(touch.location);
+                self.buttons[0] = on;
+            }
+            WindowEvent::DroppedFile(path) => self.dropped.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// A resizable winit window drawn with softbuffer (see the notes at the top).
+pub struct WinitDrawer {
+    event_loop: EventLoop<()>,
+    state: State,
+    frame_size: (usize, usize),                            // of the last frame shown (for the mouse)
+    surface_size: (u32, u32),
+    keymap: KeyMap,
+}
+
+impl WinitDrawer {
+    /// Create a window sized to the camera feed (in points, so high-DPI screens scale it).
+    /// Visual: a new empty window appears with your chosen title.
+    pub fn new(title: &str, width: usize, height: usize) -> Result<Self, Error> {
+        let event_loop = EventLoop::new().map_err(|e| Error::WindowInit(e.to_string()))?;
+        let state = State { title: title.to_string(), size: (width, height), ..State::default() };
+        let mut drawer = Self { event_loop, state, frame_size: (width, height), surface_size: (0, 0), keymap: KeyMap::default() };
+        while drawer.state.window.is_none() && !drawer.state.closed {
+            drawer.pump();                                     // `resumed` creates the window
+            if let Some(e) = drawer.state.error.take() {
+                return Err(e);
+            }
+        }
+        Ok(drawer)
+    }
+
+    /// Run the window events that arrived since the last pump. Presses, scroll and typed
+    /// text start over (the loop polls once per pump, as with minifb's `update`).
+    fn pump(&mut self) {
+        self.state.pressed.clear();
+        self.state.scroll = (0.0, 0.0);
+        self.state.typed.clear();
+        self.state.dropped.clear();
+        if let PumpStatus::Exit(_) = self.event_loop.pump_app_events(Some(Duration::ZERO), &mut self.state) {
+            self.state.closed = true;
+        }
+    }
+
+    /// The window's drawable size in pixels.
+    fn window_size(&self) -> (u32, u32) {
+        self.state.window.as_ref().map_or((0, 0), |w| {
+            let size = w.inner_size();
+            (size.width, size.height)
+        })
+    }
+
+    /// The mouse (or finger) in frame pixels, clamped to the frame like minifb's.
+    fn mouse_pos(&self) -> Option<(usize, usize)> {
+        let p = self.state.cursor?;
+        let fit = Fit::new(self.frame_size, self.window_size());
+        let x = ((p.x - fit.x) / fit.scale).clamp(0.0, self.frame_size.0.saturating_sub(1) as f64);
+        let y = ((p.y - fit.y) / fit.scale).clamp(0.0, self.frame_size.1.saturating_sub(1) as f64);
+        Some((x as usize, y as usize))
+    }
+}
+
+impl WindowBackend for WinitDrawer {
+    fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
+    fn present(&mut self, framebuffer: &FrameBuffer) -> Result<(), Error> {
+        self.frame_size = (framebuffer.width, framebuffer.height);
+        let (ww, wh) = self.window_size();
+        if let (Some(surface), Some(width), Some(height)) = (self.state.surface.as_mut(), NonZeroU32::new(ww), NonZeroU32::new(wh)) {
+            if self.surface_size != (ww, wh) {
+                surface.resize(width, height).map_err(|e| Error::WindowUpdate(e.to_string()))?;
+                self.surface_size = (ww, wh);                  // visual: the picture refits the window
+            }
+            let mut buffer = surface.buffer_mut().map_err(|e| Error::WindowUpdate(e.to_string()))?;
+            if (ww as usize, wh as usize) == self.frame_size {
+                buffer.copy_from_slice(&framebuffer.pixels);
+            } else {
+                // Nearest-neighbour into the letterbox; the bars stay black.
+                let fit = Fit::new(self.frame_size, (ww, wh));
+                let (fw, fh) = self.frame_size;
+                for (y, row) in buffer.chunks_exact_mut(ww as usize).enumerate() {
+                    let sy = (y as f64 + 0.5 - fit.y) / fit.scale;
+                    if sy < 0.0 || sy >= fh as f64 {
+                        row.fill(0);
+                        continue;
+                    }
+                    let src = &framebuffer.pixels[sy as usize * fw..][..fw];
+                    for (x, px) in row.iter_mut().enumerate() {
+                        let sx = (x as f64 + 0.5 - fit.x) / fit.scale;
+                        *px = if sx < 0.0 || sx >= fw as f64 { 0 } else { src[sx as usize] };
+                    }
+                }
+            }
+            buffer.present().map_err(|e| Error::WindowUpdate(e.to_string()))?;
+        }
+        self.pump();
+        Ok(())
+    }
+
+    fn update(&mut self) {
+        self.pump();
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) {
+        if let Some(window) = &self.state.window {
+            window.set_cursor_visible(visible);
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        !self.state.closed
+    }
+
+    fn esc_pressed(&self) -> bool {
+        self.state.held.contains(&Key::Escape)
+    }
+
+    fn poll_input(&self) -> Input {
+        const DIGITS: [Key; 9] = [
+            Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5,
+            Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+        ];
+        let s = &self.state;
+        let mut input = Input {
+            mouse_pos: self.mouse_pos(),
+            left_mouse_down: s.buttons[0],
+            right_mouse_down: s.buttons[1],
+            middle_mouse_down: s.buttons[2],
+            mods: s.mods,
+            digit: DIGITS.iter().position(|&k| s.pressed_once(k)).map(|i| i as u32 + 1),
+            adjust_steps: s.presses(Key::Equal) - s.presses(Key::Minus),
+            blur_radius_steps: s.presses(Key::Period) - s.presses(Key::Comma),
+            scroll_steps: s.scroll.1,
+            scroll_x_steps: s.scroll.0,
+            typed: s.typed.clone(),
+            dropped: s.dropped.clone(),
+            ..Input::default()
+        };
+        for (i, b) in KEY_BINDINGS.iter().enumerate() {
+            if self.keymap.keys(i).iter().any(|&k| s.pressed_once(k)) {
+                *(b.flag)(&mut input) = true;
+            }
+        }
+        input
+    }
+
+    fn arrow_keys(&self) -> ((i32, i32), bool) {
+        let held = |k| self.state.held.contains(&k) as i32;
+        let dir = (held(Key::Right) - held(Key::Left), held(Key::Down) - held(Key::Up));
+        let tapped = [Key::Left, Key::Right, Key::Up, Key::Down].iter().any(|&k| self.state.pressed_once(k));
+        (dir, tapped)
+    }
+
+    fn space_down(&self) -> bool {
+        self.state.held.contains(&Key::Space)
+    }
+}
+
+/// The minifb key for a winit key position (the keys keys.rs can bind, and the
+/// arrows, Space, Esc and the ramps). Modifiers come from `ModifiersChanged` instead.
+fn key_of(code: KeyCode) -> Option<Key> {
+    Some(match code {
+        KeyCode::KeyA => Key::A,
+        KeyCode::KeyB => Key::B,
+        KeyCode::KeyC => Key::C,
+        KeyCode::KeyD => Key::D,
+        KeyCode::KeyE => Key::E,
+        KeyCode::KeyF => Key::F,
+        KeyCode::KeyG => Key::G,
+        KeyCode::KeyH => Key::H,
+        KeyCode::KeyI => Key::I,
+        KeyCode::KeyJ => Key::J,
+        KeyCode::KeyK => Key::K,
+        KeyCode::KeyL => Key::L,
+        KeyCode::KeyM => Key::M,
+        KeyCode::KeyN => Key::N,
+        KeyCode::KeyO => Key::O,
+        KeyCode::KeyP => Key::P,
+        KeyCode::KeyQ => Key::Q,
+        KeyCode::KeyR => Key::R,
+        KeyCode::KeyS => Key::S,
+        KeyCode::KeyT => Key::T,
+        KeyCode::KeyU => Key::U,
+        KeyCode::KeyV => Key::V,
+        KeyCode::KeyW => Key::W,
+        KeyCode::KeyX => Key::X,
+        KeyCode::KeyY => Key::Y,
+        KeyCode::KeyZ => Key::Z,
+        KeyCode::Digit0 => Key::Key0,
+        KeyCode::Digit1 => Key::Key1,
+        KeyCode::Digit2 => Key::Key2,
+        KeyCode::Digit3 => Key::Key3,
+        KeyCode::Digit4 => Key::Key4,
+        KeyCode::Digit5 => Key::Key5,
+        KeyCode::Digit6 => Key::Key6,
+        KeyCode::Digit7 => Key::Key7,
+        KeyCode::Digit8 => Key::Key8,
+        KeyCode::Digit9 => Key::Key9,
+        KeyCode::F1 => Key::F1,
+        KeyCode::F2 => Key::F2,
+        KeyCode::F3 => Key::F3,
+        KeyCode::F4 => Key::F4,
+        KeyCode::F5 => Key::F5,
+        KeyCode::F6 => Key::F6,
+        KeyCode::F7 => Key::F7,
+        KeyCode::F8 => Key::F8,
+        KeyCode::F9 => Key::F9,
+        KeyCode::F10 => Key::F10,
+        KeyCode::F11 => Key::F11,
+        KeyCode::F12 => Key::F12,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::NumpadEnter => Key::NumPadEnter,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Escape => Key::Escape,
+        KeyCode::Space => Key::Space,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::ArrowLeft => Key::Left,
+        KeyCode::ArrowRight => Key::Right,
+        KeyCode::ArrowUp => Key::Up,
+        KeyCode::ArrowDown => Key::Down,
+        KeyCode::Minus => Key::Minus,
+        KeyCode::Equal => Key::Equal,
+        KeyCode::Comma => Key::Comma,
+        KeyCode::Period => Key::Period,
+        KeyCode::Slash => Key::Slash,
+        KeyCode::Semicolon => Key::Semicolon,
+        KeyCode::Quote => Key::Apostrophe,
+        KeyCode::BracketLeft => Key::LeftBracket,
+        KeyCode::BracketRight => Key::RightBracket,
+        KeyCode::Backslash => Key::Backslash,
+        KeyCode::Backquote => Key::Backquote,
+        _ => return None,
+    })
+}
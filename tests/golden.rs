@@ -0,0 +1,219 @@
+// Golden-image tests: the picture core run on synthetic frames (fixed seeds, no camera)
+// and compared against PNGs stored in tests/golden/.
+// Visual expectation: none — but every PNG in tests/golden/ is what that step looked
+// like when it was last checked by eye, so a refactor of a pixel loop that changes
+// the picture fails here instead of on someone's video call.
+//
+//     cargo test --test golden                      # compare
+//     UPDATE_GOLDEN=1 cargo test --test golden      # re-record after an intended change
+//
+// A missing golden fails like a mismatch (a fresh checkout or CI must not pass by
+// recording whatever comes out); record a new one with UPDATE_GOLDEN=1, look at it, then
+// commit it. A mismatch writes what came out to target/golden-diff/<name>.png next to
+// the expected picture.
+// Channels may differ by GOLDEN_TOLERANCE, so rounding changes (a different rayon
+// split, a new CPU) don't fail; a visible change does.
+
+use magic_eraser::fx::{Fx, Rng32};
+use magic_eraser::gamma::GammaLut;
+use magic_eraser::types::{FrameBuffer, Mask, MaskTiles, Rect};
+use magic_eraser::vision::{self, BackgroundModel};
+use std::path::{Path, PathBuf};
+
+/// Largest per-channel difference that still counts as the same picture.
+const GOLDEN_TOLERANCE: u8 = 2;
+
+const W: usize = 96;
+const H: usize = 64;
+
+/// A repeatable test scene: a diagonal colour gradient with a few hard-edged blocks
+/// (edges are what blurs and blends get wrong) and a little noise, all from `seed`.
+fn synthetic(seed: u32) -> FrameBuffer {
+    let mut rng = Rng32::from_seed(seed);
+    let mut pixels: Vec<u32> = (0..W * H)
+        .map(|i| {
+            let (x, y) = (i % W, i / W);
+            let r = (x * 255 / W) as u32;
+            let g = (y * 255 / H) as u32;
+            let b = ((x + y) * 255 / (W + H)) as u32;
+            (r << 16) | (g << 8) | b
+        })
+        .collect();
+    for _ in 0..4 {
+        let (x0, y0) = (rng.next_u32() as usize % (W - 16), rng.next_u32() as usize % (H - 16));
+        let colour = rng.next_u32() & 0x00FF_FFFF;
+        for y in y0..y0 + 16 {
+            pixels[y * W + x0..y * W + x0 + 16].fill(colour);
+        }
+    }
+    for p in pixels.iter_mut() {
+        let n = rng.next_u32() % 9;                                // ±4 levels of sensor noise
+        let jitter = |c: u32| (c as i32 + n as i32 - 4).clamp(0, 255) as u32;
+        *p = (jitter(*p >> 16 & 0xFF) << 16) | (jitter(*p >> 8 & 0xFF) << 8) | jitter(*p & 0xFF);
+    }
+    FrameBuffer { width: W, height: H, pixels }
+}
+
+fn blank() -> FrameBuffer {
+    FrameBuffer { width: W, height: H, pixels: vec![0u32; W * H] }
+}
+
+/// The mask as a grey picture (α 0 = black, 255 = white).
+fn mask_picture(mask: &Mask) -> FrameBuffer {
+    let pixels = mask.alpha.iter().map(|&a| (a as u32) * 0x0001_0101).collect();
+    FrameBuffer { width: mask.width, height: mask.height, pixels }
+}
+
+/// A soft horizontal stroke across the middle, as the brush would leave it.
+fn painted_mask() -> Mask {
+    let mut mask = Mask::new(W, H);
+    let stamp = vision::make_gaussian_stamp(12, 6.0);
+    for x in (16..80).step_by(3) {
+        vision::dab_mask(&mut mask, x, H as i32 / 2, &stamp);
+    }
+    mask
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{name}.png"))
+}
+
+fn save_png(path: &Path, frame: &FrameBuffer) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let img = image::RgbImage::from_fn(frame.width as u32, frame.height as u32, |x, y| {
+        let p = frame.pixels[y as usize * frame.width + x as usize];
+        image::Rgb([(p >> 16) as u8, (p >> 8) as u8, p as u8])
+    });
+    img.save(path).unwrap();
+}
+
+/// Compare `frame` with tests/golden/<name>.png (recording it with UPDATE_GOLDEN set).
+fn check_golden(name: &str, frame: &FrameBuffer) {
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        save_png(&path, frame);
+        eprintln!("golden {name}: recorded {}", path.display());
+        return;
+    }
+    if !path.exists() {
+        let actual = Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("golden-diff").join(format!("{name}.png"));
+        save_png(&actual, frame);
+        panic!("golden {name}: {} is missing; got {} (UPDATE_GOLDEN=1 records it)", path.display(), actual.display());
+    }
+    let expected = image::open(&path).unwrap().to_rgb8();
+    assert_eq!(
+        (expected.width() as usize, expected.height() as usize),
+        (frame.width, frame.height),
+        "golden {name}: size changed"
+    );
+    let mut worst = (0u8, 0usize, 0usize);                        // (difference, x, y)
+    let mut off = 0usize;
+    for (x, y, want) in expected.enumerate_pixels() {
+        let p = frame.pixels[y as usize * frame.width + x as usize];
+        let got = [(p >> 16) as u8, (p >> 8) as u8, p as u8];
+        let diff = (0..3).map(|c| got[c].abs_diff(want[c])).max().unwrap_or(0);
+        if diff > GOLDEN_TOLERANCE {
+            off += 1;
+        }
+        if diff > worst.0 {
+            worst = (diff, x as usize, y as usize);
+        }
+    }
+    if off > 0 {
+        let actual = Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("golden-diff").join(format!("{name}.png"));
+        save_png(&actual, frame);
+        panic!(
+            "golden {name}: {off} pixels differ by more than {GOLDEN_TOLERANCE} (worst {} at {},{}); got {}",
+            worst.0, worst.1, worst.2, actual.display()
+        );
+    }
+}
+
+#[test]
+fn box_blur_matches_golden() {
+    let src = synthetic(1);
+    let (mut tmp, mut dst) = (blank(), blank());
+    vision::box_blur_rgb(&src, &mut tmp, &mut dst, 6).unwrap();
+    check_golden("box_blur_r6", &dst);
+}
+
+#[test]
+fn stack_blur_matches_golden() {
+    let src = synthetic(1);
+    let (mut tmp, mut dst) = (blank(), blank());
+    vision::stack_blur_rgb(&src, &mut tmp, &mut dst, 6, Rect::full(W, H)).unwrap();
+    check_golden("stack_blur_r6", &dst);
+}
+
+#[test]
+fn dab_and_lift_match_golden() {
+    let mut mask = painted_mask();
+    vision::lift_mask(&mut mask, 48, H as i32 / 2, &vision::make_gaussian_stamp(8, 4.0)); // a soft gap
+    vision::clear_circle(&mut mask, 70, H as i32 / 2, 5);                                // a hard hole
+    check_golden("dab_stroke", &mask_picture(&mask));
+}
+
+#[test]
+fn blend_matches_golden() {
+    let live = synthetic(2);
+    let (mut tmp, mut blurred) = (blank(), blank());
+    vision::box_blur_rgb(&live, &mut tmp, &mut blurred, 8).unwrap();
+    let mask = painted_mask();
+    let mut tiles = MaskTiles::new(W, H);
+    tiles.rebuild(&mask);
+    let mut out = live.clone();
+    vision::blend_linear_in_place(&mut out, &blurred, &mask, &tiles, &GammaLut::new()).unwrap();
+    check_golden("blend_stroke", &out);
+}
+
+#[test]
+fn background_model_matches_golden() {
+    // The room, with a "hand" block passing through for a few of the frames: the
+    // estimate should end up as the room.
+    let room = synthetic(3);
+    let mut model = BackgroundModel::new(W, H);
+    for i in 0..vision::BG_CAPTURE_COUNT {
+        let mut frame = room.clone();
+        if (8..14).contains(&i) {
+            let x0 = 10 + i * 4;
+            for y in 20..44 {
+                frame.pixels[y * W + x0..y * W + x0 + 12].fill(0x00C0_8060);
+            }
+        }
+        model.update(&frame).unwrap();
+    }
+    check_golden("background_model", model.frame());
+}
+
+#[test]
+fn fx_matches_golden() {
+    let mut fx = Fx::new(600);                                     // fixed internal seed
+    let mut fb = blank();
+    for step in 0..6 {
+        fx.spawn_sparkles(20.0 + step as f32 * 10.0, 32.0, 12);
+        fx.maybe_spawn_bolt(20.0 + step as f32 * 10.0, 32.0);
+        fb.pixels.fill(0);
+        fx.update_and_render(&mut fb, 1.0 / 30.0);
+    }
+    check_golden("fx_sparkles", &fb);
+}
+
+#[test]
+fn pipeline_is_deterministic() {
+    // Goldens only mean something if the same input gives the same output every run.
+    let run = || {
+        let live = synthetic(4);
+        let (mut tmp, mut blurred) = (blank(), blank());
+        vision::stack_blur_rgb(&live, &mut tmp, &mut blurred, 9, Rect::full(W, H)).unwrap();
+        let mask = painted_mask();
+        let mut tiles = MaskTiles::new(W, H);
+        tiles.rebuild(&mask);
+        let mut out = live.clone();
+        vision::blend_linear_in_place(&mut out, &blurred, &mask, &tiles, &GammaLut::new()).unwrap();
+        let mut fx = Fx::new(100);
+        fx.spawn_sparkles(48.0, 32.0, 20);
+        fx.update_and_render(&mut out, 1.0 / 30.0);
+        out.pixels
+    };
+    assert_eq!(run(), run());
+}